@@ -0,0 +1,40 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use devrunner::scripts::discover_all_scripts_with;
+use std::fs;
+
+fn bench_discover(c: &mut Criterion) {
+    // Fixture with a manifest for every sequentially-read parser
+    let dir = tempfile::tempdir().unwrap();
+    let scripts: Vec<String> = (0..200)
+        .map(|i| format!("\"script-{}\": \"echo {}\"", i, i))
+        .collect();
+    fs::write(
+        dir.path().join("package.json"),
+        format!("{{\"scripts\": {{{}}}}}", scripts.join(",")),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"bench\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("pyproject.toml"),
+        "[project.scripts]\ncli = \"app:main\"\n",
+    )
+    .unwrap();
+    let targets: String = (0..200)
+        .map(|i| format!("target-{}:\n\techo {}\n", i, i))
+        .collect();
+    fs::write(dir.path().join("Makefile"), targets).unwrap();
+
+    c.bench_function("discover_all_scripts sequential", |b| {
+        b.iter(|| discover_all_scripts_with(dir.path(), 1))
+    });
+    c.bench_function("discover_all_scripts parallel", |b| {
+        b.iter(|| discover_all_scripts_with(dir.path(), 4))
+    });
+}
+
+criterion_group!(benches, bench_discover);
+criterion_main!(benches);
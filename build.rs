@@ -0,0 +1,36 @@
+//! Capture build metadata for `devrunner --version --json`
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    let git_sha = command_output("git", &["rev-parse", "--short=12", "HEAD"]);
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = command_output(&rustc, &["--version"]);
+    let target = env::var("TARGET").unwrap_or_default();
+
+    // Builds from a source tarball have no git metadata
+    println!(
+        "cargo:rustc-env=DEVRUNNER_GIT_SHA={}",
+        git_sha.as_deref().unwrap_or("unknown")
+    );
+    println!(
+        "cargo:rustc-env=DEVRUNNER_RUSTC_VERSION={}",
+        rustc_version.as_deref().unwrap_or("unknown")
+    );
+    println!("cargo:rustc-env=DEVRUNNER_TARGET={}", target);
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
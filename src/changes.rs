@@ -0,0 +1,178 @@
+use crate::scripts::ProjectScript;
+use std::path::Path;
+use std::process::Command;
+
+/// List files changed since a git ref (`git diff --name-only --relative <ref> --`)
+///
+/// Paths are relative to `project_dir`, and changes outside it are left out.
+/// Returns `None` when git is missing, the command fails or `git_ref` looks
+/// like an option, so callers can silently skip the heuristic instead of failing.
+pub fn changed_files_since(project_dir: &Path, git_ref: &str) -> Option<Vec<String>> {
+    parse_git_ref(git_ref).ok()?;
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "--relative", git_ref, "--"])
+        .current_dir(project_dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(parse_diff_names(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse a `list --since` value; a leading `-` would reach git as an option
+pub fn parse_git_ref(value: &str) -> Result<String, String> {
+    if value.starts_with('-') {
+        return Err(format!("git ref can't start with '-': {}", value));
+    }
+    Ok(value.to_string())
+}
+
+/// Parse `git diff --name-only` output into a list of paths
+pub fn parse_diff_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace('\\', "/"))
+        .collect()
+}
+
+/// Script names worth running for a set of changed paths
+pub fn relevant_script_names(changed: &[String]) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    let mut add = |new: &[&'static str]| {
+        for name in new {
+            if !names.contains(name) {
+                names.push(*name);
+            }
+        }
+    };
+
+    for path in changed {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let in_dir = |dir: &str| path.starts_with(&format!("{}/", dir));
+
+        if in_dir("docs") || in_dir("doc") || file_name.ends_with(".md") {
+            add(&["docs", "doc"]);
+        } else if in_dir("test")
+            || in_dir("tests")
+            || path.contains("__tests__")
+            || file_name.contains(".test.")
+            || file_name.contains(".spec.")
+            || file_name.contains("_test.")
+        {
+            add(&["test"]);
+        } else if matches!(
+            file_name,
+            "package.json" | "Cargo.toml" | "pyproject.toml" | "go.mod" | "Gemfile"
+        ) {
+            add(&["install", "build"]);
+        } else if in_dir("src") || in_dir("lib") || in_dir("app") || in_dir("cmd") {
+            add(&["build", "test", "lint", "check"]);
+        }
+    }
+
+    names
+}
+
+/// Whether a script matches one of the relevant names (`test:unit` matches `test`)
+fn is_relevant(script: &ProjectScript, relevant: &[&str]) -> bool {
+    let base = script.name.split(':').next().unwrap_or(&script.name);
+    relevant.iter().any(|name| base.eq_ignore_ascii_case(name))
+}
+
+/// Move scripts relevant to the changed paths to the front, keeping relative order
+///
+/// Returns the number of relevant scripts now at the start of the list.
+pub fn rank_scripts(scripts: &mut Vec<ProjectScript>, changed: &[String]) -> usize {
+    let relevant = relevant_script_names(changed);
+    let (mut ranked, rest): (Vec<_>, Vec<_>) = scripts
+        .drain(..)
+        .partition(|script| is_relevant(script, &relevant));
+    let count = ranked.len();
+    ranked.extend(rest);
+    *scripts = ranked;
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripts::ScriptCategory;
+
+    fn script(name: &str) -> ProjectScript {
+        ProjectScript {
+            name: name.to_string(),
+            command: format!("npm run {}", name),
+            description: None,
+            category: ScriptCategory::Script,
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_names() {
+        let output = "src/main.rs\n\ndocs/guide.md\r\nsrc\\windows\\path.rs\n";
+        assert_eq!(
+            parse_diff_names(output),
+            vec!["src/main.rs", "docs/guide.md", "src/windows/path.rs"]
+        );
+    }
+
+    #[test]
+    fn test_parse_git_ref() {
+        assert_eq!(parse_git_ref("origin/main"), Ok("origin/main".to_string()));
+        assert!(parse_git_ref("--output=/tmp/x").is_err());
+        assert_eq!(changed_files_since(Path::new("."), "-p"), None);
+    }
+
+    #[test]
+    fn test_relevant_script_names() {
+        let changed = vec!["src/app.ts".to_string()];
+        assert_eq!(
+            relevant_script_names(&changed),
+            vec!["build", "test", "lint", "check"]
+        );
+
+        let changed = vec!["README.md".to_string(), "docs/intro.md".to_string()];
+        assert_eq!(relevant_script_names(&changed), vec!["docs", "doc"]);
+
+        let changed = vec!["src/app.test.ts".to_string()];
+        assert_eq!(relevant_script_names(&changed), vec!["test"]);
+
+        assert!(relevant_script_names(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_rank_scripts_for_source_changes() {
+        let mut scripts = vec![
+            script("dev"),
+            script("docs"),
+            script("test:unit"),
+            script("build"),
+        ];
+        let count = rank_scripts(&mut scripts, &["src/index.ts".to_string()]);
+
+        assert_eq!(count, 2);
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["test:unit", "build", "dev", "docs"]);
+    }
+
+    #[test]
+    fn test_rank_scripts_for_doc_changes() {
+        let mut scripts = vec![script("build"), script("docs")];
+        let count = rank_scripts(&mut scripts, &["docs/api.md".to_string()]);
+
+        assert_eq!(count, 1);
+        assert_eq!(scripts[0].name, "docs");
+    }
+
+    #[test]
+    fn test_rank_scripts_no_changes() {
+        let mut scripts = vec![script("build"), script("test")];
+        assert_eq!(rank_scripts(&mut scripts, &[]), 0);
+        assert_eq!(scripts[0].name, "build");
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Runners picked to settle a conflict, per project directory (`remember_choices`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChoiceStore {
+    /// Canonical project directory -> runner name
+    choices: BTreeMap<String, String>,
+}
+
+impl ChoiceStore {
+    /// Load the store; a missing or unreadable file is an empty store
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the store, creating its directory
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// Runner remembered for `dir`, if any
+    pub fn get(&self, dir: &Path) -> Option<&str> {
+        self.choices.get(&dir_key(dir)).map(String::as_str)
+    }
+
+    /// Remember `runner` for `dir`, replacing an earlier choice
+    pub fn remember(&mut self, dir: &Path, runner: &str) {
+        self.choices.insert(dir_key(dir), runner.to_string());
+    }
+
+    /// Forget the choice for `dir`; false when there was none
+    pub fn forget(&mut self, dir: &Path) -> bool {
+        self.choices.remove(&dir_key(dir)).is_some()
+    }
+}
+
+/// The same directory reached through a symlink or `..` shares one entry
+fn dir_key(dir: &Path) -> String {
+    dir.canonicalize()
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_store_round_trip() {
+        let state = tempdir().unwrap();
+        let path = state.path().join("run").join("choices.json");
+        let project = tempdir().unwrap();
+
+        assert_eq!(ChoiceStore::load(&path), ChoiceStore::default());
+
+        let mut store = ChoiceStore::default();
+        store.remember(project.path(), "pnpm");
+        store.save(&path).unwrap();
+
+        let mut loaded = ChoiceStore::load(&path);
+        assert_eq!(loaded.get(project.path()), Some("pnpm"));
+        assert_eq!(loaded.get(&project.path().join(".")), Some("pnpm"));
+        assert_eq!(loaded.get(state.path()), None);
+
+        assert!(loaded.forget(project.path()));
+        assert!(!loaded.forget(project.path()));
+        assert_eq!(loaded.get(project.path()), None);
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(ChoiceStore::load(&path), ChoiceStore::default());
+    }
+}
@@ -1,31 +1,59 @@
-use clap::{Parser, Subcommand};
+use crate::graph::GraphFormat;
+use crate::output::{ColorChoice, OutputMode};
+use crate::runner::SearchDepth;
+use clap::{ArgAction, ArgGroup, CommandFactory, Parser, Subcommand};
+use std::path::PathBuf;
 
 /// Universal task runner - automatically detects and runs project commands
 #[derive(Parser, Debug, Clone)]
 #[command(name = "devrunner")]
 #[command(author = "PrincePal")]
-#[command(version)]
+#[command(version, disable_version_flag = true)]
 #[command(about = "Universal task runner for modern development", long_about = None)]
+#[command(group(ArgGroup::new("json_target").args(["dry_run", "list_ecosystems", "print_env", "profile_detection", "version"]).multiple(true)))]
 #[command(after_help = "SUPPORTED RUNNERS:
   Node.js:  bun, pnpm, yarn, npm
-  Python:   uv, poetry, pipenv, pip
+  Python:   uv, poetry, pdm, hatch, pipenv, pip
   Rust:     cargo
   PHP:      composer
   Go:       task, go
-  Ruby:     bundler, rake
-  Java:     gradle, maven
+  Ruby:     bundler, rake, binstubs (bin/)
+  Java:     gradle, maven, sbt, mill
   .NET:     dotnet
   Elixir:   mix
+  Xcode:    xcodebuild
   Swift:    swift
   Zig:      zig
+  Dart:     melos, flutter, dart
+  mise:     mise
+  V:        v
+  Odin:     odin
+  Bazel:    bazel
   Generic:  make
+  Infra:    terraform, tofu, ansible-playbook
+  Container: earthly, docker
 
 EXAMPLES:
   devrunner test                      # Run test command using detected runner
   devrunner build -- --verbose        # Pass extra arguments after --
   devrunner lint --levels=5           # Search up to 5 levels above current dir
+  devrunner test --levels=auto        # Choose between nested projects up to the repo root
   devrunner start --ignore=npm,yarn   # Skip specific runners
-  devrunner deploy --dry-run          # Show command without executing")]
+  devrunner dev --prefer pnpm         # Use pnpm if it's detected, else the usual pick
+  devrunner deploy --dry-run          # Show command without executing
+  devrunner build --dry-run --json    # Print the planned execution as JSON
+  devrunner test --print-env          # Show the environment test would run with
+  devrunner test --tee test.log       # Also write command output to a file
+  devrunner test --output=json-stream # Stream output as JSON events
+  devrunner test --tail-on-failure 50 # Only show the last 50 lines if it fails
+  devrunner exec eslint -- --fix .    # Run a tool via the project's runner
+  devrunner install                   # Install dependencies (pnpm install, cargo fetch, ...)
+  devrunner clean                     # cargo clean, go clean, or remove node_modules/dist
+  devrunner build --env prod          # Run build:prod if it exists, else build
+  devrunner lint --check              # Fail if the script doesn't exist
+  devrunner lint --if-present         # Do nothing if there is no lint script
+  devrunner list --all-workspaces     # Scripts of every workspace package
+  devrunner --list-ecosystems         # Show what each detector looks for")]
 pub struct Cli {
     /// Command to run (e.g., test, build, start)
     #[arg(value_name = "COMMAND")]
@@ -35,30 +63,175 @@ pub struct Cli {
     #[arg(value_name = "ARGS", trailing_var_arg = true)]
     pub args: Vec<String>,
 
-    /// How many directory levels to search above current dir
-    #[arg(short, long, default_value = "3", value_parser = clap::value_parser!(u8).range(0..=10))]
-    pub levels: u8,
+    /// How many directory levels to search above current dir (0-10), or `auto` to
+    /// consider every level up to the project root and pick between them
+    #[arg(short, long, default_value = "3", value_name = "N|auto")]
+    pub levels: SearchDepth,
 
     /// Runners to ignore (comma-separated or multiple flags)
     #[arg(short, long = "ignore", value_delimiter = ',')]
     pub ignore: Vec<String>,
 
     /// Show detailed detection information
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub verbose: bool,
 
-    /// Suppress all output except errors and command output
-    #[arg(short, long)]
-    pub quiet: bool,
+    /// On failure, also print the error's cause chain and debug representation
+    #[arg(long, global = true)]
+    pub debug: bool,
+
+    /// Hide devrunner's own output; repeat (-qq) to also silence command stdout
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub quiet: u8,
 
     /// Show command without executing
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// Print version; with --json, also the git commit, rustc version and target
+    #[arg(short = 'V', long)]
+    pub version: bool,
+
+    /// Print JSON: the planned execution with --dry-run, the build metadata with --version, the detectors with
+    /// --list-ecosystems, the environment with --print-env, the timings with
+    /// --profile-detection
+    #[arg(long, requires = "json_target")]
+    pub json: bool,
+
+    /// Print the environment the command would get (sorted KEY=value) instead of running it
+    #[arg(long)]
+    pub print_env: bool,
+
+    /// Ask for confirmation before running, even for scripts that wouldn't prompt
+    #[arg(long, conflicts_with = "yes")]
+    pub confirm: bool,
+
+    /// Run scripts that need confirmation (deploy, release, ...) without asking
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+
+    /// Safe mode for untrusted repos: skip npm lifecycle hooks, refuse curl|sh scripts and their callers
+    #[arg(long, global = true)]
+    pub ignore_scripts: bool,
+
+    /// Run inside the dev container from .devcontainer/devcontainer.json
+    #[arg(long, global = true)]
+    pub in_container: bool,
+
+    /// Show the timing line only for runs slower than DURATION (e.g. 2s, 500ms); turns timing on
+    #[arg(long, value_name = "DURATION", global = true, value_parser = crate::output::parse_duration)]
+    pub timings_threshold: Option<std::time::Duration>,
+
+    /// Favour RUNNER when it is detected (e.g. to settle a lockfile conflict); ignored otherwise
+    #[arg(long, value_name = "RUNNER", global = true)]
+    pub prefer: Option<String>,
+
+    /// Attach /dev/null as the command's stdin, so a prompt fails fast instead of hanging (CI)
+    #[arg(long, global = true)]
+    pub no_stdin: bool,
+
+    /// Run RUNNER from this binary instead of the one on PATH (repeatable, e.g. pnpm=/opt/pnpm/bin/pnpm)
+    #[arg(long, value_name = "RUNNER=PATH", action = ArgAction::Append, global = true, value_parser = crate::config::parse_runner_path)]
+    pub runner_path: Vec<(String, PathBuf)>,
+
+    /// Forget the runner remembered for this directory (remember_choices)
+    #[arg(long)]
+    pub forget: bool,
+
+    /// Ignore `[default_args]` from config for this run
+    #[arg(long)]
+    pub no_default_args: bool,
+
+    /// Don't activate a local Python virtualenv (.venv/ or venv/)
+    #[arg(long, global = true)]
+    pub no_venv: bool,
+
+    /// Prefer the `<command>:<NAME>` variant of a script (e.g. build:prod) when it exists
+    #[arg(long, value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Run the command with only PATH, HOME, clean_env_allowlist and --env-from variables
+    #[arg(long, global = true)]
+    pub clean_env: bool,
+
+    /// Append arguments from FILE, one per line (blank lines and `#` comments skipped)
+    #[arg(long, value_name = "FILE")]
+    pub args_file: Option<PathBuf>,
+
+    /// Run CMD and add the KEY=value lines it prints to the command's environment (repeatable)
+    #[arg(long, value_name = "CMD", action = ArgAction::Append, global = true)]
+    pub env_from: Vec<String>,
+
+    /// Exit successfully without running anything if the script doesn't exist
+    #[arg(long)]
+    pub if_present: bool,
+
+    /// Check the script exists and print its resolved command without running it
+    #[arg(long, visible_alias = "check")]
+    pub list_only: bool,
+
+    /// Print the steps the run would go through (Makefile prerequisites, npm hooks) and exit
+    #[arg(long)]
+    pub dump_plan: bool,
+
+    /// List every built-in detector with its ecosystem, the files it looks for and its priority
+    #[arg(long)]
+    pub list_ecosystems: bool,
+
+    /// Run detection from here, timing each detector, and print the slowest first
+    #[arg(long)]
+    pub profile_detection: bool,
+
+    /// Print a short diagnostic when the command exits non-zero
+    #[arg(long)]
+    pub explain_exit: bool,
+
+    /// Mirror the command's stdout/stderr into a log file
+    #[arg(long, value_name = "FILE")]
+    pub tee: Option<PathBuf>,
+
+    /// Run the command N times in a row and print min/max/mean/median durations
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub repeat: u32,
+
+    /// Run once per value, with `{NAME}` in the args replaced and SELECT_NAME set (repeatable: every combination runs)
+    #[arg(long, value_name = "NAME=V1,V2", action = ArgAction::Append, value_parser = crate::matrix::parse_dimension)]
+    pub select: Vec<crate::matrix::Dimension>,
+
+    /// Keep going after a failed run (--repeat) or script (a glob like `test:*`) instead of stopping
+    #[arg(long, overrides_with = "fail_fast")]
+    pub keep_going: bool,
+
+    /// Stop at the first failed run or script (the default; undoes an earlier --keep-going)
+    #[arg(long, overrides_with = "keep_going")]
+    pub fail_fast: bool,
+
+    /// Hold back output and, if the command fails, print only its last N lines per stream
+    #[arg(long, value_name = "N", visible_alias = "max-output-lines")]
+    pub tail_on_failure: Option<usize>,
+
+    /// When to use colors: auto (terminal only), always (even through pipes), never
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto
+    )]
+    pub color: ColorChoice,
+
+    /// Output format for command runs
+    #[arg(long, value_enum, default_value_t = OutputMode::Human)]
+    pub output: OutputMode,
+
     /// Force immediate update check
     #[arg(long)]
     pub update: bool,
 
+    /// Don't touch the network: no update checks and no `bazel query` (same as RUN_NO_UPDATE=1)
+    #[arg(long, visible_alias = "no-update", global = true)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub subcommand: Option<Commands>,
 }
@@ -67,16 +240,154 @@ pub struct Cli {
 pub enum Commands {
     /// Generate shell completions
     Completions {
-        /// Shell to generate completions for
+        /// Shell to generate completions for (defaults to $SHELL)
         #[arg(value_enum)]
-        shell: clap_complete::Shell,
+        shell: Option<clap_complete::Shell>,
+
+        /// Write the completions to the shell's standard location instead of stdout
+        #[arg(long)]
+        install: bool,
+
+        /// Overwrite an existing completion file when installing
+        #[arg(long, requires = "install")]
+        force: bool,
     },
     /// List available scripts/commands for the current project
-    List,
+    #[command(group(ArgGroup::new("survey").args(["all_workspaces", "recursive"])))]
+    List {
+        /// Float scripts relevant to files changed since this git ref
+        #[arg(long, value_name = "REF", value_parser = crate::changes::parse_git_ref)]
+        since: Option<String>,
+
+        /// List the scripts of every workspace package, grouped by package
+        #[arg(long)]
+        all_workspaces: bool,
+
+        /// List the scripts of this directory and those below it, grouped by path
+        #[arg(long)]
+        recursive: bool,
+
+        /// How many levels below the current directory --recursive looks
+        #[arg(long, value_name = "N", default_value_t = 2, requires = "recursive")]
+        depth: usize,
+
+        /// Only list scripts whose name or command contains this (case-insensitive)
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+
+        /// Only list scripts whose name or command matches this regex (e.g. '^test:')
+        #[arg(long, value_name = "REGEX", value_parser = crate::scripts::parse_filter_regex)]
+        filter_regex: Option<regex::Regex>,
+
+        /// List at most N scripts in total, across every section of the listing
+        #[arg(long, value_name = "N")]
+        head: Option<usize>,
+
+        /// Print the workspace or recursive listing as JSON keyed by package name or path
+        #[arg(long, requires = "survey")]
+        json: bool,
+    },
+    /// Run a tool through the detected project's runner (e.g. pnpm exec eslint)
+    Exec {
+        /// Tool to run
+        tool: String,
+
+        /// Arguments passed to the tool untouched
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+    /// Install dependencies the idiomatic way for the detected project (e.g. cargo fetch)
+    Install {
+        /// Arguments passed to the install command untouched
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+    /// Clean build output the ecosystem's way (cargo clean, go clean, make clean, ...)
+    Clean {
+        /// Arguments passed to the clean command untouched
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+    /// Print how scripts trigger each other (Makefile prerequisites, npm pre/post hooks)
+    Graph {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GraphFormat::Tree)]
+        format: GraphFormat,
+    },
+    /// Show, add or remove command aliases
+    Alias {
+        #[command(subcommand)]
+        action: AliasCommand,
+    },
+    /// Manage devrunner's cached state
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
     /// Show why a specific runner was selected
     Why,
     /// Diagnose project setup and detect issues
-    Doctor,
+    Doctor {
+        /// Print the checks as JSON, each with a status of ok, warn or fail
+        #[arg(long)]
+        json: bool,
+
+        /// Exit non-zero when any check warns or fails, for CI gates
+        #[arg(long, conflicts_with = "exit_on_fail")]
+        exit_on_warn: bool,
+
+        /// Exit non-zero when any check fails
+        #[arg(long)]
+        exit_on_fail: bool,
+    },
+    /// Create a .devrunner.toml for the current project
+    Init {
+        /// Overwrite an existing .devrunner.toml
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AliasCommand {
+    /// List configured aliases as `alias -> target`
+    List,
+    /// Add an alias (or replace an existing one)
+    Add {
+        /// Alias name, e.g. `t`
+        name: String,
+        /// Command the alias runs, e.g. `test`
+        target: String,
+        /// Edit the global config instead of .devrunner.toml
+        #[arg(long)]
+        global: bool,
+    },
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        name: String,
+        /// Edit the global config instead of .devrunner.toml
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheCommand {
+    /// Delete remembered runner choices and cached query results
+    Clear,
 }
 
 impl Cli {
@@ -86,10 +397,28 @@ impl Cli {
     }
 }
 
+/// Subcommand a mistyped command most likely meant, e.g. `doctr` -> `doctor`
+///
+/// An unknown word is a script name to clap, so this is only asked once the
+/// word turns out not to be a script (or the rest of the line fails to parse).
+pub fn suggest_subcommand(input: &str) -> Option<String> {
+    let names: Vec<String> = Cli::command()
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .flat_map(|sub| {
+            std::iter::once(sub.get_name().to_string())
+                .chain(sub.get_visible_aliases().map(str::to_string))
+        })
+        .collect();
+    if crate::fuzzy::is_exact_match(input, &names) {
+        return None;
+    }
+    crate::fuzzy::suggest_script(input, &names)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
 
     #[test]
     fn verify_cli() {
@@ -135,24 +464,37 @@ mod tests {
     #[test]
     fn test_levels() {
         let cli = Cli::parse_from(["devrunner", "test", "--levels=5"]);
-        assert_eq!(cli.levels, 5);
+        assert_eq!(cli.levels, SearchDepth::Levels(5));
+
+        let cli = Cli::parse_from(["devrunner", "test", "--levels=auto"]);
+        assert_eq!(cli.levels, SearchDepth::Auto);
+        assert!(Cli::try_parse_from(["devrunner", "test", "--levels=11"]).is_err());
     }
 
     #[test]
     fn test_default_levels() {
         let cli = Cli::parse_from(["devrunner", "test"]);
-        assert_eq!(cli.levels, 3);
+        assert_eq!(cli.levels, SearchDepth::Levels(3));
     }
 
     #[test]
     fn test_verbose_and_quiet() {
         let cli = Cli::parse_from(["devrunner", "test", "-v"]);
         assert!(cli.verbose);
-        assert!(!cli.quiet);
+        assert_eq!(cli.quiet, 0);
 
         let cli = Cli::parse_from(["devrunner", "test", "-q"]);
         assert!(!cli.verbose);
-        assert!(cli.quiet);
+        assert_eq!(cli.quiet, 1);
+    }
+
+    #[test]
+    fn test_quiet_levels() {
+        let cli = Cli::parse_from(["devrunner", "test", "-qq"]);
+        assert_eq!(cli.quiet, 2);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--quiet", "--quiet"]);
+        assert_eq!(cli.quiet, 2);
     }
 
     #[test]
@@ -160,4 +502,347 @@ mod tests {
         let cli = Cli::parse_from(["devrunner", "test", "--dry-run"]);
         assert!(cli.dry_run);
     }
+
+    #[test]
+    fn test_init_force() {
+        let cli = Cli::parse_from(["devrunner", "init", "--force"]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Init { force: true })
+        ));
+    }
+
+    #[test]
+    fn test_list_since() {
+        let cli = Cli::parse_from(["devrunner", "list", "--since", "main"]);
+        match cli.subcommand {
+            Some(Commands::List { since, .. }) => assert_eq!(since, Some("main".to_string())),
+            other => panic!("unexpected subcommand: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["devrunner", "list", "--since=--output=x"]).is_err());
+    }
+
+    #[test]
+    fn test_list_all_workspaces_json() {
+        let cli = Cli::parse_from(["devrunner", "list", "--all-workspaces", "--json"]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::List {
+                all_workspaces: true,
+                json: true,
+                ..
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["devrunner", "list", "--json"]).is_err());
+    }
+
+    #[test]
+    fn test_list_recursive() {
+        let cli = Cli::parse_from(["devrunner", "list", "--recursive", "--depth", "1", "--json"]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::List {
+                recursive: true,
+                depth: 1,
+                json: true,
+                ..
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["devrunner", "list", "--depth", "1"]).is_err());
+        assert!(
+            Cli::try_parse_from(["devrunner", "list", "--recursive", "--all-workspaces"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_list_grep_head() {
+        let cli = Cli::parse_from(["devrunner", "list", "--grep", "test", "--head", "5"]);
+        match cli.subcommand {
+            Some(Commands::List { grep, head, .. }) => {
+                assert_eq!(grep.as_deref(), Some("test"));
+                assert_eq!(head, Some(5));
+            }
+            other => panic!("unexpected subcommand: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_doctor_flags() {
+        let cli = Cli::parse_from(["devrunner", "doctor", "--json", "--exit-on-warn"]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Doctor {
+                json: true,
+                exit_on_warn: true,
+                exit_on_fail: false,
+            })
+        ));
+
+        assert!(
+            Cli::try_parse_from(["devrunner", "doctor", "--exit-on-warn", "--exit-on-fail"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_alias_add() {
+        let cli = Cli::parse_from(["devrunner", "alias", "add", "t", "test", "--global"]);
+        match cli.subcommand {
+            Some(Commands::Alias {
+                action:
+                    AliasCommand::Add {
+                        name,
+                        target,
+                        global,
+                    },
+            }) => {
+                assert_eq!(name, "t");
+                assert_eq!(target, "test");
+                assert!(global);
+            }
+            other => panic!("expected alias add, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_output_mode() {
+        let cli = Cli::parse_from(["devrunner", "test"]);
+        assert_eq!(cli.output, OutputMode::Human);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--output=json-stream"]);
+        assert_eq!(cli.output, OutputMode::JsonStream);
+    }
+
+    #[test]
+    fn test_color() {
+        let cli = Cli::parse_from(["devrunner", "test"]);
+        assert_eq!(cli.color, ColorChoice::Auto);
+
+        let cli = Cli::parse_from(["devrunner", "list", "--color=always"]);
+        assert_eq!(cli.color, ColorChoice::Always);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--color", "never"]);
+        assert_eq!(cli.color, ColorChoice::Never);
+    }
+
+    #[test]
+    fn test_explain_exit() {
+        let cli = Cli::parse_from(["devrunner", "test", "--explain-exit"]);
+        assert!(cli.explain_exit);
+    }
+
+    #[test]
+    fn test_install() {
+        let cli = Cli::parse_from(["devrunner", "install", "--", "--frozen-lockfile"]);
+        match cli.subcommand {
+            Some(Commands::Install { args }) => assert_eq!(args, vec!["--frozen-lockfile"]),
+            other => panic!("expected install subcommand, got {:?}", other),
+        }
+
+        // devrunner's own flags are read after the subcommand, not passed on
+        let cli = Cli::parse_from(["devrunner", "install", "--dry-run", "-q", "--", "--frozen"]);
+        assert!(cli.dry_run);
+        assert_eq!(cli.quiet, 1);
+        match cli.subcommand {
+            Some(Commands::Install { args }) => assert_eq!(args, vec!["--frozen"]),
+            other => panic!("expected install subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_exec() {
+        let cli = Cli::parse_from(["devrunner", "exec", "eslint", "--", "--fix", "."]);
+        match cli.subcommand {
+            Some(Commands::Exec { tool, args }) => {
+                assert_eq!(tool, "eslint");
+                assert_eq!(args, vec!["--fix", "."]);
+            }
+            other => panic!("expected exec subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_completions_install() {
+        let cli = Cli::parse_from(["devrunner", "completions", "--install", "--force"]);
+        match cli.subcommand {
+            Some(Commands::Completions {
+                shell,
+                install,
+                force,
+            }) => {
+                assert_eq!(shell, None);
+                assert!(install);
+                assert!(force);
+            }
+            other => panic!("expected completions subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env() {
+        let cli = Cli::parse_from(["devrunner", "build", "--env", "prod"]);
+        assert_eq!(cli.env, Some("prod".to_string()));
+    }
+
+    #[test]
+    fn test_args_file() {
+        let cli = Cli::parse_from(["devrunner", "--args-file", "e2e.args", "test", "signup"]);
+        assert_eq!(cli.args_file, Some(PathBuf::from("e2e.args")));
+        assert_eq!(cli.args, vec!["signup"]);
+    }
+
+    #[test]
+    fn test_clean_env() {
+        assert!(Cli::parse_from(["devrunner", "test", "--clean-env"]).clean_env);
+        assert!(!Cli::parse_from(["devrunner", "test"]).clean_env);
+    }
+
+    #[test]
+    fn test_select() {
+        let cli = Cli::parse_from([
+            "devrunner",
+            "--select",
+            "browser=chrome,firefox",
+            "--select=os=linux",
+            "test",
+        ]);
+        let names: Vec<&str> = cli.select.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["browser", "os"]);
+        assert_eq!(cli.select[0].values, ["chrome", "firefox"]);
+        assert!(Cli::try_parse_from(["devrunner", "--select", "browser", "test"]).is_err());
+    }
+
+    #[test]
+    fn test_env_from() {
+        let cli = Cli::parse_from([
+            "devrunner",
+            "deploy",
+            "--env-from",
+            "aws configure export-credentials --format env",
+            "--env-from=./secrets.sh",
+        ]);
+        assert_eq!(
+            cli.env_from,
+            vec![
+                "aws configure export-credentials --format env".to_string(),
+                "./secrets.sh".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dry_run_json() {
+        let cli = Cli::parse_from(["devrunner", "build", "--dry-run", "--json"]);
+        assert!(cli.dry_run && cli.json);
+
+        // --json on its own would silently run the command
+        assert!(Cli::try_parse_from(["devrunner", "build", "--json"]).is_err());
+
+        let cli = Cli::parse_from(["devrunner", "--list-ecosystems", "--json"]);
+        assert!(cli.list_ecosystems && cli.json);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--print-env", "--json"]);
+        assert!(cli.print_env && cli.json);
+
+        let cli = Cli::parse_from(["devrunner", "--prefer", "pnpm", "--version", "--json"]);
+        assert!(cli.version && cli.json);
+        assert_eq!(cli.prefer.as_deref(), Some("pnpm"));
+
+        // Once the command's args start, --version belongs to them
+        let cli = Cli::parse_from(["devrunner", "test", "unit", "--version", "--json"]);
+        assert!(!cli.version && !cli.json);
+        assert_eq!(cli.args, ["unit", "--version", "--json"]);
+    }
+
+    #[test]
+    fn test_confirm_and_yes() {
+        let cli = Cli::parse_from(["devrunner", "deploy", "-y"]);
+        assert!(cli.yes && !cli.confirm);
+
+        let cli = Cli::parse_from(["devrunner", "build", "--confirm"]);
+        assert!(cli.confirm);
+
+        assert!(Cli::try_parse_from(["devrunner", "deploy", "--confirm", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_in_container() {
+        assert!(!Cli::parse_from(["devrunner", "build"]).in_container);
+        assert!(Cli::parse_from(["devrunner", "build", "--in-container"]).in_container);
+    }
+
+    #[test]
+    fn test_if_present() {
+        let cli = Cli::parse_from(["devrunner", "lint", "--if-present"]);
+        assert!(cli.if_present);
+    }
+
+    #[test]
+    fn test_list_only() {
+        let cli = Cli::parse_from(["devrunner", "lint", "--list-only"]);
+        assert!(cli.list_only);
+
+        let cli = Cli::parse_from(["devrunner", "lint", "--check"]);
+        assert!(cli.list_only);
+    }
+
+    #[test]
+    fn test_ignore_scripts() {
+        let cli = Cli::parse_from(["devrunner", "build", "--ignore-scripts"]);
+        assert!(cli.ignore_scripts);
+    }
+
+    #[test]
+    fn test_no_venv() {
+        let cli = Cli::parse_from(["devrunner", "test", "--no-venv"]);
+        assert!(cli.no_venv);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let cli = Cli::parse_from(["devrunner", "test"]);
+        assert_eq!(cli.repeat, 1);
+        assert!(!cli.keep_going);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--repeat", "5", "--keep-going"]);
+        assert_eq!(cli.repeat, 5);
+        assert!(cli.keep_going);
+        assert!(Cli::try_parse_from(["devrunner", "test", "--repeat", "0"]).is_err());
+
+        // The later of --keep-going / --fail-fast wins
+        let cli = Cli::parse_from(["devrunner", "test:*", "--keep-going", "--fail-fast"]);
+        assert!(!cli.keep_going);
+    }
+
+    #[test]
+    fn test_tail_on_failure() {
+        let cli = Cli::parse_from(["devrunner", "test"]);
+        assert_eq!(cli.tail_on_failure, None);
+
+        let cli = Cli::parse_from(["devrunner", "test", "--tail-on-failure", "20"]);
+        assert_eq!(cli.tail_on_failure, Some(20));
+
+        let cli = Cli::parse_from(["devrunner", "test", "--max-output-lines=5"]);
+        assert_eq!(cli.tail_on_failure, Some(5));
+    }
+
+    #[test]
+    fn test_tee() {
+        let cli = Cli::parse_from(["devrunner", "test", "--tee", "build.log"]);
+        assert_eq!(cli.tee, Some(PathBuf::from("build.log")));
+    }
+
+    #[test]
+    fn test_suggest_subcommand() {
+        assert_eq!(suggest_subcommand("doctr").as_deref(), Some("doctor"));
+        assert_eq!(suggest_subcommand("lst").as_deref(), Some("list"));
+        assert_eq!(
+            suggest_subcommand("completons").as_deref(),
+            Some("completions")
+        );
+        assert_eq!(suggest_subcommand("doctor"), None);
+        assert_eq!(suggest_subcommand("build"), None);
+    }
 }
@@ -0,0 +1,111 @@
+//! Installing generated shell completions into each shell's conventional location
+
+use clap_complete::Shell;
+use std::path::{Path, PathBuf};
+
+/// Work out the user's shell from a `$SHELL` value like `/usr/bin/zsh`
+pub fn detect_shell(shell_env: Option<&str>) -> Option<Shell> {
+    let name = Path::new(shell_env?).file_name()?.to_str()?;
+    match name {
+        "bash" => Some(Shell::Bash),
+        "zsh" => Some(Shell::Zsh),
+        "fish" => Some(Shell::Fish),
+        "elvish" => Some(Shell::Elvish),
+        "pwsh" | "powershell" | "pwsh.exe" | "powershell.exe" => Some(Shell::PowerShell),
+        _ => None,
+    }
+}
+
+/// Where the completion script for `shell` should be written
+///
+/// `data_dir` is `$XDG_DATA_HOME` when set; bash-completion looks there first.
+pub fn install_path(shell: Shell, home: &Path, data_dir: Option<&Path>) -> PathBuf {
+    match shell {
+        Shell::Bash => data_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| home.join(".local").join("share"))
+            .join("bash-completion")
+            .join("completions")
+            .join("devrunner"),
+        Shell::Zsh => home.join(".zsh").join("completions").join("_devrunner"),
+        Shell::Fish => home
+            .join(".config")
+            .join("fish")
+            .join("completions")
+            .join("devrunner.fish"),
+        Shell::Elvish => home
+            .join(".config")
+            .join("elvish")
+            .join("lib")
+            .join("devrunner.elv"),
+        // PowerShell has no autoload directory, the script is dot-sourced from $PROFILE
+        _ => home
+            .join(".config")
+            .join("powershell")
+            .join("devrunner-completions.ps1"),
+    }
+}
+
+/// Extra setup the shell needs before it picks up the installed file
+pub fn activation_hint(shell: Shell, path: &Path) -> Option<String> {
+    let dir = path.parent()?.display();
+    match shell {
+        Shell::Zsh => Some(format!(
+            "Add to ~/.zshrc (before compinit):\n  fpath=({} $fpath)\n  autoload -Uz compinit && compinit",
+            dir
+        )),
+        Shell::Elvish => Some("Add to ~/.config/elvish/rc.elv:\n  use devrunner".to_string()),
+        Shell::PowerShell => Some(format!(
+            "Add to your $PROFILE:\n  . {}",
+            path.display()
+        )),
+        // bash-completion and fish load these directories automatically
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_shell() {
+        assert_eq!(detect_shell(Some("/bin/zsh")), Some(Shell::Zsh));
+        assert_eq!(detect_shell(Some("/usr/local/bin/fish")), Some(Shell::Fish));
+        assert_eq!(detect_shell(Some("bash")), Some(Shell::Bash));
+        assert_eq!(detect_shell(Some("/bin/tcsh")), None);
+        assert_eq!(detect_shell(None), None);
+    }
+
+    #[test]
+    fn test_install_path() {
+        let home = Path::new("/home/dev");
+        assert_eq!(
+            install_path(Shell::Zsh, home, None),
+            PathBuf::from("/home/dev/.zsh/completions/_devrunner")
+        );
+        assert_eq!(
+            install_path(Shell::Bash, home, None),
+            PathBuf::from("/home/dev/.local/share/bash-completion/completions/devrunner")
+        );
+        assert_eq!(
+            install_path(Shell::Bash, home, Some(Path::new("/data"))),
+            PathBuf::from("/data/bash-completion/completions/devrunner")
+        );
+        assert_eq!(
+            install_path(Shell::Fish, home, None),
+            PathBuf::from("/home/dev/.config/fish/completions/devrunner.fish")
+        );
+    }
+
+    #[test]
+    fn test_activation_hint() {
+        let zsh = install_path(Shell::Zsh, Path::new("/home/dev"), None);
+        assert!(activation_hint(Shell::Zsh, &zsh)
+            .unwrap()
+            .contains("fpath=(/home/dev/.zsh/completions $fpath)"));
+
+        let fish = install_path(Shell::Fish, Path::new("/home/dev"), None);
+        assert!(activation_hint(Shell::Fish, &fish).is_none());
+    }
+}
@@ -1,7 +1,26 @@
-use serde::{Deserialize, Serialize};
+use crate::detectors::{CustomRunner, Ecosystem, KNOWN_RUNNERS};
+use crate::runner::SearchDepth;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Parse a `--runner-path` value (`pnpm=/opt/pnpm/bin/pnpm`)
+pub fn parse_runner_path(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((name, path)) if !name.trim().is_empty() && !path.trim().is_empty() => {
+            Ok((name.trim().to_string(), PathBuf::from(path.trim())))
+        }
+        _ => Err(format!("expected RUNNER=PATH, got \"{}\"", value)),
+    }
+}
+
+/// Largest `update_interval_hours` accepted (100 years)
+pub const MAX_UPDATE_INTERVAL_HOURS: u64 = 24 * 365 * 100;
+
+/// Per-project configuration file name (loaded after `run.toml`)
+pub const PROJECT_CONFIG_FILE: &str = ".devrunner.toml";
 
 /// Configuration structure for the run CLI
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -13,14 +32,79 @@ pub struct Config {
     pub auto_update: Option<bool>,
     /// Tools to ignore during detection
     pub ignore_tools: Vec<String>,
+    /// Only detect these ecosystems (e.g., ["rust", "node.js"]); unset means all
+    pub enabled_ecosystems: Option<Vec<String>>,
+    /// Never detect these ecosystems
+    pub disabled_ecosystems: Vec<String>,
     /// Enable verbose output
     pub verbose: Option<bool>,
-    /// Enable quiet mode
-    pub quiet: Option<bool>,
+    /// Quiet level (same as -q/-qq); `true` means 1
+    #[serde(deserialize_with = "deserialize_quiet")]
+    pub quiet: Option<u8>,
     /// Custom command aliases (e.g., "t" -> "test")
     pub aliases: HashMap<String, String>,
     /// Show execution time after command completes
     pub show_timing: Option<bool>,
+    /// Only show the timing line for runs slower than this (e.g. "2s", "500ms")
+    pub timings_threshold: Option<String>,
+    /// Explain non-zero exit codes after a command fails
+    pub explain_exit: Option<bool>,
+    /// Always behave as if --ignore-scripts was passed
+    pub safe_mode: Option<bool>,
+    /// Run Node.js scripts under the `.nvmrc` version via fnm, nvm or Volta
+    pub use_node_version_manager: Option<bool>,
+    /// Remember the runner `--prefer` picked in a lockfile conflict, per directory
+    pub remember_choices: Option<bool>,
+    /// Minimum hours between background update checks
+    pub update_interval_hours: Option<u64>,
+    /// Have `list`/`doctor` run `bazel query '//...'` to list real Bazel targets
+    pub bazel_query: Option<bool>,
+    /// Most targets `bazel_query` lists (default 200)
+    pub bazel_max_targets: Option<usize>,
+    /// Most threads script discovery runs its parsers on (default: one per CPU)
+    pub discovery_threads: Option<usize>,
+    /// Runner to use when several are detected (e.g., "pnpm")
+    pub preferred_runner: Option<String>,
+    /// Script to run when `devrunner` is invoked without a command (e.g., "dev")
+    pub default_command: Option<String>,
+    /// Environment used when --env isn't given (e.g., "dev" picks build:dev)
+    pub default_env: Option<String>,
+    /// Extra script name globs that need confirmation (beyond deploy/release/publish/clean)
+    pub confirm_patterns: Vec<String>,
+    /// Variables `--clean-env` passes through besides PATH and HOME (e.g., ["TERM", "SSH_AUTH_SOCK"])
+    pub clean_env_allowlist: Vec<String>,
+    /// Directories containing any of these are project roots the search won't leave
+    pub root_markers: Option<Vec<String>>,
+    /// Script name -> directory (relative to the project root) it runs in
+    pub script_dirs: HashMap<String, String>,
+    /// Runner or ecosystem name -> args put before the user's (e.g., cargo = ["--offline"])
+    pub default_args: HashMap<String, Vec<String>>,
+    /// Runner name -> absolute path of the binary to run (e.g., pnpm = "/opt/pnpm/bin/pnpm")
+    pub runner_paths: HashMap<String, PathBuf>,
+    /// Priority overrides per runner (e.g., make = 5), lower wins
+    pub priorities: HashMap<String, u8>,
+    /// In-house runners defined via `[[custom_runners]]`
+    pub custom_runners: Vec<CustomRunner>,
+}
+
+/// Accept `quiet = true` from older configs as well as `quiet = 2`
+fn deserialize_quiet<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Quiet {
+        Flag(bool),
+        Level(u8),
+    }
+
+    Ok(
+        Option::<Quiet>::deserialize(deserializer)?.map(|quiet| match quiet {
+            Quiet::Flag(flag) => u8::from(flag),
+            Quiet::Level(level) => level,
+        }),
+    )
 }
 
 impl Config {
@@ -28,6 +112,7 @@ impl Config {
     /// 1. Defaults (hardcoded)
     /// 2. Global config (~/.config/run/config.toml)
     /// 3. Local config (./run.toml)
+    /// 4. Project config (the nearest .devrunner.toml, see [`Config::project_config_path`])
     pub fn load() -> Self {
         let mut config = Config::default();
 
@@ -44,9 +129,44 @@ impl Config {
             config = config.merge(local_config);
         }
 
+        // Load project config
+        if let Ok(project_config) = Self::load_from_file(&Self::project_config_path()) {
+            config = config.merge(project_config);
+        }
+
         config
     }
 
+    /// The project config in effect: the nearest .devrunner.toml at or above the
+    /// current directory, else ./.devrunner.toml
+    pub fn project_config_path() -> PathBuf {
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| Self::find_project_config(&dir))
+            .unwrap_or_else(|| PathBuf::from(PROJECT_CONFIG_FILE))
+    }
+
+    /// Find .devrunner.toml in `start_dir` or a parent
+    ///
+    /// `init` writes it to the detected project root, which may be above the
+    /// current directory. Like the runner search, the walk stops at a version
+    /// control root and after [`SearchDepth::AUTO_MAX_LEVELS`] levels.
+    pub fn find_project_config(start_dir: &Path) -> Option<PathBuf> {
+        let default_markers = Config::default().get_root_markers();
+        start_dir
+            .ancestors()
+            .take(usize::from(SearchDepth::AUTO_MAX_LEVELS) + 1)
+            .scan(false, |past_root, dir| {
+                if *past_root {
+                    return None;
+                }
+                *past_root = default_markers.iter().any(|m| dir.join(m).exists());
+                Some(dir)
+            })
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
     /// Get the path to the global configuration file
     pub fn global_config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join("run").join("config.toml"))
@@ -57,6 +177,41 @@ impl Config {
         dirs::config_dir().map(|p| p.join("run").join("update.json"))
     }
 
+    /// Get the path to the lock held while a background update runs
+    pub fn update_lock_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("run").join("update.lock"))
+    }
+
+    /// Get the path to the cached latest-release response
+    pub fn release_cache_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("run").join("latest_release.json"))
+    }
+
+    /// Get the path to the cached `bazel query` output for a workspace
+    pub fn bazel_query_cache_path(project_dir: &Path) -> Option<PathBuf> {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        project_dir.hash(&mut hasher);
+        let file = format!("{:016x}.txt", hasher.finish());
+        Self::bazel_query_cache_dir().map(|dir| dir.join(file))
+    }
+
+    /// Get the directory holding every workspace's cached `bazel query` output
+    pub fn bazel_query_cache_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("run").join("bazel"))
+    }
+
+    /// Get the path to the runners remembered per directory (`remember_choices`)
+    pub fn choices_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("run").join("choices.json"))
+    }
+
+    /// Get the path to the timestamp of the last background update check
+    pub fn last_update_check_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|p| p.join("run").join("last_check"))
+    }
+
     /// Load configuration from a specific file
     pub fn load_from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
@@ -69,7 +224,19 @@ impl Config {
         // Merge aliases, with other taking precedence for conflicts
         let mut merged_aliases = self.aliases;
         merged_aliases.extend(other.aliases);
-        
+        let mut merged_script_dirs = self.script_dirs;
+        merged_script_dirs.extend(other.script_dirs);
+        let mut merged_default_args = self.default_args;
+        merged_default_args.extend(other.default_args);
+        let mut merged_runner_paths = self.runner_paths;
+        merged_runner_paths.extend(other.runner_paths);
+        let mut merged_priorities = self.priorities;
+        merged_priorities.extend(other.priorities);
+        // Custom runners accumulate, a later definition replaces one with the same name
+        let mut merged_custom_runners = self.custom_runners;
+        merged_custom_runners.retain(|r| !other.custom_runners.iter().any(|o| o.name == r.name));
+        merged_custom_runners.extend(other.custom_runners);
+
         Config {
             max_levels: other.max_levels.or(self.max_levels),
             auto_update: other.auto_update.or(self.auto_update),
@@ -78,10 +245,46 @@ impl Config {
             } else {
                 other.ignore_tools
             },
+            enabled_ecosystems: other.enabled_ecosystems.or(self.enabled_ecosystems),
+            disabled_ecosystems: if other.disabled_ecosystems.is_empty() {
+                self.disabled_ecosystems
+            } else {
+                other.disabled_ecosystems
+            },
             verbose: other.verbose.or(self.verbose),
             quiet: other.quiet.or(self.quiet),
             aliases: merged_aliases,
             show_timing: other.show_timing.or(self.show_timing),
+            timings_threshold: other.timings_threshold.or(self.timings_threshold),
+            explain_exit: other.explain_exit.or(self.explain_exit),
+            safe_mode: other.safe_mode.or(self.safe_mode),
+            use_node_version_manager: other
+                .use_node_version_manager
+                .or(self.use_node_version_manager),
+            remember_choices: other.remember_choices.or(self.remember_choices),
+            update_interval_hours: other.update_interval_hours.or(self.update_interval_hours),
+            bazel_query: other.bazel_query.or(self.bazel_query),
+            bazel_max_targets: other.bazel_max_targets.or(self.bazel_max_targets),
+            discovery_threads: other.discovery_threads.or(self.discovery_threads),
+            preferred_runner: other.preferred_runner.or(self.preferred_runner),
+            default_command: other.default_command.or(self.default_command),
+            default_env: other.default_env.or(self.default_env),
+            confirm_patterns: if other.confirm_patterns.is_empty() {
+                self.confirm_patterns
+            } else {
+                other.confirm_patterns
+            },
+            clean_env_allowlist: if other.clean_env_allowlist.is_empty() {
+                self.clean_env_allowlist
+            } else {
+                other.clean_env_allowlist
+            },
+            root_markers: other.root_markers.or(self.root_markers),
+            script_dirs: merged_script_dirs,
+            default_args: merged_default_args,
+            runner_paths: merged_runner_paths,
+            priorities: merged_priorities,
+            custom_runners: merged_custom_runners,
         }
     }
 
@@ -100,9 +303,9 @@ impl Config {
         self.verbose.unwrap_or(false)
     }
 
-    /// Get quiet setting with default fallback
-    pub fn get_quiet(&self) -> bool {
-        self.quiet.unwrap_or(false)
+    /// Get quiet level with default fallback
+    pub fn get_quiet(&self) -> u8 {
+        self.quiet.unwrap_or(0)
     }
 
     /// Get show timing setting with default fallback
@@ -110,6 +313,71 @@ impl Config {
         self.show_timing.unwrap_or(false)
     }
 
+    /// Get the timing threshold (default 0: always show the timing line)
+    pub fn get_timings_threshold(&self) -> Result<Duration, String> {
+        match &self.timings_threshold {
+            Some(value) => crate::output::parse_duration(value),
+            None => Ok(Duration::ZERO),
+        }
+    }
+
+    /// Get explain exit setting with default fallback
+    pub fn get_explain_exit(&self) -> bool {
+        self.explain_exit.unwrap_or(false)
+    }
+
+    /// Whether Node.js scripts run through a Node version manager (default false)
+    pub fn get_use_node_version_manager(&self) -> bool {
+        self.use_node_version_manager.unwrap_or(false)
+    }
+
+    /// Whether conflict resolutions are remembered per directory (default false)
+    pub fn get_remember_choices(&self) -> bool {
+        self.remember_choices.unwrap_or(false)
+    }
+
+    /// Get the project root markers, defaulting to version control directories
+    pub fn get_root_markers(&self) -> Vec<String> {
+        self.root_markers
+            .clone()
+            .unwrap_or_else(|| vec![".git".to_string(), ".hg".to_string(), ".svn".to_string()])
+    }
+
+    /// Get safe mode setting with default fallback
+    pub fn get_safe_mode(&self) -> bool {
+        self.safe_mode.unwrap_or(false)
+    }
+
+    /// Get the background update interval (default 24), rejecting out-of-range values
+    pub fn get_update_interval_hours(&self) -> Result<u64, String> {
+        match self.update_interval_hours {
+            None => Ok(24),
+            Some(hours) if hours <= MAX_UPDATE_INTERVAL_HOURS => Ok(hours),
+            Some(hours) => Err(format!(
+                "{} is out of range (at most {})",
+                hours, MAX_UPDATE_INTERVAL_HOURS
+            )),
+        }
+    }
+
+    /// Most Bazel targets `list`/`doctor` query for, or None unless `bazel_query` is on
+    pub fn get_bazel_query_limit(&self) -> Option<usize> {
+        self.bazel_query
+            .unwrap_or(false)
+            .then(|| self.bazel_max_targets.unwrap_or(200))
+    }
+
+    /// Threads script discovery may use, defaulting to the available parallelism
+    pub fn get_discovery_threads(&self) -> usize {
+        self.discovery_threads
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+            .max(1)
+    }
+
     /// Resolve an alias to its actual command
     /// Returns the original command if no alias is found
     pub fn resolve_alias(&self, command: &str) -> String {
@@ -119,6 +387,273 @@ impl Config {
             .unwrap_or_else(|| command.to_string())
     }
 
+    /// Shell snippet for an alias whose target starts with `!` (e.g. `"!git clean -fdx"`)
+    /// Returns None for plain aliases and non-aliases, which resolve to scripts
+    pub fn shell_alias(&self, command: &str) -> Option<&str> {
+        self.aliases
+            .get(command)?
+            .strip_prefix('!')
+            .map(str::trim)
+            .filter(|snippet| !snippet.is_empty())
+    }
+
+    /// Command to run: the one given on the command line, else `default_command`
+    /// Returns None when neither is set, so the caller can fall back to help
+    pub fn resolve_command(&self, command: Option<&str>) -> Option<String> {
+        command
+            .or(self.default_command.as_deref())
+            .filter(|cmd| !cmd.trim().is_empty())
+            .map(|cmd| cmd.to_string())
+    }
+
+    /// Directory `[script_dirs]` says `script` runs in, resolved against the project root
+    ///
+    /// Returns None when the script has no override, and an error when the
+    /// configured directory is absolute, leaves the project root once `.` and
+    /// `..` are resolved, or doesn't exist.
+    pub fn script_dir(&self, script: &str, project_dir: &Path) -> Result<Option<PathBuf>, String> {
+        let Some(relative) = self.script_dirs.get(script) else {
+            return Ok(None);
+        };
+        if Path::new(relative).is_absolute() {
+            return Err(format!(
+                "[script_dirs] {} = \"{}\" must be relative to the project root",
+                script, relative
+            ));
+        }
+        let mut normalized = PathBuf::new();
+        for component in Path::new(relative).components() {
+            match component {
+                Component::Normal(part) => normalized.push(part),
+                Component::CurDir => {}
+                // Popping past the root would point the script outside the project
+                Component::ParentDir if normalized.pop() => {}
+                _ => {
+                    return Err(format!(
+                        "[script_dirs] {} = \"{}\" must stay inside the project root",
+                        script, relative
+                    ));
+                }
+            }
+        }
+        let dir = project_dir.join(normalized);
+        if !dir.is_dir() {
+            return Err(format!(
+                "[script_dirs] {} = \"{}\": {} is not a directory",
+                script,
+                relative,
+                dir.display()
+            ));
+        }
+        Ok(Some(dir))
+    }
+
+    /// `[runner_paths]` with `--runner-path` overrides applied on top
+    pub fn runner_paths_with(&self, overrides: &[(String, PathBuf)]) -> HashMap<String, PathBuf> {
+        let mut paths = self.runner_paths.clone();
+        paths.extend(overrides.iter().cloned());
+        paths
+    }
+
+    /// Ecosystems `enabled_ecosystems` allows; None when unset
+    pub fn get_enabled_ecosystems(&self) -> Option<Vec<Ecosystem>> {
+        self.enabled_ecosystems.as_ref().map(|names| {
+            names
+                .iter()
+                .filter_map(|n| Ecosystem::from_name(n))
+                .collect()
+        })
+    }
+
+    /// Ecosystems `disabled_ecosystems` turns off
+    pub fn get_disabled_ecosystems(&self) -> Vec<Ecosystem> {
+        self.disabled_ecosystems
+            .iter()
+            .filter_map(|n| Ecosystem::from_name(n))
+            .collect()
+    }
+
+    /// Names in `enabled_ecosystems`/`disabled_ecosystems` that aren't ecosystems
+    pub fn unknown_ecosystems(&self) -> Vec<&str> {
+        self.enabled_ecosystems
+            .iter()
+            .flatten()
+            .chain(&self.disabled_ecosystems)
+            .filter(|name| Ecosystem::from_name(name).is_none())
+            .map(|name| name.as_str())
+            .collect()
+    }
+
+    /// Runner names in the `[priorities]` table that no detector produces
+    pub fn unknown_priority_runners(&self) -> Vec<&str> {
+        let mut unknown: Vec<&str> = self
+            .priorities
+            .keys()
+            .filter(|name| !KNOWN_RUNNERS.iter().any(|k| k.eq_ignore_ascii_case(name)))
+            .filter(|name| {
+                !self
+                    .custom_runners
+                    .iter()
+                    .any(|r| r.name.eq_ignore_ascii_case(name))
+            })
+            .map(|name| name.as_str())
+            .collect();
+        unknown.sort();
+        unknown
+    }
+
+    /// Drop `[[custom_runners]]` entries that fail validation, returning why each was skipped
+    pub fn take_invalid_custom_runners(&mut self) -> Vec<String> {
+        let mut problems = Vec::new();
+        self.custom_runners
+            .retain(|runner| match runner.validate() {
+                Ok(()) => true,
+                Err(reason) => {
+                    problems.push(reason);
+                    false
+                }
+            });
+        problems
+    }
+
+    /// Render a commented project config for `devrunner init`
+    pub fn init_template(preferred_runner: Option<&str>) -> String {
+        let preferred = match preferred_runner {
+            Some(runner) => format!("preferred_runner = \"{}\"", runner),
+            None => "# preferred_runner = \"npm\"".to_string(),
+        };
+
+        format!(
+            r#"# devrunner project configuration
+# Values here override the global ~/.config/run/config.toml
+
+# Runner to use when several are detected in this project
+{}
+
+# Script to run when `devrunner` is called without a command
+# default_command = "dev"
+
+# Runners to skip during detection (e.g. ["npm", "yarn"])
+ignore_tools = []
+
+# Show execution time after each command
+# show_timing = true
+# timings_threshold = "2s"
+
+# Command aliases: `devrunner t` runs `devrunner test`
+[aliases]
+# t = "test"
+# b = "build"
+"#,
+            preferred
+        )
+    }
+
+    /// Check an alias can be added: a plain name that doesn't shadow a subcommand
+    pub fn validate_alias(name: &str, target: &str) -> Result<(), String> {
+        use clap::CommandFactory;
+
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+        {
+            return Err(format!("\"{}\" is not a valid alias name", name));
+        }
+        let command = crate::cli::Cli::command();
+        let reserved = command
+            .get_subcommands()
+            .flat_map(|sub| std::iter::once(sub.get_name()).chain(sub.get_all_aliases()))
+            .chain(["help"]);
+        if reserved.into_iter().any(|sub| sub == name) {
+            return Err(format!(
+                "\"{}\" is a built-in subcommand and can't be used as an alias",
+                name
+            ));
+        }
+        if target.trim().is_empty() {
+            return Err(format!("alias \"{}\" needs a target command", name));
+        }
+        if target.trim() == "!" {
+            return Err(format!(
+                "alias \"{}\" needs a shell command after `!`",
+                name
+            ));
+        }
+        if target == name {
+            return Err(format!("alias \"{}\" can't point to itself", name));
+        }
+        Ok(())
+    }
+
+    /// Add or replace `name = "target"` in the `[aliases]` table of a config file
+    ///
+    /// Edits the file as text so comments and layout survive; the file is created
+    /// if missing.
+    pub fn set_alias_in_file(
+        path: &Path,
+        name: &str,
+        target: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let entry = format!(
+            "{} = {}",
+            alias_key(name),
+            toml::Value::String(target.to_string())
+        );
+        let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+
+        match aliases_section(&lines) {
+            Some((start, end)) => {
+                match (start + 1..end)
+                    .find(|&i| alias_line_name(&lines[i]).as_deref() == Some(name))
+                {
+                    Some(i) => lines[i] = entry,
+                    None => {
+                        // After the last entry, so trailing blank lines stay between sections
+                        let insert_at = (start + 1..end)
+                            .rev()
+                            .find(|&i| alias_line_name(&lines[i]).is_some())
+                            .map_or(start + 1, |i| i + 1);
+                        lines.insert(insert_at, entry);
+                    }
+                }
+            }
+            None => {
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push("[aliases]".to_string());
+                lines.push(entry);
+            }
+        }
+
+        write_config_lines(path, &lines)
+    }
+
+    /// Remove an alias from a config file, returning whether it was there
+    pub fn remove_alias_from_file(
+        path: &Path,
+        name: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(false);
+        };
+        let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+        let Some((start, end)) = aliases_section(&lines) else {
+            return Ok(false);
+        };
+        let Some(i) =
+            (start + 1..end).find(|&i| alias_line_name(&lines[i]).as_deref() == Some(name))
+        else {
+            return Ok(false);
+        };
+
+        lines.remove(i);
+        write_config_lines(path, &lines)?;
+        Ok(true)
+    }
+
     /// Ensure config directory exists
     pub fn ensure_config_dir() -> std::io::Result<PathBuf> {
         if let Some(config_dir) = dirs::config_dir() {
@@ -134,18 +669,78 @@ impl Config {
     }
 }
 
+/// Line range of the `[aliases]` table: its header and the index where it ends
+fn aliases_section(lines: &[String]) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| l.trim() == "[aliases]")?;
+    let end = (start + 1..lines.len())
+        .find(|&i| lines[i].trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    Some((start, end))
+}
+
+/// Name defined by an `name = "target"` line, ignoring comments
+fn alias_line_name(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (key, _) = line.split_once('=')?;
+    Some(key.trim().trim_matches('"').to_string())
+}
+
+/// TOML key for an alias, quoted when it isn't a bare key (e.g. `"t:u"`)
+fn alias_key(name: &str) -> String {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// Write edited config lines back, refusing to leave an unparseable file behind
+fn write_config_lines(path: &Path, lines: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut content = lines.join("\n");
+    content.push('\n');
+    toml::from_str::<Config>(&content)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_parse_runner_path() {
+        assert_eq!(
+            parse_runner_path("pnpm=/opt/pnpm/bin/pnpm"),
+            Ok(("pnpm".to_string(), PathBuf::from("/opt/pnpm/bin/pnpm")))
+        );
+        assert!(parse_runner_path("pnpm").is_err());
+        assert!(parse_runner_path("=/opt/pnpm").is_err());
+
+        let config: Config =
+            toml::from_str("[runner_paths]\npnpm = \"/opt/pnpm\"\nyarn = \"/opt/yarn\"\n").unwrap();
+        let paths =
+            config.runner_paths_with(&[("pnpm".to_string(), PathBuf::from("/usr/bin/pnpm"))]);
+        assert_eq!(paths["pnpm"], PathBuf::from("/usr/bin/pnpm"));
+        assert_eq!(paths["yarn"], PathBuf::from("/opt/yarn"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
         assert_eq!(config.get_max_levels(), 3);
         assert!(config.get_auto_update());
         assert!(!config.get_verbose());
-        assert!(!config.get_quiet());
+        assert_eq!(config.get_quiet(), 0);
     }
 
     #[test]
@@ -154,20 +749,64 @@ mod tests {
             max_levels: Some(3),
             auto_update: Some(true),
             ignore_tools: vec!["npm".to_string()],
+            enabled_ecosystems: None,
+            disabled_ecosystems: Vec::new(),
             verbose: None,
             quiet: None,
             aliases: HashMap::new(),
             show_timing: None,
+            timings_threshold: None,
+            explain_exit: None,
+            safe_mode: None,
+            use_node_version_manager: None,
+            remember_choices: None,
+            update_interval_hours: None,
+            bazel_query: None,
+            bazel_max_targets: None,
+            discovery_threads: None,
+            preferred_runner: None,
+            default_command: None,
+            default_env: None,
+            confirm_patterns: Vec::new(),
+            clean_env_allowlist: vec!["TERM".to_string()],
+            root_markers: None,
+            script_dirs: HashMap::new(),
+            default_args: HashMap::new(),
+            runner_paths: HashMap::new(),
+            priorities: HashMap::new(),
+            custom_runners: Vec::new(),
         };
 
         let override_config = Config {
             max_levels: Some(5),
             auto_update: None,
             ignore_tools: vec!["yarn".to_string()],
+            enabled_ecosystems: None,
+            disabled_ecosystems: Vec::new(),
             verbose: Some(true),
             quiet: None,
             aliases: HashMap::new(),
             show_timing: None,
+            timings_threshold: None,
+            explain_exit: None,
+            safe_mode: None,
+            use_node_version_manager: None,
+            remember_choices: None,
+            update_interval_hours: None,
+            bazel_query: None,
+            bazel_max_targets: None,
+            discovery_threads: None,
+            preferred_runner: Some("pnpm".to_string()),
+            default_command: Some("dev".to_string()),
+            default_env: Some("prod".to_string()),
+            confirm_patterns: vec!["db:*".to_string()],
+            clean_env_allowlist: Vec::new(),
+            root_markers: Some(Vec::new()),
+            script_dirs: HashMap::new(),
+            default_args: HashMap::new(),
+            runner_paths: HashMap::new(),
+            priorities: HashMap::new(),
+            custom_runners: Vec::new(),
         };
 
         let merged = base.merge(override_config);
@@ -175,6 +814,111 @@ mod tests {
         assert!(merged.get_auto_update());
         assert_eq!(merged.ignore_tools, vec!["yarn".to_string()]);
         assert!(merged.get_verbose());
+        assert_eq!(merged.get_update_interval_hours(), Ok(24));
+        assert_eq!(merged.preferred_runner, Some("pnpm".to_string()));
+        assert_eq!(merged.default_command, Some("dev".to_string()));
+        assert_eq!(merged.default_env, Some("prod".to_string()));
+        assert_eq!(merged.confirm_patterns, vec!["db:*".to_string()]);
+        // An empty allowlist doesn't clear the one from a lower layer
+        assert_eq!(merged.clean_env_allowlist, vec!["TERM".to_string()]);
+        assert!(merged.get_root_markers().is_empty());
+    }
+
+    #[test]
+    fn test_update_interval_hours_range() {
+        let config: Config = toml::from_str("update_interval_hours = 1\n").unwrap();
+        assert_eq!(config.get_update_interval_hours(), Ok(1));
+        let config = Config {
+            update_interval_hours: Some(MAX_UPDATE_INTERVAL_HOURS),
+            ..Config::default()
+        };
+        assert!(config.get_update_interval_hours().is_ok());
+        let config = Config {
+            update_interval_hours: Some(u64::MAX),
+            ..Config::default()
+        };
+        assert!(config.get_update_interval_hours().is_err());
+    }
+
+    #[test]
+    fn test_discovery_threads() {
+        assert!(Config::default().get_discovery_threads() >= 1);
+        let config: Config = toml::from_str("discovery_threads = 1\n").unwrap();
+        assert_eq!(config.get_discovery_threads(), 1);
+        let config: Config = toml::from_str("discovery_threads = 0\n").unwrap();
+        assert_eq!(config.get_discovery_threads(), 1);
+    }
+
+    #[test]
+    fn test_shell_alias() {
+        let config: Config = toml::from_str(
+            "[aliases]\nreset = \"!git clean -fdx && npm ci\"\nt = \"test\"\nbare = \"!\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.shell_alias("reset"),
+            Some("git clean -fdx && npm ci")
+        );
+        assert_eq!(config.shell_alias("t"), None);
+        assert_eq!(config.resolve_alias("t"), "test");
+        assert_eq!(config.shell_alias("bare"), None);
+        assert_eq!(config.shell_alias("build"), None);
+        assert!(Config::validate_alias("bare", "!").is_err());
+    }
+
+    #[test]
+    fn test_resolve_command() {
+        let mut config = Config::default();
+        assert_eq!(config.resolve_command(None), None);
+        assert_eq!(
+            config.resolve_command(Some("test")),
+            Some("test".to_string())
+        );
+
+        config.default_command = Some("dev".to_string());
+        assert_eq!(config.resolve_command(None), Some("dev".to_string()));
+        assert_eq!(
+            config.resolve_command(Some("test")),
+            Some("test".to_string())
+        );
+
+        config.default_command = Some("  ".to_string());
+        assert_eq!(config.resolve_command(None), None);
+    }
+
+    #[test]
+    fn test_script_dir() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("db")).unwrap();
+        let config: Config =
+            toml::from_str("[script_dirs]\nmigrate = \"db\"\nseed = \"missing\"\n").unwrap();
+
+        assert_eq!(
+            config.script_dir("migrate", dir.path()),
+            Ok(Some(dir.path().join("db")))
+        );
+        assert_eq!(config.script_dir("test", dir.path()), Ok(None));
+        assert!(config
+            .script_dir("seed", dir.path())
+            .unwrap_err()
+            .contains("not a directory"));
+
+        // `..` may move around inside the project, but not out of it
+        let config: Config = toml::from_str(
+            "[script_dirs]\nmigrate = \"./src/../db\"\nup = \"..\"\nout = \"db/../../x\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.script_dir("migrate", dir.path()),
+            Ok(Some(dir.path().join("db")))
+        );
+        for script in ["up", "out"] {
+            assert!(config
+                .script_dir(script, dir.path())
+                .unwrap_err()
+                .contains("inside the project root"));
+        }
     }
 
     #[test]
@@ -200,6 +944,192 @@ verbose = true
         assert!(config.get_verbose());
     }
 
+    #[test]
+    fn test_find_project_config_walks_up_to_the_repo_root() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let sub = repo.join("packages/app");
+        fs::create_dir_all(&sub).unwrap();
+        fs::create_dir(repo.join(".git")).unwrap();
+        assert_eq!(Config::find_project_config(&sub), None);
+
+        // A config above the repository root belongs to some other project
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "").unwrap();
+        assert_eq!(Config::find_project_config(&sub), None);
+
+        fs::write(repo.join(PROJECT_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            Config::find_project_config(&sub),
+            Some(repo.join(PROJECT_CONFIG_FILE))
+        );
+        fs::write(sub.join(PROJECT_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            Config::find_project_config(&sub),
+            Some(sub.join(PROJECT_CONFIG_FILE))
+        );
+    }
+
+    #[test]
+    fn test_alias_add_remove_round_trip() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join(PROJECT_CONFIG_FILE);
+        fs::write(
+            &config_path,
+            "# project settings\nshow_timing = true\n\n[aliases]\n# t = \"test\"\nb = \"build\"\n\n[priorities]\nmake = 5\n",
+        )
+        .unwrap();
+
+        Config::set_alias_in_file(&config_path, "t", "test").unwrap();
+        Config::set_alias_in_file(&config_path, "b", "build:prod").unwrap();
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.resolve_alias("t"), "test");
+        assert_eq!(config.resolve_alias("b"), "build:prod");
+        assert_eq!(config.priorities.get("make"), Some(&5));
+        assert!(fs::read_to_string(&config_path)
+            .unwrap()
+            .contains("# project settings"));
+
+        assert!(Config::remove_alias_from_file(&config_path, "t").unwrap());
+        assert!(!Config::remove_alias_from_file(&config_path, "t").unwrap());
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.resolve_alias("t"), "t");
+        assert_eq!(config.resolve_alias("b"), "build:prod");
+    }
+
+    #[test]
+    fn test_alias_add_creates_file() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("run").join("config.toml");
+
+        Config::set_alias_in_file(&config_path, "tu", "test:unit").unwrap();
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.resolve_alias("tu"), "test:unit");
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(Config::validate_alias("t", "test").is_ok());
+        assert!(Config::validate_alias("list", "test").is_err());
+        assert!(Config::validate_alias("doctor", "test").is_err());
+        assert!(Config::validate_alias("why", "test").is_err());
+        assert!(Config::validate_alias("t t", "test").is_err());
+        assert!(Config::validate_alias("t", " ").is_err());
+    }
+
+    #[test]
+    fn test_load_quiet_level() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        fs::write(&config_path, "quiet = true\n").unwrap();
+        assert_eq!(Config::load_from_file(&config_path).unwrap().get_quiet(), 1);
+
+        fs::write(&config_path, "quiet = false\n").unwrap();
+        assert_eq!(Config::load_from_file(&config_path).unwrap().get_quiet(), 0);
+
+        fs::write(&config_path, "quiet = 2\n").unwrap();
+        assert_eq!(Config::load_from_file(&config_path).unwrap().get_quiet(), 2);
+
+        fs::write(&config_path, "max_levels = 4\n").unwrap();
+        assert_eq!(Config::load_from_file(&config_path).unwrap().get_quiet(), 0);
+    }
+
+    #[test]
+    fn test_load_priorities() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"
+[priorities]
+make = 5
+not-a-runner = 1
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.priorities.get("make"), Some(&5));
+        assert_eq!(config.unknown_priority_runners(), vec!["not-a-runner"]);
+    }
+
+    #[test]
+    fn test_ecosystem_filters() {
+        let config: Config = toml::from_str(
+            "enabled_ecosystems = [\"rust\", \"Node.js\", \"cobol\"]\ndisabled_ecosystems = [\"nodejs\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_enabled_ecosystems(),
+            Some(vec![Ecosystem::Rust, Ecosystem::NodeJs])
+        );
+        assert_eq!(config.get_disabled_ecosystems(), vec![Ecosystem::NodeJs]);
+        assert_eq!(config.unknown_ecosystems(), vec!["cobol"]);
+
+        let config = Config::default();
+        assert_eq!(config.get_enabled_ecosystems(), None);
+        assert!(config.unknown_ecosystems().is_empty());
+    }
+
+    #[test]
+    fn test_load_custom_runners() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"
+[[custom_runners]]
+name = "acme"
+detect_file = "acme.build"
+priority = 2
+
+[custom_runners.scripts]
+test = "acme check --all"
+
+[[custom_runners]]
+name = "broken"
+detect_file = "broken.cfg"
+ecosystem = "not-an-ecosystem"
+"#,
+        )
+        .unwrap();
+
+        let mut config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.custom_runners.len(), 2);
+
+        let problems = config.take_invalid_custom_runners();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("broken"));
+        assert_eq!(config.custom_runners.len(), 1);
+
+        // Custom runners are detected in a directory containing their detect_file
+        let project = tempdir().unwrap();
+        fs::write(project.path().join("acme.build"), "").unwrap();
+        let options = crate::detectors::DetectOptions {
+            custom_runners: config.custom_runners.clone(),
+            ..Default::default()
+        };
+        let runners = crate::detectors::detect_all(project.path(), &options);
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "acme");
+        assert_eq!(runners[0].priority, 2);
+        assert_eq!(
+            runners[0].build_command("test", &[]),
+            vec!["acme", "check", "--all"]
+        );
+    }
+
+    #[test]
+    fn test_init_template_parses() {
+        let config: Config = toml::from_str(&Config::init_template(Some("pnpm"))).unwrap();
+        assert_eq!(config.preferred_runner, Some("pnpm".to_string()));
+        assert!(config.ignore_tools.is_empty());
+        assert!(config.aliases.is_empty());
+
+        let config: Config = toml::from_str(&Config::init_template(None)).unwrap();
+        assert_eq!(config.preferred_runner, None);
+    }
+
     #[test]
     fn test_invalid_toml() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,87 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Ansible projects
+/// Priority: 29 (after Make and Terraform)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    let ansible_cfg = dir.join("ansible.cfg");
+    let playbook_yml = dir.join("playbook.yml");
+    if ansible_cfg.exists() {
+        runners.push(
+            DetectedRunner::new("ansible-playbook", "ansible.cfg", Ecosystem::Infra, 29)
+                .with_evidence(dir, &["playbook.yml"]),
+        );
+    } else if playbook_yml.exists() {
+        runners.push(DetectedRunner::new(
+            "ansible-playbook",
+            "playbook.yml",
+            Ecosystem::Infra,
+            29,
+        ));
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new(
+    "ansible-playbook",
+    &["ansible.cfg", "playbook.yml"],
+    29,
+)];
+
+/// Detector for Ansible projects
+pub struct AnsibleDetector;
+
+impl Detector for AnsibleDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "ansible",
+            ecosystem: Ecosystem::Infra,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_ansible_cfg() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("ansible.cfg")).unwrap();
+        File::create(dir.path().join("playbook.yml")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "ansible-playbook");
+        assert_eq!(runners[0].detected_file(), "ansible.cfg");
+    }
+
+    #[test]
+    fn test_detect_playbook() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("playbook.yml")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), "playbook.yml");
+    }
+
+    #[test]
+    fn test_no_ansible() {
+        let dir = tempdir().unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Files marking a Bazel workspace root, bzlmod first
+pub const BAZEL_WORKSPACE_FILES: &[&str] = &["MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE"];
+
+/// Detect Bazel workspaces
+/// Priority: 25 (after Make, so a Makefile wrapping bazel still runs first)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    BAZEL_WORKSPACE_FILES
+        .iter()
+        .find(|file| dir.join(file).is_file())
+        .map(|file| {
+            vec![DetectedRunner::new("bazel", file, Ecosystem::Bazel, 25)
+                .with_evidence(dir, &[".bazelversion", ".bazelrc"])]
+        })
+        .unwrap_or_default()
+}
+
+/// Build the `bazel` command for a task
+///
+/// Target labels (`//app:server`) are built, `build`/`test`/`coverage` cover
+/// every target (`//...`), and anything else is a bazel command (`clean`).
+pub fn build_command(task: &str, extra_args: &[String]) -> Vec<String> {
+    let mut cmd = vec!["bazel".to_string()];
+    if task.starts_with("//") || task.starts_with('@') || task.starts_with(':') {
+        cmd.extend(["build".to_string(), task.to_string()]);
+    } else if matches!(task, "build" | "test" | "coverage") {
+        cmd.extend([task.to_string(), "//...".to_string()]);
+    } else {
+        cmd.push(task.to_string());
+    }
+    cmd.extend(extra_args.iter().cloned());
+    cmd
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("bazel", BAZEL_WORKSPACE_FILES, 25)];
+
+/// Detector for Bazel workspaces
+pub struct BazelDetector;
+
+impl Detector for BazelDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "bazel",
+            ecosystem: Ecosystem::Bazel,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_workspace_files() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("WORKSPACE")).unwrap();
+        File::create(dir.path().join("MODULE.bazel")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "bazel");
+        assert_eq!(runners[0].detected_file(), "MODULE.bazel");
+
+        // BUILD files alone are packages inside a workspace, not its root
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("BUILD.bazel")).unwrap();
+        fs::create_dir(dir.path().join("WORKSPACE.bazel")).unwrap();
+        assert!(detect(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_build_command() {
+        assert_eq!(build_command("build", &[]), vec!["bazel", "build", "//..."]);
+        assert_eq!(
+            build_command("//app:server", &["--config=ci".to_string()]),
+            vec!["bazel", "build", "//app:server", "--config=ci"]
+        );
+        assert_eq!(build_command("clean", &[]), vec!["bazel", "clean"]);
+    }
+}
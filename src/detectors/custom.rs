@@ -0,0 +1,251 @@
+use super::{DetectedRunner, Ecosystem};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Priority given to custom runners that don't set one (after every built-in)
+pub const DEFAULT_CUSTOM_PRIORITY: u8 = 30;
+
+/// A runner defined in config via `[[custom_runners]]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomRunner {
+    /// Runner name, also the program run for tasks without a script entry
+    pub name: String,
+    /// File (or glob, e.g. `*.acme`) whose presence marks a project
+    pub detect_file: String,
+    /// Ecosystem label (e.g. "Node.js", "Go"); defaults to "Custom"
+    #[serde(default)]
+    pub ecosystem: Option<String>,
+    /// Priority (lower = higher priority)
+    #[serde(default)]
+    pub priority: Option<u8>,
+    /// Script name -> command template (e.g. test = "acme test --all")
+    #[serde(default)]
+    pub scripts: HashMap<String, String>,
+}
+
+impl CustomRunner {
+    /// Check the entry is usable, returning a reason when it is not
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("missing `name`".to_string());
+        }
+        if self.detect_file.trim().is_empty() {
+            return Err(format!("\"{}\" has an empty `detect_file`", self.name));
+        }
+        if let Some(label) = &self.ecosystem {
+            if Ecosystem::from_name(label).is_none() {
+                return Err(format!(
+                    "\"{}\" has unknown ecosystem \"{}\"",
+                    self.name, label
+                ));
+            }
+        }
+        if let Some((script, _)) = self
+            .scripts
+            .iter()
+            .find(|(_, template)| split_command(template).is_empty())
+        {
+            return Err(format!(
+                "\"{}\" has an empty command for script \"{}\"",
+                self.name, script
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ecosystem this runner is grouped under
+    pub fn ecosystem(&self) -> Ecosystem {
+        self.ecosystem
+            .as_deref()
+            .and_then(Ecosystem::from_name)
+            .unwrap_or(Ecosystem::Custom)
+    }
+}
+
+/// Detect projects matching any of the configured custom runners
+/// Priority: from config (defaults to 30, after built-in detectors)
+pub fn detect(dir: &Path, custom_runners: &[CustomRunner]) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    for custom in custom_runners {
+        if let Some(file) = find_detect_file(dir, &custom.detect_file) {
+            let mut runner = DetectedRunner::new(
+                &custom.name,
+                &file,
+                custom.ecosystem(),
+                custom.priority.unwrap_or(DEFAULT_CUSTOM_PRIORITY),
+            );
+            runner.custom_scripts = custom.scripts.clone();
+            runners.push(runner);
+        }
+    }
+
+    runners
+}
+
+/// Resolve `detect_file` against the directory, returning the matched path
+fn find_detect_file(dir: &Path, pattern: &str) -> Option<String> {
+    if !pattern.contains(['*', '?']) {
+        return dir.join(pattern).exists().then(|| pattern.to_string());
+    }
+
+    // Only the last path component may contain wildcards
+    let (parent, file_pattern) = match pattern.rsplit_once('/') {
+        Some((parent, file)) => (Some(parent), file),
+        None => (None, pattern),
+    };
+    let search_dir = match parent {
+        Some(parent) => dir.join(parent),
+        None => dir.to_path_buf(),
+    };
+
+    let mut matches: Vec<String> = std::fs::read_dir(search_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| glob_match(file_pattern, name))
+        .collect();
+    matches.sort();
+
+    let name = matches.into_iter().next()?;
+    Some(match parent {
+        Some(parent) => format!("{}/{}", parent, name),
+        None => name,
+    })
+}
+
+/// Match a file name against a pattern supporting `*` and `?`
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, n));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Split a command template into argv, honoring single and double quotes
+pub fn split_command(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+
+    for c in template.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                in_token = true;
+            }
+            None if c.is_whitespace() => {
+                if in_token {
+                    args.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        args.push(current);
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    fn acme(detect_file: &str) -> CustomRunner {
+        CustomRunner {
+            name: "acme".to_string(),
+            detect_file: detect_file.to_string(),
+            ecosystem: None,
+            priority: Some(3),
+            scripts: HashMap::from([("test".to_string(), "acme check --all".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_detect_custom_runner() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("acme.build")).unwrap();
+
+        let runners = detect(dir.path(), &[acme("acme.build")]);
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "acme");
+        assert_eq!(runners[0].detected_file(), "acme.build");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Custom);
+        assert_eq!(runners[0].priority, 3);
+    }
+
+    #[test]
+    fn test_detect_custom_runner_glob() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("conf")).unwrap();
+        File::create(dir.path().join("conf").join("app.acme")).unwrap();
+
+        let runners = detect(dir.path(), &[acme("conf/*.acme")]);
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), "conf/app.acme");
+
+        assert!(detect(dir.path(), &[acme("*.acme")]).is_empty());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.acme", "app.acme"));
+        assert!(glob_match("build-?.cfg", "build-1.cfg"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("*.acme", "app.acme.bak"));
+        assert!(!glob_match("build-?.cfg", "build-10.cfg"));
+    }
+
+    #[test]
+    fn test_validate_custom_runner() {
+        assert!(acme("acme.build").validate().is_ok());
+        assert!(acme("").validate().is_err());
+
+        let mut runner = acme("acme.build");
+        runner.ecosystem = Some("go".to_string());
+        assert!(runner.validate().is_ok());
+        runner.ecosystem = Some("cobol".to_string());
+        assert!(runner.validate().is_err());
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(
+            split_command(r#"acme run "hello world" --name='x y'"#),
+            vec!["acme", "run", "hello world", "--name=x y"]
+        );
+        assert!(split_command("   ").is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::fs;
+use std::path::Path;
+
+/// Detect Dart and Flutter projects
+/// Priority: 22 (melos > flutter > dart)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // A melos workspace drives every package below it, so it replaces the root pubspec
+    if dir.join("melos.yaml").exists() {
+        runners.push(
+            DetectedRunner::new("melos", "melos.yaml", Ecosystem::Dart, 22)
+                .with_evidence(dir, &["pubspec.yaml"]),
+        );
+        return runners;
+    }
+
+    let pubspec = dir.join("pubspec.yaml");
+    if pubspec.exists() {
+        let content = fs::read_to_string(&pubspec).unwrap_or_default();
+        let name = if depends_on_flutter(&content) {
+            "flutter"
+        } else {
+            "dart"
+        };
+        runners.push(
+            DetectedRunner::new(name, "pubspec.yaml", Ecosystem::Dart, 22)
+                .with_evidence(dir, &["pubspec.lock"]),
+        );
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("melos", &["melos.yaml"], 22),
+    RunnerMeta::new("flutter", &["pubspec.yaml"], 22),
+    RunnerMeta::new("dart", &["pubspec.yaml"], 22),
+];
+
+/// Detector for Dart and Flutter projects
+pub struct DartDetector;
+
+impl Detector for DartDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "dart",
+            ecosystem: Ecosystem::Dart,
+            runners: RUNNERS,
+        }
+    }
+}
+
+/// Whether a pubspec lists the Flutter SDK (`flutter:` with `sdk: flutter`)
+fn depends_on_flutter(pubspec: &str) -> bool {
+    pubspec
+        .lines()
+        .any(|line| line.trim().replace(' ', "") == "sdk:flutter")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_dart() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("pubspec.yaml"),
+            "name: cli\ndependencies:\n  args: ^2.4.0\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "dart");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Dart);
+    }
+
+    #[test]
+    fn test_detect_flutter() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("pubspec.yaml"),
+            "name: app\ndependencies:\n  flutter:\n    sdk: flutter\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "flutter");
+    }
+
+    #[test]
+    fn test_detect_melos_preferred() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("pubspec.yaml")).unwrap();
+        File::create(dir.path().join("melos.yaml")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "melos");
+        assert_eq!(runners[0].detected_file(), "melos.yaml");
+    }
+}
@@ -0,0 +1,267 @@
+use crate::scripts::strip_jsonc;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Where the Dev Containers spec looks for the config, relative to the project root
+const CONFIG_FILES: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// A project's dev container, from `devcontainer.json`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevContainer {
+    /// Project root the config belongs to (mounted into the container)
+    pub root: PathBuf,
+    /// Config file relative to `root`
+    pub config_file: String,
+    /// Display name (`name`)
+    pub name: Option<String>,
+    /// Prebuilt image (`image`); unset for Dockerfile/compose based containers
+    pub image: Option<String>,
+    /// Path of the project inside the container (`workspaceFolder`)
+    pub workspace_folder: String,
+}
+
+impl DevContainer {
+    /// Label for messages: the configured name, else the image
+    pub fn label(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.image.as_deref())
+            .unwrap_or("dev container")
+    }
+
+    /// Wrap `cmd` to run in the container, from `working_dir` on the host
+    ///
+    /// Uses `devcontainer exec` when the CLI is available, otherwise a
+    /// throwaway `docker run` of the configured image with the project mounted,
+    /// attached (`-it`) when `tty` says devrunner's stdin and stdout are terminals.
+    pub fn wrap_command(
+        &self,
+        working_dir: &Path,
+        cmd: &[String],
+        has_devcontainer_cli: bool,
+        tty: bool,
+    ) -> Result<Vec<String>, String> {
+        let relative = working_dir
+            .strip_prefix(&self.root)
+            .unwrap_or(Path::new(""));
+        let container_dir = relative
+            .components()
+            .fold(self.workspace_folder.clone(), |dir, part| {
+                format!("{}/{}", dir, part.as_os_str().to_string_lossy())
+            });
+
+        let mut wrapped: Vec<String> = if has_devcontainer_cli {
+            let mut wrapped = vec![
+                "devcontainer".to_string(),
+                "exec".to_string(),
+                "--workspace-folder".to_string(),
+                self.root.display().to_string(),
+            ];
+            if !relative.as_os_str().is_empty() {
+                // devcontainer exec starts in the workspace folder
+                wrapped.extend(["sh".to_string(), "-c".to_string()]);
+                wrapped.push(format!(
+                    "cd {} && exec \"$@\"",
+                    crate::runner::shell_join(&[container_dir])
+                ));
+                wrapped.push("devrunner".to_string());
+            }
+            wrapped
+        } else if let Some(image) = &self.image {
+            let mut wrapped = vec!["docker".to_string(), "run".to_string(), "--rm".to_string()];
+            if tty {
+                wrapped.push("-it".to_string());
+            }
+            wrapped.extend([
+                "-v".to_string(),
+                format!("{}:{}", self.root.display(), self.workspace_folder),
+                "-w".to_string(),
+                container_dir,
+                image.clone(),
+            ]);
+            wrapped
+        } else {
+            return Err(format!(
+                "{} builds its container from a Dockerfile or compose file; install the devcontainer CLI to run in it",
+                self.config_file
+            ));
+        };
+        wrapped.extend(cmd.iter().cloned());
+        Ok(wrapped)
+    }
+
+    /// Pass the variables `keys` from docker's environment into a `docker run`
+    /// from [`wrap_command`](Self::wrap_command), which otherwise starts the
+    /// container with only the image's; `devcontainer exec` is left as it is
+    ///
+    /// `-e KEY` without a value keeps the values out of the printed command.
+    pub fn forward_env(wrapped: Vec<String>, keys: &[String]) -> Vec<String> {
+        if wrapped.len() < 3 || wrapped[..3] != ["docker", "run", "--rm"] {
+            return wrapped;
+        }
+        let mut forwarded = wrapped[..3].to_vec();
+        for key in keys {
+            forwarded.extend(["-e".to_string(), key.clone()]);
+        }
+        forwarded.extend(wrapped[3..].iter().cloned());
+        forwarded
+    }
+}
+
+/// Detect a dev container configured in the directory
+pub fn detect(dir: &Path) -> Option<DevContainer> {
+    CONFIG_FILES.iter().find_map(|file| {
+        let content = std::fs::read_to_string(dir.join(file)).ok()?;
+        parse(&content, dir, file)
+    })
+}
+
+/// Find the dev container for `dir`, checking its ancestors (e.g. a monorepo root)
+pub fn find(dir: &Path) -> Option<DevContainer> {
+    dir.ancestors().find_map(detect)
+}
+
+/// Parse a (JSONC) `devcontainer.json`
+pub fn parse(content: &str, root: &Path, config_file: &str) -> Option<DevContainer> {
+    let json: Value = serde_json::from_str(&strip_jsonc(content)).ok()?;
+    let field = |key: &str| json.get(key).and_then(Value::as_str).map(str::to_string);
+
+    // The spec's default mount point is /workspaces/<folder name>
+    let workspace_folder = field("workspaceFolder").unwrap_or_else(|| {
+        let folder = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("/workspaces/{}", folder)
+    });
+
+    Some(DevContainer {
+        root: root.to_path_buf(),
+        config_file: config_file.to_string(),
+        name: field("name"),
+        image: field("image"),
+        workspace_folder,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const CONFIG: &str = r#"{
+        // Created by the VS Code wizard
+        "name": "Rust dev",
+        "image": "mcr.microsoft.com/devcontainers/rust:1", /* pinned major */
+        "customizations": { "vscode": { "extensions": ["rust-lang.rust-analyzer",] } },
+    }"#;
+
+    fn argv(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_devcontainer() {
+        let container = parse(
+            CONFIG,
+            Path::new("/src/app"),
+            ".devcontainer/devcontainer.json",
+        )
+        .unwrap();
+        assert_eq!(container.name.as_deref(), Some("Rust dev"));
+        assert_eq!(
+            container.image.as_deref(),
+            Some("mcr.microsoft.com/devcontainers/rust:1")
+        );
+        assert_eq!(container.workspace_folder, "/workspaces/app");
+        assert_eq!(container.label(), "Rust dev");
+
+        let container = parse(
+            r#"{"build": {"dockerfile": "Dockerfile"}, "workspaceFolder": "/code"}"#,
+            Path::new("/src/app"),
+            ".devcontainer.json",
+        )
+        .unwrap();
+        assert_eq!(container.image, None);
+        assert_eq!(container.workspace_folder, "/code");
+        assert_eq!(container.label(), "dev container");
+
+        assert!(parse("not json", Path::new("/"), ".devcontainer.json").is_none());
+    }
+
+    #[test]
+    fn test_detect_devcontainer() {
+        let dir = tempdir().unwrap();
+        assert!(detect(dir.path()).is_none());
+
+        fs::create_dir(dir.path().join(".devcontainer")).unwrap();
+        fs::write(dir.path().join(".devcontainer/devcontainer.json"), CONFIG).unwrap();
+        let sub = dir.path().join("packages").join("api");
+        fs::create_dir_all(&sub).unwrap();
+
+        assert!(detect(&sub).is_none());
+        let container = find(&sub).unwrap();
+        assert_eq!(container.root, dir.path());
+        assert_eq!(container.config_file, ".devcontainer/devcontainer.json");
+    }
+
+    #[test]
+    fn test_wrap_command() {
+        let container = parse(CONFIG, Path::new("/src/app"), ".devcontainer.json").unwrap();
+        let cmd = argv(&["cargo", "build"]);
+
+        assert_eq!(
+            container
+                .wrap_command(Path::new("/src/app"), &cmd, true, true)
+                .unwrap(),
+            argv(&[
+                "devcontainer",
+                "exec",
+                "--workspace-folder",
+                "/src/app",
+                "cargo",
+                "build"
+            ])
+        );
+        let wrapped = container
+            .wrap_command(Path::new("/src/app/crates/core"), &cmd, false, false)
+            .unwrap();
+        assert_eq!(
+            wrapped,
+            argv(&[
+                "docker",
+                "run",
+                "--rm",
+                "-v",
+                "/src/app:/workspaces/app",
+                "-w",
+                "/workspaces/app/crates/core",
+                "mcr.microsoft.com/devcontainers/rust:1",
+                "cargo",
+                "build"
+            ])
+        );
+        assert_eq!(
+            DevContainer::forward_env(wrapped, &argv(&["API_TOKEN"]))[..6],
+            argv(&["docker", "run", "--rm", "-e", "API_TOKEN", "-v"])
+        );
+        assert_eq!(
+            container
+                .wrap_command(Path::new("/src/app"), &cmd, false, true)
+                .unwrap()[..4],
+            argv(&["docker", "run", "--rm", "-it"])
+        );
+
+        let built = parse(
+            r#"{"build": {}}"#,
+            Path::new("/src/app"),
+            ".devcontainer.json",
+        )
+        .unwrap();
+        assert!(built
+            .wrap_command(Path::new("/src/app"), &cmd, false, false)
+            .unwrap_err()
+            .contains("install the devcontainer CLI"));
+    }
+}
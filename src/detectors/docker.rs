@@ -0,0 +1,67 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Dockerfile-based projects
+/// Priority: 31 (lowest built-in, only used when nothing else builds the repo)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // An Earthfile drives the container build itself (and Earthly needs docker
+    // installed), so reporting both would always look like a conflict
+    if dir.join("Dockerfile").exists() && !dir.join("Earthfile").exists() {
+        runners.push(
+            DetectedRunner::new("docker", "Dockerfile", Ecosystem::Container, 31)
+                .with_evidence(dir, &[".dockerignore"]),
+        );
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("docker", &["Dockerfile"], 31)];
+
+/// Detector for Dockerfile-based projects
+pub struct DockerDetector;
+
+impl Detector for DockerDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "docker",
+            ecosystem: Ecosystem::Container,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_dockerfile() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Dockerfile")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "docker");
+        assert_eq!(runners[0].priority, 31);
+    }
+
+    #[test]
+    fn test_earthfile_takes_over_dockerfile() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Dockerfile")).unwrap();
+        File::create(dir.path().join("Earthfile")).unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect .NET projects
@@ -28,6 +28,26 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("dotnet", &["*.csproj", "*.sln"], 17)];
+
+/// Detector for .NET projects
+pub struct DotNetDetector;
+
+impl Detector for DotNetDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "dotnet",
+            ecosystem: Ecosystem::DotNet,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,7 +62,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "dotnet");
-        assert_eq!(runners[0].detected_file, "MyApp.csproj");
+        assert_eq!(runners[0].detected_file(), "MyApp.csproj");
     }
 
     #[test]
@@ -53,7 +73,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "dotnet");
-        assert_eq!(runners[0].detected_file, "MySolution.sln");
+        assert_eq!(runners[0].detected_file(), "MySolution.sln");
     }
 
     #[test]
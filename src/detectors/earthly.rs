@@ -0,0 +1,65 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Earthly projects
+/// Priority: 30 (containers sit below every language runner and infra tool)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    if dir.join("Earthfile").exists() {
+        runners.push(DetectedRunner::new(
+            "earthly",
+            "Earthfile",
+            Ecosystem::Container,
+            30,
+        ));
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("earthly", &["Earthfile"], 30)];
+
+/// Detector for Earthly projects
+pub struct EarthlyDetector;
+
+impl Detector for EarthlyDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "earthly",
+            ecosystem: Ecosystem::Container,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_earthfile() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Earthfile")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "earthly");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Container);
+    }
+
+    #[test]
+    fn test_no_earthfile() {
+        let dir = tempdir().unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Elixir projects (Mix)
@@ -10,12 +10,35 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
 
     // mix.exs is sufficient for detection (mix.lock is optional)
     if mix_exs.exists() {
-        runners.push(DetectedRunner::new("mix", "mix.exs", Ecosystem::Elixir, 18));
+        runners.push(
+            DetectedRunner::new("mix", "mix.exs", Ecosystem::Elixir, 18)
+                .with_evidence(dir, &["mix.lock"]),
+        );
     }
 
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("mix", &["mix.exs"], 18)];
+
+/// Detector for Elixir projects (Mix)
+pub struct ElixirDetector;
+
+impl Detector for ElixirDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "elixir",
+            ecosystem: Ecosystem::Elixir,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
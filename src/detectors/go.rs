@@ -1,4 +1,4 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Go task runners and Go modules
@@ -26,19 +26,127 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     }
 
     // Check for Go Modules (priority 12)
-    // go.mod is sufficient for detection (go.sum is optional)
-    let go_mod = dir.join("go.mod");
-    if go_mod.exists() {
-        runners.push(DetectedRunner::new("go", "go.mod", Ecosystem::Go, 12));
+    // A go.work workspace takes over from its root go.mod (go.sum is optional)
+    if dir.join("go.work").exists() {
+        runners.push(
+            DetectedRunner::new("go", "go.work", Ecosystem::Go, 12)
+                .with_evidence(dir, &["go.mod", "go.sum"]),
+        );
+    } else if dir.join("go.mod").exists() {
+        runners.push(
+            DetectedRunner::new("go", "go.mod", Ecosystem::Go, 12).with_evidence(dir, &["go.sum"]),
+        );
     }
 
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("task", &["Taskfile.yml", "Taskfile.yaml"], 11),
+    RunnerMeta::new("go", &["go.work", "go.mod"], 12),
+];
+
+/// Detector for Go task runners and Go modules
+pub struct GoDetector;
+
+impl Detector for GoDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "go",
+            ecosystem: Ecosystem::Go,
+            runners: RUNNERS,
+        }
+    }
+}
+
+/// Build the `go` command for a task
+///
+/// Common tasks expand to their usual package patterns (`go test ./...`);
+/// in a go.work workspace `./...` covers every module and `tidy` becomes
+/// `go work sync`. Anything else is passed to `go` as-is.
+pub fn build_command(task: &str, workspace: bool, extra_args: &[String]) -> Vec<String> {
+    let names_program = |arg: &str| arg.contains('/') || arg.ends_with(".go") || arg == ".";
+
+    // Paths and .go files are programs to run
+    if names_program(task) {
+        let mut cmd = vec!["go".to_string(), "run".to_string(), task.to_string()];
+        cmd.extend(extra_args.iter().cloned());
+        return cmd;
+    }
+
+    // `run`'s args belong to the program, after the package it runs
+    if task == "run" {
+        let mut cmd = vec!["go".to_string(), "run".to_string()];
+        if !extra_args.first().is_some_and(|arg| names_program(arg)) {
+            cmd.push(".".to_string());
+        }
+        cmd.extend(extra_args.iter().cloned());
+        return cmd;
+    }
+
+    let (mut cmd, pattern) = match task {
+        "build" | "test" | "vet" => (vec!["go".to_string(), task.to_string()], Some("./...")),
+        "tidy" if workspace => (
+            vec!["go".to_string(), "work".to_string(), "sync".to_string()],
+            None,
+        ),
+        "tidy" => (
+            vec!["go".to_string(), "mod".to_string(), "tidy".to_string()],
+            None,
+        ),
+        _ => (vec!["go".to_string(), task.to_string()], None),
+    };
+
+    // Flags go before the packages; explicit packages replace the default pattern
+    cmd.extend(extra_args.iter().cloned());
+    if let Some(pattern) = pattern {
+        if extra_args.iter().all(|arg| arg.starts_with('-')) {
+            cmd.push(pattern.to_string());
+        }
+    }
+    cmd
+}
+
+/// Module directories listed by `use` directives in go.work
+pub fn workspace_modules(dir: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(dir.join("go.work")) else {
+        return Vec::new();
+    };
+
+    let mut modules = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                modules.push(line.trim_matches('"').to_string());
+            }
+        } else if let Some(rest) = line
+            .strip_prefix("use")
+            .filter(|rest| rest.starts_with([' ', '\t', '(']))
+        {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                modules.push(rest.trim_matches('"').to_string());
+            }
+        }
+    }
+    modules
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use tempfile::tempdir;
 
     #[test]
@@ -49,7 +157,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "task");
-        assert_eq!(runners[0].detected_file, "Taskfile.yml");
+        assert_eq!(runners[0].detected_file(), "Taskfile.yml");
     }
 
     #[test]
@@ -60,7 +168,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "task");
-        assert_eq!(runners[0].detected_file, "Taskfile.yaml");
+        assert_eq!(runners[0].detected_file(), "Taskfile.yaml");
     }
 
     #[test]
@@ -84,6 +192,71 @@ mod tests {
         assert_eq!(runners[0].name, "go");
     }
 
+    #[test]
+    fn test_detect_go_work() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("go.mod")).unwrap();
+        fs::write(
+            dir.path().join("go.work"),
+            "go 1.22\n\nuse (\n\t.\n\t./api // service\n\t\"./tools\"\n)\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "go");
+        assert_eq!(runners[0].detected_file(), "go.work");
+        assert_eq!(workspace_modules(dir.path()), vec![".", "./api", "./tools"]);
+    }
+
+    #[test]
+    fn test_workspace_modules_single_use() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("go.work"),
+            "go 1.22\nuse ./svc\nuse ./lib\n",
+        )
+        .unwrap();
+        assert_eq!(workspace_modules(dir.path()), vec!["./svc", "./lib"]);
+        assert!(workspace_modules(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_build_command() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            build_command("build", false, &[]),
+            vec!["go", "build", "./..."]
+        );
+        assert_eq!(build_command("run", false, &[]), vec!["go", "run", "."]);
+        assert_eq!(
+            build_command("run", false, &args(&["--port", "8080"])),
+            vec!["go", "run", ".", "--port", "8080"]
+        );
+        assert_eq!(
+            build_command("run", false, &args(&["./cmd/api", "--port", "8080"])),
+            vec!["go", "run", "./cmd/api", "--port", "8080"]
+        );
+        assert_eq!(
+            build_command("run", false, &args(&["main.go", "-v"])),
+            vec!["go", "run", "main.go", "-v"]
+        );
+        assert_eq!(build_command("tidy", false, &[]), vec!["go", "mod", "tidy"]);
+        assert_eq!(build_command("tidy", true, &[]), vec!["go", "work", "sync"]);
+        assert_eq!(
+            build_command("test", true, &args(&["-race"])),
+            vec!["go", "test", "-race", "./..."]
+        );
+        assert_eq!(
+            build_command("test", false, &args(&["-v", "./pkg/db"])),
+            vec!["go", "test", "-v", "./pkg/db"]
+        );
+        assert_eq!(
+            build_command("generate", false, &[]),
+            vec!["go", "generate"]
+        );
+    }
+
     #[test]
     fn test_detect_both_taskfile_and_go() {
         let dir = tempdir().unwrap();
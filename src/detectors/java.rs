@@ -1,8 +1,43 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
+/// Kind of Gradle project, which decides the task set shown by `list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradleFlavor {
+    /// Plain JVM project (Java/Kotlin library or application)
+    Jvm,
+    /// Android application (has an `app/` module with an AndroidManifest.xml)
+    Android,
+}
+
+/// Determine whether a Gradle project is a plain JVM or an Android build
+///
+/// Android markers live in the `app/` module, so this descends one level.
+pub fn gradle_flavor(dir: &Path) -> GradleFlavor {
+    let app = dir.join("app");
+    let has_app_build = app.join("build.gradle").exists() || app.join("build.gradle.kts").exists();
+    let has_manifest = dir.join("AndroidManifest.xml").exists()
+        || app.join("AndroidManifest.xml").exists()
+        || app
+            .join("src")
+            .join("main")
+            .join("AndroidManifest.xml")
+            .exists();
+
+    if has_app_build && has_manifest {
+        GradleFlavor::Android
+    } else {
+        GradleFlavor::Jvm
+    }
+}
+
+/// Files next to a Gradle build script that confirm it (settings, the wrapper)
+const GRADLE_EVIDENCE: &[&str] = &["settings.gradle", "settings.gradle.kts", "gradlew"];
+
 /// Detect Java/JVM build tools
 /// Priority: Gradle (15) > Maven (16)
+///
+/// sbt and Mill share Maven's 16, so mixed JVM builds surface as a conflict.
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
@@ -10,34 +45,56 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let build_gradle = dir.join("build.gradle");
     let build_gradle_kts = dir.join("build.gradle.kts");
     if build_gradle.exists() {
-        runners.push(DetectedRunner::new(
-            "gradle",
-            "build.gradle",
-            Ecosystem::Java,
-            15,
-        ));
+        runners.push(
+            DetectedRunner::new("gradle", "build.gradle", Ecosystem::Java, 15)
+                .with_evidence(dir, GRADLE_EVIDENCE),
+        );
     } else if build_gradle_kts.exists() {
-        runners.push(DetectedRunner::new(
-            "gradle",
-            "build.gradle.kts",
-            Ecosystem::Java,
-            15,
-        ));
+        runners.push(
+            DetectedRunner::new("gradle", "build.gradle.kts", Ecosystem::Java, 15)
+                .with_evidence(dir, GRADLE_EVIDENCE),
+        );
     }
 
     // Check for Maven (priority 16)
     let pom_xml = dir.join("pom.xml");
     if pom_xml.exists() {
-        runners.push(DetectedRunner::new("maven", "pom.xml", Ecosystem::Java, 16));
+        runners.push(
+            DetectedRunner::new("maven", "pom.xml", Ecosystem::Java, 16)
+                .with_evidence(dir, &["mvnw"]),
+        );
     }
 
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("gradle", &["build.gradle", "build.gradle.kts"], 15),
+    RunnerMeta::new("maven", &["pom.xml"], 16),
+];
+
+/// Detector for Java/JVM build tools
+pub struct JavaDetector;
+
+impl Detector for JavaDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "java",
+            ecosystem: Ecosystem::Java,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use tempfile::tempdir;
 
     #[test]
@@ -48,7 +105,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "gradle");
-        assert_eq!(runners[0].detected_file, "build.gradle");
+        assert_eq!(runners[0].detected_file(), "build.gradle");
     }
 
     #[test]
@@ -59,7 +116,40 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "gradle");
-        assert_eq!(runners[0].detected_file, "build.gradle.kts");
+        assert_eq!(runners[0].detected_file(), "build.gradle.kts");
+    }
+
+    #[test]
+    fn test_gradle_flavor_jvm() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.gradle")).unwrap();
+
+        assert_eq!(gradle_flavor(dir.path()), GradleFlavor::Jvm);
+    }
+
+    #[test]
+    fn test_gradle_flavor_android() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.gradle.kts")).unwrap();
+        let src_main = dir.path().join("app").join("src").join("main");
+        fs::create_dir_all(&src_main).unwrap();
+        File::create(dir.path().join("app").join("build.gradle.kts")).unwrap();
+        File::create(src_main.join("AndroidManifest.xml")).unwrap();
+
+        assert_eq!(gradle_flavor(dir.path()), GradleFlavor::Android);
+        // Still detected as a regular Gradle runner
+        let runners = detect(dir.path());
+        assert_eq!(runners[0].name, "gradle");
+    }
+
+    #[test]
+    fn test_gradle_flavor_app_without_manifest() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.gradle")).unwrap();
+        fs::create_dir(dir.path().join("app")).unwrap();
+        File::create(dir.path().join("app").join("build.gradle")).unwrap();
+
+        assert_eq!(gradle_flavor(dir.path()), GradleFlavor::Jvm);
     }
 
     #[test]
@@ -1,8 +1,8 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Makefile projects
-/// Priority: 21 (last, as it's the most generic)
+/// Priority: 24 (last, as it's the most generic)
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
@@ -11,7 +11,7 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
                 if name == "Makefile" || name == "makefile" {
-                    runners.push(DetectedRunner::new("make", name, Ecosystem::Generic, 21));
+                    runners.push(DetectedRunner::new("make", name, Ecosystem::Generic, 24));
                     break;
                 }
             }
@@ -21,6 +21,26 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("make", &["Makefile", "makefile"], 24)];
+
+/// Detector for Makefile projects
+pub struct MakeDetector;
+
+impl Detector for MakeDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "make",
+            ecosystem: Ecosystem::Generic,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,7 +55,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "make");
-        assert_eq!(runners[0].detected_file, "Makefile");
+        assert_eq!(runners[0].detected_file(), "Makefile");
     }
 
     #[test]
@@ -46,7 +66,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "make");
-        assert_eq!(runners[0].detected_file, "makefile");
+        assert_eq!(runners[0].detected_file(), "makefile");
     }
 
     #[test]
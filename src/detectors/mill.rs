@@ -0,0 +1,101 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Mill builds (Scala/Java)
+/// Priority: 16, Maven's (see the java detector)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // The `mill` bootstrap script is checked in next to (or instead of) build.sc
+    if dir.join("build.sc").exists() {
+        runners.push(
+            DetectedRunner::new("mill", "build.sc", Ecosystem::Java, 16)
+                .with_evidence(dir, &["mill"]),
+        );
+    } else if dir.join("mill").is_file() {
+        runners.push(DetectedRunner::new("mill", "mill", Ecosystem::Java, 16));
+    }
+
+    runners
+}
+
+/// Build the `mill` command for a task, through the checked-in `./mill` when `wrapper`
+///
+/// Bare tasks run across every module (`test` -> `mill _.test`); anything
+/// already naming a module (`core.test`) or selector is passed through.
+pub fn build_command(task: &str, wrapper: bool, extra_args: &[String]) -> Vec<String> {
+    let target = if task.contains(['.', '_']) {
+        task.to_string()
+    } else {
+        format!("_.{}", task)
+    };
+    // Like gradlew, the wrapper pins the Mill version
+    let program = if wrapper { "./mill" } else { "mill" };
+    let mut cmd = vec![program.to_string(), target];
+    cmd.extend(extra_args.iter().cloned());
+    cmd
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("mill", &["build.sc", "mill"], 16)];
+
+/// Detector for Mill builds (Scala/Java)
+pub struct MillDetector;
+
+impl Detector for MillDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "mill",
+            ecosystem: Ecosystem::Java,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_build_sc() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.sc")).unwrap();
+        File::create(dir.path().join("mill")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "mill");
+        assert_eq!(runners[0].detected_file(), "build.sc");
+    }
+
+    #[test]
+    fn test_detect_mill_wrapper() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("mill")).unwrap();
+        assert_eq!(detect(dir.path())[0].detected_file(), "mill");
+
+        // A `mill/` directory is just a module, not the wrapper
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("mill")).unwrap();
+        assert!(detect(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_build_command() {
+        assert_eq!(
+            build_command("compile", false, &[]),
+            vec!["mill", "_.compile"]
+        );
+        assert_eq!(build_command("test", true, &[]), vec!["./mill", "_.test"]);
+        assert_eq!(
+            build_command("core.test", false, &["-j".to_string(), "4".to_string()]),
+            vec!["mill", "core.test", "-j", "4"]
+        );
+    }
+}
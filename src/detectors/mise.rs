@@ -0,0 +1,107 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Config files that may define a `[tasks]` table, in mise's lookup order
+pub const MISE_CONFIG_FILES: &[&str] = &["mise.toml", ".mise.toml"];
+
+/// Directory holding file-based mise tasks
+pub const MISE_TASKS_DIR: &str = ".config/mise/tasks";
+
+/// Detect mise task runner projects
+/// Priority: 23 (dedicated task runner, just ahead of Make)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // mise.toml is often only used to pin tool versions, so require a [tasks] table
+    let config = MISE_CONFIG_FILES
+        .iter()
+        .find(|file| has_tasks_table(&dir.join(file)));
+    if let Some(file) = config {
+        runners.push(
+            DetectedRunner::new("mise", file, Ecosystem::Mise, 23)
+                .with_evidence(dir, &[MISE_TASKS_DIR]),
+        );
+    } else if dir.join(MISE_TASKS_DIR).is_dir() {
+        runners.push(DetectedRunner::new(
+            "mise",
+            MISE_TASKS_DIR,
+            Ecosystem::Mise,
+            23,
+        ));
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new(
+    "mise",
+    &["mise.toml", ".mise.toml", MISE_TASKS_DIR],
+    23,
+)];
+
+/// Detector for mise task runner projects
+pub struct MiseDetector;
+
+impl Detector for MiseDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "mise",
+            ecosystem: Ecosystem::Mise,
+            runners: RUNNERS,
+        }
+    }
+}
+
+/// Check whether a mise config file defines any tasks
+fn has_tasks_table(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .is_some_and(|table| table.get("tasks").is_some_and(|t| t.is_table()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_mise_toml_with_tasks() {
+        let dir = tempdir().unwrap();
+        let mut file = File::create(dir.path().join("mise.toml")).unwrap();
+        file.write_all(b"[tasks.build]\nrun = \"cargo build\"\n")
+            .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "mise");
+        assert_eq!(runners[0].detected_file(), "mise.toml");
+    }
+
+    #[test]
+    fn test_ignore_mise_toml_without_tasks() {
+        let dir = tempdir().unwrap();
+        let mut file = File::create(dir.path().join(".mise.toml")).unwrap();
+        file.write_all(b"[tools]\nnode = \"20\"\n").unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+
+    #[test]
+    fn test_detect_mise_tasks_dir() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(MISE_TASKS_DIR)).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), MISE_TASKS_DIR);
+    }
+}
@@ -1,45 +1,211 @@
+mod ansible;
+mod bazel;
+mod custom;
+mod dart;
+mod devcontainer;
+mod docker;
 mod dotnet;
+mod earthly;
 mod elixir;
 mod go;
 mod java;
 mod make;
+mod mill;
+mod mise;
 mod node;
+mod node_manager;
+mod odin;
 mod php;
 mod python;
+mod registry;
 mod ruby;
 mod rust;
+mod sbt;
 mod swift;
+mod terraform;
+mod vlang;
+mod xcode;
 mod zig;
 
+pub use bazel::BAZEL_WORKSPACE_FILES;
+pub use custom::{glob_match, split_command, CustomRunner};
+pub use devcontainer::{find as find_devcontainer, DevContainer};
+pub use go::workspace_modules as go_workspace_modules;
+pub use java::{gradle_flavor, GradleFlavor};
+pub use mise::{MISE_CONFIG_FILES, MISE_TASKS_DIR};
+pub use node::is_bun_file_target;
+pub use node_manager::{nvmrc_version, pick_node_manager, NodeManager};
+pub use registry::{builtin_detectors, Detector, DetectorMeta, RunnerMeta};
+pub use ruby::bin_scripts as ruby_bin_scripts;
+pub use xcode::detect as detect_xcode;
+
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Names of all built-in runners
+pub const KNOWN_RUNNERS: &[&str] = &[
+    "bun",
+    "pnpm",
+    "yarn",
+    "npm",
+    "uv",
+    "poetry",
+    "pdm",
+    "hatch",
+    "pipenv",
+    "pip",
+    "cargo",
+    "composer",
+    "task",
+    "go",
+    "bundler",
+    "rake",
+    "binstubs",
+    "gradle",
+    "maven",
+    "sbt",
+    "mill",
+    "dotnet",
+    "mix",
+    "xcodebuild",
+    "swift",
+    "zig",
+    "melos",
+    "flutter",
+    "dart",
+    "mise",
+    "v",
+    "odin",
+    "bazel",
+    "make",
+    "terraform",
+    "tofu",
+    "ansible-playbook",
+    "earthly",
+    "docker",
+];
+
+/// Settings that adjust detection on top of the built-in rules
+#[derive(Debug, Clone, Default)]
+pub struct DetectOptions {
+    /// Runners to skip (case-insensitive)
+    pub ignore_list: Vec<String>,
+    /// Priority overrides keyed by runner name (lower = higher priority)
+    pub priorities: HashMap<String, u8>,
+    /// Runners defined in config, evaluated after the built-in detectors
+    pub custom_runners: Vec<CustomRunner>,
+    /// Files or directories (e.g. ".git") marking a project root the search won't leave
+    pub root_markers: Vec<String>,
+    /// Only these ecosystems are detected (`enabled_ecosystems`); None allows every one
+    pub enabled_ecosystems: Option<Vec<Ecosystem>>,
+    /// Ecosystems never detected (`disabled_ecosystems`), even when enabled
+    pub disabled_ecosystems: Vec<Ecosystem>,
+}
+
+impl DetectOptions {
+    /// Same options without the ignore list, for showing every candidate
+    pub fn without_ignores(&self) -> Self {
+        Self {
+            ignore_list: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// The first root marker present in `dir`, if it is a project root
+    pub fn root_marker_in(&self, dir: &Path) -> Option<&str> {
+        self.root_markers
+            .iter()
+            .find(|marker| dir.join(marker).exists())
+            .map(String::as_str)
+    }
+
+    /// Check if a runner should be ignored
+    pub fn is_ignored(&self, runner: &str) -> bool {
+        self.ignore_list
+            .iter()
+            .any(|i| i.eq_ignore_ascii_case(runner))
+    }
+
+    /// Whether detectors for `ecosystem` run at all
+    pub fn allows_ecosystem(&self, ecosystem: Ecosystem) -> bool {
+        self.enabled_ecosystems
+            .as_ref()
+            .is_none_or(|enabled| enabled.contains(&ecosystem))
+            && !self.disabled_ecosystems.contains(&ecosystem)
+    }
+}
 
 /// Represents a detected runner with its command and configuration
 #[derive(Debug, Clone, PartialEq)]
 pub struct DetectedRunner {
     /// Name of the runner (e.g., "pnpm", "cargo", "poetry")
     pub name: String,
-    /// The file that triggered detection
-    pub detected_file: String,
+    /// Files that triggered detection, the primary one first (see [`DetectedRunner::detected_file`])
+    pub detected_files: Vec<String>,
     /// The ecosystem this runner belongs to
     pub ecosystem: Ecosystem,
     /// Priority (lower = higher priority)
     pub priority: u8,
+    /// Script name -> command template, set for runners defined in config
+    pub custom_scripts: HashMap<String, String>,
+    /// Why the project pins this runner (e.g. ".tool-versions pins pnpm")
+    pub pinned_by: Option<String>,
 }
 
 impl DetectedRunner {
     pub fn new(name: &str, detected_file: &str, ecosystem: Ecosystem, priority: u8) -> Self {
         Self {
             name: name.to_string(),
-            detected_file: detected_file.to_string(),
+            detected_files: vec![detected_file.to_string()],
             ecosystem,
             priority,
+            custom_scripts: HashMap::new(),
+            pinned_by: None,
+        }
+    }
+
+    /// The file that triggered detection, e.g. "pnpm-lock.yaml"
+    pub fn detected_file(&self) -> &str {
+        self.detected_files.first().map_or("", String::as_str)
+    }
+
+    /// Add the `files` present in `dir` as further evidence, after the primary file
+    ///
+    /// e.g. pnpm found by `pnpm-lock.yaml` also lists `pnpm-workspace.yaml`.
+    pub fn with_evidence(mut self, dir: &Path, files: &[&str]) -> Self {
+        for file in files {
+            if dir.join(file).exists() && !self.detected_files.iter().any(|f| f == file) {
+                self.detected_files.push(file.to_string());
+            }
+        }
+        self
+    }
+
+    /// The program [`build_command`](Self::build_command) runs for built-in tasks,
+    /// where it isn't named after the runner (`python` for pip, `mvn` for maven)
+    pub fn program(&self) -> &str {
+        match self.name.as_str() {
+            "pip" => "python",
+            "bundler" => "bundle",
+            "maven" => "mvn",
+            name => name,
         }
     }
 
     /// Build the command to execute
     pub fn build_command(&self, task: &str, extra_args: &[String]) -> Vec<String> {
+        // Scripts configured for a custom runner replace the default mapping
+        if let Some(template) = self.custom_scripts.get(task) {
+            let mut cmd = split_command(template);
+            cmd.extend(extra_args.iter().cloned());
+            return cmd;
+        }
+
         let mut cmd = match self.name.as_str() {
             // Node.js ecosystem
+            // `bun ./script.ts` runs a file directly; names go through `bun run`
+            "bun" if node::is_bun_file_target(task) => vec!["bun".to_string(), task.to_string()],
             "bun" => vec!["bun".to_string(), "run".to_string(), task.to_string()],
             "pnpm" => vec!["pnpm".to_string(), "run".to_string(), task.to_string()],
             "yarn" => vec!["yarn".to_string(), "run".to_string(), task.to_string()],
@@ -48,11 +214,13 @@ impl DetectedRunner {
             // Python ecosystem
             "uv" => vec!["uv".to_string(), "run".to_string(), task.to_string()],
             "poetry" => vec!["poetry".to_string(), "run".to_string(), task.to_string()],
+            "pdm" => vec!["pdm".to_string(), "run".to_string(), task.to_string()],
+            "hatch" => vec!["hatch".to_string(), "run".to_string(), task.to_string()],
             "pipenv" => vec!["pipenv".to_string(), "run".to_string(), task.to_string()],
             "pip" => vec!["python".to_string(), "-m".to_string(), task.to_string()],
 
             // Rust ecosystem
-            "cargo" => vec!["cargo".to_string(), task.to_string()],
+            "cargo" => return rust::build_command(task, extra_args),
 
             // PHP ecosystem
             "composer" => vec!["composer".to_string(), "run".to_string(), task.to_string()],
@@ -60,21 +228,40 @@ impl DetectedRunner {
             // Go ecosystem
             "task" => vec!["task".to_string(), task.to_string()],
             "go" => {
-                // Check if task looks like a path (contains / or ends with .go)
-                if task.contains('/') || task.ends_with(".go") {
-                    vec!["go".to_string(), "run".to_string(), task.to_string()]
-                } else {
-                    vec!["go".to_string(), task.to_string()]
-                }
+                // Package patterns have to follow any flags, so go assembles its own args
+                return go::build_command(task, self.detected_file() == "go.work", extra_args);
             }
 
             // Ruby ecosystem
-            "bundler" => vec!["bundle".to_string(), "exec".to_string(), task.to_string()],
+            "bundler" => {
+                // A binstub wins over whatever shares its name on PATH: `setup` -> bin/setup
+                let binstub = format!("bin/{}", task);
+                let target = if self.detected_files.contains(&binstub) {
+                    binstub
+                } else {
+                    task.to_string()
+                };
+                vec!["bundle".to_string(), "exec".to_string(), target]
+            }
             "rake" => vec!["rake".to_string(), task.to_string()],
+            "binstubs" => vec![format!("bin/{}", task.trim_start_matches("bin/"))],
 
             // Java ecosystem
-            "gradle" => vec!["gradle".to_string(), task.to_string()],
+            "gradle" => {
+                // The project's wrapper pins the Gradle version, so it wins over a global gradle
+                let program = if self.detected_files.iter().any(|f| f == "gradlew") {
+                    "./gradlew"
+                } else {
+                    "gradle"
+                };
+                vec![program.to_string(), task.to_string()]
+            }
             "maven" => vec!["mvn".to_string(), task.to_string()],
+            "sbt" => vec!["sbt".to_string(), task.to_string()],
+            "mill" => {
+                let wrapper = self.detected_files.iter().any(|f| f == "mill");
+                return mill::build_command(task, wrapper, extra_args);
+            }
 
             // .NET ecosystem
             "dotnet" => vec!["dotnet".to_string(), task.to_string()],
@@ -82,22 +269,93 @@ impl DetectedRunner {
             // Elixir ecosystem
             "mix" => vec!["mix".to_string(), task.to_string()],
 
+            // Xcode ecosystem
+            "xcodebuild" => return xcode::build_command(self, task, extra_args),
+
             // Swift ecosystem
             "swift" => vec!["swift".to_string(), "run".to_string(), task.to_string()],
 
             // Zig ecosystem
             "zig" => vec!["zig".to_string(), "build".to_string(), task.to_string()],
 
+            // Dart ecosystem
+            "melos" => vec!["melos".to_string(), "run".to_string(), task.to_string()],
+            "flutter" => vec!["flutter".to_string(), task.to_string()],
+            "dart" => vec!["dart".to_string(), task.to_string()],
+
+            // mise task runner
+            "mise" => vec!["mise".to_string(), "run".to_string(), task.to_string()],
+
+            // V ecosystem: `v .` builds, run/test take the module directory
+            "v" => match task {
+                "build" => vec!["v".to_string(), ".".to_string()],
+                _ => vec!["v".to_string(), task.to_string(), ".".to_string()],
+            },
+
+            // Odin ecosystem
+            "odin" => vec!["odin".to_string(), task.to_string(), ".".to_string()],
+
+            // Bazel: labels are built, build/test cover `//...`
+            "bazel" => return bazel::build_command(task, extra_args),
+
             // Generic
             "make" => vec!["make".to_string(), task.to_string()],
 
+            // Infra ecosystem
+            "terraform" => vec!["terraform".to_string(), task.to_string()],
+            "tofu" => vec!["tofu".to_string(), task.to_string()],
+            "ansible-playbook" => {
+                // Tasks name a playbook, e.g. `site` -> `site.yml`
+                let playbook = if task.ends_with(".yml") || task.ends_with(".yaml") {
+                    task.to_string()
+                } else {
+                    format!("{}.yml", task)
+                };
+                vec!["ansible-playbook".to_string(), playbook]
+            }
+
+            // Container ecosystem
+            "earthly" => vec![
+                "earthly".to_string(),
+                format!("+{}", task.trim_start_matches('+')),
+            ],
+            "docker" => {
+                // `build` builds the whole image, any other task names a stage
+                let mut cmd = vec!["docker".to_string(), "build".to_string()];
+                if task != "build" {
+                    cmd.push("--target".to_string());
+                    cmd.push(task.to_string());
+                }
+                cmd.push(".".to_string());
+                cmd
+            }
+
             // Fallback
             _ => vec![self.name.clone(), task.to_string()],
         };
 
-        cmd.extend(extra_args.iter().cloned());
+        self.append_extra_args(&mut cmd, extra_args);
         cmd
     }
+
+    /// Append the user's trailing args (everything after `--`) to the command
+    ///
+    /// Args are passed verbatim, except where the tool would otherwise treat
+    /// them as its own flags:
+    /// - npm: `npm run build -- <args>` (npm parses flags before a second `--`)
+    /// - cargo: verbatim, so `devrunner test -- --release` reaches cargo and
+    ///   `devrunner test -- -- --nocapture` reaches the test binary
+    /// - everything else (bun, pnpm, yarn, uv, make, ...): verbatim
+    fn append_extra_args(&self, cmd: &mut Vec<String>, extra_args: &[String]) {
+        if extra_args.is_empty() {
+            return;
+        }
+        let already_separated = extra_args[0] == "--";
+        if self.name == "npm" && !already_separated {
+            cmd.push("--".to_string());
+        }
+        cmd.extend(extra_args.iter().cloned());
+    }
 }
 
 /// Ecosystem categories
@@ -112,9 +370,18 @@ pub enum Ecosystem {
     Java,
     DotNet,
     Elixir,
+    Xcode,
     Swift,
     Zig,
+    Dart,
+    Mise,
+    V,
+    Odin,
+    Bazel,
     Generic,
+    Infra,
+    Container,
+    Custom,
 }
 
 impl Ecosystem {
@@ -129,53 +396,189 @@ impl Ecosystem {
             Ecosystem::Java => "Java",
             Ecosystem::DotNet => ".NET",
             Ecosystem::Elixir => "Elixir",
+            Ecosystem::Xcode => "Xcode",
             Ecosystem::Swift => "Swift",
             Ecosystem::Zig => "Zig",
+            Ecosystem::Dart => "Dart",
+            Ecosystem::Mise => "mise",
+            Ecosystem::V => "V",
+            Ecosystem::Odin => "Odin",
+            Ecosystem::Bazel => "Bazel",
             Ecosystem::Generic => "Generic",
+            Ecosystem::Infra => "Infra",
+            Ecosystem::Container => "Container",
+            Ecosystem::Custom => "Custom",
         }
     }
+
+    /// Look up an ecosystem by its label (case-insensitive, e.g. "node.js" or "go")
+    pub fn from_name(name: &str) -> Option<Self> {
+        const ALL: &[Ecosystem] = &[
+            Ecosystem::NodeJs,
+            Ecosystem::Python,
+            Ecosystem::Rust,
+            Ecosystem::Php,
+            Ecosystem::Go,
+            Ecosystem::Ruby,
+            Ecosystem::Java,
+            Ecosystem::DotNet,
+            Ecosystem::Elixir,
+            Ecosystem::Xcode,
+            Ecosystem::Swift,
+            Ecosystem::Zig,
+            Ecosystem::Dart,
+            Ecosystem::Mise,
+            Ecosystem::V,
+            Ecosystem::Odin,
+            Ecosystem::Bazel,
+            Ecosystem::Generic,
+            Ecosystem::Infra,
+            Ecosystem::Container,
+            Ecosystem::Custom,
+        ];
+        ALL.iter().copied().find(|e| {
+            e.as_str().eq_ignore_ascii_case(name) || format!("{:?}", e).eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+/// Time spent in one detector, summed over every directory it ran in
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectorTiming {
+    /// Detector name, e.g. "node" (`custom` for `[[custom_runners]]`)
+    pub name: String,
+    pub elapsed: Duration,
+    /// Files of the runners it found, e.g. "Cargo.toml"
+    pub found: Vec<String>,
+}
+
+/// Per-detector timings collected by [`detect_all_profiled`] (`--profile-detection`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetectionProfile {
+    /// In registry order; see [`DetectionProfile::sorted`]
+    pub timings: Vec<DetectorTiming>,
+    /// Directories detection ran in
+    pub dirs_walked: usize,
+}
+
+impl DetectionProfile {
+    fn record(&mut self, name: &str, elapsed: Duration, found: &[DetectedRunner]) {
+        let timing = match self.timings.iter().position(|t| t.name == name) {
+            Some(index) => &mut self.timings[index],
+            None => {
+                self.timings.push(DetectorTiming {
+                    name: name.to_string(),
+                    elapsed: Duration::ZERO,
+                    found: Vec::new(),
+                });
+                self.timings.last_mut().unwrap()
+            }
+        };
+        timing.elapsed += elapsed;
+        timing
+            .found
+            .extend(found.iter().map(|r| r.detected_file().to_string()));
+    }
+
+    /// Time spent across all detectors
+    pub fn total(&self) -> Duration {
+        self.timings.iter().map(|t| t.elapsed).sum()
+    }
+
+    /// Timings, slowest first
+    pub fn sorted(&self) -> Vec<&DetectorTiming> {
+        let mut timings: Vec<&DetectorTiming> = self.timings.iter().collect();
+        timings.sort_by_key(|t| std::cmp::Reverse(t.elapsed));
+        timings
+    }
 }
 
 /// Detect all runners in the given directory
-pub fn detect_all(dir: &Path, ignore_list: &[String]) -> Vec<DetectedRunner> {
+pub fn detect_all(dir: &Path, options: &DetectOptions) -> Vec<DetectedRunner> {
+    detect_all_with(dir, options, None)
+}
+
+/// [`detect_all`], timing each detector into `profile`
+pub fn detect_all_profiled(
+    dir: &Path,
+    options: &DetectOptions,
+    profile: &mut DetectionProfile,
+) -> Vec<DetectedRunner> {
+    detect_all_with(dir, options, Some(profile))
+}
+
+fn detect_all_with(
+    dir: &Path,
+    options: &DetectOptions,
+    mut profile: Option<&mut DetectionProfile>,
+) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
+    if let Some(profile) = profile.as_deref_mut() {
+        profile.dirs_walked += 1;
+    }
 
-    // Helper to add runners if not ignored
-    let mut add_runners = |detected: Vec<DetectedRunner>| {
+    // Run one detector, timing it when profiling, and keep what isn't ignored
+    let mut run = |name: &str, detect: &dyn Fn() -> Vec<DetectedRunner>| {
+        let start = Instant::now();
+        let detected = detect();
+        if let Some(profile) = profile.as_deref_mut() {
+            profile.record(name, start.elapsed(), &detected);
+        }
         for runner in detected {
-            if !ignore_list
-                .iter()
-                .any(|i| i.eq_ignore_ascii_case(&runner.name))
-            {
+            if !options.is_ignored(&runner.name) {
                 runners.push(runner);
             }
         }
     };
 
-    // Run all detectors in priority order
-    add_runners(node::detect(dir)); // Node.js (1-4)
-    add_runners(python::detect(dir)); // Python (5-8)
-    add_runners(rust::detect(dir)); // Rust (9)
-    add_runners(php::detect(dir)); // PHP (10)
-    add_runners(go::detect(dir)); // Go (11-12)
-    add_runners(ruby::detect(dir)); // Ruby (13-14)
-    add_runners(java::detect(dir)); // Java (15-16)
-    add_runners(dotnet::detect(dir)); // .NET (17)
-    add_runners(elixir::detect(dir)); // Elixir (18)
-    add_runners(swift::detect(dir)); // Swift (19)
-    add_runners(zig::detect(dir)); // Zig (20)
-    add_runners(make::detect(dir)); // Make (21)
-
-    // Sort by priority
+    // The registry lists the built-in detectors in priority order; a disabled
+    // ecosystem's detectors never run
+    for detector in builtin_detectors()
+        .into_iter()
+        .filter(|d| options.allows_ecosystem(d.metadata().ecosystem))
+    {
+        run(detector.name(), &|| detector.detect(dir));
+    }
+    // Config-defined (30 by default)
+    if !options.custom_runners.is_empty() && options.allows_ecosystem(Ecosystem::Custom) {
+        run("custom", &|| custom::detect(dir, &options.custom_runners));
+    }
+
+    // Apply user overrides, then sort by priority
+    apply_priority_overrides(&mut runners, &options.priorities);
     runners.sort_by_key(|r| r.priority);
     runners
 }
 
+/// Replace the built-in priority of runners named in the overrides table
+pub fn apply_priority_overrides(runners: &mut [DetectedRunner], overrides: &HashMap<String, u8>) {
+    for runner in runners.iter_mut() {
+        if let Some((_, priority)) = overrides
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&runner.name))
+        {
+            runner.priority = *priority;
+        }
+    }
+}
+
 /// Check if a tool is installed on the system
 pub fn is_tool_installed(tool: &str) -> bool {
     which::which(tool).is_ok()
 }
 
+/// Whether a file's permissions let it be run (always true off Unix)
+#[cfg(unix)]
+pub fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,7 +594,29 @@ mod tests {
     fn test_build_command_with_args() {
         let runner = DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4);
         let cmd = runner.build_command("test", &["--coverage".to_string()]);
-        assert_eq!(cmd, vec!["npm", "run", "test", "--coverage"]);
+        assert_eq!(cmd, vec!["npm", "run", "test", "--", "--coverage"]);
+
+        // An explicit separator from the user is not doubled
+        let cmd = runner.build_command("test", &["--".to_string(), "--coverage".to_string()]);
+        assert_eq!(cmd, vec!["npm", "run", "test", "--", "--coverage"]);
+    }
+
+    #[test]
+    fn test_build_command_pnpm_args_verbatim() {
+        let runner = DetectedRunner::new("pnpm", "pnpm-lock.yaml", Ecosystem::NodeJs, 2);
+        let cmd = runner.build_command("test", &["--coverage".to_string()]);
+        assert_eq!(cmd, vec!["pnpm", "run", "test", "--coverage"]);
+    }
+
+    #[test]
+    fn test_build_command_bun() {
+        let runner = DetectedRunner::new("bun", "bun.lock", Ecosystem::NodeJs, 1);
+        let cmd = runner.build_command("dev", &["--port=3000".to_string()]);
+        assert_eq!(cmd, vec!["bun", "run", "dev", "--port=3000"]);
+
+        // A path runs the file itself, like `bun ./script.ts`
+        let cmd = runner.build_command("./scripts/seed.ts", &["--reset".to_string()]);
+        assert_eq!(cmd, vec!["bun", "./scripts/seed.ts", "--reset"]);
     }
 
     #[test]
@@ -199,6 +624,14 @@ mod tests {
         let runner = DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9);
         let cmd = runner.build_command("build", &["--release".to_string()]);
         assert_eq!(cmd, vec!["cargo", "build", "--release"]);
+
+        // Test binary args go after cargo's own separator
+        let args: Vec<String> = ["--release", "--", "--nocapture"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let cmd = runner.build_command("test", &args);
+        assert_eq!(cmd, vec!["cargo", "test", "--release", "--", "--nocapture"]);
     }
 
     #[test]
@@ -208,10 +641,277 @@ mod tests {
         assert_eq!(cmd, vec!["go", "run", "./cmd/main.go"]);
     }
 
+    #[test]
+    fn test_build_command_xcodeproj() {
+        let runner = DetectedRunner::new("xcodebuild", "MyApp.xcodeproj", Ecosystem::Xcode, 19);
+        let cmd = runner.build_command("test", &[]);
+        assert_eq!(cmd, vec!["xcodebuild", "test"]);
+    }
+
+    #[test]
+    fn test_build_command_xcworkspace() {
+        let runner = DetectedRunner::new("xcodebuild", "MyApp.xcworkspace", Ecosystem::Xcode, 19);
+        let cmd = runner.build_command("build", &["-scheme".to_string(), "Tests".to_string()]);
+        assert_eq!(
+            cmd,
+            vec![
+                "xcodebuild",
+                "-workspace",
+                "MyApp.xcworkspace",
+                "build",
+                "-scheme",
+                "Tests"
+            ]
+        );
+        assert_eq!(
+            runner.build_command("build", &[]),
+            vec![
+                "xcodebuild",
+                "-workspace",
+                "MyApp.xcworkspace",
+                "-scheme",
+                "MyApp",
+                "build"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_command_gradle_wrapper() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("build.gradle")).unwrap();
+        let runner = DetectedRunner::new("gradle", "build.gradle", Ecosystem::Java, 15);
+        assert_eq!(runner.build_command("build", &[]), vec!["gradle", "build"]);
+
+        std::fs::File::create(dir.path().join("gradlew")).unwrap();
+        let runner = runner.with_evidence(dir.path(), &["gradlew"]);
+        assert_eq!(
+            runner.build_command("build", &[]),
+            vec!["./gradlew", "build"]
+        );
+    }
+
+    #[test]
+    fn test_build_command_mise() {
+        let runner = DetectedRunner::new("mise", "mise.toml", Ecosystem::Mise, 23);
+        let cmd = runner.build_command("lint", &[]);
+        assert_eq!(cmd, vec!["mise", "run", "lint"]);
+    }
+
+    #[test]
+    fn test_build_command_custom_script() {
+        let mut runner = DetectedRunner::new("acme", "acme.build", Ecosystem::Custom, 30);
+        runner.custom_scripts =
+            HashMap::from([("test".to_string(), "acme check --all".to_string())]);
+        assert_eq!(
+            runner.build_command("test", &["-v".to_string()]),
+            vec!["acme", "check", "--all", "-v"]
+        );
+        assert_eq!(runner.build_command("build", &[]), vec!["acme", "build"]);
+    }
+
+    #[test]
+    fn test_build_command_v_and_odin() {
+        let v = DetectedRunner::new("v", "v.mod", Ecosystem::V, 26);
+        assert_eq!(v.build_command("run", &[]), vec!["v", "run", "."]);
+        assert_eq!(v.build_command("test", &[]), vec!["v", "test", "."]);
+        assert_eq!(v.build_command("build", &[]), vec!["v", "."]);
+
+        let odin = DetectedRunner::new("odin", "main.odin", Ecosystem::Odin, 27);
+        assert_eq!(
+            odin.build_command("run", &["-debug".to_string()]),
+            vec!["odin", "run", ".", "-debug"]
+        );
+        assert_eq!(Ecosystem::from_name("odin"), Some(Ecosystem::Odin));
+    }
+
+    #[test]
+    fn test_build_command_dart() {
+        let melos = DetectedRunner::new("melos", "melos.yaml", Ecosystem::Dart, 22);
+        assert_eq!(
+            melos.build_command("analyze", &[]),
+            vec!["melos", "run", "analyze"]
+        );
+
+        let flutter = DetectedRunner::new("flutter", "pubspec.yaml", Ecosystem::Dart, 22);
+        assert_eq!(flutter.build_command("test", &[]), vec!["flutter", "test"]);
+    }
+
+    #[test]
+    fn test_build_command_container() {
+        let earthly = DetectedRunner::new("earthly", "Earthfile", Ecosystem::Container, 30);
+        assert_eq!(earthly.build_command("test", &[]), vec!["earthly", "+test"]);
+
+        let docker = DetectedRunner::new("docker", "Dockerfile", Ecosystem::Container, 31);
+        assert_eq!(
+            docker.build_command("build", &[]),
+            vec!["docker", "build", "."]
+        );
+        assert_eq!(
+            docker.build_command("runtime", &[]),
+            vec!["docker", "build", "--target", "runtime", "."]
+        );
+    }
+
+    #[test]
+    fn test_build_command_ansible_playbook() {
+        let runner = DetectedRunner::new("ansible-playbook", "ansible.cfg", Ecosystem::Infra, 29);
+        assert_eq!(
+            runner.build_command("site", &[]),
+            vec!["ansible-playbook", "site.yml"]
+        );
+        assert_eq!(
+            runner.build_command("deploy.yaml", &["--check".to_string()]),
+            vec!["ansible-playbook", "deploy.yaml", "--check"]
+        );
+    }
+
+    #[test]
+    fn test_priority_override_flips_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("package.json")).unwrap();
+        std::fs::File::create(dir.path().join("Makefile")).unwrap();
+
+        let runners = detect_all(dir.path(), &DetectOptions::default());
+        assert_eq!(runners[0].name, "npm");
+
+        let options = DetectOptions {
+            priorities: HashMap::from([("make".to_string(), 3)]),
+            ..Default::default()
+        };
+        let runners = detect_all(dir.path(), &options);
+        assert_eq!(runners[0].name, "make");
+        assert_eq!(runners[0].priority, 3);
+    }
+
+    #[test]
+    fn test_detect_all_ecosystem_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("package.json")).unwrap();
+        std::fs::File::create(dir.path().join("Cargo.toml")).unwrap();
+        std::fs::File::create(dir.path().join("Makefile")).unwrap();
+        let names = |options: &DetectOptions| -> Vec<String> {
+            detect_all(dir.path(), options)
+                .into_iter()
+                .map(|r| r.name)
+                .collect()
+        };
+
+        let options = DetectOptions {
+            disabled_ecosystems: vec![Ecosystem::NodeJs],
+            ..Default::default()
+        };
+        assert_eq!(names(&options), vec!["cargo", "make"]);
+
+        let options = DetectOptions {
+            enabled_ecosystems: Some(vec![Ecosystem::Rust, Ecosystem::NodeJs]),
+            ..Default::default()
+        };
+        assert_eq!(names(&options), vec!["npm", "cargo"]);
+
+        // The denylist wins over the allowlist
+        let options = DetectOptions {
+            enabled_ecosystems: Some(vec![Ecosystem::Rust, Ecosystem::NodeJs]),
+            disabled_ecosystems: vec![Ecosystem::Rust],
+            ..Default::default()
+        };
+        assert_eq!(names(&options), vec!["npm"]);
+
+        // Skipped detectors don't even show up in a profile
+        let mut profile = DetectionProfile::default();
+        detect_all_profiled(dir.path(), &options, &mut profile);
+        assert_eq!(profile.timings.len(), 1);
+        assert_eq!(profile.timings[0].name, "node");
+    }
+
+    #[test]
+    fn test_detect_all_profiled_times_every_detector() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("Cargo.toml")).unwrap();
+
+        let mut profile = DetectionProfile::default();
+        let runners = detect_all_profiled(dir.path(), &DetectOptions::default(), &mut profile);
+        assert_eq!(runners, detect_all(dir.path(), &DetectOptions::default()));
+        detect_all_profiled(dir.path(), &DetectOptions::default(), &mut profile);
+
+        // One entry per registered detector, accumulated across directories
+        let names: Vec<&str> = profile.timings.iter().map(|t| t.name.as_str()).collect();
+        let registered: Vec<String> = builtin_detectors()
+            .iter()
+            .map(|d| d.name().to_string())
+            .collect();
+        assert_eq!(names, registered);
+        assert_eq!(profile.dirs_walked, 2);
+        let rust = profile.timings.iter().find(|t| t.name == "rust").unwrap();
+        assert_eq!(rust.found, vec!["Cargo.toml", "Cargo.toml"]);
+        assert!(profile
+            .sorted()
+            .windows(2)
+            .all(|w| w[0].elapsed >= w[1].elapsed));
+    }
+
+    #[test]
+    fn test_build_command_scala() {
+        let sbt = DetectedRunner::new("sbt", "build.sbt", Ecosystem::Java, 16);
+        assert_eq!(sbt.build_command("compile", &[]), vec!["sbt", "compile"]);
+        assert_eq!(sbt.build_command("run", &[]), vec!["sbt", "run"]);
+
+        let mill = DetectedRunner::new("mill", "build.sc", Ecosystem::Java, 16);
+        assert_eq!(mill.build_command("test", &[]), vec!["mill", "_.test"]);
+    }
+
+    #[test]
+    fn test_jvm_build_tools_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("pom.xml")).unwrap();
+        std::fs::File::create(dir.path().join("build.sbt")).unwrap();
+
+        // Both land in the Java group, so selection treats them as a conflict
+        let runners = detect_all(dir.path(), &DetectOptions::default());
+        let names: Vec<&str> = runners.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["maven", "sbt"]);
+        assert!(runners.iter().all(|r| r.ecosystem == Ecosystem::Java));
+    }
+
+    #[test]
+    fn test_make_wins_over_late_ecosystems() {
+        // Bazel, V and Odin repos often keep a Makefile that wraps the real tool
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["Makefile", "MODULE.bazel", "v.mod", "main.odin"] {
+            std::fs::File::create(dir.path().join(file)).unwrap();
+        }
+        let runners = detect_all(dir.path(), &DetectOptions::default());
+        let names: Vec<&str> = runners.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["make", "bazel", "v", "odin"]);
+    }
+
+    #[test]
+    fn test_build_tools_rank_above_infra_and_containers() {
+        // Containers rank lowest and infra below the build tools (ADR-002)
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "Dockerfile",
+            "main.tf",
+            "MODULE.bazel",
+            "v.mod",
+            "main.odin",
+        ] {
+            std::fs::File::create(dir.path().join(file)).unwrap();
+        }
+        let runners = detect_all(dir.path(), &DetectOptions::default());
+        let names: Vec<&str> = runners.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(&names[..3], ["bazel", "v", "odin"]);
+        assert_eq!(names.last(), Some(&"docker"));
+
+        // Distinct priorities, so no two of them tie as a conflict
+        let priorities: Vec<u8> = runners.iter().map(|r| r.priority).collect();
+        assert!(priorities.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[test]
     fn test_build_command_go_task() {
         let runner = DetectedRunner::new("go", "go.mod", Ecosystem::Go, 12);
         let cmd = runner.build_command("build", &[]);
-        assert_eq!(cmd, vec!["go", "build"]);
+        assert_eq!(cmd, vec!["go", "build", "./..."]);
     }
 }
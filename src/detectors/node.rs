@@ -1,9 +1,131 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
+/// Package managers that can be pinned, with their priorities
+const PINNABLE: [(&str, u8); 4] = [("bun", 1), ("pnpm", 2), ("yarn", 3), ("npm", 4)];
+
 /// Detect Node.js package managers
-/// Priority: Bun (1) > PNPM (2) > Yarn (3) > NPM (4)
+/// Priority: Bun (1) > PNPM (2) > Yarn (3) > NPM (4), unless package.json
+/// `packageManager` or `.tool-versions` pins one
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = detect_lockfiles(dir);
+    if !dir.join("package.json").exists() {
+        return runners;
+    }
+
+    // Corepack's packageManager is authoritative, then `.tool-versions`
+    let pin = package_manager_pin(dir)
+        .map(|pm| {
+            let reason = format!("package.json packageManager={}", pm);
+            (pm, "package.json", reason)
+        })
+        .or_else(|| {
+            tool_versions_pin(dir).map(|pm| {
+                let reason = format!(".tool-versions pins {}", pm);
+                (pm, ".tool-versions", reason)
+            })
+        });
+
+    // A pin wins over stray lockfiles; it goes first so conflict checks and
+    // `why` pick it
+    if let Some((pinned, pin_file, reason)) = pin {
+        let index = match runners.iter().position(|r| r.name == pinned) {
+            Some(index) => index,
+            None => {
+                let priority = PINNABLE
+                    .iter()
+                    .find(|(name, _)| *name == pinned)
+                    .map_or(4, |(_, priority)| *priority);
+                runners.push(DetectedRunner::new(
+                    &pinned,
+                    pin_file,
+                    Ecosystem::NodeJs,
+                    priority,
+                ));
+                runners.len() - 1
+            }
+        };
+        let mut runner = runners.remove(index).with_evidence(dir, &[pin_file]);
+        runner.pinned_by = Some(reason);
+        // package.json alone only stood in for "no lockfile"
+        runners.retain(|r| r.detected_file() != "package.json");
+        runners.insert(0, runner);
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("bun", &["bun.lockb", "bun.lock", "bunfig.toml"], 1),
+    RunnerMeta::new("pnpm", &["pnpm-lock.yaml"], 2),
+    RunnerMeta::new("yarn", &["yarn.lock"], 3),
+    RunnerMeta::new("npm", &["package-lock.json", "package.json"], 4),
+];
+
+/// Detector for Node.js package managers
+pub struct NodeDetector;
+
+impl Detector for NodeDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "node",
+            ecosystem: Ecosystem::NodeJs,
+            runners: RUNNERS,
+        }
+    }
+}
+
+/// Package manager named by package.json `packageManager` (e.g. "pnpm@8.6.0"), if any
+pub fn package_manager_pin(dir: &Path) -> Option<String> {
+    let content = crate::scripts::read_manifest(dir.join("package.json"))?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let (name, _version) = json.get("packageManager")?.as_str()?.split_once('@')?;
+    PINNABLE
+        .iter()
+        .any(|(pm, _)| *pm == name)
+        .then(|| name.to_string())
+}
+
+/// Package manager pinned in `.tool-versions` (asdf/mise), if any
+pub fn tool_versions_pin(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join(".tool-versions")).ok()?;
+    content.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or("");
+        let mut fields = line.split_whitespace();
+        let tool = fields.next()?;
+        // A tool line without a version isn't a pin
+        fields.next()?;
+        PINNABLE
+            .iter()
+            .any(|(name, _)| *name == tool)
+            .then(|| tool.to_string())
+    })
+}
+
+/// Extensions `bun <file>` runs directly
+const BUN_FILE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Whether a `bun` task names a file to run (`./script.ts`) rather than a
+/// package.json script
+///
+/// Mirrors `bun <file>`: relative or absolute paths, or a bare file name with
+/// a JavaScript/TypeScript extension.
+pub fn is_bun_file_target(task: &str) -> bool {
+    if task.starts_with("./") || task.starts_with("../") || Path::new(task).is_absolute() {
+        return true;
+    }
+    Path::new(task)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| BUN_FILE_EXTENSIONS.contains(&ext))
+}
+
+fn detect_lockfiles(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
     let has_package_json = dir.join("package.json").exists();
@@ -12,47 +134,49 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let bun_lockb = dir.join("bun.lockb");
     let bun_lock = dir.join("bun.lock");
     if bun_lockb.exists() && has_package_json {
-        runners.push(DetectedRunner::new(
-            "bun",
-            "bun.lockb",
-            Ecosystem::NodeJs,
-            1,
-        ));
+        runners.push(
+            DetectedRunner::new("bun", "bun.lockb", Ecosystem::NodeJs, 1)
+                .with_evidence(dir, &["bunfig.toml", "package.json"]),
+        );
     } else if bun_lock.exists() && has_package_json {
-        runners.push(DetectedRunner::new("bun", "bun.lock", Ecosystem::NodeJs, 1));
+        runners.push(
+            DetectedRunner::new("bun", "bun.lock", Ecosystem::NodeJs, 1)
+                .with_evidence(dir, &["bunfig.toml", "package.json"]),
+        );
     }
 
     // Check for PNPM (priority 2)
     let pnpm_lock = dir.join("pnpm-lock.yaml");
     if pnpm_lock.exists() && has_package_json {
-        runners.push(DetectedRunner::new(
-            "pnpm",
-            "pnpm-lock.yaml",
-            Ecosystem::NodeJs,
-            2,
-        ));
+        runners.push(
+            DetectedRunner::new("pnpm", "pnpm-lock.yaml", Ecosystem::NodeJs, 2)
+                .with_evidence(dir, &["pnpm-workspace.yaml", "package.json"]),
+        );
     }
 
     // Check for Yarn (priority 3)
     let yarn_lock = dir.join("yarn.lock");
     if yarn_lock.exists() && has_package_json {
-        runners.push(DetectedRunner::new(
-            "yarn",
-            "yarn.lock",
-            Ecosystem::NodeJs,
-            3,
-        ));
+        runners.push(
+            DetectedRunner::new("yarn", "yarn.lock", Ecosystem::NodeJs, 3)
+                .with_evidence(dir, &[".yarnrc.yml", "package.json"]),
+        );
     }
 
     // Check for NPM (priority 4)
     let npm_lock = dir.join("package-lock.json");
     if npm_lock.exists() && has_package_json {
-        runners.push(DetectedRunner::new(
-            "npm",
-            "package-lock.json",
-            Ecosystem::NodeJs,
-            4,
-        ));
+        runners.push(
+            DetectedRunner::new("npm", "package-lock.json", Ecosystem::NodeJs, 4)
+                .with_evidence(dir, &["package.json"]),
+        );
+    } else if has_package_json && runners.is_empty() && dir.join("bunfig.toml").is_file() {
+        // bunfig.toml configures Bun (e.g. its `[install]` auto-install), so a
+        // project without a lockfile yet is still a Bun project
+        runners.push(
+            DetectedRunner::new("bun", "bunfig.toml", Ecosystem::NodeJs, 1)
+                .with_evidence(dir, &["package.json"]),
+        );
     } else if has_package_json && runners.is_empty() {
         // Fallback to npm if only package.json exists and no other Node runner detected
         runners.push(DetectedRunner::new(
@@ -69,7 +193,7 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use tempfile::tempdir;
 
     #[test]
@@ -81,7 +205,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "bun");
-        assert_eq!(runners[0].detected_file, "bun.lockb");
+        assert_eq!(runners[0].detected_file(), "bun.lockb");
     }
 
     #[test]
@@ -93,7 +217,36 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "bun");
-        assert_eq!(runners[0].detected_file, "bun.lock");
+        assert_eq!(runners[0].detected_file(), "bun.lock");
+    }
+
+    #[test]
+    fn test_detect_bunfig() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        File::create(dir.path().join("bunfig.toml")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "bun");
+        assert_eq!(runners[0].detected_file(), "bunfig.toml");
+
+        // A lockfile says more about the package manager in use than bunfig.toml
+        File::create(dir.path().join("package-lock.json")).unwrap();
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "npm");
+    }
+
+    #[test]
+    fn test_is_bun_file_target() {
+        assert!(is_bun_file_target("./script.ts"));
+        assert!(is_bun_file_target("../tools/seed.js"));
+        assert!(is_bun_file_target("/tmp/one-off.ts"));
+        assert!(is_bun_file_target("index.tsx"));
+        assert!(!is_bun_file_target("build"));
+        assert!(!is_bun_file_target("test:unit"));
+        assert!(!is_bun_file_target("lint.fix"));
     }
 
     #[test]
@@ -105,6 +258,25 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "pnpm");
+        assert_eq!(
+            runners[0].detected_files,
+            ["pnpm-lock.yaml", "package.json"]
+        );
+    }
+
+    #[test]
+    fn test_detect_pnpm_workspace_evidence() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+        File::create(dir.path().join("pnpm-workspace.yaml")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners[0].detected_file(), "pnpm-lock.yaml");
+        assert_eq!(
+            runners[0].detected_files,
+            ["pnpm-lock.yaml", "pnpm-workspace.yaml", "package.json"]
+        );
     }
 
     #[test]
@@ -137,7 +309,121 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "npm");
-        assert_eq!(runners[0].detected_file, "package.json");
+        assert_eq!(runners[0].detected_file(), "package.json");
+    }
+
+    #[test]
+    fn test_tool_versions_pins_pnpm_over_stray_lockfile() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        File::create(dir.path().join("package-lock.json")).unwrap();
+        fs::write(
+            dir.path().join(".tool-versions"),
+            "nodejs 20.11.0\npnpm 8.15.1\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 2);
+        assert_eq!(runners[0].name, "pnpm");
+        assert_eq!(runners[0].detected_file(), ".tool-versions");
+        assert_eq!(
+            runners[0].pinned_by.as_deref(),
+            Some(".tool-versions pins pnpm")
+        );
+        assert_eq!(runners[1].name, "npm");
+        assert_eq!(runners[1].pinned_by, None);
+    }
+
+    #[test]
+    fn test_tool_versions_pin_keeps_matching_lockfile() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        File::create(dir.path().join("bun.lockb")).unwrap();
+        File::create(dir.path().join("yarn.lock")).unwrap();
+        fs::write(
+            dir.path().join(".tool-versions"),
+            "yarn 1.22.19 # classic\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners[0].name, "yarn");
+        assert_eq!(runners[0].detected_file(), "yarn.lock");
+        assert_eq!(runners[1].name, "bun");
+    }
+
+    #[test]
+    fn test_package_manager_overrides_lockfile() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "app", "packageManager": "pnpm@8.6.0+sha256.abc"}"#,
+        )
+        .unwrap();
+        File::create(dir.path().join("package-lock.json")).unwrap();
+        // packageManager outranks .tool-versions
+        fs::write(dir.path().join(".tool-versions"), "yarn 1.22.19\n").unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 2);
+        assert_eq!(runners[0].name, "pnpm");
+        assert_eq!(runners[0].detected_file(), "package.json");
+        assert_eq!(
+            runners[0].pinned_by.as_deref(),
+            Some("package.json packageManager=pnpm")
+        );
+        assert_eq!(runners[1].name, "npm");
+    }
+
+    #[test]
+    fn test_package_manager_pin_parsing() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"packageManager": "yarn@4.1.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(package_manager_pin(dir.path()).as_deref(), Some("yarn"));
+
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"packageManager": "deno@1.0.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(package_manager_pin(dir.path()), None);
+
+        File::create(dir.path().join("package.json")).unwrap();
+        assert_eq!(package_manager_pin(dir.path()), None);
+    }
+
+    #[test]
+    fn test_tool_versions_pin_parsing() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(".tool-versions"),
+            "nodejs 20\npython 3.12\n",
+        )
+        .unwrap();
+        assert_eq!(tool_versions_pin(dir.path()), None);
+
+        fs::write(
+            dir.path().join(".tool-versions"),
+            "# pnpm 8\npnpm\nbun 1.1.0\n",
+        )
+        .unwrap();
+        assert_eq!(tool_versions_pin(dir.path()).as_deref(), Some("bun"));
+    }
+
+    #[test]
+    fn test_tool_versions_replaces_package_json_fallback() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        fs::write(dir.path().join(".tool-versions"), "pnpm 8.15.1\n").unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "pnpm");
     }
 
     #[test]
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+/// A Node version manager that can run a command under a pinned version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeManager {
+    Fnm,
+    Nvm,
+    Volta,
+}
+
+impl NodeManager {
+    /// Checked in this order; the first one available wins
+    pub const ALL: [NodeManager; 3] = [NodeManager::Fnm, NodeManager::Nvm, NodeManager::Volta];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeManager::Fnm => "fnm",
+            NodeManager::Nvm => "nvm",
+            NodeManager::Volta => "volta",
+        }
+    }
+
+    /// Program that runs the wrapped command, for the installed check
+    pub fn program(self) -> &'static str {
+        match self {
+            // nvm is a shell function, loaded from $NVM_DIR/nvm.sh
+            NodeManager::Nvm => "bash",
+            manager => manager.name(),
+        }
+    }
+
+    /// `cmd` run under Node `version`
+    pub fn wrap_command(self, version: &str, cmd: &[String]) -> Vec<String> {
+        let mut wrapped: Vec<String> = match self {
+            NodeManager::Fnm => vec!["fnm", "exec", "--using", version, "--"],
+            NodeManager::Volta => vec!["volta", "run", "--node", version],
+            // `$0` is the version and `$@` the command, so neither is re-parsed
+            NodeManager::Nvm => vec![
+                "bash",
+                "-c",
+                ". \"$NVM_DIR/nvm.sh\" && nvm exec \"$0\" \"$@\"",
+                version,
+            ],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+        wrapped.extend(cmd.iter().cloned());
+        wrapped
+    }
+
+    /// Whether this manager can be used here
+    ///
+    /// fnm and Volta are binaries on PATH; nvm needs `$NVM_DIR/nvm.sh`.
+    pub fn is_available(self) -> bool {
+        match self {
+            NodeManager::Nvm => std::env::var_os("NVM_DIR")
+                .is_some_and(|dir| Path::new(&dir).join("nvm.sh").is_file()),
+            manager => super::is_tool_installed(manager.name()),
+        }
+    }
+}
+
+/// The Node version pinned in `.nvmrc` (e.g. "18.17.0" or "lts/hydrogen")
+pub fn nvmrc_version(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join(".nvmrc")).ok()?;
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// First manager in [`NodeManager::ALL`] order that `is_available` accepts
+pub fn pick_node_manager(is_available: impl Fn(NodeManager) -> bool) -> Option<NodeManager> {
+    NodeManager::ALL.into_iter().find(|m| is_available(*m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_nvmrc_version() {
+        let dir = tempdir().unwrap();
+        assert_eq!(nvmrc_version(dir.path()), None);
+
+        fs::write(dir.path().join(".nvmrc"), "v18.17.0\n").unwrap();
+        assert_eq!(nvmrc_version(dir.path()).as_deref(), Some("v18.17.0"));
+
+        fs::write(
+            dir.path().join(".nvmrc"),
+            "# pinned for CI\n\n  lts/hydrogen  \n",
+        )
+        .unwrap();
+        assert_eq!(nvmrc_version(dir.path()).as_deref(), Some("lts/hydrogen"));
+
+        fs::write(dir.path().join(".nvmrc"), "\n").unwrap();
+        assert_eq!(nvmrc_version(dir.path()), None);
+    }
+
+    #[test]
+    fn test_pick_node_manager() {
+        assert_eq!(pick_node_manager(|_| true), Some(NodeManager::Fnm));
+        assert_eq!(
+            pick_node_manager(|m| m == NodeManager::Volta),
+            Some(NodeManager::Volta)
+        );
+        assert_eq!(
+            pick_node_manager(|m| m != NodeManager::Fnm),
+            Some(NodeManager::Nvm)
+        );
+        assert_eq!(pick_node_manager(|_| false), None);
+    }
+
+    #[test]
+    fn test_wrap_command() {
+        let cmd = vec!["npm".to_string(), "run".to_string(), "build".to_string()];
+        assert_eq!(
+            NodeManager::Fnm.wrap_command("18", &cmd),
+            vec!["fnm", "exec", "--using", "18", "--", "npm", "run", "build"]
+        );
+        assert_eq!(
+            NodeManager::Volta.wrap_command("18", &cmd),
+            vec!["volta", "run", "--node", "18", "npm", "run", "build"]
+        );
+        let nvm = NodeManager::Nvm.wrap_command("18", &cmd);
+        assert_eq!(nvm[0], "bash");
+        assert_eq!(&nvm[3..], ["18", "npm", "run", "build"]);
+    }
+}
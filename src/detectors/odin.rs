@@ -0,0 +1,102 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Odin packages
+/// Priority: 27 (after Make, Bazel and V)
+///
+/// Odin has no manifest, so besides the language server's `ols.json` a
+/// directory counts when it directly contains `.odin` source files.
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    if dir.join("ols.json").exists() {
+        let mut runner = DetectedRunner::new("odin", "ols.json", Ecosystem::Odin, 27);
+        if let Some(source) = first_odin_file(dir) {
+            runner = runner.with_evidence(dir, &[&source]);
+        }
+        runners.push(runner);
+    } else if let Some(source) = first_odin_file(dir) {
+        runners.push(DetectedRunner::new("odin", &source, Ecosystem::Odin, 27));
+    }
+
+    runners
+}
+
+/// Name of the first `*.odin` file directly in `dir` (not in subdirectories)
+fn first_odin_file(dir: &Path) -> Option<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.len() > ".odin".len() && name.ends_with(".odin"))
+        .collect();
+    // read_dir order is platform-dependent; keep the reported file stable
+    names.sort();
+    names.into_iter().next()
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("odin", &["ols.json", "*.odin"], 27)];
+
+/// Detector for Odin packages
+pub struct OdinDetector;
+
+impl Detector for OdinDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "odin",
+            ecosystem: Ecosystem::Odin,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_ols_json() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("ols.json")).unwrap();
+        File::create(dir.path().join("main.odin")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "odin");
+        assert_eq!(runners[0].detected_file(), "ols.json");
+    }
+
+    #[test]
+    fn test_detect_odin_sources() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("util.odin")).unwrap();
+        File::create(dir.path().join("main.odin")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), "main.odin");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Odin);
+    }
+
+    #[test]
+    fn test_no_odin() {
+        let dir = tempdir().unwrap();
+        // Sources in a subdirectory, a directory named like a source and a
+        // bare `.odin` dotfile don't make this an Odin package
+        fs::create_dir_all(dir.path().join("vendor")).unwrap();
+        File::create(dir.path().join("vendor/lib.odin")).unwrap();
+        fs::create_dir(dir.path().join("assets.odin")).unwrap();
+        File::create(dir.path().join(".odin")).unwrap();
+        File::create(dir.path().join("notes.odinx")).unwrap();
+
+        assert!(detect(dir.path()).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect PHP package manager (Composer)
@@ -10,12 +10,10 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let composer_lock = dir.join("composer.lock");
 
     if composer_lock.exists() && composer_json.exists() {
-        runners.push(DetectedRunner::new(
-            "composer",
-            "composer.lock",
-            Ecosystem::Php,
-            10,
-        ));
+        runners.push(
+            DetectedRunner::new("composer", "composer.lock", Ecosystem::Php, 10)
+                .with_evidence(dir, &["composer.json"]),
+        );
     } else if composer_json.exists() {
         runners.push(DetectedRunner::new(
             "composer",
@@ -28,6 +26,30 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new(
+    "composer",
+    &["composer.lock", "composer.json"],
+    10,
+)];
+
+/// Detector for PHP package manager (Composer)
+pub struct PhpDetector;
+
+impl Detector for PhpDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "php",
+            ecosystem: Ecosystem::Php,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,7 +65,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "composer");
-        assert_eq!(runners[0].detected_file, "composer.lock");
+        assert_eq!(runners[0].detected_file(), "composer.lock");
     }
 
     #[test]
@@ -54,7 +76,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "composer");
-        assert_eq!(runners[0].detected_file, "composer.json");
+        assert_eq!(runners[0].detected_file(), "composer.json");
     }
 
     #[test]
@@ -1,8 +1,8 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Python package managers
-/// Priority: UV (5) > Poetry (6) > Pipenv (7) > Pip (8)
+/// Priority: UV (5) > Poetry, PDM, Hatch (6) > Pipenv (7) > Pip (8)
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
@@ -11,30 +11,60 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     // Check for UV (priority 5)
     let uv_lock = dir.join("uv.lock");
     if uv_lock.exists() && has_pyproject {
-        runners.push(DetectedRunner::new("uv", "uv.lock", Ecosystem::Python, 5));
+        runners.push(
+            DetectedRunner::new("uv", "uv.lock", Ecosystem::Python, 5)
+                .with_evidence(dir, &["pyproject.toml"]),
+        );
     }
 
     // Check for Poetry (priority 6)
     let poetry_lock = dir.join("poetry.lock");
     if poetry_lock.exists() && has_pyproject {
-        runners.push(DetectedRunner::new(
-            "poetry",
-            "poetry.lock",
-            Ecosystem::Python,
-            6,
-        ));
+        runners.push(
+            DetectedRunner::new("poetry", "poetry.lock", Ecosystem::Python, 6)
+                .with_evidence(dir, &["pyproject.toml"]),
+        );
+    }
+
+    // Check for PDM (priority 6); pdm.lock, or a [tool.pdm] table before the first lock
+    if has_pyproject && (dir.join("pdm.lock").exists() || has_tool_table(dir, "pdm")) {
+        let detected_file = if dir.join("pdm.lock").exists() {
+            "pdm.lock"
+        } else {
+            "pyproject.toml"
+        };
+        runners.push(
+            DetectedRunner::new("pdm", detected_file, Ecosystem::Python, 6)
+                .with_evidence(dir, &["pyproject.toml"]),
+        );
     }
 
-    // Check for Pipenv (priority 7)
+    // Check for Hatch (priority 6); hatch has no lockfile, only its config
+    if has_pyproject && (dir.join("hatch.toml").exists() || has_tool_table(dir, "hatch")) {
+        let detected_file = if dir.join("hatch.toml").exists() {
+            "hatch.toml"
+        } else {
+            "pyproject.toml"
+        };
+        runners.push(
+            DetectedRunner::new("hatch", detected_file, Ecosystem::Python, 6)
+                .with_evidence(dir, &["pyproject.toml"]),
+        );
+    }
+
+    // Check for Pipenv (priority 7); a Pipfile is enough before the first lock
     let pipfile = dir.join("Pipfile");
     let pipfile_lock = dir.join("Pipfile.lock");
-    if pipfile_lock.exists() && pipfile.exists() {
-        runners.push(DetectedRunner::new(
-            "pipenv",
-            "Pipfile.lock",
-            Ecosystem::Python,
-            7,
-        ));
+    if pipfile.exists() {
+        let detected_file = if pipfile_lock.exists() {
+            "Pipfile.lock"
+        } else {
+            "Pipfile"
+        };
+        runners.push(
+            DetectedRunner::new("pipenv", detected_file, Ecosystem::Python, 7)
+                .with_evidence(dir, &["Pipfile"]),
+        );
     }
 
     // Check for Pip (priority 8) - fallback
@@ -59,6 +89,46 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     runners
 }
 
+/// Whether pyproject.toml has a `[tool.<tool>]` table or one of its subtables
+fn has_tool_table(dir: &Path, tool: &str) -> bool {
+    let header = format!("[tool.{}", tool);
+    std::fs::read_to_string(dir.join("pyproject.toml"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim_start)
+        .any(|line| {
+            line.strip_prefix(header.as_str())
+                .is_some_and(|rest| rest.starts_with(']') || rest.starts_with('.'))
+        })
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("uv", &["uv.lock"], 5),
+    RunnerMeta::new("poetry", &["poetry.lock"], 6),
+    RunnerMeta::new("pdm", &["pdm.lock"], 6),
+    RunnerMeta::new("hatch", &["hatch.toml"], 6),
+    RunnerMeta::new("pipenv", &["Pipfile.lock", "Pipfile"], 7),
+    RunnerMeta::new("pip", &["requirements.txt", "pyproject.toml"], 8),
+];
+
+/// Detector for Python package managers
+pub struct PythonDetector;
+
+impl Detector for PythonDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "python",
+            ecosystem: Ecosystem::Python,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +157,36 @@ mod tests {
         assert_eq!(runners[0].name, "poetry");
     }
 
+    #[test]
+    fn test_detect_pdm_and_hatch() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("pyproject.toml")).unwrap();
+        File::create(dir.path().join("pdm.lock")).unwrap();
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "pdm");
+        assert_eq!(
+            runners[0].build_command("lint", &[]),
+            ["pdm", "run", "lint"]
+        );
+
+        // Without a lock or hatch.toml, the pyproject.toml tables decide
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[tool.hatch.envs.default.scripts]\ntest = \"pytest\"\n\n[tool.pdmx]\n",
+        )
+        .unwrap();
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "hatch");
+        assert_eq!(runners[0].detected_file(), "pyproject.toml");
+        assert_eq!(
+            runners[0].build_command("test", &[]),
+            ["hatch", "run", "test"]
+        );
+    }
+
     #[test]
     fn test_detect_pipenv() {
         let dir = tempdir().unwrap();
@@ -96,6 +196,23 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "pipenv");
+        assert_eq!(runners[0].detected_file(), "Pipfile.lock");
+    }
+
+    #[test]
+    fn test_detect_pipenv_without_lock() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Pipfile")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "pipenv");
+        assert_eq!(runners[0].detected_file(), "Pipfile");
+
+        // A stray Pipfile.lock alone isn't a pipenv project
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Pipfile.lock")).unwrap();
+        assert!(detect(dir.path()).is_empty());
     }
 
     #[test]
@@ -106,7 +223,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "pip");
-        assert_eq!(runners[0].detected_file, "requirements.txt");
+        assert_eq!(runners[0].detected_file(), "requirements.txt");
     }
 
     #[test]
@@ -117,7 +234,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "pip");
-        assert_eq!(runners[0].detected_file, "pyproject.toml");
+        assert_eq!(runners[0].detected_file(), "pyproject.toml");
     }
 
     #[test]
@@ -0,0 +1,185 @@
+use super::{
+    ansible, bazel, dart, docker, dotnet, earthly, elixir, go, java, make, mill, mise, node, odin,
+    php, python, ruby, rust, sbt, swift, terraform, vlang, xcode, zig, DetectedRunner, Ecosystem,
+};
+use std::path::Path;
+
+/// A runner a built-in detector can report, and the files that trigger it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunnerMeta {
+    /// Runner name, as in `DetectedRunner::name`
+    pub name: &'static str,
+    /// Files checked, strongest signal first (`*` marks a wildcard)
+    pub files: &'static [&'static str],
+    /// Built-in priority (lower = higher priority)
+    pub priority: u8,
+}
+
+impl RunnerMeta {
+    pub const fn new(name: &'static str, files: &'static [&'static str], priority: u8) -> Self {
+        Self {
+            name,
+            files,
+            priority,
+        }
+    }
+}
+
+/// Metadata for one built-in detector (one ecosystem module)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectorMeta {
+    /// Detector name, e.g. "node"
+    pub name: &'static str,
+    pub ecosystem: Ecosystem,
+    pub runners: &'static [RunnerMeta],
+}
+
+/// A built-in detector: one ecosystem module's rules plus what they look for
+pub trait Detector {
+    /// Detector name, e.g. "node"
+    fn name(&self) -> &str {
+        self.metadata().name
+    }
+
+    /// Runners this detector finds in `dir`, unfiltered and in its own order
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner>;
+
+    /// Ecosystem, runners, files checked and priorities, for `--list-ecosystems`
+    fn metadata(&self) -> DetectorMeta;
+}
+
+/// Every built-in detector, in the order `detect_all` runs them (priority order)
+pub fn builtin_detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(node::NodeDetector),
+        Box::new(python::PythonDetector),
+        Box::new(rust::RustDetector),
+        Box::new(php::PhpDetector),
+        Box::new(go::GoDetector),
+        Box::new(ruby::RubyDetector),
+        Box::new(java::JavaDetector),
+        Box::new(sbt::SbtDetector),
+        Box::new(mill::MillDetector),
+        Box::new(dotnet::DotNetDetector),
+        Box::new(elixir::ElixirDetector),
+        Box::new(xcode::XcodeDetector),
+        Box::new(swift::SwiftDetector),
+        Box::new(zig::ZigDetector),
+        Box::new(dart::DartDetector),
+        Box::new(mise::MiseDetector),
+        Box::new(make::MakeDetector),
+        Box::new(bazel::BazelDetector),
+        Box::new(vlang::VlangDetector),
+        Box::new(odin::OdinDetector),
+        Box::new(terraform::TerraformDetector),
+        Box::new(ansible::AnsibleDetector),
+        Box::new(earthly::EarthlyDetector),
+        Box::new(docker::DockerDetector),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::KNOWN_RUNNERS;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    fn metas() -> Vec<DetectorMeta> {
+        builtin_detectors().iter().map(|d| d.metadata()).collect()
+    }
+
+    fn find(name: &str) -> (DetectorMeta, RunnerMeta) {
+        metas()
+            .into_iter()
+            .find_map(|d| d.runners.iter().find(|r| r.name == name).map(|r| (d, *r)))
+            .unwrap_or_else(|| panic!("{} missing from the registry", name))
+    }
+
+    #[test]
+    fn test_registry_lists_known_runners() {
+        let mut listed: Vec<&str> = metas()
+            .iter()
+            .flat_map(|d| d.runners.iter().map(|r| r.name))
+            .collect();
+        let mut known = KNOWN_RUNNERS.to_vec();
+        listed.sort();
+        known.sort();
+        assert_eq!(listed, known);
+
+        assert_eq!(find("bun").1.priority, 1);
+        assert_eq!(find("npm").1.priority, 4);
+        assert_eq!(find("cargo").1.priority, 9);
+        assert_eq!(find("go").1.priority, 12);
+        assert_eq!(find("make").1.priority, 24);
+        assert_eq!(find("bazel").1.priority, 25);
+        assert_eq!(find("odin").1.priority, 27);
+        assert_eq!(find("docker").1.priority, 31);
+        assert_eq!(find("pip").0.ecosystem, Ecosystem::Python);
+    }
+
+    #[test]
+    fn test_registry_names_unique() {
+        let mut names: Vec<String> = builtin_detectors()
+            .iter()
+            .map(|d| d.name().to_string())
+            .collect();
+        assert_eq!(names[0], "node");
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), builtin_detectors().len());
+    }
+
+    #[test]
+    fn test_registry_in_priority_order() {
+        let priorities: Vec<u8> = metas()
+            .iter()
+            .flat_map(|d| d.runners.iter().map(|r| r.priority))
+            .collect();
+        assert!(priorities.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_registry_matches_detectors() {
+        // Each runner's files are detected with the listed priority and ecosystem
+        for detector in builtin_detectors() {
+            let meta = detector.metadata();
+            // Content-based detection (pubspec deps, mise [tasks]) needs real files
+            if meta.name == "dart" || meta.name == "mise" {
+                continue;
+            }
+            for runner in meta.runners {
+                let dir = tempdir().unwrap();
+                for file in runner.files {
+                    let path = dir.path().join(file.replace('*', "App"));
+                    fs::create_dir_all(path.parent().unwrap()).unwrap();
+                    // Xcode projects and workspaces are directories
+                    if file.starts_with("*.xc") {
+                        fs::create_dir(&path).unwrap();
+                    } else {
+                        File::create(&path).unwrap();
+                    }
+                }
+                // Lockfiles only count next to their manifest
+                let manifest = match meta.name {
+                    "node" => Some("package.json"),
+                    "python" if runner.name == "pipenv" => Some("Pipfile"),
+                    "python" => Some("pyproject.toml"),
+                    _ => None,
+                };
+                if let Some(manifest) = manifest {
+                    File::create(dir.path().join(manifest)).unwrap();
+                }
+
+                let found = detector.detect(dir.path());
+                let detected = found.iter().find(|r| r.priority == runner.priority);
+                assert!(
+                    detected.is_some_and(|r| r.ecosystem == meta.ecosystem),
+                    "{} not detected from {:?}",
+                    runner.name,
+                    runner.files
+                );
+            }
+        }
+    }
+}
@@ -1,28 +1,37 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{is_executable, DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Ruby package managers
-/// Priority: Bundler (13) > Rake (14)
+/// Priority: Bundler (13) > Rake (14) = binstubs (14, only without Gemfile/Rakefile)
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
     // Check for Bundler (priority 13)
     let gemfile = dir.join("Gemfile");
     let gemfile_lock = dir.join("Gemfile.lock");
-    if gemfile_lock.exists() && gemfile.exists() {
-        runners.push(DetectedRunner::new(
-            "bundler",
-            "Gemfile.lock",
-            Ecosystem::Ruby,
-            13,
-        ));
+    let bundler = if gemfile_lock.exists() && gemfile.exists() {
+        Some(
+            DetectedRunner::new("bundler", "Gemfile.lock", Ecosystem::Ruby, 13)
+                .with_evidence(dir, &["Gemfile"]),
+        )
     } else if gemfile.exists() {
-        runners.push(DetectedRunner::new(
+        Some(DetectedRunner::new(
             "bundler",
             "Gemfile",
             Ecosystem::Ruby,
             13,
-        ));
+        ))
+    } else {
+        None
+    };
+    if let Some(bundler) = bundler {
+        // Binstubs are evidence too, so `setup` runs bin/setup through `bundle exec`
+        let binstubs: Vec<String> = bin_scripts(dir)
+            .iter()
+            .map(|name| format!("bin/{}", name))
+            .collect();
+        let binstubs: Vec<&str> = binstubs.iter().map(String::as_str).collect();
+        runners.push(bundler.with_evidence(dir, &binstubs));
     }
 
     // Check for Rake (priority 14)
@@ -31,9 +40,81 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
         runners.push(DetectedRunner::new("rake", "Rakefile", Ecosystem::Ruby, 14));
     }
 
+    // Apps without a Gemfile or Rakefile can still drive everything through bin/
+    if runners.is_empty() {
+        if let Some(script) = ruby_binstub(dir) {
+            runners.push(DetectedRunner::new(
+                "binstubs",
+                &format!("bin/{}", script),
+                Ecosystem::Ruby,
+                14,
+            ));
+        }
+    }
+
     runners
 }
 
+/// Executable files directly in `bin/`, sorted by name
+pub fn bin_scripts(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir.join("bin")) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .is_ok_and(|m| m.is_file() && is_executable(&m))
+        })
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The binstub that marks `dir` as a Ruby app: `bin/rails` or `bin/rake`, else
+/// the first executable in `bin/` with a ruby shebang
+fn ruby_binstub(dir: &Path) -> Option<String> {
+    let bin = dir.join("bin");
+    if let Some(name) = ["rails", "rake"]
+        .into_iter()
+        .find(|name| bin.join(name).is_file())
+    {
+        return Some(name.to_string());
+    }
+    bin_scripts(dir).into_iter().find(|name| {
+        std::fs::read_to_string(bin.join(name))
+            .ok()
+            .and_then(|content| content.lines().next().map(str::to_string))
+            .is_some_and(|line| line.starts_with("#!") && line.contains("ruby"))
+    })
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("bundler", &["Gemfile.lock", "Gemfile"], 13),
+    RunnerMeta::new("rake", &["Rakefile"], 14),
+    RunnerMeta::new("binstubs", &["bin/rails", "bin/rake"], 14),
+];
+
+/// Detector for Ruby package managers
+pub struct RubyDetector;
+
+impl Detector for RubyDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "ruby",
+            ecosystem: Ecosystem::Ruby,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,7 +130,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "bundler");
-        assert_eq!(runners[0].detected_file, "Gemfile.lock");
+        assert_eq!(runners[0].detected_file(), "Gemfile.lock");
     }
 
     #[test]
@@ -60,7 +141,7 @@ mod tests {
         let runners = detect(dir.path());
         assert_eq!(runners.len(), 1);
         assert_eq!(runners[0].name, "bundler");
-        assert_eq!(runners[0].detected_file, "Gemfile");
+        assert_eq!(runners[0].detected_file(), "Gemfile");
     }
 
     #[test]
@@ -84,4 +165,54 @@ mod tests {
         assert!(runners.iter().any(|r| r.name == "bundler"));
         assert!(runners.iter().any(|r| r.name == "rake"));
     }
+
+    #[cfg(unix)]
+    fn write_executable(path: &Path, content: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, content).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_binstubs() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("bin")).unwrap();
+        write_executable(
+            &dir.path().join("bin/setup"),
+            "#!/usr/bin/env ruby\nputs 1\n",
+        );
+        write_executable(&dir.path().join("bin/dev"), "#!/bin/sh\n");
+        File::create(dir.path().join("bin/README")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "binstubs");
+        assert_eq!(runners[0].detected_file(), "bin/setup");
+        assert_eq!(bin_scripts(dir.path()), vec!["dev", "setup"]);
+
+        // With a Gemfile, Bundler drives the project and runs bin/ through `bundle exec`
+        File::create(dir.path().join("Gemfile")).unwrap();
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "bundler");
+        assert_eq!(
+            runners[0].build_command("setup", &[]),
+            vec!["bundle", "exec", "bin/setup"]
+        );
+        assert_eq!(
+            runners[0].build_command("rspec", &[]),
+            vec!["bundle", "exec", "rspec"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_binstubs_without_ruby() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("bin")).unwrap();
+        write_executable(&dir.path().join("bin/build"), "#!/bin/bash\n");
+
+        assert!(detect(dir.path()).is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Rust package manager (Cargo)
@@ -10,12 +10,10 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let cargo_lock = dir.join("Cargo.lock");
 
     if cargo_toml.exists() && cargo_lock.exists() {
-        runners.push(DetectedRunner::new(
-            "cargo",
-            "Cargo.toml",
-            Ecosystem::Rust,
-            9,
-        ));
+        runners.push(
+            DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9)
+                .with_evidence(dir, &["Cargo.lock"]),
+        );
     } else if cargo_toml.exists() {
         // Even without lock file, Cargo.toml is sufficient
         runners.push(DetectedRunner::new(
@@ -29,12 +27,76 @@ pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     runners
 }
 
+/// Build the `cargo` command for a task
+///
+/// The target entries `list` shows select their target: `run:example:<name>`
+/// runs an example, `run:bin:<name>` a binary, `run:package:<member>` a
+/// workspace member and `test:<name>` one integration test. Anything else is
+/// a cargo subcommand; those never contain a `:`.
+pub fn build_command(task: &str, extra_args: &[String]) -> Vec<String> {
+    let mut cmd = vec!["cargo".to_string()];
+    if let Some(example) = task.strip_prefix("run:example:") {
+        cmd.extend(["run", "--example", example].map(String::from));
+    } else if let Some(bin) = task.strip_prefix("run:bin:") {
+        cmd.extend(["run", "--bin", bin].map(String::from));
+    } else if let Some(member) = task.strip_prefix("run:package:") {
+        cmd.extend(["run", "-p", member].map(String::from));
+    } else if let Some(test) = task.strip_prefix("test:") {
+        cmd.extend(["test", "--test", test].map(String::from));
+    } else {
+        cmd.push(task.to_string());
+    }
+    cmd.extend(extra_args.iter().cloned());
+    cmd
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("cargo", &["Cargo.toml"], 9)];
+
+/// Detector for Rust package manager (Cargo)
+pub struct RustDetector;
+
+impl Detector for RustDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "rust",
+            ecosystem: Ecosystem::Rust,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::File;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_build_command_targets() {
+        assert_eq!(
+            build_command("run:example:demo", &["--release".to_string()]),
+            vec!["cargo", "run", "--example", "demo", "--release"]
+        );
+        assert_eq!(
+            build_command("test:api", &[]),
+            vec!["cargo", "test", "--test", "api"]
+        );
+        assert_eq!(
+            build_command("run:bin:migrate", &["--".to_string(), "up".to_string()]),
+            vec!["cargo", "run", "--bin", "migrate", "--", "up"]
+        );
+        assert_eq!(
+            build_command("run:package:server", &[]),
+            vec!["cargo", "run", "-p", "server"]
+        );
+        assert_eq!(build_command("clippy", &[]), vec!["cargo", "clippy"]);
+    }
+
     #[test]
     fn test_detect_cargo_with_lock() {
         let dir = tempdir().unwrap();
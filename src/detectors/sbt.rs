@@ -0,0 +1,89 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Scala projects built with sbt
+/// Priority: 16, Maven's (see the java detector)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // project/build.properties pins the sbt version even before build.sbt exists
+    if dir.join("build.sbt").exists() {
+        runners.push(
+            DetectedRunner::new("sbt", "build.sbt", Ecosystem::Java, 16)
+                .with_evidence(dir, &["project/build.properties"]),
+        );
+    } else if dir.join("project").join("build.properties").exists() {
+        runners.push(DetectedRunner::new(
+            "sbt",
+            "project/build.properties",
+            Ecosystem::Java,
+            16,
+        ));
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new(
+    "sbt",
+    &["build.sbt", "project/build.properties"],
+    16,
+)];
+
+/// Detector for Scala projects built with sbt
+pub struct SbtDetector;
+
+impl Detector for SbtDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "sbt",
+            ecosystem: Ecosystem::Java,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_build_sbt() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.sbt")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "sbt");
+        assert_eq!(runners[0].detected_file(), "build.sbt");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Java);
+    }
+
+    #[test]
+    fn test_detect_build_properties() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("project")).unwrap();
+        fs::write(
+            dir.path().join("project/build.properties"),
+            "sbt.version=1.9.7\n",
+        )
+        .unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), "project/build.properties");
+    }
+
+    #[test]
+    fn test_no_sbt() {
+        let dir = tempdir().unwrap();
+        assert!(detect(dir.path()).is_empty());
+    }
+}
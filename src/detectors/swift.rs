@@ -1,24 +1,42 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Swift Package Manager projects
-/// Priority: 19
+/// Priority: 20
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
     let package_swift = dir.join("Package.swift");
     if package_swift.exists() {
-        runners.push(DetectedRunner::new(
-            "swift",
-            "Package.swift",
-            Ecosystem::Swift,
-            19,
-        ));
+        runners.push(
+            DetectedRunner::new("swift", "Package.swift", Ecosystem::Swift, 20)
+                .with_evidence(dir, &["Package.resolved"]),
+        );
     }
 
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("swift", &["Package.swift"], 20)];
+
+/// Detector for Swift Package Manager projects
+pub struct SwiftDetector;
+
+impl Detector for SwiftDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "swift",
+            ecosystem: Ecosystem::Swift,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,138 @@
+use super::{is_tool_installed, DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Terraform / OpenTofu configurations
+/// Priority: 28 (after Make and the build tools below it, so infra never shadows app runners)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    detect_with(dir, is_tool_installed("tofu"))
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[
+    RunnerMeta::new("terraform", &[".terraform.lock.hcl", "*.tf"], 28),
+    RunnerMeta::new("tofu", &[".terraform.lock.hcl", "*.tf"], 28),
+];
+
+/// Detector for Terraform / OpenTofu configurations
+pub struct TerraformDetector;
+
+impl Detector for TerraformDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "terraform",
+            ecosystem: Ecosystem::Infra,
+            runners: RUNNERS,
+        }
+    }
+}
+
+fn detect_with(dir: &Path, tofu_installed: bool) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // The dependency lock file is the strongest signal, then any *.tf file
+    let detected_file = if dir.join(".terraform.lock.hcl").exists() {
+        Some(".terraform.lock.hcl".to_string())
+    } else {
+        first_tf_file(dir)
+    };
+
+    if let Some(file) = detected_file {
+        // OpenTofu is a drop-in replacement, prefer it when the project opts in
+        let binary = if dir.join(".opentofu").exists() || tofu_installed {
+            "tofu"
+        } else {
+            "terraform"
+        };
+        let mut runner = DetectedRunner::new(binary, &file, Ecosystem::Infra, 28);
+        if let Some(tf_file) = first_tf_file(dir) {
+            runner = runner.with_evidence(dir, &[&tf_file]);
+        }
+        runners.push(runner.with_evidence(dir, &[".opentofu"]));
+    }
+
+    runners
+}
+
+/// Find the first `*.tf` file (sorted for stable output)
+fn first_tf_file(dir: &Path) -> Option<String> {
+    let mut tf_files: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| name.ends_with(".tf"))
+        .collect();
+    tf_files.sort();
+    tf_files.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_tf_files() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("variables.tf")).unwrap();
+        File::create(dir.path().join("main.tf")).unwrap();
+
+        let runners = detect_with(dir.path(), false);
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "terraform");
+        assert_eq!(runners[0].detected_file(), "main.tf");
+        assert_eq!(runners[0].ecosystem, Ecosystem::Infra);
+    }
+
+    #[test]
+    fn test_detect_lock_file() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("main.tf")).unwrap();
+        File::create(dir.path().join(".terraform.lock.hcl")).unwrap();
+
+        let runners = detect_with(dir.path(), false);
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), ".terraform.lock.hcl");
+    }
+
+    #[test]
+    fn test_prefer_tofu_with_marker() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("main.tf")).unwrap();
+        File::create(dir.path().join(".opentofu")).unwrap();
+
+        let runners = detect_with(dir.path(), false);
+        assert_eq!(runners[0].name, "tofu");
+    }
+
+    #[test]
+    fn test_prefer_tofu_when_installed() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("main.tf")).unwrap();
+
+        let runners = detect_with(dir.path(), true);
+        assert_eq!(runners[0].name, "tofu");
+    }
+
+    #[test]
+    fn test_ignore_tf_directory() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("modules.tf")).unwrap();
+
+        let runners = detect_with(dir.path(), false);
+        assert!(runners.is_empty());
+    }
+
+    #[test]
+    fn test_no_terraform() {
+        let dir = tempdir().unwrap();
+
+        let runners = detect_with(dir.path(), false);
+        assert!(runners.is_empty());
+    }
+}
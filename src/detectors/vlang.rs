@@ -0,0 +1,61 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect V modules
+/// Priority: 26 (after Make and Bazel, which may wrap a V build)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    if dir.join("v.mod").exists() {
+        runners.push(DetectedRunner::new("v", "v.mod", Ecosystem::V, 26));
+    }
+
+    runners
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("v", &["v.mod"], 26)];
+
+/// Detector for V modules
+pub struct VlangDetector;
+
+impl Detector for VlangDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "vlang",
+            ecosystem: Ecosystem::V,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_v_mod() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("v.mod")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "v");
+        assert_eq!(runners[0].ecosystem, Ecosystem::V);
+    }
+
+    #[test]
+    fn test_no_v() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("main.v")).unwrap();
+
+        // Loose .v files are as likely to be Verilog; only v.mod counts
+        assert!(detect(dir.path()).is_empty());
+    }
+}
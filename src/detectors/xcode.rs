@@ -0,0 +1,209 @@
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
+use std::path::Path;
+
+/// Detect Xcode projects and workspaces
+/// Priority: 19 (ahead of Swift Package Manager, app repos usually want xcodebuild)
+pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
+    let mut runners = Vec::new();
+
+    // .xcodeproj and .xcworkspace are directories, so match on directory entries
+    let mut workspaces = Vec::new();
+    let mut projects = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("xcworkspace") => workspaces.push(file_name),
+                Some("xcodeproj") => projects.push(file_name),
+                _ => {}
+            }
+        }
+    }
+
+    // A workspace wraps its projects (e.g. CocoaPods), so it wins when present
+    workspaces.sort();
+    projects.sort();
+    if let Some(workspace) = workspaces.first() {
+        let mut runner = DetectedRunner::new("xcodebuild", workspace, Ecosystem::Xcode, 19);
+        if let Some(project) = projects.first() {
+            runner = runner.with_evidence(dir, &[project]);
+        }
+        if let Some(scheme) = shared_scheme(dir, workspace, &projects) {
+            runner = runner.with_evidence(dir, &[&scheme]);
+        }
+        runners.push(runner);
+    } else if let Some(project) = projects.first() {
+        runners.push(DetectedRunner::new(
+            "xcodebuild",
+            project,
+            Ecosystem::Xcode,
+            19,
+        ));
+    }
+
+    runners
+}
+
+/// The shared `.xcscheme` a workspace build should use
+///
+/// Shared schemes live in `xcshareddata/xcschemes` of the workspace or its
+/// projects; the one named after the workspace wins, else the first by name.
+fn shared_scheme(dir: &Path, workspace: &str, projects: &[String]) -> Option<String> {
+    let mut schemes: Vec<String> = std::iter::once(workspace)
+        .chain(projects.iter().map(String::as_str))
+        .flat_map(|container| {
+            let schemes_dir = format!("{}/xcshareddata/xcschemes", container);
+            std::fs::read_dir(dir.join(&schemes_dir))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name.ends_with(".xcscheme"))
+                .map(move |name| format!("{}/{}", schemes_dir, name))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    schemes.sort();
+    let named_like_workspace = format!("/{}.xcscheme", workspace.trim_end_matches(".xcworkspace"));
+    schemes
+        .iter()
+        .position(|scheme| scheme.ends_with(&named_like_workspace))
+        .map(|i| schemes.swap_remove(i))
+        .or_else(|| schemes.into_iter().next())
+}
+
+/// Build the `xcodebuild` command for an action (`build`, `test`, ...)
+///
+/// Workspaces can't build without a scheme: a detected shared scheme is used,
+/// else the one Xcode names after the workspace (the CocoaPods layout). An
+/// explicit `-scheme` in the args replaces it.
+pub fn build_command(runner: &DetectedRunner, task: &str, extra_args: &[String]) -> Vec<String> {
+    let mut cmd = vec!["xcodebuild".to_string()];
+    let workspace = runner.detected_file();
+    if workspace.ends_with(".xcworkspace") {
+        cmd.extend(["-workspace".to_string(), workspace.to_string()]);
+        if !extra_args.iter().any(|arg| arg == "-scheme") {
+            let scheme = runner
+                .detected_files
+                .iter()
+                .find_map(|f| f.rsplit('/').next()?.strip_suffix(".xcscheme"))
+                .unwrap_or_else(|| workspace.trim_end_matches(".xcworkspace"));
+            cmd.extend(["-scheme".to_string(), scheme.to_string()]);
+        }
+    }
+    cmd.push(task.to_string());
+    cmd.extend(extra_args.iter().cloned());
+    cmd
+}
+
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new(
+    "xcodebuild",
+    &["*.xcworkspace", "*.xcodeproj"],
+    19,
+)];
+
+/// Detector for Xcode projects and workspaces
+pub struct XcodeDetector;
+
+impl Detector for XcodeDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "xcode",
+            ecosystem: Ecosystem::Xcode,
+            runners: RUNNERS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_xcodeproj() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("MyApp.xcodeproj")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "xcodebuild");
+        assert_eq!(runners[0].detected_file(), "MyApp.xcodeproj");
+    }
+
+    #[test]
+    fn test_detect_xcworkspace_preferred() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("MyApp.xcodeproj")).unwrap();
+        fs::create_dir(dir.path().join("MyApp.xcworkspace")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].detected_file(), "MyApp.xcworkspace");
+    }
+
+    #[test]
+    fn test_workspace_uses_shared_scheme() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("Pods.xcworkspace")).unwrap();
+        let schemes = dir.path().join("App.xcodeproj/xcshareddata/xcschemes");
+        fs::create_dir_all(&schemes).unwrap();
+        File::create(schemes.join("App.xcscheme")).unwrap();
+
+        let runners = detect(dir.path());
+        assert_eq!(
+            build_command(&runners[0], "build", &[]),
+            vec![
+                "xcodebuild",
+                "-workspace",
+                "Pods.xcworkspace",
+                "-scheme",
+                "App",
+                "build"
+            ]
+        );
+
+        // Without a shared scheme, Xcode's default one is named after the workspace
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("MyApp.xcworkspace")).unwrap();
+        let runners = detect(dir.path());
+        assert_eq!(
+            build_command(&runners[0], "test", &[]),
+            vec![
+                "xcodebuild",
+                "-workspace",
+                "MyApp.xcworkspace",
+                "-scheme",
+                "MyApp",
+                "test"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_xcodeproj_file() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("MyApp.xcodeproj")).unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+
+    #[test]
+    fn test_no_xcode() {
+        let dir = tempdir().unwrap();
+
+        let runners = detect(dir.path());
+        assert!(runners.is_empty());
+    }
+}
@@ -1,19 +1,42 @@
-use super::{DetectedRunner, Ecosystem};
+use super::{DetectedRunner, Detector, DetectorMeta, Ecosystem, RunnerMeta};
 use std::path::Path;
 
 /// Detect Zig Build projects
-/// Priority: 20
+/// Priority: 21
 pub fn detect(dir: &Path) -> Vec<DetectedRunner> {
     let mut runners = Vec::new();
 
     let build_zig = dir.join("build.zig");
     if build_zig.exists() {
-        runners.push(DetectedRunner::new("zig", "build.zig", Ecosystem::Zig, 20));
+        runners.push(
+            DetectedRunner::new("zig", "build.zig", Ecosystem::Zig, 21)
+                .with_evidence(dir, &["build.zig.zon"]),
+        );
     }
 
     runners
 }
 
+/// Runners [`detect`] can report
+const RUNNERS: &[RunnerMeta] = &[RunnerMeta::new("zig", &["build.zig"], 21)];
+
+/// Detector for Zig Build projects
+pub struct ZigDetector;
+
+impl Detector for ZigDetector {
+    fn detect(&self, dir: &Path) -> Vec<DetectedRunner> {
+        detect(dir)
+    }
+
+    fn metadata(&self) -> DetectorMeta {
+        DetectorMeta {
+            name: "zig",
+            ecosystem: Ecosystem::Zig,
+            runners: RUNNERS,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
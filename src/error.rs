@@ -6,20 +6,51 @@ pub mod exit_codes {
     pub const GENERIC_ERROR: i32 = 1;
     pub const RUNNER_NOT_FOUND: i32 = 2;
     pub const LOCKFILE_CONFLICT: i32 = 3;
+    pub const NOT_CONFIRMED: i32 = 4;
+    /// The program exists but can't be executed (as shells report it)
+    pub const NOT_EXECUTABLE: i32 = 126;
     pub const TOOL_NOT_INSTALLED: i32 = 127;
 }
 
+/// Failures devrunner reports itself
+///
+/// Each message ends with a `Hint:` line when there is an obvious next step.
 #[derive(Error, Debug)]
 pub enum RunError {
-    #[error("No runner found in {0} levels above the current directory")]
+    #[error("No runner found in {0} levels above the current directory\nHint: Use --levels=N to search further up, or check you're in the right directory")]
     RunnerNotFound(u8),
 
+    #[error("No runner found in project root {0} (marked by {1})\nHint: Set root_markers = [] in config to search above the project root")]
+    NoRunnerInProjectRoot(String, String),
+
+    #[error("No runner found in {0} or any directory above it, up to the filesystem root\nHint: No parent directory has a project file; check you're inside the project")]
+    ReachedFilesystemRoot(String),
+
     #[error("Lockfile conflict detected: {0}")]
     LockfileConflict(String),
 
-    #[error("Tool not installed: {0}")]
+    #[error("Tool not installed: {0}\nHint: If it's installed outside PATH, use --runner-path NAME=PATH")]
     ToolNotInstalled(String),
 
+    #[error("Script \"{script}\" not found in {source_file}\nHint: `devrunner list` shows the scripts this project defines")]
+    ScriptNotFound { script: String, source_file: String },
+
+    #[error(
+        "Failed to execute {program}: {source}\nHint: Check that {program} exists and is on PATH"
+    )]
+    ExecSpawnFailed {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Permission denied running {program}\nHint: Make it executable (chmod +x) or check the permissions of its directory")]
+    PermissionDenied {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("Command execution failed: {0}")]
     CommandFailed(String),
 
@@ -31,15 +62,128 @@ pub enum RunError {
 
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+
+    #[error("Not confirmed: {0}")]
+    NotConfirmed(String),
 }
 
 impl RunError {
+    /// Failure to start `program`, told apart by the OS error: EACCES means
+    /// the file can't be executed, anything else that it couldn't be spawned
+    pub fn spawn_failed(program: &str, source: std::io::Error) -> Self {
+        let program = program.to_string();
+        if source.kind() == std::io::ErrorKind::PermissionDenied {
+            RunError::PermissionDenied { program, source }
+        } else {
+            RunError::ExecSpawnFailed { program, source }
+        }
+    }
+
     pub fn exit_code(&self) -> i32 {
         match self {
-            RunError::RunnerNotFound(_) => exit_codes::RUNNER_NOT_FOUND,
+            RunError::RunnerNotFound(_)
+            | RunError::NoRunnerInProjectRoot(..)
+            | RunError::ReachedFilesystemRoot(_) => exit_codes::RUNNER_NOT_FOUND,
             RunError::LockfileConflict(_) => exit_codes::LOCKFILE_CONFLICT,
             RunError::ToolNotInstalled(_) => exit_codes::TOOL_NOT_INSTALLED,
+            RunError::ExecSpawnFailed { source, .. }
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                exit_codes::TOOL_NOT_INSTALLED
+            }
+            RunError::PermissionDenied { .. } => exit_codes::NOT_EXECUTABLE,
+            RunError::NotConfirmed(_) => exit_codes::NOT_CONFIRMED,
             _ => exit_codes::GENERIC_ERROR,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_exit_codes() {
+        let cases = [
+            (RunError::RunnerNotFound(3), exit_codes::RUNNER_NOT_FOUND),
+            (
+                RunError::NoRunnerInProjectRoot("/repo".into(), ".git".into()),
+                exit_codes::RUNNER_NOT_FOUND,
+            ),
+            (
+                RunError::ReachedFilesystemRoot("/tmp/x".into()),
+                exit_codes::RUNNER_NOT_FOUND,
+            ),
+            (
+                RunError::LockfileConflict("npm and yarn".into()),
+                exit_codes::LOCKFILE_CONFLICT,
+            ),
+            (
+                RunError::ToolNotInstalled("pnpm".into()),
+                exit_codes::TOOL_NOT_INSTALLED,
+            ),
+            (
+                RunError::ScriptNotFound {
+                    script: "tets".into(),
+                    source_file: "package.json".into(),
+                },
+                exit_codes::GENERIC_ERROR,
+            ),
+            (
+                RunError::spawn_failed("pnpm", io::ErrorKind::NotFound.into()),
+                exit_codes::TOOL_NOT_INSTALLED,
+            ),
+            (
+                RunError::spawn_failed("pnpm", io::ErrorKind::Interrupted.into()),
+                exit_codes::GENERIC_ERROR,
+            ),
+            (
+                RunError::spawn_failed("./build.sh", io::ErrorKind::PermissionDenied.into()),
+                exit_codes::NOT_EXECUTABLE,
+            ),
+            (
+                RunError::NotConfirmed("deploy".into()),
+                exit_codes::NOT_CONFIRMED,
+            ),
+            (
+                RunError::ConfigError("bad".into()),
+                exit_codes::GENERIC_ERROR,
+            ),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.exit_code(), code, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_messages_carry_hints() {
+        let error = RunError::RunnerNotFound(3);
+        assert!(error.to_string().contains("\nHint: Use --levels=N"));
+        let error = RunError::ScriptNotFound {
+            script: "tets".into(),
+            source_file: "package.json".into(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Script \"tets\" not found in package.json\nHint: `devrunner list` shows the scripts this project defines"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_eacces_spawn_is_permission_denied() {
+        use std::error::Error as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("build.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+
+        let spawn_error = std::process::Command::new(&script).spawn().unwrap_err();
+        assert_eq!(spawn_error.raw_os_error(), Some(13)); // EACCES
+        let error = RunError::spawn_failed("build.sh", spawn_error);
+        assert!(matches!(error, RunError::PermissionDenied { .. }));
+        assert_eq!(error.exit_code(), exit_codes::NOT_EXECUTABLE);
+        assert!(error.source().is_some());
+    }
+}
@@ -1,52 +1,155 @@
-/// Fuzzy string matching utilities for script suggestions
-/// 
-/// This module implements Levenshtein distance for finding similar strings,
-/// useful for suggesting corrections when a user types an incorrect command.
+//! Fuzzy string matching utilities for script suggestions
+//!
+//! This module implements Levenshtein distance for finding similar strings,
+//! useful for suggesting corrections when a user types an incorrect command.
 
 /// Calculate the Levenshtein distance between two strings
-/// 
+///
 /// This is a classic dynamic programming algorithm that measures the minimum
 /// number of single-character edits (insertions, deletions, substitutions)
 /// required to change one string into another.
-/// 
+///
+/// Only two rows of the DP matrix are kept alive, so memory is
+/// O(min(len_a, len_b)) instead of O(len_a * len_b).
+///
 /// # Rust Concepts Learned:
-/// - Dynamic programming with 2D vectors
+/// - Dynamic programming with rolling buffers
 /// - String slicing with .chars()
-/// - Iterators and enumerate()
+/// - std::mem::swap to reuse allocations
 pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    bounded_distance(a, b, usize::MAX)
+}
+
+/// Calculate the Levenshtein distance only if it is at most `max`
+///
+/// Returns `None` as soon as the distance is known to exceed `max`, which lets
+/// the suggestion path skip obviously-dissimilar names cheaply.
+pub fn levenshtein_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let distance = bounded_distance(a, b, max);
+    if distance > max {
+        None
+    } else {
+        Some(distance)
+    }
+}
+
+/// Shared implementation: returns the exact distance, or `max + 1` once it exceeds `max`
+fn bounded_distance(a: &str, b: &str, max: usize) -> usize {
     let a_chars: Vec<char> = a.chars().collect();
     let b_chars: Vec<char> = b.chars().collect();
-    
-    let len_a = a_chars.len();
-    let len_b = b_chars.len();
-    
+
+    // Keep the shorter string on the row axis to minimise the buffer size
+    let (long, short) = if a_chars.len() >= b_chars.len() {
+        (a_chars, b_chars)
+    } else {
+        (b_chars, a_chars)
+    };
+    let exceeded = max.saturating_add(1);
+
+    // The length difference is a lower bound on the distance
+    if long.len() - short.len() > max {
+        return exceeded;
+    }
+
     // Early exit for empty strings
-    if len_a == 0 { return len_b; }
-    if len_b == 0 { return len_a; }
-    
-    // Create a 2D matrix for dynamic programming
-    let mut matrix: Vec<Vec<usize>> = vec![vec![0; len_b + 1]; len_a + 1];
-    
-    // Initialize first row and column
-    for i in 0..=len_a {
-        matrix[i][0] = i;
-    }
-    for j in 0..=len_b {
-        matrix[0][j] = j;
-    }
-    
-    // Fill in the rest of the matrix
-    for i in 1..=len_a {
-        for j in 1..=len_b {
-            let cost = if a_chars[i - 1] == b_chars[j - 1] { 0 } else { 1 };
-            
-            matrix[i][j] = (matrix[i - 1][j] + 1)           // deletion
-                .min(matrix[i][j - 1] + 1)                   // insertion
-                .min(matrix[i - 1][j - 1] + cost);           // substitution
+    if short.is_empty() {
+        return long.len();
+    }
+
+    let mut previous: Vec<usize> = (0..=short.len()).collect();
+    let mut current: Vec<usize> = vec![0; short.len() + 1];
+
+    for (i, long_char) in long.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+
+        for (j, short_char) in short.iter().enumerate() {
+            let cost = if long_char == short_char { 0 } else { 1 };
+
+            current[j + 1] = (previous[j + 1] + 1) // deletion
+                .min(current[j] + 1) // insertion
+                .min(previous[j] + cost); // substitution
+            row_min = row_min.min(current[j + 1]);
+        }
+
+        // Distances never decrease from one row to the next
+        if row_min > max {
+            return exceeded;
         }
+        std::mem::swap(&mut previous, &mut current);
     }
-    
-    matrix[len_a][len_b]
+
+    previous[short.len()]
+}
+
+/// One step of turning an input into a suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    /// The character is the same in both
+    Keep(char),
+    /// The input character (first) is replaced by the suggestion's (second)
+    Substitute(char, char),
+    /// The suggestion has a character the input lacks
+    Insert(char),
+    /// The input has a character the suggestion lacks
+    Delete(char),
+}
+
+/// The edits behind `levenshtein_distance(from, to)`, in order
+///
+/// Unlike the distance, this needs the whole DP matrix, which is walked back
+/// from the bottom-right corner. Keeps and substitutions are preferred over
+/// insertions and deletions when several paths are equally short.
+pub fn edit_operations(from: &str, to: &str) -> Vec<EditOp> {
+    let a: Vec<char> = from.chars().collect();
+    let b: Vec<char> = to.chars().collect();
+    let cost = |i: usize, j: usize| usize::from(a[i - 1] != b[j - 1]);
+
+    // dist[i][j]: edits turning the first i chars of `from` into the first j of `to`
+    let mut dist: Vec<Vec<usize>> = (0..=a.len())
+        .map(|i| {
+            (0..=b.len())
+                .map(|j| {
+                    if i == 0 {
+                        j
+                    } else if j == 0 {
+                        i
+                    } else {
+                        0
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            dist[i][j] = (dist[i - 1][j] + 1) // deletion
+                .min(dist[i][j - 1] + 1) // insertion
+                .min(dist[i - 1][j - 1] + cost(i, j)); // substitution
+        }
+    }
+
+    let (mut i, mut j) = (a.len(), b.len());
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && dist[i][j] == dist[i - 1][j - 1] + cost(i, j) {
+            ops.push(if cost(i, j) == 0 {
+                EditOp::Keep(a[i - 1])
+            } else {
+                EditOp::Substitute(a[i - 1], b[j - 1])
+            });
+            i -= 1;
+            j -= 1;
+        } else if j > 0 && dist[i][j] == dist[i][j - 1] + 1 {
+            ops.push(EditOp::Insert(b[j - 1]));
+            j -= 1;
+        } else {
+            ops.push(EditOp::Delete(a[i - 1]));
+            i -= 1;
+        }
+    }
+    ops.reverse();
+    ops
 }
 
 /// Calculate similarity score between 0.0 and 1.0
@@ -54,17 +157,57 @@ pub fn levenshtein_distance(a: &str, b: &str) -> usize {
 pub fn similarity_score(a: &str, b: &str) -> f64 {
     let distance = levenshtein_distance(a, b);
     let max_len = a.len().max(b.len());
-    
+
     if max_len == 0 {
         return 1.0;
     }
-    
+
     1.0 - (distance as f64 / max_len as f64)
 }
 
+/// A script name together with the file it was discovered in (e.g. "Makefile")
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledScript {
+    pub name: String,
+    pub source: String,
+}
+
+/// Score every item whose name is at least `threshold` similar to the input
+/// Returns matches sorted by similarity (best first); ties keep the input order
+fn rank_by_similarity<'a, T>(
+    input: &str,
+    items: &'a [T],
+    name: impl Fn(&T) -> &str,
+    threshold: f64,
+) -> Vec<(&'a T, f64)> {
+    let input_lower = input.to_lowercase();
+
+    let mut matches: Vec<(&T, f64)> = items
+        .iter()
+        .filter_map(|item| {
+            let script_lower = name(item).to_lowercase();
+            let max_len = input_lower.len().max(script_lower.len());
+            if max_len == 0 {
+                return Some((item, 1.0));
+            }
+            // Largest distance that can still reach the threshold
+            let max_distance = ((1.0 - threshold) * max_len as f64).ceil() as usize;
+            let distance = levenshtein_within(&input_lower, &script_lower, max_distance)?;
+            let score = 1.0 - (distance as f64 / max_len as f64);
+            Some((item, score))
+        })
+        .filter(|(_, score)| *score >= threshold)
+        .collect();
+
+    // Sort by score descending (best match first); sort_by is stable
+    matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    matches
+}
+
 /// Find the best matching scripts for a given input
 /// Returns matches sorted by similarity (best first)
-/// 
+///
 /// # Rust Concepts Learned:
 /// - Sorting with sort_by() and custom comparators
 /// - Closures with |a, b| syntax
@@ -74,22 +217,19 @@ pub fn find_similar_scripts<'a>(
     available_scripts: &'a [String],
     threshold: f64,
 ) -> Vec<(&'a str, f64)> {
-    let input_lower = input.to_lowercase();
-    
-    let mut matches: Vec<(&str, f64)> = available_scripts
-        .iter()
-        .map(|script| {
-            let script_lower = script.to_lowercase();
-            let score = similarity_score(&input_lower, &script_lower);
-            (script.as_str(), score)
-        })
-        .filter(|(_, score)| *score >= threshold)
-        .collect();
-    
-    // Sort by score descending (best match first)
-    matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    
-    matches
+    rank_by_similarity(input, available_scripts, |s| s.as_str(), threshold)
+        .into_iter()
+        .map(|(script, score)| (script.as_str(), score))
+        .collect()
+}
+
+/// Find the best matching scripts across several sources, keeping their labels
+pub fn find_similar_labeled<'a>(
+    input: &str,
+    available_scripts: &'a [LabeledScript],
+    threshold: f64,
+) -> Vec<(&'a LabeledScript, f64)> {
+    rank_by_similarity(input, available_scripts, |s| s.name.as_str(), threshold)
 }
 
 /// Suggest the best matching script if one is similar enough
@@ -98,10 +238,21 @@ pub fn suggest_script(input: &str, available_scripts: &[String]) -> Option<Strin
     matches.first().map(|(script, _)| script.to_string())
 }
 
+/// Suggest the best matching script from any source, with the file it came from
+pub fn suggest_labeled_script(
+    input: &str,
+    available_scripts: &[LabeledScript],
+) -> Option<LabeledScript> {
+    let matches = find_similar_labeled(input, available_scripts, 0.5);
+    matches.first().map(|(script, _)| (*script).clone())
+}
+
 /// Check if input is an exact match (case-insensitive)
 pub fn is_exact_match(input: &str, available_scripts: &[String]) -> bool {
     let input_lower = input.to_lowercase();
-    available_scripts.iter().any(|s| s.to_lowercase() == input_lower)
+    available_scripts
+        .iter()
+        .any(|s| s.to_lowercase() == input_lower)
 }
 
 #[cfg(test)]
@@ -119,6 +270,104 @@ mod tests {
         assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
     }
 
+    #[test]
+    fn test_levenshtein_within() {
+        assert_eq!(levenshtein_within("kitten", "sitting", 3), Some(3));
+        assert_eq!(levenshtein_within("kitten", "sitting", 2), None);
+        // Length difference alone exceeds the bound
+        assert_eq!(levenshtein_within("a", "abcdef", 2), None);
+        assert_eq!(levenshtein_within("", "", 0), Some(0));
+    }
+
+    /// Original full-matrix implementation, kept as a reference for the property test
+    fn reference_levenshtein(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut matrix = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in matrix[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+                matrix[i][j] = (matrix[i - 1][j] + 1)
+                    .min(matrix[i][j - 1] + 1)
+                    .min(matrix[i - 1][j - 1] + cost);
+            }
+        }
+        matrix[a.len()][b.len()]
+    }
+
+    #[test]
+    fn test_levenshtein_matches_reference() {
+        // Small xorshift PRNG so the property test is deterministic
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let alphabet: Vec<char> = "abcdé-:".chars().collect();
+        let random_string = |next: &mut dyn FnMut() -> u64| {
+            let len = (next() % 10) as usize;
+            (0..len)
+                .map(|_| alphabet[(next() % alphabet.len() as u64) as usize])
+                .collect::<String>()
+        };
+
+        for _ in 0..2000 {
+            let a = random_string(&mut next);
+            let b = random_string(&mut next);
+            let expected = reference_levenshtein(&a, &b);
+            assert_eq!(levenshtein_distance(&a, &b), expected, "{a:?} vs {b:?}");
+
+            let max = (next() % 6) as usize;
+            let bounded = levenshtein_within(&a, &b, max);
+            if expected <= max {
+                assert_eq!(bounded, Some(expected), "{a:?} vs {b:?} (max {max})");
+            } else {
+                assert_eq!(bounded, None, "{a:?} vs {b:?} (max {max})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_operations() {
+        use EditOp::*;
+
+        assert_eq!(
+            edit_operations("tets", "test"),
+            vec![
+                Keep('t'),
+                Keep('e'),
+                Substitute('t', 's'),
+                Substitute('s', 't')
+            ]
+        );
+        assert_eq!(
+            edit_operations("buld", "build"),
+            vec![Keep('b'), Keep('u'), Insert('i'), Keep('l'), Keep('d')]
+        );
+        assert_eq!(
+            edit_operations("lintt", "lint"),
+            vec![Keep('l'), Keep('i'), Keep('n'), Delete('t'), Keep('t')]
+        );
+        assert_eq!(edit_operations("", "go"), vec![Insert('g'), Insert('o')]);
+
+        // As many non-keep steps as the distance says
+        for (a, b) in [("kitten", "sitting"), ("srve", "serve"), ("dev", "deploy")] {
+            let edits = edit_operations(a, b)
+                .into_iter()
+                .filter(|op| !matches!(op, Keep(_)))
+                .count();
+            assert_eq!(edits, levenshtein_distance(a, b), "{} -> {}", a, b);
+        }
+    }
+
     #[test]
     fn test_similarity_score() {
         assert!((similarity_score("abc", "abc") - 1.0).abs() < 0.001);
@@ -146,21 +395,51 @@ mod tests {
 
     #[test]
     fn test_suggest_script() {
-        let scripts = vec![
-            "dev".to_string(),
-            "build".to_string(),
-            "test".to_string(),
-        ];
+        let scripts = vec!["dev".to_string(), "build".to_string(), "test".to_string()];
 
         assert_eq!(suggest_script("tets", &scripts), Some("test".to_string()));
         assert_eq!(suggest_script("bld", &scripts), Some("build".to_string()));
         assert_eq!(suggest_script("xyz123", &scripts), None);
     }
 
+    fn labeled(name: &str, source: &str) -> LabeledScript {
+        LabeledScript {
+            name: name.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_suggest_labeled_script() {
+        // package.json has a near miss, the Makefile has the closer match
+        let scripts = vec![
+            labeled("bundle", "package.json"),
+            labeled("lint", "package.json"),
+            labeled("build", "Makefile"),
+        ];
+
+        let matches = find_similar_labeled("buld", &scripts, 0.5);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].0, &labeled("build", "Makefile"));
+        assert_eq!(matches[1].0, &labeled("bundle", "package.json"));
+        assert_eq!(
+            suggest_labeled_script("buld", &scripts),
+            Some(labeled("build", "Makefile"))
+        );
+
+        // Equal scores keep source order
+        let tied = vec![labeled("test", "package.json"), labeled("task", "Makefile")];
+        assert_eq!(
+            suggest_labeled_script("tast", &tied).unwrap().source,
+            "package.json"
+        );
+        assert_eq!(suggest_labeled_script("xyz123", &scripts), None);
+    }
+
     #[test]
     fn test_is_exact_match() {
         let scripts = vec!["dev".to_string(), "Build".to_string()];
-        
+
         assert!(is_exact_match("dev", &scripts));
         assert!(is_exact_match("DEV", &scripts));
         assert!(is_exact_match("build", &scripts));
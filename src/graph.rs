@@ -0,0 +1,358 @@
+use crate::detectors::{DetectedRunner, Ecosystem};
+use crate::scripts::{self, read_manifest};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Output format for `devrunner graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Indented tree, one root per line
+    #[default]
+    Tree,
+    /// Graphviz DOT (`devrunner graph --format=dot | dot -Tsvg`)
+    Dot,
+}
+
+/// Scripts and the scripts each one triggers, in declaration order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptGraph {
+    pub nodes: Vec<String>,
+    /// `(from, to)`: running `from` also runs `to`
+    pub edges: Vec<(String, String)>,
+}
+
+impl ScriptGraph {
+    fn add_node(&mut self, name: &str) {
+        if !self.nodes.iter().any(|n| n == name) {
+            self.nodes.push(name.to_string());
+        }
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str) {
+        let edge = (from.to_string(), to.to_string());
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Scripts `name` triggers directly
+    pub fn children(&self, name: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(from, _)| from == name)
+            .map(|(_, to)| to.as_str())
+            .collect()
+    }
+}
+
+/// Dependency graph for the detected runner
+///
+/// Makefiles contribute their prerequisites and npm-style runners their
+/// `pre<name>`/`post<name>` hooks; other runners don't express dependencies.
+pub fn graph_for_runner(runner: &DetectedRunner, project_dir: &Path) -> Option<ScriptGraph> {
+    match runner.ecosystem {
+        Ecosystem::Generic if runner.name == "make" => {
+            read_manifest(project_dir.join(runner.detected_file()))
+                .map(|content| makefile_graph(&content))
+        }
+        Ecosystem::NodeJs => scripts::parse_package_json_scripts(project_dir).map(|list| {
+            let names: Vec<String> = list.scripts.into_iter().map(|s| s.name).collect();
+            npm_graph(&names)
+        }),
+        _ => None,
+    }
+}
+
+/// Targets of a Makefile and the prerequisites that are themselves targets
+///
+/// File prerequisites (`main.o: main.c`) aren't scripts, so they're left out.
+pub fn makefile_graph(content: &str) -> ScriptGraph {
+    // Join `\` continuations so long prerequisite lists parse as one rule
+    let content = content.replace("\r\n", "\n").replace("\\\n", " ");
+    let mut rules: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+
+    for line in content.lines() {
+        if line.starts_with('\t') || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let line = line.split('#').next().unwrap_or_default();
+        let Some((targets, prerequisites)) = line.split_once(':') else {
+            continue;
+        };
+        // `:=` and `::=` are assignments, `::` a double-colon rule
+        let prerequisites = prerequisites.trim_start_matches(':');
+        if prerequisites.starts_with('=') || targets.contains('=') || targets.contains('$') {
+            continue;
+        }
+        let targets: Vec<&str> = targets
+            .split_whitespace()
+            .filter(|t| !t.starts_with('.') && !t.contains('%'))
+            .collect();
+        if targets.is_empty() {
+            continue;
+        }
+        // A `;` starts an inline recipe, `|` only separates order-only prerequisites
+        let prerequisites = prerequisites.split(';').next().unwrap_or_default();
+        let prerequisites: Vec<&str> = prerequisites
+            .split_whitespace()
+            .filter(|p| *p != "|" && !p.contains('$'))
+            .collect();
+        rules.push((targets, prerequisites));
+    }
+
+    let mut graph = ScriptGraph::default();
+    for (targets, _) in &rules {
+        for target in targets {
+            graph.add_node(target);
+        }
+    }
+    let known: HashSet<String> = graph.nodes.iter().cloned().collect();
+    for (targets, prerequisites) in &rules {
+        for target in targets {
+            for prerequisite in prerequisites.iter().filter(|p| known.contains(**p)) {
+                graph.add_edge(target, prerequisite);
+            }
+        }
+    }
+    graph
+}
+
+/// npm runs `pre<name>` before and `post<name>` after a script when they exist
+pub fn npm_graph(script_names: &[String]) -> ScriptGraph {
+    let mut graph = ScriptGraph::default();
+    for name in script_names {
+        graph.add_node(name);
+    }
+    for name in script_names {
+        for hook in [format!("pre{}", name), format!("post{}", name)] {
+            if script_names.contains(&hook) {
+                graph.add_edge(name, &hook);
+            }
+        }
+    }
+    graph
+}
+
+/// Steps a run of `script` goes through, in order
+///
+/// Makefile prerequisites come before the target that needs them and npm
+/// hooks wrap their script; other runners run just `script`.
+pub fn plan_for_runner(
+    runner: &DetectedRunner,
+    project_dir: &Path,
+    script: &str,
+) -> Result<Vec<String>, String> {
+    match graph_for_runner(runner, project_dir) {
+        Some(graph) if runner.ecosystem == Ecosystem::NodeJs => {
+            let hooks = graph.children(script);
+            let mut plan = Vec::new();
+            for step in [
+                format!("pre{}", script),
+                script.to_string(),
+                format!("post{}", script),
+            ] {
+                if step == script || hooks.contains(&step.as_str()) {
+                    plan.push(step);
+                }
+            }
+            Ok(plan)
+        }
+        Some(graph) => execution_plan(&graph, script),
+        None => Ok(vec![script.to_string()]),
+    }
+}
+
+/// `target` and everything it depends on, dependencies first, each once
+///
+/// Errors naming the cycle (`a -> b -> a`) when a dependency leads back to
+/// itself. A target the graph doesn't know is a plan of its own.
+pub fn execution_plan(graph: &ScriptGraph, target: &str) -> Result<Vec<String>, String> {
+    fn visit<'a>(
+        graph: &'a ScriptGraph,
+        name: &'a str,
+        path: &mut Vec<&'a str>,
+        plan: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if let Some(start) = path.iter().position(|step| *step == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name);
+            return Err(format!("Dependency cycle: {}", cycle.join(" -> ")));
+        }
+        if plan.iter().any(|step| step == name) {
+            return Ok(());
+        }
+        path.push(name);
+        for child in graph.children(name) {
+            visit(graph, child, path, plan)?;
+        }
+        path.pop();
+        plan.push(name.to_string());
+        Ok(())
+    }
+
+    let mut plan = Vec::new();
+    visit(graph, target, &mut Vec::new(), &mut plan)?;
+    Ok(plan)
+}
+
+/// Indented tree with each entry point (nothing depends on it) at the top level
+pub fn render_tree(graph: &ScriptGraph) -> String {
+    fn walk<'a>(
+        graph: &'a ScriptGraph,
+        name: &'a str,
+        depth: usize,
+        path: &mut Vec<&'a str>,
+        printed: &mut HashSet<&'a str>,
+        out: &mut String,
+    ) {
+        let indent = "  ".repeat(depth);
+        if path.contains(&name) {
+            out.push_str(&format!("{}{} (cycle)\n", indent, name));
+            return;
+        }
+        out.push_str(&format!("{}{}\n", indent, name));
+        printed.insert(name);
+        path.push(name);
+        for child in graph.children(name) {
+            walk(graph, child, depth + 1, path, printed, out);
+        }
+        path.pop();
+    }
+
+    let has_parent: HashSet<&str> = graph.edges.iter().map(|(_, to)| to.as_str()).collect();
+    let mut out = String::new();
+    let mut printed = HashSet::new();
+    for name in graph
+        .nodes
+        .iter()
+        .filter(|n| !has_parent.contains(n.as_str()))
+    {
+        walk(graph, name, 0, &mut Vec::new(), &mut printed, &mut out);
+    }
+    // Scripts only reachable through a cycle have no entry point of their own
+    for name in &graph.nodes {
+        if !printed.contains(name.as_str()) {
+            walk(graph, name, 0, &mut Vec::new(), &mut printed, &mut out);
+        }
+    }
+    out
+}
+
+/// Graphviz DOT for the graph
+pub fn render_dot(graph: &ScriptGraph) -> String {
+    let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut out = String::from("digraph scripts {\n");
+    for name in &graph.nodes {
+        out.push_str(&format!("  {};\n", quote(name)));
+    }
+    for (from, to) in &graph.edges {
+        out.push_str(&format!("  {} -> {};\n", quote(from), quote(to)));
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &ScriptGraph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_makefile_graph_edges() {
+        let makefile = "\
+CC := gcc
+.PHONY: all build test clean
+
+all: build test ## everything
+build: deps src/main.c
+\t$(CC) -o app src/main.c
+test: build | deps
+\t./app --test
+deps lint: \\
+    fmt
+fmt:
+clean:
+\trm -rf app
+";
+        let graph = makefile_graph(makefile);
+        assert_eq!(
+            graph.nodes,
+            vec!["all", "build", "test", "deps", "lint", "fmt", "clean"]
+        );
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("all", "build"),
+                ("all", "test"),
+                ("build", "deps"),
+                ("test", "build"),
+                ("test", "deps"),
+                ("deps", "fmt"),
+                ("lint", "fmt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_npm_graph_pre_post() {
+        let names: Vec<String> = ["build", "prebuild", "test", "posttest", "lint"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let graph = npm_graph(&names);
+        assert_eq!(
+            edges(&graph),
+            vec![("build", "prebuild"), ("test", "posttest")]
+        );
+    }
+
+    #[test]
+    fn test_render_tree_and_dot() {
+        let graph = makefile_graph("all: build test\nbuild: deps\ntest: build\ndeps:\n");
+        assert_eq!(
+            render_tree(&graph),
+            "all\n  build\n    deps\n  test\n    build\n      deps\n"
+        );
+        assert_eq!(
+            render_dot(&graph),
+            "digraph scripts {\n  \"all\";\n  \"build\";\n  \"test\";\n  \"deps\";\n  \"all\" -> \"build\";\n  \"all\" -> \"test\";\n  \"build\" -> \"deps\";\n  \"test\" -> \"build\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_execution_plan_order() {
+        let graph = makefile_graph("all: build test\nbuild: deps\ntest: build\ndeps:\nlint:\n");
+        assert_eq!(
+            execution_plan(&graph, "all").unwrap(),
+            vec!["deps", "build", "test", "all"]
+        );
+        assert_eq!(
+            execution_plan(&graph, "test").unwrap(),
+            vec!["deps", "build", "test"]
+        );
+        assert_eq!(execution_plan(&graph, "lint").unwrap(), vec!["lint"]);
+        assert_eq!(execution_plan(&graph, "app.o").unwrap(), vec!["app.o"]);
+    }
+
+    #[test]
+    fn test_execution_plan_cycle() {
+        let graph = makefile_graph("all: a\na: b\nb: c\nc: a\n");
+        assert_eq!(
+            execution_plan(&graph, "all").unwrap_err(),
+            "Dependency cycle: a -> b -> c -> a"
+        );
+    }
+
+    #[test]
+    fn test_render_tree_cycle() {
+        let graph = makefile_graph("a: b\nb: a\n");
+        assert_eq!(render_tree(&graph), "a\n  b\n    a (cycle)\n");
+    }
+}
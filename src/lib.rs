@@ -1,12 +1,22 @@
+pub mod changes;
+pub mod choices;
 pub mod cli;
+pub mod completions;
 pub mod config;
 pub mod detectors;
+pub mod doctor;
 pub mod error;
 pub mod fuzzy;
+pub mod graph;
+pub mod matrix;
 pub mod output;
 pub mod runner;
+pub mod safety;
 pub mod scripts;
+pub mod signals;
+pub mod telemetry;
 pub mod update;
+pub mod workspaces;
 
 pub use cli::Cli;
 pub use config::Config;
@@ -1,15 +1,26 @@
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
-use devrunner::cli::{Cli, Commands};
+use devrunner::choices::ChoiceStore;
+use devrunner::cli::{AliasCommand, CacheCommand, Cli, Commands};
 use devrunner::config::Config;
+use devrunner::detectors::{
+    builtin_detectors, DetectOptions, DetectedRunner, DetectorMeta, RunnerMeta,
+};
 use devrunner::error::exit_codes;
-use devrunner::output;
-use devrunner::runner::{check_conflicts, execute, search_runners};
+use devrunner::graph::{self, GraphFormat};
+use devrunner::output::{self, Severity};
+use devrunner::runner::{
+    check_conflicts, execute, execute_install, execute_shell, execute_tool, exit_code_from_status,
+    exit_hint, exit_signal, find_preferred, level_with_runner, profile_search,
+    search_runner_levels, search_runners, shell_join, ExecOptions, LevelRunners, SearchDepth,
+};
 use devrunner::scripts;
+use devrunner::telemetry::Tracer;
 use devrunner::update;
 use std::env;
 use std::io;
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 fn main() {
     // Check for internal update flag (used by background updater)
@@ -24,52 +35,295 @@ fn main() {
         return;
     }
 
-    // Parse CLI arguments
-    let cli = Cli::parse();
+    // Parse CLI arguments; a parse error after a mistyped subcommand says which one was meant
+    let mut cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            use clap::error::ErrorKind;
+            let own_args: Vec<&String> = args.iter().skip(1).take_while(|a| *a != "--").collect();
+            let suggestion = matches!(
+                e.kind(),
+                ErrorKind::UnknownArgument
+                    | ErrorKind::InvalidSubcommand
+                    | ErrorKind::MissingRequiredArgument
+            )
+            .then(|| own_args.iter().find(|a| !a.starts_with('-')))
+            .flatten()
+            .and_then(|word| devrunner::cli::suggest_subcommand(word).map(|name| (word, name)));
+            match suggestion {
+                Some((word, name)) => {
+                    let _ = e.print();
+                    eprintln!("\n{}", subcommand_suggestion(word, &name));
+                    process::exit(e.exit_code());
+                }
+                None => e.exit(),
+            }
+        }
+    };
+    output::set_color_choice(cli.color);
+    DEBUG.store(cli.debug, Ordering::Relaxed);
+
+    // `--version --json` prints build metadata for bug reports
+    if cli.version {
+        if cli.json {
+            let config = Config::load();
+            let info =
+                update::version_info(config.get_auto_update() && !update::is_update_disabled());
+            println!("{}", serde_json::to_string_pretty(&info).unwrap());
+        } else {
+            print!("{}", Cli::command().render_version());
+        }
+        return;
+    }
+
+    // --args-file: appended after the inline args, so both can be combined
+    if let Some(path) = cli.args_file.clone() {
+        match devrunner::runner::read_args_file(&path) {
+            Ok(args) => cli.args.extend(args),
+            Err(e) => exit_with_error(&e),
+        }
+    }
 
     // Load configuration
-    let config = Config::load();
+    let mut config = Config::load();
+
+    // Invalid custom runners are skipped rather than failing every command
+    for problem in config.take_invalid_custom_runners() {
+        output::warn(&format!(
+            "Skipping invalid [[custom_runners]] entry: {}",
+            problem
+        ));
+    }
 
     // Merge config with CLI arguments
     let verbose = cli.verbose || config.get_verbose();
-    let quiet = cli.quiet || config.get_quiet();
-    let max_levels = cli.levels;
+    let quiet = cli.quiet.max(config.get_quiet());
+    let safe_mode = cli.ignore_scripts || config.get_safe_mode();
+    // --offline and RUN_NO_UPDATE both mean: nothing that reaches the network
+    let offline = update::is_offline(cli.offline);
+    let bazel_query = if offline {
+        None
+    } else {
+        config.get_bazel_query_limit()
+    };
+    let max_levels = cli.levels.max_levels();
     let mut ignore_list = config.ignore_tools.clone();
     ignore_list.extend(cli.ignore.clone());
+    let detect_options = DetectOptions {
+        ignore_list,
+        priorities: config.priorities.clone(),
+        custom_runners: config.custom_runners.clone(),
+        root_markers: config.get_root_markers(),
+        enabled_ecosystems: config.get_enabled_ecosystems(),
+        disabled_ecosystems: config.get_disabled_ecosystems(),
+    };
+    let exec_options = ExecOptions {
+        dry_run: cli.dry_run,
+        json: cli.json,
+        verbose,
+        quiet,
+        tee: cli.tee.clone(),
+        output: cli.output,
+        no_venv: cli.no_venv,
+        ignore_scripts: safe_mode,
+        tail_on_failure: cli.tail_on_failure,
+        env_from: cli.env_from.clone(),
+        in_container: cli.in_container,
+        print_env: cli.print_env,
+        no_stdin: cli.no_stdin,
+        default_args: if cli.no_default_args {
+            Default::default()
+        } else {
+            config.default_args.clone()
+        },
+        node_version_manager: config.get_use_node_version_manager(),
+        runner_paths: config.runner_paths_with(&cli.runner_path),
+        clean_env: cli.clean_env.then(|| config.clean_env_allowlist.clone()),
+        // Filled in per run from --select
+        select: Vec::new(),
+    };
+
+    // Unknown runners in [priorities] are likely typos, but never fatal
+    for name in config.unknown_priority_runners() {
+        output::warn(&format!(
+            "Unknown runner \"{}\" in [priorities], ignoring",
+            name
+        ));
+    }
+    for name in config.unknown_ecosystems() {
+        output::warn(&format!(
+            "Unknown ecosystem \"{}\" in enabled_ecosystems/disabled_ecosystems, ignoring (see --list-ecosystems)",
+            name
+        ));
+    }
 
     // Check for update notification
-    update::check_update_notification(quiet);
+    update::check_update_notification(quiet > 0 || offline);
+
+    if cli.list_ecosystems {
+        handle_list_ecosystems(cli.json);
+        return;
+    }
+
+    if cli.profile_detection {
+        handle_profile_detection(&detect_options, cli.levels, cli.json);
+        return;
+    }
+
+    if cli.forget {
+        handle_forget();
+        if cli.command.is_none() && cli.subcommand.is_none() {
+            return;
+        }
+    }
 
     // Handle subcommands
     match &cli.subcommand {
-        Some(Commands::Completions { shell }) => {
-            let mut cmd = Cli::command();
-            let name = cmd.get_name().to_string();
-            generate(*shell, &mut cmd, name, &mut io::stdout());
+        Some(Commands::Completions {
+            shell,
+            install,
+            force,
+        }) => {
+            handle_completions_command(*shell, *install, *force);
+            return;
+        }
+        Some(Commands::List {
+            all_workspaces: true,
+            json,
+            grep,
+            filter_regex,
+            head,
+            ..
+        }) => {
+            let filter = scripts::ScriptFilter::new(grep.as_deref(), filter_regex.clone(), *head);
+            handle_list_workspaces_command(max_levels, *json, filter);
+            return;
+        }
+        Some(Commands::List {
+            recursive: true,
+            depth,
+            json,
+            grep,
+            filter_regex,
+            head,
+            ..
+        }) => {
+            let filter = scripts::ScriptFilter::new(grep.as_deref(), filter_regex.clone(), *head);
+            handle_list_recursive_command(
+                *depth,
+                config.get_discovery_threads(),
+                *json,
+                verbose,
+                filter,
+            );
+            return;
+        }
+        Some(Commands::List {
+            since,
+            grep,
+            filter_regex,
+            head,
+            ..
+        }) => {
+            handle_list_command(
+                &detect_options,
+                max_levels,
+                verbose,
+                since.as_deref(),
+                bazel_query,
+                scripts::ScriptFilter::new(grep.as_deref(), filter_regex.clone(), *head),
+            );
             return;
         }
-        Some(Commands::List) => {
-            handle_list_command(&ignore_list, max_levels, verbose);
+        Some(Commands::Graph { format }) => {
+            handle_graph_command(&detect_options, max_levels, verbose, *format);
             return;
         }
+        Some(Commands::Alias { action }) => {
+            handle_alias_command(&config, action);
+        }
+        Some(Commands::Cache { action }) => {
+            handle_cache_command(action);
+        }
         Some(Commands::Why) => {
-            handle_why_command(&ignore_list, max_levels, verbose);
+            handle_why_command(&detect_options, cli.levels, cli.prefer.as_deref());
+            return;
+        }
+        Some(Commands::Doctor {
+            json,
+            exit_on_warn,
+            exit_on_fail,
+        }) => {
+            use devrunner::doctor::CheckStatus;
+            let threshold = if *exit_on_warn {
+                Some(CheckStatus::Warn)
+            } else if *exit_on_fail {
+                Some(CheckStatus::Fail)
+            } else {
+                None
+            };
+            handle_doctor_command(
+                &detect_options,
+                max_levels,
+                config.get_auto_update(),
+                offline,
+                bazel_query,
+                *json,
+                threshold,
+            );
             return;
         }
-        Some(Commands::Doctor) => {
-            handle_doctor_command(&ignore_list, max_levels);
+        Some(Commands::Init { force }) => {
+            handle_init_command(&detect_options, max_levels, *force);
             return;
         }
+        Some(Commands::Install { args }) => {
+            handle_install_command(
+                &config,
+                &detect_options,
+                max_levels,
+                cli.prefer.as_deref(),
+                args,
+                &exec_options,
+            );
+        }
+        Some(Commands::Clean { args }) => {
+            handle_clean_command(
+                &detect_options,
+                max_levels,
+                args,
+                cli.yes,
+                &config.confirm_patterns,
+                &exec_options,
+            );
+        }
+        Some(Commands::Exec { tool, args }) => {
+            handle_exec_command(
+                &config,
+                &detect_options,
+                max_levels,
+                cli.prefer.as_deref(),
+                tool,
+                args,
+                &exec_options,
+            );
+        }
         None => {}
     }
 
     // Handle --update flag
+    if cli.update && cli.offline {
+        let e = devrunner::RunError::InvalidArgument(
+            "--update needs the network; drop --offline".to_string(),
+        );
+        exit_with_error(&e);
+    }
     if cli.update {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
-        match rt.block_on(update::perform_blocking_update(quiet)) {
+        match rt.block_on(update::perform_blocking_update(quiet > 0)) {
             Ok(_) => process::exit(exit_codes::SUCCESS),
             Err(e) => {
                 output::error(&format!("Update failed: {}", e));
@@ -78,9 +332,9 @@ fn main() {
         }
     }
 
-    // Require a command
-    let command = match &cli.command {
-        Some(cmd) => cmd.clone(),
+    // Require a command, falling back to the configured default_command
+    let command = match config.resolve_command(cli.command.as_deref()) {
+        Some(cmd) => cmd,
         None => {
             // If no command, just show help
             Cli::command().print_help().unwrap();
@@ -89,9 +343,6 @@ fn main() {
         }
     };
 
-    // Resolve alias (e.g., "t" -> "test")
-    let command = config.resolve_alias(&command);
-
     // Get current directory
     let current_dir = match env::current_dir() {
         Ok(dir) => dir,
@@ -101,161 +352,715 @@ fn main() {
         }
     };
 
+    // `!`-prefixed aliases are raw shell snippets: no runner detection at all.
+    // --select runs them once per variant, stopping at a failure unless --keep-going.
+    if let Some(snippet) = config.shell_alias(&command) {
+        let variants = match devrunner::matrix::expand(&cli.select) {
+            Ok(variants) => variants,
+            Err(message) => exit_with_error(&devrunner::RunError::InvalidArgument(message)),
+        };
+        let mut exit_code = exit_codes::SUCCESS;
+        for (index, variant) in variants.iter().enumerate() {
+            if variants.len() > 1 && quiet == 0 {
+                output::info(&format!(
+                    "Variant {}/{}: {}",
+                    index + 1,
+                    variants.len(),
+                    devrunner::matrix::label(variant)
+                ));
+            }
+            let options = ExecOptions {
+                select: variant.to_vec(),
+                ..exec_options.clone()
+            };
+            match execute_shell(snippet, &cli.args, &current_dir, &options) {
+                Ok(status) if !status.success() => {
+                    exit_code = exit_code_from_status(status);
+                    if !cli.keep_going {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => exit_with_error(&e),
+            }
+        }
+        process::exit(exit_code);
+    }
+
+    // Resolve alias (e.g., "t" -> "test")
+    let command = config.resolve_alias(&command);
+
+    // Trace the run when DEVRUNNER_OTLP_ENDPOINT is set and not offline (no-op otherwise)
+    let mut tracer = Tracer::from_env(offline);
+    let root_span = tracer.start("devrunner", None);
+    tracer.set_attribute(root_span, "command", command.as_str());
+
     // Search for runners
-    let (runners, working_dir) = match search_runners(
+    let detect_span = tracer.start("detect", Some(root_span));
+    let (runners, working_dir) = match search_project(
         &current_dir,
-        max_levels,
-        &ignore_list,
+        cli.levels,
+        &detect_options,
+        cli.prefer.as_deref(),
         verbose,
     ) {
         Ok(result) => result,
-        Err(e) => {
-            output::error(&e.to_string());
-            eprintln!("Hint: Use --levels=N to increase search depth or check if you're in the right directory.");
-            process::exit(e.exit_code());
-        }
+        Err(e) => exit_with_error(&e),
     };
 
-    // Check for conflicts and select runner
-    let runner = match check_conflicts(&runners, verbose) {
+    // --prefer or the configured preferred runner if detected, otherwise check for conflicts
+    let runner = match select_runner(
+        &runners,
+        &working_dir,
+        cli.prefer.as_deref(),
+        &config,
+        verbose,
+    ) {
         Ok(r) => r,
         Err(e) => {
-            output::error(&e.to_string());
-            process::exit(e.exit_code());
+            exit_with_error(&e);
         }
     };
+    tracer.set_attribute(detect_span, "runner", runner.name.as_str());
+    tracer.end(detect_span);
+    tracer.set_attribute(root_span, "runner", runner.name.as_str());
 
-    // Check if script exists and suggest alternatives if not (for Node.js projects)
-    if runner.ecosystem == devrunner::detectors::Ecosystem::NodeJs {
+    // --env: prefer `build:prod` over `build` when the project defines it
+    let discover_span = tracer.start("discover", Some(root_span));
+    let env_name = cli.env.clone().or_else(|| config.default_env.clone());
+    let command = match &env_name {
+        Some(env_name) => {
+            let script_names: Vec<String> = scripts::get_scripts_for_runner(&runner, &working_dir)
+                .map(|list| list.scripts.into_iter().map(|s| s.name).collect())
+                .unwrap_or_default();
+            let resolved = scripts::resolve_env_script(&command, env_name, &script_names);
+            if verbose && resolved != command {
+                output::info(&format!("Using \"{}\" for env \"{}\"", resolved, env_name));
+            }
+            resolved
+        }
+        None => command,
+    };
+
+    // A glob such as `test:*` runs every matching script, one after another
+    let glob_scripts =
+        if command.contains('*') && is_missing_script(&runner, &command, &working_dir) {
+            scripts::get_scripts_for_runner(&runner, &working_dir)
+                .map(|list| scripts::matching_scripts(&command, &list.scripts))
+        } else {
+            None
+        };
+    if glob_scripts
+        .as_ref()
+        .is_some_and(|matched| matched.is_empty())
+    {
+        if cli.if_present {
+            process::exit(exit_codes::SUCCESS);
+        }
+        output::error(&format!("No scripts match \"{}\"", command));
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+    if glob_scripts.is_some() && cli.list_only {
+        let e = devrunner::RunError::InvalidArgument(
+            "--list-only takes a single script, not a glob".to_string(),
+        );
+        exit_with_error(&e);
+    }
+    let is_glob = glob_scripts.is_some();
+    let scripts_to_run = glob_scripts.unwrap_or_else(|| vec![command.clone()]);
+
+    // --select: every script runs once per combination of the selected values
+    let variants = match devrunner::matrix::expand(&cli.select) {
+        Ok(variants) => variants,
+        Err(message) => exit_with_error(&devrunner::RunError::InvalidArgument(message)),
+    };
+
+    // --if-present: a missing script is a silent no-op, like `npm run --if-present`
+    if cli.if_present && !is_glob && is_missing_script(&runner, &command, &working_dir) {
+        if verbose {
+            output::info(&format!(
+                "Script \"{}\" not found, skipping (--if-present)",
+                command
+            ));
+        }
+        process::exit(exit_codes::SUCCESS);
+    }
+
+    // --list-only: validate the script and show what would run, for hooks and CI
+    if cli.list_only {
+        handle_list_only(
+            &runner,
+            &runners,
+            &command,
+            &cli.args,
+            &working_dir,
+            config.get_discovery_threads(),
+        );
+    }
+
+    // --dump-plan: the steps the run goes through, dependencies first; verbose runs log it
+    if cli.dump_plan || verbose {
+        let mut plan = Vec::new();
+        for script in &scripts_to_run {
+            match graph::plan_for_runner(&runner, &working_dir, script) {
+                Ok(steps) => plan.extend(steps),
+                Err(e) if cli.dump_plan => {
+                    output::error(&e);
+                    process::exit(exit_codes::GENERIC_ERROR);
+                }
+                // The runner reports cycles its own way (make drops the edge)
+                Err(e) => output::warn(&e),
+            }
+        }
+        if cli.dump_plan {
+            for (i, step) in plan.iter().enumerate() {
+                println!("{}. {}", i + 1, step);
+            }
+            process::exit(exit_codes::SUCCESS);
+        }
+        if plan.len() > scripts_to_run.len() {
+            output::info(&format!("Plan: {}", plan.join(" -> ")));
+        }
+    }
+
+    // Check if script exists and suggest alternatives if not (for Node.js projects);
+    // Bun runs a file path directly instead of a script
+    let bun_file = runner.name == "bun" && devrunner::detectors::is_bun_file_target(&command);
+    if runner.ecosystem == devrunner::detectors::Ecosystem::NodeJs && !is_glob && !bun_file {
         if let Some(script_list) = scripts::get_scripts_for_runner(&runner, &working_dir) {
-            let script_names: Vec<String> = script_list.scripts.iter().map(|s| s.name.clone()).collect();
-            
-            if !devrunner::fuzzy::is_exact_match(&command, &script_names) {
-                use owo_colors::OwoColorize;
-                
+            // Binaries run through npx, not `npm run`, so only scripts count here
+            let script_names: Vec<String> = script_list
+                .scripts
+                .iter()
+                .filter(|s| s.category == scripts::ScriptCategory::Script)
+                .map(|s| s.name.clone())
+                .collect();
+
+            if !script_names.is_empty()
+                && !devrunner::fuzzy::is_exact_match(&command, &script_names)
+            {
+                use devrunner::output::Paint;
+
                 output::error(&format!("Script \"{}\" not found", command));
                 println!();
-                println!("{}", format!("Available scripts: {}", script_names.join(", ")).dimmed());
-                
-                if let Some(suggestion) = devrunner::fuzzy::suggest_script(&command, &script_names) {
+                println!(
+                    "{}",
+                    format!("Available scripts: {}", script_names.join(", ")).dimmed()
+                );
+
+                if let Some(suggestion) = suggest_from_all_sources(
+                    &command,
+                    env_name.as_deref(),
+                    &runner,
+                    &runners,
+                    &working_dir,
+                    config.get_discovery_threads(),
+                ) {
                     println!();
-                    println!("💡 Did you mean: {} {}", "devrunner".cyan(), suggestion.green().bold());
+                    println!("{}", script_suggestion(&command, &suggestion));
+                } else if let Some(name) = devrunner::cli::suggest_subcommand(&command) {
+                    println!();
+                    println!("{}", subcommand_suggestion(&command, &name));
                 }
                 process::exit(exit_codes::GENERIC_ERROR);
             }
         }
     }
 
+    // In safe mode, never pipe a downloaded script into a shell
+    if safe_mode {
+        let script_list = scripts::get_scripts_for_runner(&runner, &working_dir);
+        let scripts = script_list.map(|list| list.scripts).unwrap_or_default();
+        for script in scripts.iter().filter(|s| scripts_to_run.contains(&s.name)) {
+            if devrunner::safety::pipes_remote_script(&script.command) {
+                output::warn(&format!(
+                    "Not running \"{}\" in safe mode: it pipes a downloaded script into a shell ({})",
+                    script.name, script.command
+                ));
+                process::exit(exit_codes::GENERIC_ERROR);
+            }
+            // One level down: the scripts it runs by name (`npm run x`)
+            let called = devrunner::safety::script_references(&script.command)
+                .into_iter()
+                .filter_map(|name| scripts.iter().find(|s| s.name == name));
+            for called in called {
+                if devrunner::safety::pipes_remote_script(&called.command) {
+                    output::warn(&format!(
+                        "Not running \"{}\" in safe mode: it runs \"{}\", which pipes a downloaded script into a shell ({})",
+                        script.name, called.name, called.command
+                    ));
+                    process::exit(exit_codes::GENERIC_ERROR);
+                }
+            }
+        }
+    }
+
+    // Destructive scripts (deploy, release, ...) need a yes from the user
+    if !cli.dry_run && !cli.print_env {
+        use devrunner::safety::needs_confirmation;
+
+        let needed = cli.confirm
+            || scripts_to_run
+                .iter()
+                .any(|script| needs_confirmation(script, &config.confirm_patterns));
+        confirm_or_exit(
+            needed,
+            cli.yes,
+            &format!("Run '{}'?", scripts_to_run.join("', '")),
+            &format!(
+                "'{}' needs confirmation; pass --yes to run it without a terminal",
+                scripts_to_run.join("', '")
+            ),
+        );
+    }
+    tracer.end(discover_span);
+
     // Record start time for timing
     let start_time = std::time::Instant::now();
 
     // Execute the command
-    let result = match execute(
-        &runner,
-        &command,
-        &cli.args,
-        &working_dir,
-        cli.dry_run,
-        verbose,
-        quiet,
-    ) {
-        Ok(r) => r,
-        Err(e) => {
-            output::error(&e.to_string());
-            process::exit(e.exit_code());
+    let execute_span = tracer.start("execute", Some(root_span));
+    tracer.set_attribute(execute_span, "runner", runner.name.as_str());
+    // --repeat runs back to back; the result kept is the last failure, else the last run.
+    // Several scripts (from a glob) and --select variants work the same way, stopping at
+    // the first failing one unless --keep-going is given.
+    let runs = if cli.dry_run || cli.print_env {
+        1
+    } else {
+        cli.repeat
+    };
+    let jobs: Vec<(&String, &devrunner::matrix::Variant)> = scripts_to_run
+        .iter()
+        .flat_map(|script| variants.iter().map(move |variant| (script, variant)))
+        .collect();
+    let is_matrix = variants.len() > 1;
+    let job_kind = if is_matrix { "variant" } else { "script" };
+    let mut failed_scripts = Vec::new();
+    let mut job_results = Vec::new();
+    let mut outcome = None;
+    for (index, (script, variant)) in jobs.iter().enumerate() {
+        let job = if variant.is_empty() {
+            script.to_string()
+        } else {
+            format!("{} [{}]", script, devrunner::matrix::label(variant))
+        };
+        if is_matrix && quiet == 0 {
+            output::info(&format!(
+                "Variant {}/{}: {}",
+                index % variants.len() + 1,
+                variants.len(),
+                devrunner::matrix::label(variant)
+            ));
+        }
+        let job_options = ExecOptions {
+            select: variant.to_vec(),
+            ..exec_options.clone()
+        };
+
+        // [script_dirs]: some monorepo scripts only work from a subdirectory
+        let run_dir = match config.script_dir(script, &working_dir) {
+            Ok(Some(dir)) => {
+                if verbose {
+                    output::info(&format!("Running \"{}\" in {}", script, dir.display()));
+                }
+                dir
+            }
+            Ok(None) => working_dir.clone(),
+            Err(message) => {
+                let e = devrunner::RunError::ConfigError(message);
+                exit_with_error(&e);
+            }
+        };
+
+        let mut durations = Vec::new();
+        let mut failures = 0;
+        let mut script_outcome = None;
+        for run in 1..=runs {
+            if runs > 1 && quiet == 0 {
+                output::info(&format!("Run {}/{}", run, runs));
+            }
+            let run_start = std::time::Instant::now();
+            let result = match execute(&runner, script, &cli.args, &run_dir, &job_options) {
+                Ok(r) => r,
+                Err(e) => {
+                    tracer.set_attribute(root_span, "exit_code", e.exit_code());
+                    tracer.finish();
+                    exit_with_error(&e);
+                }
+            };
+            durations.push(run_start.elapsed());
+            let failed = !result.exit_status.success();
+            if failed || failures == 0 {
+                script_outcome = Some(result);
+            }
+            if failed {
+                failures += 1;
+                if !cli.keep_going {
+                    break;
+                }
+            }
+        }
+        if runs > 1 && quiet == 0 {
+            output::print_repeat_summary(&durations, failures);
+        }
+        job_results.push((job.clone(), durations.iter().sum(), failures == 0));
+
+        if failures > 0 || failed_scripts.is_empty() {
+            outcome = script_outcome;
+        }
+        if failures > 0 {
+            failed_scripts.push(job.clone());
+            let remaining = jobs.len() - index - 1;
+            if !cli.keep_going {
+                if remaining > 0 && quiet == 0 {
+                    output::warn(&format!(
+                        "\"{}\" failed, skipping {} remaining {}(s) (--keep-going runs them)",
+                        job, remaining, job_kind
+                    ));
+                }
+                break;
+            }
         }
+    }
+    let result = outcome.expect("at least one run");
+    tracer.end(execute_span);
+
+    // A threshold hides the timing of fast runs, the per-job table included
+    let timing_on = config.get_show_timing() || cli.timings_threshold.is_some();
+    let threshold = if timing_on {
+        cli.timings_threshold.unwrap_or_else(|| {
+            config.get_timings_threshold().unwrap_or_else(|e| {
+                output::warn(&format!("Ignoring timings_threshold: {}", e));
+                std::time::Duration::ZERO
+            })
+        })
+    } else {
+        std::time::Duration::ZERO
     };
+    let elapsed = start_time.elapsed();
+    let show_timing = output::should_show_timing(elapsed, threshold);
+
+    // The per-job table is for several scripts or variants; a single run keeps its one line
+    if jobs.len() > 1 && show_timing && quiet == 0 && !cli.dry_run && !cli.print_env {
+        output::print_timing_summary(&job_results);
+    }
+    if cli.keep_going && jobs.len() > 1 && !failed_scripts.is_empty() && quiet == 0 {
+        output::error(&format!(
+            "{} of {} {}s failed: {}",
+            failed_scripts.len(),
+            jobs.len(),
+            job_kind,
+            failed_scripts.join(", ")
+        ));
+    }
 
     // Show execution time if enabled
-    if config.get_show_timing() && !quiet && !cli.dry_run {
-        use owo_colors::OwoColorize;
-        let elapsed = start_time.elapsed();
-        let seconds = elapsed.as_secs_f64();
-        
-        if seconds < 60.0 {
-            eprintln!("\n{} Completed in {:.2}s", "✓".green(), seconds);
-        } else {
-            let minutes = (seconds / 60.0).floor() as u64;
-            let remaining_secs = seconds % 60.0;
-            eprintln!("\n{} Completed in {}m {:.1}s", "✓".green(), minutes, remaining_secs);
-        }
+    if timing_on && show_timing && quiet == 0 && !cli.dry_run {
+        use devrunner::output::Paint;
+        eprintln!(
+            "\n{} Completed in {}",
+            output::glyph("✓", "ok:").green(),
+            output::format_duration(elapsed)
+        );
     }
 
     // For dry run, always exit successfully
     if cli.dry_run {
+        tracer.set_attribute(root_span, "exit_code", exit_codes::SUCCESS);
+        tracer.finish();
         process::exit(exit_codes::SUCCESS);
     }
 
+    let exit_code = exit_code_from_status(result.exit_status);
+    tracer.set_attribute(execute_span, "exit_code", exit_code);
+    tracer.set_attribute(root_span, "exit_code", exit_code);
+    tracer.finish();
+    if exit_code != exit_codes::SUCCESS && (cli.explain_exit || config.get_explain_exit()) {
+        output::exit_explanation(
+            exit_code,
+            exit_signal(result.exit_status),
+            &result.runner.name,
+            &shell_join(&result.command),
+            exit_hint(exit_code),
+        );
+    }
+
+    // A task the runner doesn't know that's close to a subcommand was probably meant as one
+    if exit_code != exit_codes::SUCCESS
+        && quiet == 0
+        && !is_glob
+        && is_missing_script(&runner, &command, &working_dir)
+    {
+        if let Some(name) = devrunner::cli::suggest_subcommand(&command) {
+            eprintln!("\n{}", subcommand_suggestion(&command, &name));
+        }
+    }
+
     // Spawn background update check (after command completes)
-    if config.get_auto_update() && !update::is_update_disabled() {
-        update::spawn_background_update();
+    if update::should_spawn_background_update(config.get_auto_update(), offline) {
+        let interval_hours = config.get_update_interval_hours().unwrap_or_else(|e| {
+            output::warn(&format!("Ignoring update_interval_hours: {}", e));
+            24
+        });
+        update::spawn_background_update(interval_hours);
     }
 
-    // Exit with the same code as the executed command
-    let exit_code = result
-        .exit_status
-        .code()
-        .unwrap_or(exit_codes::GENERIC_ERROR);
+    // Exit with the same code as the executed command (128 + signal if it was killed)
     process::exit(exit_code);
 }
 
-/// Handle the `list` subcommand - show available scripts
-fn handle_list_command(ignore_list: &[String], max_levels: u8, verbose: bool) {
-    use owo_colors::OwoColorize;
+/// Set by `--debug`: failures also print their cause chain
+static DEBUG: AtomicBool = AtomicBool::new(false);
 
-    let current_dir = match env::current_dir() {
-        Ok(dir) => dir,
+/// Print a failure (with its cause chain under `--debug`) and exit with its code
+fn exit_with_error(e: &devrunner::RunError) -> ! {
+    output::error(&e.to_string());
+    if DEBUG.load(Ordering::Relaxed) {
+        let mut source = std::error::Error::source(e);
+        while let Some(cause) = source {
+            eprintln!("Caused by: {}", cause);
+            source = cause.source();
+        }
+        eprintln!("Debug: {:?}", e);
+    }
+    process::exit(e.exit_code());
+}
+
+/// Pick the runner: `--prefer` when detected, then config `preferred_runner`,
+/// then normal conflict resolution
+///
+/// With `remember_choices`, a conflict `--prefer` settled is stored for the
+/// project directory and settles it again on later runs.
+fn select_runner(
+    runners: &[DetectedRunner],
+    project_dir: &std::path::Path,
+    prefer: Option<&str>,
+    config: &Config,
+    verbose: bool,
+) -> Result<DetectedRunner, devrunner::RunError> {
+    let prefer_detected = prefer.is_some_and(|p| find_preferred(runners, Some(p)).is_some());
+    if !prefer_detected {
+        if let Some(runner) = find_preferred(runners, config.preferred_runner.as_deref()) {
+            return Ok(runner);
+        }
+    }
+
+    let choices_path = Config::choices_path().filter(|_| config.get_remember_choices());
+    let mut choices = choices_path
+        .as_deref()
+        .map(ChoiceStore::load)
+        .unwrap_or_default();
+    let runner = check_conflicts(runners, prefer, choices.get(project_dir), verbose)?;
+
+    let settled_conflict = prefer_detected
+        && runners
+            .iter()
+            .filter(|r| r.ecosystem == runner.ecosystem)
+            .count()
+            > 1;
+    if let Some(path) = choices_path.filter(|_| settled_conflict) {
+        if choices.get(project_dir) != Some(runner.name.as_str()) {
+            choices.remember(project_dir, &runner.name);
+            match choices.save(&path) {
+                Ok(()) if verbose => output::info(&format!(
+                    "Remembering {} for {} (--forget to reset)",
+                    runner.name,
+                    project_dir.display()
+                )),
+                Ok(()) => {}
+                Err(e) => output::warn(&format!("Could not save {}: {}", path.display(), e)),
+            }
+        }
+    }
+    Ok(runner)
+}
+
+/// `--forget`: drop the runner remembered for the current directory, or the
+/// nearest parent project that has one
+fn handle_forget() {
+    let Some(path) = Config::choices_path() else {
+        output::error("Could not determine the cache directory");
+        process::exit(exit_codes::GENERIC_ERROR);
+    };
+    let current_dir = env::current_dir().unwrap_or_default();
+    let mut choices = ChoiceStore::load(&path);
+    let Some(dir) = current_dir.ancestors().find(|dir| choices.forget(dir)) else {
+        output::info("No remembered runner for this directory");
+        return;
+    };
+    match choices.save(&path) {
+        Ok(()) => output::success(&format!(
+            "Forgot the runner remembered for {}",
+            dir.display()
+        )),
         Err(e) => {
-            output::error(&format!("Failed to get current directory: {}", e));
+            output::error(&format!("Failed to update {}: {}", path.display(), e));
             process::exit(exit_codes::GENERIC_ERROR);
         }
-    };
+    }
+}
 
-    // Find the project directory
-    let (runners, working_dir) = match search_runners(&current_dir, max_levels, ignore_list, verbose) {
-        Ok(result) => result,
-        Err(e) => {
-            output::error(&e.to_string());
-            process::exit(e.exit_code());
+/// `devrunner cache clear`: remembered choices and cached `bazel query` and
+/// release lookups
+fn handle_cache_command(action: &CacheCommand) -> ! {
+    match action {
+        CacheCommand::Clear => {
+            let mut removed = 0;
+            for path in [Config::choices_path(), Config::release_cache_path()]
+                .into_iter()
+                .flatten()
+            {
+                match std::fs::remove_file(&path) {
+                    Ok(()) => removed += 1,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => output::warn(&format!("Could not remove {}: {}", path.display(), e)),
+                }
+            }
+            if let Some(dir) = Config::bazel_query_cache_dir() {
+                match std::fs::remove_dir_all(&dir) {
+                    Ok(()) => removed += 1,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => output::warn(&format!("Could not remove {}: {}", dir.display(), e)),
+                }
+            }
+            if removed == 0 {
+                output::info("Cache is already empty");
+            } else {
+                output::success("Cleared remembered runners and cached query results");
+            }
+        }
+    }
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Find the project to run in
+///
+/// With `--levels=auto` and runners at several levels, the nearest level with
+/// the `--prefer` runner wins, else the user picks; without a terminal the
+/// nearest level is used.
+fn search_project(
+    current_dir: &std::path::Path,
+    depth: SearchDepth,
+    detect_options: &DetectOptions,
+    prefer: Option<&str>,
+    verbose: bool,
+) -> Result<(Vec<DetectedRunner>, std::path::PathBuf), devrunner::RunError> {
+    use std::io::IsTerminal;
+
+    if depth != SearchDepth::Auto {
+        return search_runners(current_dir, depth.max_levels(), detect_options, verbose);
+    }
+
+    let mut levels = search_runner_levels(current_dir, detect_options, verbose)?;
+    let index = if levels.len() == 1 {
+        0
+    } else if let Some(index) = level_with_runner(&levels, prefer) {
+        index
+    } else if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        prompt_level(&levels)
+    } else {
+        if verbose {
+            output::info(&format!(
+                "Runners found at {} levels, using the nearest (pass --prefer to choose)",
+                levels.len()
+            ));
         }
+        0
     };
+    let chosen = levels.swap_remove(index);
+    Ok((chosen.runners, chosen.dir))
+}
 
-    if runners.is_empty() {
-        output::error("No runner detected in this project");
-        process::exit(exit_codes::RUNNER_NOT_FOUND);
+/// Ask which level of a `--levels=auto` search to run in; defaults to the nearest
+fn prompt_level(levels: &[LevelRunners]) -> usize {
+    use std::io::Write;
+
+    eprintln!("Runners found at several levels:");
+    for (i, level) in levels.iter().enumerate() {
+        let names: Vec<&str> = level.runners.iter().map(|r| r.name.as_str()).collect();
+        eprintln!(
+            "  {}) {} ({}) [level {}]",
+            i + 1,
+            level.dir.display(),
+            names.join(", "),
+            level.level
+        );
     }
+    eprint!("Run in [1-{}, default 1]: ", levels.len());
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return 0;
+    }
+    match answer.trim().parse::<usize>() {
+        Ok(choice) if (1..=levels.len()).contains(&choice) => choice - 1,
+        _ => 0,
+    }
+}
 
-    let runner = &runners[0];
-    println!("📦 Detected: {} ({})", runner.name.green(), runner.detected_file.dimmed());
-    println!();
+/// Handle the `completions` subcommand - print or install completion scripts
+fn handle_completions_command(shell: Option<clap_complete::Shell>, install: bool, force: bool) {
+    use devrunner::completions;
 
-    // Get scripts for this runner
-    if let Some(script_list) = scripts::get_scripts_for_runner(runner, &working_dir) {
-        println!("{}", "Available scripts:".bold());
-        
-        // Find the longest script name for alignment
-        let max_name_len = script_list.scripts.iter().map(|s| s.name.len()).max().unwrap_or(0);
-        
-        for script in &script_list.scripts {
-            println!(
-                "  {}{}  {}",
-                script.name.cyan(),
-                " ".repeat(max_name_len - script.name.len()),
-                script.command.dimmed()
-            );
+    let shell = match shell.or_else(|| completions::detect_shell(env::var("SHELL").ok().as_deref()))
+    {
+        Some(shell) => shell,
+        None => {
+            output::error("Could not detect your shell from $SHELL. Pass it explicitly, e.g. `devrunner completions zsh`");
+            process::exit(exit_codes::GENERIC_ERROR);
         }
-    } else {
-        println!("{}", "No scripts found for this project type.".dimmed());
+    };
+
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    if !install {
+        generate(shell, &mut cmd, name, &mut io::stdout());
+        return;
     }
 
-    process::exit(exit_codes::SUCCESS);
-}
+    let Some(home) = dirs::home_dir() else {
+        output::error("Could not determine your home directory");
+        process::exit(exit_codes::GENERIC_ERROR);
+    };
+    let data_dir = env::var_os("XDG_DATA_HOME").map(std::path::PathBuf::from);
+    let path = completions::install_path(shell, &home, data_dir.as_deref());
 
-/// Handle the `why` subcommand - explain runner selection
-fn handle_why_command(ignore_list: &[String], max_levels: u8, _verbose: bool) {
-    use devrunner::detectors::detect_all;
-    use owo_colors::OwoColorize;
+    if path.exists() && !force {
+        output::error(&format!(
+            "{} already exists. Use --force to overwrite it.",
+            path.display()
+        ));
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+
+    let mut script = Vec::new();
+    generate(shell, &mut cmd, name, &mut script);
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, script));
+    if let Err(e) = written {
+        output::error(&format!("Failed to write {}: {}", path.display(), e));
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+
+    output::success(&format!(
+        "Installed {} completions to {}",
+        shell,
+        path.display()
+    ));
+    if let Some(hint) = completions::activation_hint(shell, &path) {
+        eprintln!("{}", hint);
+    }
+}
 
+/// Handle the `graph` subcommand - show how the scripts depend on each other
+fn handle_graph_command(
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    verbose: bool,
+    format: GraphFormat,
+) {
     let current_dir = match env::current_dir() {
         Ok(dir) => dir,
         Err(e) => {
@@ -264,22 +1069,531 @@ fn handle_why_command(ignore_list: &[String], max_levels: u8, _verbose: bool) {
         }
     };
 
-    // Search for all runners (without ignoring for comparison)
-    let mut search_dir = current_dir.clone();
-    let mut found_level = 0;
-    let mut all_runners = Vec::new();
-
-    for level in 0..=max_levels {
-        let runners = detect_all(&search_dir, &[]);
-        if !runners.is_empty() {
-            all_runners = runners;
-            found_level = level;
-            break;
-        }
-        if let Some(parent) = search_dir.parent() {
-            search_dir = parent.to_path_buf();
+    // The search fails rather than coming back empty, so there is a first runner
+    let (runners, working_dir) =
+        match search_runners(&current_dir, max_levels, detect_options, verbose) {
+            Ok(result) => result,
+            Err(e) => {
+                exit_with_error(&e);
+            }
+        };
+
+    let runner = &runners[0];
+    let Some(graph) = graph::graph_for_runner(runner, &working_dir) else {
+        output::error(&format!(
+            "{} scripts don't declare dependencies; graph supports Makefiles and package.json scripts",
+            runner.name
+        ));
+        process::exit(exit_codes::GENERIC_ERROR);
+    };
+
+    match format {
+        GraphFormat::Tree => print!("{}", graph::render_tree(&graph)),
+        GraphFormat::Dot => print!("{}", graph::render_dot(&graph)),
+    }
+}
+
+/// Handle the `list` subcommand - show available scripts
+fn handle_list_command(
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    verbose: bool,
+    since: Option<&str>,
+    bazel_query: Option<usize>,
+    mut filter: scripts::ScriptFilter,
+) {
+    use devrunner::changes;
+    use devrunner::output::Paint;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    // Find the project directory
+    let (runners, working_dir) =
+        match search_runners(&current_dir, max_levels, detect_options, verbose) {
+            Ok(result) => result,
+            Err(e) => {
+                exit_with_error(&e);
+            }
+        };
+
+    if runners.is_empty() {
+        output::error("No runner detected in this project");
+        process::exit(exit_codes::RUNNER_NOT_FOUND);
+    }
+
+    let runner = &runners[0];
+    println!(
+        "{} Detected: {} ({})",
+        output::glyph("📦", "*"),
+        runner.name.green(),
+        runner.detected_file().dimmed()
+    );
+    println!();
+
+    // Get scripts for this runner
+    if let Some(mut script_list) =
+        scripts::list_scripts_for_runner(runner, &working_dir, bazel_query)
+    {
+        // Heuristic only: silently skip when git is unavailable
+        if let Some(git_ref) = since {
+            if let Some(changed) = changes::changed_files_since(&working_dir, git_ref) {
+                let relevant = changes::rank_scripts(&mut script_list.scripts, &changed);
+                if relevant > 0 {
+                    let names: Vec<&str> = script_list.scripts[..relevant]
+                        .iter()
+                        .map(|s| s.name.as_str())
+                        .collect();
+                    println!(
+                        "{} {} changed since {}, consider: {}",
+                        output::glyph("💡", "hint:"),
+                        format!("{} file(s)", changed.len()).bold(),
+                        git_ref.cyan(),
+                        names.join(", ").green()
+                    );
+                    println!();
+                }
+            }
+        }
+
+        // After --since ranking, so --head keeps the relevant ones
+        let (binaries, runnable): (Vec<_>, Vec<_>) = filter
+            .apply(script_list.scripts)
+            .into_iter()
+            .partition(|s| s.category == scripts::ScriptCategory::Binary);
+        if let Some(pattern) = filter
+            .pattern()
+            .filter(|_| runnable.is_empty() && binaries.is_empty())
+        {
+            println!("{}", format!("No scripts match \"{}\".", pattern).dimmed());
+        }
+        if !runnable.is_empty() {
+            println!("{}", "Available scripts:".bold());
+            print_scripts(&runnable);
+        }
+        if !binaries.is_empty() {
+            if !runnable.is_empty() {
+                println!();
+            }
+            println!("{}", "Binaries:".bold());
+            print_scripts(&binaries);
+        }
+    } else {
+        println!("{}", "No scripts found for this project type.".dimmed());
+    }
+
+    // Workspace tools complement the package.json scripts in Node projects
+    if runner.ecosystem == devrunner::detectors::Ecosystem::NodeJs {
+        let framework_lists = [
+            ("Angular", scripts::parse_angular_tasks(&working_dir)),
+            ("Nx", scripts::parse_nx_tasks(&working_dir)),
+            ("Turbo", scripts::parse_turbo_tasks(&working_dir)),
+        ];
+        for (label, task_list) in framework_lists {
+            if let Some(task_list) = task_list {
+                let tasks = filter.apply(task_list.scripts);
+                if tasks.is_empty() {
+                    continue;
+                }
+                println!();
+                println!(
+                    "{} {}",
+                    format!("{} tasks", label).bold(),
+                    format!("({})", task_list.source_file).dimmed()
+                );
+                print_scripts(&tasks);
+            }
+        }
+    }
+
+    // Supplementary sources that apply regardless of the detected runner
+    if let Some(task_list) = scripts::parse_vscode_tasks(&working_dir) {
+        let tasks = filter.apply(task_list.scripts);
+        if !tasks.is_empty() {
+            println!();
+            println!(
+                "{} {}",
+                "VS Code tasks".bold(),
+                format!("({})", task_list.source_file).dimmed()
+            );
+            print_scripts(&tasks);
+        }
+    }
+
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Handle `list --all-workspaces` - scripts of every workspace package
+fn handle_list_workspaces_command(max_levels: u8, json: bool, mut filter: scripts::ScriptFilter) {
+    use devrunner::output::Paint;
+    use devrunner::workspaces;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    // Packages usually sit below the root, so look upwards for the workspace definition
+    let root = current_dir
+        .ancestors()
+        .take(max_levels as usize + 1)
+        .find(|dir| !workspaces::workspace_patterns(dir).is_empty());
+    let Some(root) = root else {
+        output::error("No workspace found (package.json `workspaces` or pnpm-workspace.yaml)");
+        process::exit(exit_codes::RUNNER_NOT_FOUND);
+    };
+
+    let packages: Vec<(workspaces::WorkspacePackage, Vec<scripts::ProjectScript>)> =
+        workspaces::workspace_packages(root)
+            .into_iter()
+            .map(|package| {
+                let package_scripts = scripts::parse_package_json_scripts(&package.dir)
+                    .map(|list| list.scripts)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|s| s.category == scripts::ScriptCategory::Script)
+                    .collect();
+                (package, filter.apply(package_scripts))
+            })
+            .collect();
+
+    if json {
+        let listing: serde_json::Map<String, serde_json::Value> = packages
+            .iter()
+            .map(|(package, package_scripts)| {
+                let script_map: serde_json::Map<String, serde_json::Value> = package_scripts
+                    .iter()
+                    .map(|s| (s.name.clone(), serde_json::Value::String(s.command.clone())))
+                    .collect();
+                (
+                    package.name.clone(),
+                    serde_json::json!({ "path": package.path, "scripts": script_map }),
+                )
+            })
+            .collect();
+        println!("{}", serde_json::Value::Object(listing));
+        process::exit(exit_codes::SUCCESS);
+    }
+
+    println!(
+        "{} Workspace: {} package(s)",
+        output::glyph("📦", "*"),
+        packages.len().to_string().green()
+    );
+    for (package, package_scripts) in &packages {
+        println!();
+        println!(
+            "{} {}",
+            package.name.bold(),
+            format!("({})", package.path).dimmed()
+        );
+        if package_scripts.is_empty() {
+            println!("  {}", "No scripts".dimmed());
+        } else {
+            print_scripts(package_scripts);
+        }
+    }
+
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Handle `list --recursive` - survey the scripts below the current directory
+fn handle_list_recursive_command(
+    depth: usize,
+    discovery_threads: usize,
+    json: bool,
+    verbose: bool,
+    mut filter: scripts::ScriptFilter,
+) {
+    use devrunner::output::Paint;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    if verbose {
+        output::info(&format!(
+            "Discovering scripts on up to {} thread(s) per directory",
+            discovery_threads
+        ));
+    }
+    let mut found = scripts::discover_scripts_recursive(&current_dir, depth, discovery_threads);
+    if filter.is_active() {
+        for dir in &mut found {
+            for list in &mut dir.lists {
+                list.scripts = filter.apply(std::mem::take(&mut list.scripts));
+            }
+            dir.lists.retain(|list| !list.scripts.is_empty());
+        }
+        found.retain(|dir| !dir.lists.is_empty());
+    }
+
+    if json {
+        let listing: serde_json::Map<String, serde_json::Value> = found
+            .iter()
+            .map(|dir| {
+                let sources: serde_json::Map<String, serde_json::Value> = dir
+                    .lists
+                    .iter()
+                    .map(|list| {
+                        let script_map: serde_json::Map<String, serde_json::Value> = list
+                            .scripts
+                            .iter()
+                            .map(|s| (s.name.clone(), serde_json::Value::String(s.command.clone())))
+                            .collect();
+                        (
+                            list.source_file.clone(),
+                            serde_json::Value::Object(script_map),
+                        )
+                    })
+                    .collect();
+                (dir.path.clone(), serde_json::Value::Object(sources))
+            })
+            .collect();
+        println!("{}", serde_json::Value::Object(listing));
+        return;
+    }
+
+    if found.is_empty() {
+        println!(
+            "{}",
+            format!("No scripts found within {} level(s) below here.", depth).dimmed()
+        );
+        return;
+    }
+    println!(
+        "{} Scripts in {} {}",
+        output::glyph("📦", "*"),
+        found.len().to_string().green(),
+        if found.len() == 1 {
+            "directory"
         } else {
-            break;
+            "directories"
+        }
+    );
+    for dir in &found {
+        for list in &dir.lists {
+            println!();
+            println!(
+                "{} {}",
+                dir.path.bold(),
+                format!("({})", list.source_file).dimmed()
+            );
+            print_scripts(&list.scripts);
+        }
+    }
+}
+
+/// Handle `--list-ecosystems` - print what each built-in detector looks for
+fn handle_list_ecosystems(json: bool) {
+    use devrunner::output::Paint;
+
+    let metas: Vec<DetectorMeta> = builtin_detectors().iter().map(|d| d.metadata()).collect();
+    let rows: Vec<(&DetectorMeta, &RunnerMeta)> = metas
+        .iter()
+        .flat_map(|detector| {
+            detector
+                .runners
+                .iter()
+                .map(move |runner| (detector, runner))
+        })
+        .collect();
+
+    if json {
+        let listing: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(detector, runner)| {
+                serde_json::json!({
+                    "runner": runner.name,
+                    "detector": detector.name,
+                    "ecosystem": detector.ecosystem.as_str(),
+                    "files": runner.files,
+                    "priority": runner.priority,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&listing).unwrap());
+        return;
+    }
+
+    let name_width = rows
+        .iter()
+        .map(|(_, r)| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+    let eco_width = rows
+        .iter()
+        .map(|(d, _)| d.ecosystem.as_str().len())
+        .max()
+        .unwrap_or(0)
+        .max(9);
+    println!(
+        "{}",
+        format!(
+            "{:<name_width$}  {:<eco_width$}  PRIORITY  FILES",
+            "RUNNER", "ECOSYSTEM"
+        )
+        .bold()
+    );
+    for (detector, runner) in &rows {
+        println!(
+            "{}  {:<eco_width$}  {:>8}  {}",
+            format!("{:<name_width$}", runner.name).cyan(),
+            detector.ecosystem.as_str(),
+            runner.priority,
+            runner.files.join(", ").dimmed()
+        );
+    }
+}
+
+/// Handle `--profile-detection`: time every detector over the upward search
+fn handle_profile_detection(detect_options: &DetectOptions, depth: SearchDepth, json: bool) {
+    use devrunner::output::Paint;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    let (profile, result) = profile_search(&current_dir, depth, detect_options);
+    let millis = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let timings = profile.sorted();
+
+    if json {
+        let detectors: Vec<serde_json::Value> = timings
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "name": t.name,
+                    "elapsed_ms": millis(t.elapsed),
+                    "found": t.found,
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "dirs_walked": profile.dirs_walked,
+            "total_ms": millis(profile.total()),
+            "detectors": detectors,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Detection profile: {} {} walked, {:.2}ms total",
+            profile.dirs_walked,
+            if profile.dirs_walked == 1 {
+                "directory"
+            } else {
+                "directories"
+            },
+            millis(profile.total())
+        )
+        .bold()
+    );
+    let name_width = timings.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for timing in &timings {
+        let found = if timing.found.is_empty() {
+            String::new()
+        } else {
+            format!("  (found {})", timing.found.join(", "))
+        };
+        println!(
+            "  {:<name_width$}  {:>9}{}",
+            timing.name,
+            format!("{:.2}ms", millis(timing.elapsed)),
+            found.dimmed()
+        );
+    }
+    if let Err(e) = result {
+        output::warn(&e.to_string());
+    }
+}
+
+/// Print scripts as an aligned name/command table
+fn print_scripts(scripts: &[scripts::ProjectScript]) {
+    use devrunner::output::Paint;
+
+    // Find the longest script name for alignment
+    let max_name_len = scripts.iter().map(|s| s.name.len()).max().unwrap_or(0);
+
+    for script in scripts {
+        let description = script
+            .description
+            .as_ref()
+            .map(|d| format!("  # {}", d))
+            .unwrap_or_default();
+        println!(
+            "  {}{}  {}{}",
+            script.name.cyan(),
+            " ".repeat(max_name_len - script.name.len()),
+            script.command.dimmed(),
+            description.dimmed()
+        );
+    }
+}
+
+/// Handle the `why` subcommand - explain runner selection
+fn handle_why_command(detect_options: &DetectOptions, depth: SearchDepth, prefer: Option<&str>) {
+    use devrunner::detectors::detect_all;
+    use devrunner::output::Paint;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    // Search for all runners (without ignoring for comparison)
+    let mut search_dir = current_dir.clone();
+    let mut found_level = 0;
+    let mut all_runners = Vec::new();
+    let mut other_levels = Vec::new();
+
+    if depth == SearchDepth::Auto {
+        // Same pick as a run without a terminal: the --prefer level, else the nearest
+        if let Ok(mut levels) =
+            search_runner_levels(&current_dir, &detect_options.without_ignores(), false)
+        {
+            let chosen = levels.remove(level_with_runner(&levels, prefer).unwrap_or(0));
+            search_dir = chosen.dir;
+            found_level = chosen.level;
+            all_runners = chosen.runners;
+            other_levels = levels;
+        }
+    } else {
+        for level in 0..=depth.max_levels() {
+            let runners = detect_all(&search_dir, &detect_options.without_ignores());
+            if !runners.is_empty() {
+                all_runners = runners;
+                found_level = level;
+                break;
+            }
+            if let Some(parent) = search_dir.parent() {
+                search_dir = parent.to_path_buf();
+            } else {
+                break;
+            }
         }
     }
 
@@ -291,54 +1605,138 @@ fn handle_why_command(ignore_list: &[String], max_levels: u8, _verbose: bool) {
     // Get the selected runner (with ignore list applied)
     let filtered_runners: Vec<_> = all_runners
         .iter()
-        .filter(|r| !ignore_list.iter().any(|i| i.eq_ignore_ascii_case(&r.name)))
+        .filter(|r| !detect_options.is_ignored(&r.name))
         .collect();
 
     println!("{}", "Runner Selection Analysis".bold().underline());
     println!();
 
-    if let Some(selected) = filtered_runners.first() {
-        println!("📦 {} {}", "Using:".bold(), selected.name.green().bold());
+    // --prefer only changes the outcome when its runner is detected
+    let usual = filtered_runners.first().copied();
+    let preferred = prefer.and_then(|p| {
+        filtered_runners
+            .iter()
+            .find(|r| r.name.eq_ignore_ascii_case(p))
+            .copied()
+    });
+    if let Some(selected) = preferred.or(usual) {
+        println!(
+            "{} {} {}",
+            output::glyph("📦", "*"),
+            "Using:".bold(),
+            selected.name.green().bold()
+        );
         println!(
             "   {} Found {} in {} (level {})",
             "→".dimmed(),
-            selected.detected_file.cyan(),
+            selected.detected_file().cyan(),
             search_dir.display(),
             found_level
         );
+        if let Some(evidence) = selected
+            .detected_files
+            .get(1..)
+            .filter(|rest| !rest.is_empty())
+        {
+            println!(
+                "   {} Also found: {}",
+                "→".dimmed(),
+                evidence.join(", ").cyan()
+            );
+        }
         println!(
             "   {} Priority: {} (lower = higher priority)",
             "→".dimmed(),
             selected.priority
         );
+        if selected.name == "go" && selected.detected_file() == "go.work" {
+            let modules = devrunner::detectors::go_workspace_modules(&search_dir);
+            println!(
+                "   {} go.work workspace: build/test/vet span {} module(s) ({})",
+                "→".dimmed(),
+                modules.len(),
+                modules.join(", ")
+            );
+        }
+        if let Some(reason) = &selected.pinned_by {
+            println!("   {} Chosen because {}", "→".dimmed(), reason.cyan());
+        }
+        match (preferred, usual) {
+            (Some(preferred), Some(usual)) if preferred.name != usual.name => println!(
+                "   {} Chosen because of --prefer {} (would otherwise use {})",
+                "→".dimmed(),
+                preferred.name.cyan(),
+                usual.name
+            ),
+            (None, _) if prefer.is_some() => println!(
+                "   {} --prefer {} ignored: not detected here",
+                "→".dimmed(),
+                prefer.unwrap_or_default()
+            ),
+            _ => {}
+        }
         println!();
 
+        if let Some(container) = devrunner::detectors::find_devcontainer(&search_dir) {
+            println!(
+                "🐳 {} {} ({}), use --in-container to run inside it",
+                "Dev container:".bold(),
+                container.label().cyan(),
+                container.config_file
+            );
+            println!();
+        }
+
         // Show other candidates
         if all_runners.len() > 1 {
             println!("{}", "Other detected runners:".bold());
             for runner in &all_runners {
                 if runner.name != selected.name {
-                    let status = if ignore_list.iter().any(|i| i.eq_ignore_ascii_case(&runner.name)) {
+                    let status = if detect_options.is_ignored(&runner.name) {
                         "(ignored via --ignore)".red().to_string()
                     } else {
-                        format!("(priority {})", runner.priority).dimmed().to_string()
+                        format!("(priority {})", runner.priority)
+                            .dimmed()
+                            .to_string()
                     };
                     println!(
                         "  {} {} - {} {}",
                         "•".dimmed(),
                         runner.name,
-                        runner.detected_file,
+                        runner.detected_files.join(", "),
                         status
                     );
                 }
             }
         }
+
+        if !other_levels.is_empty() {
+            if all_runners.len() > 1 {
+                println!();
+            }
+            println!("{}", "Runners at other levels (--levels=auto):".bold());
+            for level in &other_levels {
+                let names: Vec<&str> = level.runners.iter().map(|r| r.name.as_str()).collect();
+                println!(
+                    "  {} level {}: {} - {}",
+                    "•".dimmed(),
+                    level.level,
+                    level.dir.display(),
+                    names.join(", ")
+                );
+            }
+        }
     } else {
         println!("{}", "All detected runners were ignored!".red());
         println!();
         println!("{}", "Detected (but ignored):".bold());
         for runner in &all_runners {
-            println!("  {} {} - {}", "•".dimmed(), runner.name, runner.detected_file);
+            println!(
+                "  {} {} - {}",
+                "•".dimmed(),
+                runner.name,
+                runner.detected_files.join(", ")
+            );
         }
     }
 
@@ -346,9 +1744,17 @@ fn handle_why_command(ignore_list: &[String], max_levels: u8, _verbose: bool) {
 }
 
 /// Handle the `doctor` subcommand - diagnose project setup
-fn handle_doctor_command(ignore_list: &[String], max_levels: u8) {
+fn handle_doctor_command(
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    auto_update: bool,
+    offline: bool,
+    bazel_query: Option<usize>,
+    json: bool,
+    threshold: Option<devrunner::doctor::CheckStatus>,
+) {
     use devrunner::detectors::{detect_all, is_tool_installed};
-    use owo_colors::OwoColorize;
+    use devrunner::doctor::{CheckStatus, DoctorReport, Section};
 
     let current_dir = match env::current_dir() {
         Ok(dir) => dir,
@@ -358,53 +1764,50 @@ fn handle_doctor_command(ignore_list: &[String], max_levels: u8) {
         }
     };
 
-    println!("{}", "🩺 Devrunner Project Diagnosis".bold().underline());
-    println!();
+    let mut report = DoctorReport::default();
 
     // Find project directory
-    let (runners, working_dir) = match search_runners(&current_dir, max_levels, ignore_list, false) {
-        Ok(result) => result,
-        Err(_) => {
-            println!("{} No project detected", "❌".red());
-            process::exit(exit_codes::RUNNER_NOT_FOUND);
-        }
-    };
-
-    println!("{}", "Project Detection:".bold());
-    println!("  {} Project root: {}", "→".dimmed(), working_dir.display());
-    println!();
+    let (runners, working_dir) =
+        match search_runners(&current_dir, max_levels, detect_options, false) {
+            Ok(result) => result,
+            Err(_) => {
+                let mut detection = Section::new("Project Detection");
+                detection.push(CheckStatus::Fail, "No project detected");
+                report.sections.push(detection);
+                print_doctor_report(&report, json);
+                process::exit(exit_codes::RUNNER_NOT_FOUND);
+            }
+        };
+    report.project_root = Some(working_dir.display().to_string());
 
     // Check all runners and their tools
-    println!("{}", "Detected Runners:".bold());
-    let all_runners = detect_all(&working_dir, &[]);
-    
+    let mut detected = Section::new("Detected Runners");
+    let all_runners = detect_all(&working_dir, &detect_options.without_ignores());
+
     for runner in &all_runners {
         let installed = is_tool_installed(&runner.name);
-        let status_text = if installed {
+        let (status, status_text) = if installed {
             let version = get_tool_version(&runner.name).unwrap_or_else(|| "installed".to_string());
-            format!("{}", version.dimmed())
+            (CheckStatus::Ok, version)
         } else {
-            format!("{}", "not installed".red())
+            (CheckStatus::Fail, "not installed".to_string())
         };
-        
-        if installed {
-            print!("  {} ", "✓".green());
-        } else {
-            print!("  {} ", "✗".red());
-        }
-        println!(
-            "{} ({}) - {}",
-            runner.name,
-            runner.detected_file,
-            status_text
+        detected.push(
+            status,
+            format!(
+                "{} ({}) - {}",
+                runner.name,
+                runner.detected_files.join(", "),
+                status_text
+            ),
         );
     }
-    println!();
+    report.sections.push(detected);
 
     // Check for conflicts
-    let mut has_conflicts = false;
-    let mut ecosystems: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
-    
+    let mut ecosystems: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+
     for runner in &all_runners {
         ecosystems
             .entry(runner.ecosystem.as_str().to_string())
@@ -412,37 +1815,662 @@ fn handle_doctor_command(ignore_list: &[String], max_levels: u8) {
             .push(runner.name.clone());
     }
 
-    println!("{}", "Conflict Analysis:".bold());
+    let mut conflicts = Section::new("Conflict Analysis");
     for (ecosystem, tools) in &ecosystems {
         if tools.len() > 1 {
-            has_conflicts = true;
-            println!(
-                "  {} {} ecosystem has multiple lockfiles: {}",
-                "⚠".yellow(),
-                ecosystem,
-                tools.join(", ").yellow()
+            conflicts.push(
+                CheckStatus::Warn,
+                format!(
+                    "{} ecosystem has multiple lockfiles: {}",
+                    ecosystem,
+                    tools.join(", ")
+                ),
             );
         }
     }
-    
-    if !has_conflicts {
-        println!("  {} No lockfile conflicts detected", "✓".green());
+
+    if conflicts.checks.is_empty() {
+        conflicts.push(CheckStatus::Ok, "No lockfile conflicts detected");
+    }
+    report.sections.push(conflicts);
+
+    // Lockfile freshness, only when there's a lockfile to compare
+    if devrunner::doctor::has_known_lockfile(&working_dir) {
+        let mut freshness = Section::new("Lockfile Freshness");
+        for stale in devrunner::doctor::lockfile_drift(&working_dir) {
+            freshness.push(
+                CheckStatus::Warn,
+                format!(
+                    "{} is older than {} — run {}",
+                    stale.lockfile, stale.manifest, stale.fix
+                ),
+            );
+        }
+        if freshness.checks.is_empty() {
+            freshness.push(CheckStatus::Ok, "Lockfiles are up to date");
+        }
+        report.sections.push(freshness);
     }
-    println!();
 
     // Script count
-    if let Some(script_list) = scripts::get_scripts_for_runner(&runners[0], &working_dir) {
-        println!(
-            "{} {} scripts available in {}",
-            "✓".green(),
-            script_list.scripts.len(),
-            script_list.source_file
+    if let Some(script_list) =
+        scripts::list_scripts_for_runner(&runners[0], &working_dir, bazel_query)
+    {
+        let mut scripts_section = Section::new("Scripts");
+        scripts_section.push(
+            CheckStatus::Ok,
+            format!(
+                "{} scripts available in {}",
+                script_list.scripts.len(),
+                script_list.source_file
+            ),
         );
+        report.sections.push(scripts_section);
+    }
+
+    report
+        .sections
+        .push(updater_diagnostics(auto_update, offline));
+
+    print_doctor_report(&report, json);
+    if report.fails(threshold) {
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Print a `doctor` report as sections of diagnostics, or as JSON
+fn print_doctor_report(report: &devrunner::doctor::DoctorReport, json: bool) {
+    use devrunner::doctor::CheckStatus;
+    use devrunner::output::Paint;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    println!("{}", "🩺 Devrunner Project Diagnosis".bold().underline());
+    println!();
+    if let Some(root) = &report.project_root {
+        println!("{}", "Project Detection:".bold());
+        println!("  {} Project root: {}", "→".dimmed(), root);
+        println!();
+    }
+    for section in &report.sections {
+        println!("{}", format!("{}:", section.title).bold());
+        for check in &section.checks {
+            let severity = match check.status {
+                Some(CheckStatus::Ok) => Severity::Success,
+                Some(CheckStatus::Warn) => Severity::Warning,
+                Some(CheckStatus::Fail) => Severity::Error,
+                None => {
+                    println!("  {} {}", "→".dimmed(), check.message);
+                    continue;
+                }
+            };
+            println!("  {}", output::diagnostic(severity, &check.message));
+        }
+        println!();
+    }
+}
+
+/// `doctor` section explaining why background updates might silently fail
+fn updater_diagnostics(auto_update: bool, offline: bool) -> devrunner::doctor::Section {
+    use devrunner::doctor::{redact_proxy, CheckStatus, ProxyEnv, Section};
+
+    let mut section = Section::new("Updater Connectivity");
+    if update::is_update_disabled() {
+        section.note("Updates disabled via RUN_NO_UPDATE, skipping network check");
+        return section;
+    }
+    if offline {
+        section.note("Offline (--offline), skipping network check");
+        return section;
+    }
+    if auto_update {
+        section.push(CheckStatus::Ok, "Background updates enabled");
+    } else {
+        section.note("Background updates off (auto_update = false); --update still uses this");
+    }
+
+    let proxy = ProxyEnv::from_env();
+    match &proxy.https_proxy {
+        Some(url) => section.note(format!("HTTPS proxy: {}", redact_proxy(url))),
+        None => section.push(CheckStatus::Ok, "No HTTPS proxy configured"),
+    }
+    if let Some(no_proxy) = &proxy.no_proxy {
+        let note = if proxy.bypasses("api.github.com") {
+            " (bypasses api.github.com)"
+        } else {
+            ""
+        };
+        section.note(format!("NO_PROXY: {}{}", no_proxy, note));
+    }
+
+    // A short timeout keeps doctor quick when the network is blackholed
+    let probe = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())
+        .and_then(|rt| {
+            rt.block_on(update::probe_release_endpoint(
+                std::time::Duration::from_secs(3),
+            ))
+        });
+    match probe {
+        Ok(status) if (200..400).contains(&status) => section.push(
+            CheckStatus::Ok,
+            format!("Release endpoint reachable (HTTP {})", status),
+        ),
+        Ok(status) => section.push(
+            CheckStatus::Fail,
+            format!(
+                "Release endpoint answered HTTP {} ({})",
+                status,
+                update::latest_release_url()
+            ),
+        ),
+        Err(e) => section.push(
+            CheckStatus::Fail,
+            format!("Could not reach {}: {}", update::latest_release_url(), e),
+        ),
+    }
+    section
+}
+
+/// Handle the `init` subcommand - scaffold a .devrunner.toml
+fn handle_init_command(detect_options: &DetectOptions, max_levels: u8, force: bool) {
+    use devrunner::config::PROJECT_CONFIG_FILE;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    // The config belongs next to the detected project, which may be above the current
+    // directory; an undetected project still gets a template where init was run
+    let (detected, working_dir) =
+        match search_runners(&current_dir, max_levels, detect_options, false) {
+            Ok((runners, working_dir)) => (runners.into_iter().next(), working_dir),
+            Err(_) => (None, current_dir),
+        };
+
+    let config_path = working_dir.join(PROJECT_CONFIG_FILE);
+    if config_path.exists() && !force {
+        output::error(&format!(
+            "{} already exists. Use --force to overwrite it.",
+            config_path.display()
+        ));
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+
+    let template = Config::init_template(detected.as_ref().map(|r| r.name.as_str()));
+    if let Err(e) = std::fs::write(&config_path, template) {
+        output::error(&format!("Failed to write {}: {}", config_path.display(), e));
+        process::exit(exit_codes::GENERIC_ERROR);
+    }
+
+    output::success(&format!("Created {}", config_path.display()));
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Handle the `alias` subcommand - list or edit configured aliases
+fn handle_alias_command(config: &Config, action: &AliasCommand) -> ! {
+    use devrunner::output::Paint;
+
+    let config_path = |global: bool| -> std::path::PathBuf {
+        if !global {
+            return Config::project_config_path();
+        }
+        match Config::global_config_path() {
+            Some(path) => path,
+            None => {
+                output::error("Could not determine the global config directory");
+                process::exit(exit_codes::GENERIC_ERROR);
+            }
+        }
+    };
+
+    match action {
+        AliasCommand::List => {
+            if config.aliases.is_empty() {
+                println!(
+                    "{}",
+                    "No aliases configured. Add one with `devrunner alias add <name> <target>`."
+                        .dimmed()
+                );
+                process::exit(exit_codes::SUCCESS);
+            }
+            let mut names: Vec<&String> = config.aliases.keys().collect();
+            names.sort();
+            let width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+            for name in names {
+                println!(
+                    "  {}{} -> {}",
+                    name.cyan(),
+                    " ".repeat(width - name.len()),
+                    config.resolve_alias(name).green()
+                );
+            }
+        }
+        AliasCommand::Add {
+            name,
+            target,
+            global,
+        } => {
+            if let Err(reason) = Config::validate_alias(name, target) {
+                output::error(&reason);
+                process::exit(exit_codes::GENERIC_ERROR);
+            }
+            let path = config_path(*global);
+            if let Err(e) = Config::set_alias_in_file(&path, name, target) {
+                output::error(&format!("Failed to update {}: {}", path.display(), e));
+                process::exit(exit_codes::GENERIC_ERROR);
+            }
+            output::success(&format!(
+                "Added alias {} -> {} in {}",
+                name,
+                target,
+                path.display()
+            ));
+        }
+        AliasCommand::Remove { name, global } => {
+            let path = config_path(*global);
+            match Config::remove_alias_from_file(&path, name) {
+                Ok(true) => {
+                    output::success(&format!("Removed alias {} from {}", name, path.display()))
+                }
+                Ok(false) => {
+                    output::error(&format!("No alias \"{}\" in {}", name, path.display()));
+                    process::exit(exit_codes::GENERIC_ERROR);
+                }
+                Err(e) => {
+                    output::error(&format!("Failed to update {}: {}", path.display(), e));
+                    process::exit(exit_codes::GENERIC_ERROR);
+                }
+            }
+        }
     }
 
     process::exit(exit_codes::SUCCESS);
 }
 
+/// Check a script exists for the runner and print its resolved command
+fn handle_list_only(
+    runner: &devrunner::DetectedRunner,
+    runners: &[devrunner::DetectedRunner],
+    command: &str,
+    args: &[String],
+    working_dir: &std::path::Path,
+    discovery_threads: usize,
+) -> ! {
+    // Runners without a script list (e.g. go, make without targets) accept any task
+    if let Some(script_list) = scripts::get_scripts_for_runner(runner, working_dir) {
+        if !script_list.runs_by_name(command) {
+            let e = devrunner::RunError::ScriptNotFound {
+                script: command.to_string(),
+                source_file: script_list.source_file.clone(),
+            };
+            output::error(&e.to_string());
+            if let Some(suggestion) = suggest_from_all_sources(
+                command,
+                None,
+                runner,
+                runners,
+                working_dir,
+                discovery_threads,
+            ) {
+                eprintln!("{}", script_suggestion(command, &suggestion));
+            } else if let Some(name) = devrunner::cli::suggest_subcommand(command) {
+                eprintln!("{}", subcommand_suggestion(command, &name));
+            }
+            process::exit(e.exit_code());
+        }
+    }
+
+    println!("{}", shell_join(&runner.build_command(command, args)));
+    process::exit(exit_codes::SUCCESS);
+}
+
+/// Ask a `[y/N]` question on the terminal; anything but y/yes means no
+fn prompt_yes_no(question: &str) -> bool {
+    use std::io::Write;
+
+    eprint!("{} [y/N] ", question);
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Prompt for `question` when `needed`, exiting if the user says no
+///
+/// Without a terminal nobody can answer, so devrunner exits with `refusal`
+/// unless `--yes` was given.
+fn confirm_or_exit(needed: bool, yes: bool, question: &str, refusal: &str) {
+    use devrunner::safety::{confirm_action, ConfirmAction};
+    use std::io::IsTerminal;
+
+    let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+    match confirm_action(needed, yes, interactive) {
+        ConfirmAction::Run => {}
+        ConfirmAction::Prompt => {
+            if !prompt_yes_no(question) {
+                output::error("Cancelled");
+                process::exit(exit_codes::NOT_CONFIRMED);
+            }
+        }
+        ConfirmAction::Refuse => {
+            exit_with_error(&devrunner::RunError::NotConfirmed(refusal.to_string()));
+        }
+    }
+}
+
+/// `💡 Did you mean: devrunner doctor?` (`hint:` without color) for a word that resembles a subcommand
+fn subcommand_suggestion(word: &str, subcommand: &str) -> String {
+    use devrunner::output::Paint;
+
+    format!(
+        "{} Did you mean: {} {}?",
+        output::glyph("💡", "hint:"),
+        "devrunner".cyan(),
+        output::highlight_suggestion(word, subcommand)
+    )
+}
+
+/// Closest script to a mistyped command, pooled from every discovered source
+///
+/// With an env, a typo of the base name most likely meant its `:env` variant.
+fn suggest_from_all_sources(
+    command: &str,
+    env_name: Option<&str>,
+    runner: &devrunner::DetectedRunner,
+    runners: &[devrunner::DetectedRunner],
+    working_dir: &std::path::Path,
+    discovery_threads: usize,
+) -> Option<ScriptSuggestion> {
+    use devrunner::fuzzy::{suggest_labeled_script, LabeledScript};
+
+    // Which detected runner runs each source's scripts; a source no runner
+    // reads (or the typed command through the same runner) is no help
+    let source_runners: Vec<(String, &str)> = runners
+        .iter()
+        .filter_map(|r| {
+            scripts::get_scripts_for_runner(r, working_dir)
+                .map(|list| (list.source_file, r.name.as_str()))
+        })
+        .collect();
+    let runner_for = |source: &str| {
+        source_runners
+            .iter()
+            .find(|(file, _)| file == source)
+            .map(|(_, name)| *name)
+    };
+
+    // Binaries run through npx, not as tasks, so only scripts count here
+    let pool: Vec<LabeledScript> =
+        scripts::discover_all_scripts_with(working_dir, discovery_threads)
+            .into_iter()
+            .filter(|list| runner_for(&list.source_file).is_some())
+            .flat_map(|list| {
+                let source = list.source_file;
+                list.scripts
+                    .into_iter()
+                    .filter(|s| s.category == scripts::ScriptCategory::Script)
+                    .map(move |s| LabeledScript {
+                        name: s.name,
+                        source: source.clone(),
+                    })
+            })
+            .filter(|s| s.name != command || runner_for(&s.source) != Some(runner.name.as_str()))
+            .collect();
+
+    let script = env_name
+        .and_then(|env_name| suggest_labeled_script(&format!("{}:{}", command, env_name), &pool))
+        .or_else(|| suggest_labeled_script(command, &pool))?;
+    let prefer = runner_for(&script.source)
+        .filter(|name| *name != runner.name)
+        .map(str::to_string);
+    Some(ScriptSuggestion { script, prefer })
+}
+
+/// A script from any source for a mistyped command
+struct ScriptSuggestion {
+    script: devrunner::fuzzy::LabeledScript,
+    /// The runner to `--prefer` when it isn't the one selected
+    prefer: Option<String>,
+}
+
+/// `💡 Did you mean: devrunner --prefer make build (Makefile)?`
+fn script_suggestion(command: &str, suggestion: &ScriptSuggestion) -> String {
+    use devrunner::output::Paint;
+
+    let prefer = suggestion
+        .prefer
+        .as_ref()
+        .map(|name| format!("--prefer {} ", name))
+        .unwrap_or_default();
+    format!(
+        "{} Did you mean: {} {}{} {}?",
+        output::glyph("💡", "hint:"),
+        "devrunner".cyan(),
+        prefer,
+        output::highlight_suggestion(command, &suggestion.script.name),
+        format!("({})", suggestion.script.source).dimmed()
+    )
+}
+
+/// Whether the runner lists its scripts and `command` isn't one of them
+///
+/// Runners without a script list (e.g. go) accept any task, so nothing is missing.
+fn is_missing_script(
+    runner: &devrunner::DetectedRunner,
+    command: &str,
+    working_dir: &std::path::Path,
+) -> bool {
+    scripts::get_scripts_for_runner(runner, working_dir)
+        .is_some_and(|script_list| !script_list.runs_by_name(command))
+}
+
+/// Handle the `exec` subcommand - run a tool in the detected project's context
+fn handle_exec_command(
+    config: &Config,
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    prefer: Option<&str>,
+    tool: &str,
+    args: &[String],
+    exec_options: &ExecOptions,
+) -> ! {
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    let (runners, working_dir) = match search_runners(
+        &current_dir,
+        max_levels,
+        detect_options,
+        exec_options.verbose,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    let runner = match select_runner(&runners, &working_dir, prefer, config, exec_options.verbose) {
+        Ok(r) => r,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    match execute_tool(&runner, tool, args, &working_dir, exec_options) {
+        Ok(result) => process::exit(exit_code_from_status(result.exit_status)),
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    }
+}
+
+/// Handle the `clean` subcommand - run or perform the ecosystem's cleanup
+fn handle_clean_command(
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    args: &[String],
+    yes: bool,
+    confirm_patterns: &[String],
+    exec_options: &ExecOptions,
+) -> ! {
+    use devrunner::runner::{plan_clean, remove_clean_dirs, CleanPlan};
+    use devrunner::safety::needs_confirmation;
+
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    let (runners, working_dir) = match search_runners(
+        &current_dir,
+        max_levels,
+        detect_options,
+        exec_options.verbose,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    let plan = match plan_clean(&runners, &working_dir) {
+        Ok(plan) => plan,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    let (runner, dirs) = match plan {
+        CleanPlan::Task(runner) => {
+            // The runner's own clean task is confirmed like a `clean` script
+            if !exec_options.dry_run {
+                confirm_or_exit(
+                    needs_confirmation("clean", confirm_patterns),
+                    yes,
+                    &format!("Run '{} clean'?", runner.name),
+                    "'clean' needs confirmation; pass --yes to run it without a terminal",
+                );
+            }
+            match execute(&runner, "clean", args, &working_dir, exec_options) {
+                Ok(result) => process::exit(exit_code_from_status(result.exit_status)),
+                Err(e) => {
+                    exit_with_error(&e);
+                }
+            }
+        }
+        CleanPlan::Remove(runner, dirs) => (runner, dirs),
+    };
+
+    // Args would be silently dropped (a stray --dry-run here must not delete anything)
+    if !args.is_empty() {
+        let e = devrunner::RunError::InvalidArgument(format!(
+            "clean takes no arguments for {} projects (got {})",
+            runner.ecosystem.as_str(),
+            shell_join(args)
+        ));
+        exit_with_error(&e);
+    }
+
+    if dirs.is_empty() {
+        if exec_options.quiet == 0 {
+            output::success("Nothing to clean");
+        }
+        process::exit(exit_codes::SUCCESS);
+    }
+
+    // Deleting node_modules and friends is always confirmed
+    let listing: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+    if !exec_options.dry_run {
+        confirm_or_exit(
+            true,
+            yes,
+            &format!("Remove {}?", listing.join(", ")),
+            "clean deletes directories; pass --yes to run it without a terminal",
+        );
+    }
+
+    match remove_clean_dirs(&dirs, &working_dir, exec_options) {
+        Ok(()) => {
+            if !exec_options.dry_run && exec_options.quiet == 0 {
+                output::success(&format!(
+                    "Removed {} ({})",
+                    listing.join(", "),
+                    runner.ecosystem.as_str()
+                ));
+            }
+            process::exit(exit_codes::SUCCESS);
+        }
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    }
+}
+
+/// Handle the `install` subcommand - install dependencies with the detected runner
+fn handle_install_command(
+    config: &Config,
+    detect_options: &DetectOptions,
+    max_levels: u8,
+    prefer: Option<&str>,
+    args: &[String],
+    exec_options: &ExecOptions,
+) -> ! {
+    let current_dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            output::error(&format!("Failed to get current directory: {}", e));
+            process::exit(exit_codes::GENERIC_ERROR);
+        }
+    };
+
+    let (runners, working_dir) = match search_runners(
+        &current_dir,
+        max_levels,
+        detect_options,
+        exec_options.verbose,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    let runner = match select_runner(&runners, &working_dir, prefer, config, exec_options.verbose) {
+        Ok(r) => r,
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    };
+
+    match execute_install(&runner, args, &working_dir, exec_options) {
+        Ok(result) => process::exit(exit_code_from_status(result.exit_status)),
+        Err(e) => {
+            exit_with_error(&e);
+        }
+    }
+}
+
 /// Try to get the version of a tool
 fn get_tool_version(tool: &str) -> Option<String> {
     use std::process::Command;
@@ -456,10 +2484,7 @@ fn get_tool_version(tool: &str) -> Option<String> {
         _ => "--version",
     };
 
-    let output = Command::new(tool)
-        .arg(version_flag)
-        .output()
-        .ok()?;
+    let output = Command::new(tool).arg(version_flag).output().ok()?;
 
     if output.status.success() {
         let version = String::from_utf8_lossy(&output.stdout);
@@ -467,7 +2492,12 @@ fn get_tool_version(tool: &str) -> Option<String> {
         // Extract just the version number if possible
         let version = version
             .split_whitespace()
-            .find(|s| s.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false))
+            .find(|s| {
+                s.chars()
+                    .next()
+                    .map(|c| c.is_ascii_digit())
+                    .unwrap_or(false)
+            })
             .unwrap_or(version);
         Some(version.trim_start_matches('v').to_string())
     } else {
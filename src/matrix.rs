@@ -0,0 +1,179 @@
+//! `--select` matrix expansion for parameterized scripts
+//!
+//! Each `--select NAME=V1,V2` is a dimension; a script runs once per variant,
+//! one for every combination of values. In a variant, `{NAME}` anywhere in the
+//! command (the user's args or a `[[custom_runners]]` template) becomes the
+//! value, and `SELECT_NAME` is set to it, so a package.json script or Makefile
+//! target can read it too. Placeholders of names that weren't selected are
+//! left alone.
+
+/// One `--select` dimension, e.g. `browser=chrome,firefox`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dimension {
+    pub name: String,
+    /// In the order given, without duplicates
+    pub values: Vec<String>,
+}
+
+/// The value picked for each dimension, in `--select` order
+pub type Variant = Vec<(String, String)>;
+
+/// Parse a `--select` value (`browser=chrome,firefox`)
+///
+/// Names are letters, digits and `_`, so `SELECT_<name>` is a valid variable.
+pub fn parse_dimension(value: &str) -> Result<Dimension, String> {
+    let (name, values) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE[,VALUE...], got \"{}\"", value))?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!(
+            "invalid --select name \"{}\" (use letters, digits and _)",
+            name
+        ));
+    }
+
+    let mut unique: Vec<String> = Vec::new();
+    for value in values.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+        if !unique.iter().any(|v| v == value) {
+            unique.push(value.to_string());
+        }
+    }
+    if unique.is_empty() {
+        return Err(format!("--select {} needs at least one value", name));
+    }
+    Ok(Dimension {
+        name: name.to_string(),
+        values: unique,
+    })
+}
+
+/// Every combination of the dimensions' values, the last dimension varying fastest
+///
+/// No dimensions is a single variant that selects nothing. A name given twice
+/// is an error rather than a silent override.
+pub fn expand(dimensions: &[Dimension]) -> Result<Vec<Variant>, String> {
+    for (index, dimension) in dimensions.iter().enumerate() {
+        if dimensions[..index].iter().any(|d| d.name == dimension.name) {
+            return Err(format!(
+                "--select {} is given more than once",
+                dimension.name
+            ));
+        }
+    }
+
+    let mut variants: Vec<Variant> = vec![Vec::new()];
+    for dimension in dimensions {
+        variants = variants
+            .into_iter()
+            .flat_map(|variant| {
+                dimension.values.iter().map(move |value| {
+                    let mut next = variant.clone();
+                    next.push((dimension.name.clone(), value.clone()));
+                    next
+                })
+            })
+            .collect();
+    }
+    Ok(variants)
+}
+
+/// `args` with each `{name}` of the variant replaced by its value
+pub fn substitute(args: &[String], variant: &[(String, String)]) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            variant.iter().fold(arg.clone(), |arg, (name, value)| {
+                arg.replace(&format!("{{{}}}", name), value)
+            })
+        })
+        .collect()
+}
+
+/// `SELECT_<name>` variables for a variant
+pub fn env_vars(variant: &[(String, String)]) -> Vec<(String, String)> {
+    variant
+        .iter()
+        .map(|(name, value)| (format!("SELECT_{}", name), value.clone()))
+        .collect()
+}
+
+/// Short description for progress lines and the summary, e.g. `browser=chrome os=linux`
+pub fn label(variant: &[(String, String)]) -> String {
+    variant
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dimension(value: &str) -> Dimension {
+        parse_dimension(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!(
+            dimension("browser=chrome, firefox,,chrome"),
+            Dimension {
+                name: "browser".to_string(),
+                values: vec!["chrome".to_string(), "firefox".to_string()],
+            }
+        );
+        assert!(parse_dimension("browser").is_err());
+        assert!(parse_dimension("browser=").is_err());
+        assert!(parse_dimension("=chrome").is_err());
+        assert!(parse_dimension("my-browser=chrome").is_err());
+    }
+
+    #[test]
+    fn test_expand_cartesian() {
+        let variants = expand(&[
+            dimension("browser=chrome,firefox"),
+            dimension("os=linux,mac,win"),
+        ])
+        .unwrap();
+        let labels: Vec<String> = variants.iter().map(|v| label(v)).collect();
+        assert_eq!(
+            labels,
+            [
+                "browser=chrome os=linux",
+                "browser=chrome os=mac",
+                "browser=chrome os=win",
+                "browser=firefox os=linux",
+                "browser=firefox os=mac",
+                "browser=firefox os=win",
+            ]
+        );
+
+        assert_eq!(expand(&[]).unwrap(), vec![Vec::new()]);
+        assert!(expand(&[dimension("os=linux"), dimension("os=mac")]).is_err());
+    }
+
+    #[test]
+    fn test_substitute_and_env() {
+        let variant = vec![
+            ("browser".to_string(), "firefox".to_string()),
+            ("os".to_string(), "linux".to_string()),
+        ];
+        let args = vec![
+            "--project={browser}".to_string(),
+            "{os}-{browser}".to_string(),
+            "{shard}".to_string(),
+        ];
+        assert_eq!(
+            substitute(&args, &variant),
+            ["--project=firefox", "linux-firefox", "{shard}"]
+        );
+        assert_eq!(
+            env_vars(&variant),
+            [
+                ("SELECT_browser".to_string(), "firefox".to_string()),
+                ("SELECT_os".to_string(), "linux".to_string()),
+            ]
+        );
+    }
+}
@@ -1,51 +1,218 @@
-use owo_colors::OwoColorize;
 use std::env;
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+/// How command output is presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputMode {
+    /// Stream output straight to the terminal
+    #[default]
+    Human,
+    /// Newline-delimited JSON events (start, stdout/stderr lines, exit)
+    JsonStream,
+}
+
+/// When to color output (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    /// Always emit ANSI colors, even through a pipe
+    Always,
+    /// Never emit ANSI colors
+    Never,
+}
+
+/// Process-wide `--color` setting, stored as the enum's discriminant
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Apply the `--color` setting for the rest of the process
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+}
+
+fn color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        1 => ColorChoice::Always,
+        2 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// Whether a color choice results in colored output
+fn should_color(choice: ColorChoice, no_color: bool, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => !no_color && is_terminal,
+    }
+}
 
 /// Check if colors should be disabled
 pub fn colors_disabled() -> bool {
-    env::var("NO_COLOR").is_ok()
+    !should_color(
+        color_choice(),
+        env::var("NO_COLOR").is_ok(),
+        io::stdout().is_terminal(),
+    )
 }
 
-/// Print a success message
-pub fn success(message: &str) {
+/// `fancy` (an emoji) for colored output, else its ASCII stand-in `plain`
+///
+/// Plain output goes to pipes, CI logs and terminals without emoji fonts.
+pub fn glyph(fancy: &'static str, plain: &'static str) -> &'static str {
     if colors_disabled() {
-        eprintln!("✓ {}", message);
+        plain
     } else {
-        eprintln!("{} {}", "✓".green(), message.green());
+        fancy
     }
 }
 
-/// Print a warning message
-pub fn warning(message: &str) {
+/// Styling that honors `--color` and NO_COLOR
+///
+/// Mirrors the owo-colors methods used across the CLI, whose own output is
+/// unconditional; import this instead of `owo_colors::OwoColorize`.
+pub trait Paint: Display {
+    fn green(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::green(&s).to_string())
+    }
+    fn red(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::red(&s).to_string())
+    }
+    fn yellow(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::yellow(&s).to_string())
+    }
+    fn blue(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::blue(&s).to_string())
+    }
+    fn cyan(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::cyan(&s).to_string())
+    }
+    fn bold(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::bold(&s).to_string())
+    }
+    fn dimmed(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::dimmed(&s).to_string())
+    }
+    fn underline(&self) -> String {
+        paint(self, |s| owo_colors::OwoColorize::underline(&s).to_string())
+    }
+}
+
+impl<T: Display + ?Sized> Paint for T {}
+
+fn paint<T: Display + ?Sized>(text: &T, style: impl Fn(&str) -> String) -> String {
+    let text = text.to_string();
     if colors_disabled() {
-        eprintln!("⚠ {}", message);
+        text
     } else {
-        eprintln!("{} {}", "⚠".yellow(), message.yellow());
+        style(&text)
+    }
+}
+
+/// `suggestion` with what differs from `input` emphasised, like a
+/// character-level diff: inserted and substituted characters stand out
+/// from the ones already typed correctly. Plain text when colors are off.
+pub fn highlight_suggestion(input: &str, suggestion: &str) -> String {
+    use crate::fuzzy::{edit_operations, EditOp};
+
+    // Runs of (changed, text), so each run gets one escape sequence
+    let mut runs: Vec<(bool, String)> = Vec::new();
+    for op in edit_operations(input, suggestion) {
+        let (changed, c) = match op {
+            EditOp::Keep(c) => (false, c),
+            EditOp::Substitute(_, c) | EditOp::Insert(c) => (true, c),
+            EditOp::Delete(_) => continue,
+        };
+        match runs.last_mut() {
+            Some((last, text)) if *last == changed => text.push(c),
+            _ => runs.push((changed, c.to_string())),
+        }
     }
+    runs.iter()
+        .map(|(changed, text)| {
+            if *changed {
+                text.yellow().bold().underline()
+            } else {
+                text.green().bold()
+            }
+        })
+        .collect()
+}
+
+/// How serious a diagnostic is, which picks its prefix and color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Symbol that starts the line, so severity survives without color; a
+    /// word like `warning:` in plain output
+    pub fn prefix(self, color: bool) -> &'static str {
+        match (self, color) {
+            (Severity::Info, true) => "ℹ",
+            (Severity::Success, true) => "✓",
+            (Severity::Warning, true) => "⚠",
+            (Severity::Error, true) => "❌",
+            (Severity::Info, false) => "info:",
+            (Severity::Success, false) => "ok:",
+            (Severity::Warning, false) => "warning:",
+            (Severity::Error, false) => "error:",
+        }
+    }
+}
+
+/// `<prefix> <message>`, colored by severity unless `--color`/NO_COLOR turn colors off
+///
+/// For report lines on stdout (e.g. `doctor`); [`warn`] and friends print it to stderr.
+pub fn diagnostic(severity: Severity, message: &str) -> String {
+    format_diagnostic(severity, message, !colors_disabled())
+}
+
+fn format_diagnostic(severity: Severity, message: &str, color: bool) -> String {
+    let line = format!("{} {}", severity.prefix(color), message);
+    if !color {
+        return line;
+    }
+    match severity {
+        Severity::Info => owo_colors::OwoColorize::cyan(&line).to_string(),
+        Severity::Success => owo_colors::OwoColorize::green(&line).to_string(),
+        Severity::Warning => owo_colors::OwoColorize::yellow(&line).to_string(),
+        Severity::Error => owo_colors::OwoColorize::red(&line).to_string(),
+    }
+}
+
+/// Print a success message
+pub fn success(message: &str) {
+    eprintln!("{}", diagnostic(Severity::Success, message));
+}
+
+/// Print a warning message
+pub fn warn(message: &str) {
+    eprintln!("{}", diagnostic(Severity::Warning, message));
 }
 
 /// Print an error message
 pub fn error(message: &str) {
-    if colors_disabled() {
-        eprintln!("❌ {}", message);
-    } else {
-        eprintln!("{} {}", "❌".red(), message.red());
-    }
+    eprintln!("{}", diagnostic(Severity::Error, message));
 }
 
 /// Print an info message (for verbose mode)
 pub fn info(message: &str) {
-    if colors_disabled() {
-        eprintln!("🔍 {}", message);
-    } else {
-        eprintln!("{} {}", "🔍".cyan(), message.cyan());
-    }
+    eprintln!("{}", diagnostic(Severity::Info, message));
 }
 
 /// Print a detection message (for verbose mode)
 pub fn detected(runner: &str, file: &str) {
     if colors_disabled() {
-        eprintln!("📦 Detected: {} ({})", runner, file);
+        eprintln!("* Detected: {} ({})", runner, file);
     } else {
         eprintln!(
             "{} Detected: {} ({})",
@@ -59,12 +226,41 @@ pub fn detected(runner: &str, file: &str) {
 /// Print a command execution message
 pub fn executing(command: &str) {
     if colors_disabled() {
-        eprintln!("✓ Executing: {}", command);
+        eprintln!("> Executing: {}", command);
     } else {
         eprintln!("{} Executing: {}", "✓".green(), command.green());
     }
 }
 
+/// Print a diagnostic summary for a failed command
+pub fn exit_explanation(
+    code: i32,
+    signal: Option<i32>,
+    runner: &str,
+    command: &str,
+    hint: Option<&str>,
+) {
+    let status = match signal {
+        Some(signal) => format!("exit code {} (killed by signal {})", code, signal),
+        None => format!("exit code {}", code),
+    };
+    if colors_disabled() {
+        eprintln!("error: Command failed with {}", status);
+        eprintln!("   Runner:  {}", runner);
+        eprintln!("   Command: {}", command);
+        if let Some(hint) = hint {
+            eprintln!("   Hint:    {}", hint);
+        }
+    } else {
+        eprintln!("{} Command failed with {}", "❌".red(), status.red().bold());
+        eprintln!("   Runner:  {}", runner.cyan());
+        eprintln!("   Command: {}", command.dimmed());
+        if let Some(hint) = hint {
+            eprintln!("   Hint:    {}", hint.yellow());
+        }
+    }
+}
+
 /// Print an update notification
 pub fn update_notification(from_version: &str, to_version: &str, changelog: Option<&str>) {
     if colors_disabled() {
@@ -87,3 +283,299 @@ pub fn update_notification(from_version: &str, to_version: &str, changelog: Opti
         }
     }
 }
+
+/// Format an elapsed time the way timing output shows it (e.g. "1.25s", "2m 3.5s")
+pub fn format_duration(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64();
+    if seconds < 60.0 {
+        format!("{:.2}s", seconds)
+    } else {
+        let minutes = (seconds / 60.0).floor() as u64;
+        format!("{}m {:.1}s", minutes, seconds % 60.0)
+    }
+}
+
+/// Parse a duration like "500ms", "2s", "1.5m"; a bare number means seconds
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, scale) = if let Some(n) = value.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = value.strip_suffix('s') {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix('m') {
+        (n, 60.0)
+    } else {
+        (value, 1.0)
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .and_then(|n| Duration::try_from_secs_f64(n * scale).ok())
+        .ok_or_else(|| format!("invalid duration '{}' (expected e.g. 500ms, 2s, 1m)", value))
+}
+
+/// Whether the completion timing line is worth printing: always with no
+/// threshold, otherwise only for runs slower than it
+pub fn should_show_timing(elapsed: Duration, threshold: Duration) -> bool {
+    threshold.is_zero() || elapsed > threshold
+}
+
+/// Render a per-command timing table: name, duration and pass/fail, plus a total
+///
+/// `fancy` marks the status with ✓/✗, which plain output leaves out.
+pub fn timing_summary(results: &[(String, Duration, bool)], fancy: bool) -> String {
+    let name_width = results
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .chain(["total".len()])
+        .max()
+        .unwrap_or(0);
+    let durations: Vec<String> = results
+        .iter()
+        .map(|(_, duration, _)| format_duration(*duration))
+        .collect();
+    let total = format_duration(results.iter().map(|(_, duration, _)| *duration).sum());
+    let duration_width = durations
+        .iter()
+        .map(|d| d.len())
+        .chain([total.len()])
+        .max()
+        .unwrap_or(0);
+
+    let mut table = String::from("Timing summary:\n");
+    for ((name, _, success), duration) in results.iter().zip(&durations) {
+        let status = match (*success, fancy) {
+            (true, true) => "✓ passed",
+            (false, true) => "✗ failed",
+            (true, false) => "passed",
+            (false, false) => "failed",
+        };
+        table.push_str(&format!(
+            "  {:<name_width$}  {:>duration_width$}  {}\n",
+            name, duration, status
+        ));
+    }
+    table.push_str(&format!(
+        "  {:<name_width$}  {:>duration_width$}\n",
+        "total", total
+    ));
+    table
+}
+
+/// Print the per-command timing table after a multi-command run
+pub fn print_timing_summary(results: &[(String, Duration, bool)]) {
+    let plain = colors_disabled();
+    let table = timing_summary(results, !plain);
+    if plain {
+        eprint!("\n{}", table);
+    } else {
+        eprint!("\n{}", table.dimmed());
+    }
+}
+
+/// Min/max/mean/median of repeated runs (`--repeat`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DurationStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+}
+
+/// Summarize run durations; None when there are none
+pub fn duration_stats(durations: &[Duration]) -> Option<DurationStats> {
+    let mut sorted = durations.to_vec();
+    sorted.sort();
+    let count = sorted.len();
+    let median = match count {
+        0 => return None,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+    };
+    Some(DurationStats {
+        min: sorted[0],
+        max: sorted[count - 1],
+        mean: sorted.iter().sum::<Duration>() / count as u32,
+        median,
+    })
+}
+
+/// Print the `--repeat` summary: how many runs passed, then the duration stats
+pub fn print_repeat_summary(durations: &[Duration], failures: usize) {
+    let Some(stats) = duration_stats(durations) else {
+        return;
+    };
+    let summary = format!(
+        "{} run(s), {} failed: min {}  max {}  mean {}  median {}",
+        durations.len(),
+        failures,
+        format_duration(stats.min),
+        format_duration(stats.max),
+        format_duration(stats.mean),
+        format_duration(stats.median)
+    );
+    if colors_disabled() {
+        eprintln!("\n{}", summary);
+    } else {
+        eprintln!("\n{}", summary.dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(should_color(ColorChoice::Auto, false, true));
+        assert!(!should_color(ColorChoice::Auto, false, false));
+        assert!(!should_color(ColorChoice::Auto, true, true));
+        assert!(should_color(ColorChoice::Always, true, false));
+        assert!(!should_color(ColorChoice::Never, false, true));
+    }
+
+    // The only test touching the process-wide choice, so it can't race another
+    #[test]
+    fn test_color_choice_styles_strings() {
+        set_color_choice(ColorChoice::Always);
+        assert_eq!(Paint::green("ok"), "\x1b[32mok\x1b[39m");
+        // `tets` -> `test`: the typed "te" stays green, the swapped "st" stands out
+        let highlighted = highlight_suggestion("tets", "test");
+        assert!(highlighted.starts_with(&Paint::bold(&Paint::green("te"))));
+        assert!(highlighted.ends_with(&Paint::underline(&Paint::bold(&Paint::yellow("st")))));
+
+        set_color_choice(ColorChoice::Never);
+        assert_eq!(Paint::green("ok"), "ok");
+        assert_eq!(Paint::bold(&Paint::red("failed")), "failed");
+        assert_eq!(highlight_suggestion("tets", "test"), "test");
+
+        set_color_choice(ColorChoice::Auto);
+        let auto = Paint::green("ok");
+        if env::var("NO_COLOR").is_ok() || !io::stdout().is_terminal() {
+            assert_eq!(auto, "ok");
+        } else {
+            assert_eq!(auto, "\x1b[32mok\x1b[39m");
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_prefixes() {
+        assert_eq!(
+            format_diagnostic(Severity::Warning, "stale lockfile", false),
+            "warning: stale lockfile"
+        );
+        assert_eq!(
+            format_diagnostic(Severity::Info, "using pnpm", false),
+            "info: using pnpm"
+        );
+        assert_eq!(
+            format_diagnostic(Severity::Success, "done", false),
+            "ok: done"
+        );
+        assert_eq!(
+            format_diagnostic(Severity::Error, "failed", false),
+            "error: failed"
+        );
+        assert_eq!(
+            format_diagnostic(Severity::Warning, "stale lockfile", true),
+            "\x1b[33m⚠ stale lockfile\x1b[39m"
+        );
+
+        assert_eq!(
+            format_diagnostic(Severity::Error, "failed", true),
+            "\x1b[31m❌ failed\x1b[39m"
+        );
+
+        // NO_COLOR on a terminal leaves the plain ASCII line
+        let color = should_color(ColorChoice::Auto, true, true);
+        assert_eq!(
+            format_diagnostic(Severity::Error, "failed", color),
+            "error: failed"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(1250)), "1.25s");
+        assert_eq!(format_duration(Duration::from_millis(123_500)), "2m 3.5s");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("3"), Ok(Duration::from_secs(3)));
+        assert_eq!(parse_duration("0"), Ok(Duration::ZERO));
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("1e300").is_err());
+        assert!(parse_duration("1e300m").is_err());
+    }
+
+    #[test]
+    fn test_should_show_timing() {
+        let second = Duration::from_secs(1);
+        assert!(should_show_timing(
+            Duration::from_millis(10),
+            Duration::ZERO
+        ));
+        assert!(!should_show_timing(Duration::from_millis(10), second));
+        assert!(!should_show_timing(second, second));
+        assert!(should_show_timing(Duration::from_millis(1001), second));
+    }
+
+    #[test]
+    fn test_duration_stats() {
+        let ms = Duration::from_millis;
+        let stats = duration_stats(&[ms(300), ms(100), ms(200), ms(600)]).unwrap();
+        assert_eq!(
+            stats,
+            DurationStats {
+                min: ms(100),
+                max: ms(600),
+                mean: ms(300),
+                median: ms(250),
+            }
+        );
+
+        let stats = duration_stats(&[ms(40), ms(10), ms(25)]).unwrap();
+        assert_eq!(stats.median, ms(25));
+        assert_eq!(stats.mean, ms(25));
+
+        assert_eq!(duration_stats(&[ms(7)]).unwrap().median, ms(7));
+        assert_eq!(duration_stats(&[]), None);
+    }
+
+    #[test]
+    fn test_timing_summary() {
+        let results = vec![
+            ("lint".to_string(), Duration::from_millis(1200), true),
+            (
+                "test:unit".to_string(),
+                Duration::from_millis(12_340),
+                false,
+            ),
+        ];
+
+        assert_eq!(
+            timing_summary(&results, true),
+            "Timing summary:\n\
+             \x20 lint        1.20s  ✓ passed\n\
+             \x20 test:unit  12.34s  ✗ failed\n\
+             \x20 total      13.54s\n"
+        );
+        assert!(timing_summary(&results, false).contains("12.34s  failed\n"));
+        assert!(!timing_summary(&results, false).contains('✓'));
+    }
+
+    #[test]
+    fn test_timing_summary_empty() {
+        assert_eq!(
+            timing_summary(&[], true),
+            "Timing summary:\n  total  0.00s\n"
+        );
+    }
+}
@@ -1,56 +1,323 @@
-use crate::detectors::{detect_all, is_tool_installed, DetectedRunner, Ecosystem};
-use crate::error::RunError;
-use crate::output;
-use std::collections::HashMap;
+use crate::detectors::{
+    detect_all, detect_all_profiled, find_devcontainer, is_executable, is_tool_installed,
+    nvmrc_version, pick_node_manager, DetectOptions, DetectedRunner, DetectionProfile,
+    DevContainer, Ecosystem, NodeManager,
+};
+use crate::error::{exit_codes, RunError};
+use crate::output::{self, OutputMode};
+use crate::safety;
+use crate::scripts;
+use crate::signals::{self, ForwardedChild};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 
 /// Result of running a command
 pub struct RunResult {
     pub exit_status: ExitStatus,
     pub runner: DetectedRunner,
     pub working_dir: PathBuf,
+    /// The argv that was (or, for dry runs, would have been) executed
+    pub command: Vec<String>,
+}
+
+/// Result of running a command with its output captured
+#[derive(Debug)]
+pub struct CapturedRun {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_status: ExitStatus,
+    /// Some output was dropped because a stream went past the byte cap
+    pub truncated: bool,
+}
+
+/// Options controlling how a command is executed
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+    /// Show command without executing
+    pub dry_run: bool,
+    /// With `dry_run`, print the planned execution as JSON instead of the command line
+    pub json: bool,
+    /// Show detailed detection information
+    pub verbose: bool,
+    /// Quiet level: 1 hides devrunner's own output, 2 also silences the child's stdout
+    pub quiet: u8,
+    /// Mirror the child's stdout/stderr into this file
+    pub tee: Option<PathBuf>,
+    /// How command output is presented
+    pub output: OutputMode,
+    /// Don't activate a local Python virtualenv
+    pub no_venv: bool,
+    /// Safe mode for untrusted checkouts: skip npm pre/post hooks, refuse lifecycle scripts
+    pub ignore_scripts: bool,
+    /// Buffer output and only print the last N lines per stream if the command fails
+    pub tail_on_failure: Option<usize>,
+    /// Shell commands whose `KEY=value` output is added to the environment, in order
+    pub env_from: Vec<String>,
+    /// Run inside the project's dev container (`.devcontainer/devcontainer.json`)
+    pub in_container: bool,
+    /// Print the child's resolved environment instead of running the command
+    pub print_env: bool,
+    /// Give the child `/dev/null` as stdin instead of the terminal, so prompts hit EOF
+    pub no_stdin: bool,
+    /// `[default_args]`: runner or ecosystem name -> args put before the user's
+    pub default_args: HashMap<String, Vec<String>>,
+    /// Run Node.js commands under the `.nvmrc` version via fnm, nvm or Volta
+    pub node_version_manager: bool,
+    /// `[runner_paths]`/`--runner-path`: runner name -> absolute path of its binary
+    pub runner_paths: HashMap<String, PathBuf>,
+    /// `--clean-env`: start from an empty environment, passing through only
+    /// [`CLEAN_ENV_BASE`] and these variables (`clean_env_allowlist`)
+    pub clean_env: Option<Vec<String>>,
+    /// `--select` variant being run: `{name}` in the command becomes its value
+    /// and `SELECT_<name>` is set
+    pub select: crate::matrix::Variant,
+}
+
+/// Variables `--clean-env` always passes through
+#[cfg(not(windows))]
+pub const CLEAN_ENV_BASE: &[&str] = &["PATH", "HOME"];
+/// Variables `--clean-env` always passes through; Windows programs need their
+/// system directory and profile as well
+#[cfg(windows)]
+pub const CLEAN_ENV_BASE: &[&str] = &["PATH", "HOME", "SystemRoot", "USERPROFILE"];
+
+/// How far the upward runner search goes (`--levels`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchDepth {
+    /// Stop at the first directory with a runner, at most N levels up
+    Levels(u8),
+    /// Collect runners at every level up to the project root
+    Auto,
+}
+
+impl SearchDepth {
+    /// Levels `auto` may climb when no root marker stops it first
+    pub const AUTO_MAX_LEVELS: u8 = 10;
+
+    /// Hard cap on levels climbed, whatever a caller asks for
+    pub const WALK_CAP: u8 = 64;
+
+    /// Maximum levels searched above the start directory
+    pub fn max_levels(self) -> u8 {
+        match self {
+            SearchDepth::Levels(levels) => levels,
+            SearchDepth::Auto => Self::AUTO_MAX_LEVELS,
+        }
+    }
+}
+
+impl std::str::FromStr for SearchDepth {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(SearchDepth::Auto);
+        }
+        match value.parse::<u8>() {
+            Ok(levels) if levels <= Self::AUTO_MAX_LEVELS => Ok(SearchDepth::Levels(levels)),
+            _ => Err(format!(
+                "expected a number from 0 to {} or `auto`",
+                Self::AUTO_MAX_LEVELS
+            )),
+        }
+    }
+}
+
+/// Runners found in one directory of the upward search
+#[derive(Debug, Clone)]
+pub struct LevelRunners {
+    /// Levels above the start directory (0 = the start directory itself)
+    pub level: u8,
+    pub dir: PathBuf,
+    pub runners: Vec<DetectedRunner>,
 }
 
 /// Search for runners in the directory hierarchy
+///
+/// The walk uses canonical paths, so a symlinked start directory reports the
+/// real `working_dir`, and a symlink cycle can't send it round in circles:
+/// each parent of a canonical path is strictly shorter. It never ascends
+/// past a directory holding one of the root markers.
 pub fn search_runners(
     start_dir: &Path,
     max_levels: u8,
-    ignore_list: &[String],
+    options: &DetectOptions,
     verbose: bool,
 ) -> Result<(Vec<DetectedRunner>, PathBuf), RunError> {
-    let mut current_dir = start_dir.to_path_buf();
+    let mut levels = walk_levels(start_dir, max_levels, options, verbose, false, None)?;
+    let nearest = levels.remove(0);
+    Ok((nearest.runners, nearest.dir))
+}
+
+/// Walk like a normal search, timing every detector (`--profile-detection`)
+///
+/// The profile covers each directory visited even when the search fails.
+pub fn profile_search(
+    start_dir: &Path,
+    depth: SearchDepth,
+    options: &DetectOptions,
+) -> (DetectionProfile, Result<Vec<LevelRunners>, RunError>) {
+    let mut profile = DetectionProfile::default();
+    let collect_all = depth == SearchDepth::Auto;
+    let levels = walk_levels(
+        start_dir,
+        depth.max_levels(),
+        options,
+        false,
+        collect_all,
+        Some(&mut profile),
+    );
+    (profile, levels)
+}
+
+/// Search every level up to the project root (`--levels=auto`), nearest first
+///
+/// Unlike [`search_runners`] the walk doesn't stop at the first directory with
+/// a runner, so nested projects report each enclosing project too.
+pub fn search_runner_levels(
+    start_dir: &Path,
+    options: &DetectOptions,
+    verbose: bool,
+) -> Result<Vec<LevelRunners>, RunError> {
+    walk_levels(
+        start_dir,
+        SearchDepth::AUTO_MAX_LEVELS,
+        options,
+        verbose,
+        true,
+        None,
+    )
+}
+
+/// Index of the nearest level where `prefer` was detected
+pub fn level_with_runner(levels: &[LevelRunners], prefer: Option<&str>) -> Option<usize> {
+    let prefer = prefer?;
+    levels.iter().position(|level| {
+        level
+            .runners
+            .iter()
+            .any(|r| r.name.eq_ignore_ascii_case(prefer))
+    })
+}
+
+/// Walk up from `start_dir`; returns at least one level or an error
+///
+/// The walk never climbs more than [`SearchDepth::WALK_CAP`] levels. Running
+/// out of parents is reported apart from running out of levels.
+fn walk_levels(
+    start_dir: &Path,
+    max_levels: u8,
+    options: &DetectOptions,
+    verbose: bool,
+    collect_all: bool,
+    mut profile: Option<&mut DetectionProfile>,
+) -> Result<Vec<LevelRunners>, RunError> {
+    let max_levels = max_levels.min(SearchDepth::WALK_CAP);
+    let mut current_dir = canonical_or_self(start_dir);
+    let mut found = Vec::new();
 
     for level in 0..=max_levels {
         if verbose {
             output::info(&format!("Searching in {:?} (level {})", current_dir, level));
         }
 
-        let runners = detect_all(&current_dir, ignore_list);
+        let runners = match profile.as_deref_mut() {
+            Some(profile) => detect_all_profiled(&current_dir, options, profile),
+            None => detect_all(&current_dir, options),
+        };
         if !runners.is_empty() {
-            return Ok((runners, current_dir));
+            found.push(LevelRunners {
+                level,
+                dir: current_dir.clone(),
+                runners,
+            });
+            if !collect_all {
+                break;
+            }
+        }
+
+        // Don't leak into whatever contains the project (e.g. a home directory)
+        if let Some(marker) = options.root_marker_in(&current_dir) {
+            if found.is_empty() {
+                return Err(RunError::NoRunnerInProjectRoot(
+                    current_dir.display().to_string(),
+                    marker.to_string(),
+                ));
+            }
+            break;
         }
 
         // Move up one directory
         if let Some(parent) = current_dir.parent() {
-            current_dir = parent.to_path_buf();
+            current_dir = canonical_or_self(parent);
+        } else if found.is_empty() {
+            return Err(RunError::ReachedFilesystemRoot(
+                canonical_or_self(start_dir).display().to_string(),
+            ));
         } else {
             break;
         }
     }
 
-    Err(RunError::RunnerNotFound(max_levels))
+    if found.is_empty() {
+        return Err(RunError::RunnerNotFound(max_levels));
+    }
+    Ok(found)
+}
+
+/// Resolve symlinks, keeping the path as given if it can't be resolved
+fn canonical_or_self(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Find the configured preferred runner among the detected ones, if present
+pub fn find_preferred(
+    runners: &[DetectedRunner],
+    preferred: Option<&str>,
+) -> Option<DetectedRunner> {
+    let preferred = preferred?;
+    runners
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(preferred))
+        .cloned()
 }
 
 /// Check for lockfile conflicts within the same ecosystem
+///
+/// `remembered` is the runner picked for this directory last time
+/// (`remember_choices`); it settles a conflict before anything else does.
 pub fn check_conflicts(
     runners: &[DetectedRunner],
+    prefer: Option<&str>,
+    remembered: Option<&str>,
     verbose: bool,
 ) -> Result<DetectedRunner, RunError> {
     if runners.is_empty() {
         return Err(RunError::RunnerNotFound(0));
     }
 
+    // --prefer is a soft bias: it wins whenever it was detected, and is
+    // ignored (normal resolution below) when it wasn't
+    if let Some(mut preferred) = find_preferred(runners, prefer) {
+        preferred.pinned_by = Some(format!("--prefer {}", preferred.name));
+        if verbose {
+            output::info(&format!(
+                "Using {}: chosen because of --prefer",
+                preferred.name
+            ));
+        }
+        return Ok(preferred);
+    }
+
     if runners.len() == 1 {
         return Ok(runners[0].clone());
     }
@@ -67,6 +334,36 @@ pub fn check_conflicts(
     // Check for conflicts within ecosystems
     for (ecosystem, eco_runners) in &by_ecosystem {
         if eco_runners.len() > 1 {
+            if let Some(chosen) = remembered.and_then(|name| {
+                eco_runners
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(name))
+            }) {
+                let mut chosen = (*chosen).clone();
+                chosen.pinned_by =
+                    Some("it was chosen here before (--forget to reset)".to_string());
+                if verbose {
+                    output::info(&format!(
+                        "Using {}: chosen because {}",
+                        chosen.name,
+                        chosen.pinned_by.as_deref().unwrap_or_default()
+                    ));
+                }
+                return Ok(chosen);
+            }
+
+            // A pin (e.g. `.tool-versions`) acts as an implicit preferred_runner
+            if let Some(pinned) = eco_runners.iter().find(|r| r.pinned_by.is_some()) {
+                if verbose {
+                    output::info(&format!(
+                        "Using {}: chosen because {}",
+                        pinned.name,
+                        pinned.pinned_by.as_deref().unwrap_or_default()
+                    ));
+                }
+                return Ok((*pinned).clone());
+            }
+
             // Check which tools are installed
             let installed: Vec<&&DetectedRunner> = eco_runners
                 .iter()
@@ -87,15 +384,15 @@ pub fn check_conflicts(
                 let others: Vec<&str> = eco_runners
                     .iter()
                     .filter(|r| r.name != runner.name)
-                    .map(|r| r.detected_file.as_str())
+                    .map(|r| r.detected_file())
                     .collect();
 
                 if !verbose {
-                    output::warning(&format!(
+                    output::warn(&format!(
                         "Found {} but only {} is installed. Consider removing: {}",
                         eco_runners
                             .iter()
-                            .map(|r| r.detected_file.as_str())
+                            .map(|r| r.detected_file())
                             .collect::<Vec<_>>()
                             .join(" and "),
                         runner.name,
@@ -106,10 +403,7 @@ pub fn check_conflicts(
                 return Ok((*runner).clone());
             } else {
                 // Multiple tools installed - error
-                let lockfiles: Vec<&str> = eco_runners
-                    .iter()
-                    .map(|r| r.detected_file.as_str())
-                    .collect();
+                let lockfiles: Vec<&str> = eco_runners.iter().map(|r| r.detected_file()).collect();
                 let tools: Vec<&str> = installed.iter().map(|r| r.name.as_str()).collect();
 
                 return Err(RunError::LockfileConflict(format!(
@@ -133,28 +427,599 @@ pub fn execute(
     task: &str,
     extra_args: &[String],
     working_dir: &Path,
-    dry_run: bool,
-    verbose: bool,
-    quiet: bool,
+    options: &ExecOptions,
 ) -> Result<RunResult, RunError> {
+    let (cmd_parts, tool) = task_command(runner, task, extra_args, options)?;
+    run_command(runner, cmd_parts, &tool, working_dir, options)
+}
+
+/// Execute a command with the detected runner, capturing its output instead of
+/// streaming it
+///
+/// Output beyond `max_bytes` per stream is read and discarded (flagged with
+/// `truncated`), so a chatty command can't exhaust memory. Presentation
+/// options (`dry_run`, `print_env`, `quiet`, `tee`, `output`) don't apply.
+pub fn execute_captured(
+    runner: &DetectedRunner,
+    task: &str,
+    extra_args: &[String],
+    working_dir: &Path,
+    options: &ExecOptions,
+    max_bytes: Option<usize>,
+) -> Result<CapturedRun, RunError> {
+    let (cmd_parts, tool) = task_command(runner, task, extra_args, options)?;
+    let options = ExecOptions {
+        dry_run: false,
+        print_env: false,
+        ..options.clone()
+    };
+    let (cmd_parts, venv) = prepare_command(runner, cmd_parts, &tool, working_dir, &options)?;
+
+    let program =
+        project_program(&cmd_parts[0], working_dir).unwrap_or_else(|| cmd_parts[0].clone().into());
+    let mut command = Command::new(program);
+    command
+        .current_dir(working_dir)
+        .stdin(child_stdin(&options));
+    let env_keys = apply_env(&mut command, venv.as_deref(), working_dir, &options)?;
+    let cmd_parts = forward_container_env(cmd_parts, &env_keys, &options);
+    command.args(&cmd_parts[1..]);
+    capture_command(command, max_bytes).map_err(|e| RunError::spawn_failed(&cmd_parts[0], e))
+}
+
+/// The argv for a task, and the program whose installation should be checked
+fn task_command(
+    runner: &DetectedRunner,
+    task: &str,
+    extra_args: &[String],
+    options: &ExecOptions,
+) -> Result<(Vec<String>, String), RunError> {
+    // `install` under safe mode means the package manager's install, minus hooks
+    if options.ignore_scripts
+        && runner.ecosystem == Ecosystem::NodeJs
+        && runner.custom_scripts.is_empty()
+        && task == "install"
+    {
+        let cmd_parts = install_command(runner, extra_args, options)?;
+        let tool = runner.name.clone();
+        return Ok((cmd_parts, tool));
+    }
+
+    // Install/publish hooks are how malicious packages usually get code execution
+    if options.ignore_scripts
+        && runner.ecosystem == Ecosystem::NodeJs
+        && safety::is_lifecycle_hook(task)
+    {
+        return Err(RunError::InvalidArgument(format!(
+            "Refusing to run lifecycle script \"{}\" with --ignore-scripts",
+            task
+        )));
+    }
+
+    // Build the command. npm's own flags have to precede the `--` it adds
+    // before the script's args; everywhere else they lead the user's args.
+    let default_args = default_args_for(runner, options);
+    let mut cmd_parts = if runner.name == "npm" && runner.custom_scripts.is_empty() {
+        let mut parts = runner.build_command(task, extra_args);
+        parts.splice(3..3, default_args.iter().cloned());
+        parts
+    } else {
+        let args: Vec<String> = default_args.iter().chain(extra_args).cloned().collect();
+        runner.build_command(task, &args)
+    };
+
+    // npm skips the pre<task>/post<task> hooks with --ignore-scripts; it must
+    // follow `run <task>` and come before the `--` that separates the script's
+    // own args. A custom runner's template is whatever the user wrote, so leave it.
+    if options.ignore_scripts
+        && runner.name == "npm"
+        && runner.custom_scripts.is_empty()
+        && cmd_parts.len() >= 3
+    {
+        if let Some(run) = cmd_parts.iter().position(|part| part == "run") {
+            cmd_parts.insert(run + 2, "--ignore-scripts".to_string());
+        }
+    }
+
+    // --select: the variant's values fill the `{name}` placeholders
+    if !options.select.is_empty() {
+        cmd_parts = crate::matrix::substitute(&cmd_parts, &options.select);
+    }
+
+    // Custom runner scripts may invoke any program, and binstubs are the program
+    // themselves, so check the one that will actually run
+    let tool = if runner.custom_scripts.is_empty() && runner.name != "binstubs" {
+        runner.name.clone()
+    } else {
+        cmd_parts[0].clone()
+    };
+    Ok((cmd_parts, tool))
+}
+
+/// `[default_args]` for the runner, by runner name (`cargo`) or else ecosystem (`rust`)
+fn default_args_for<'a>(runner: &DetectedRunner, options: &'a ExecOptions) -> &'a [String] {
+    options
+        .default_args
+        .get(&runner.name)
+        .or_else(|| {
+            options
+                .default_args
+                .iter()
+                .find(|(key, _)| Ecosystem::from_name(key) == Some(runner.ecosystem))
+                .map(|(_, args)| args)
+        })
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Run an arbitrary tool inside the detected project's context (`devrunner exec`)
+pub fn execute_tool(
+    runner: &DetectedRunner,
+    tool: &str,
+    args: &[String],
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<RunResult, RunError> {
+    let cmd_parts = build_exec_command(runner, tool, args, working_dir)?;
+    let program = cmd_parts[0].clone();
+    run_command(runner, cmd_parts, &program, working_dir, options)
+}
+
+/// Install the project's dependencies with the detected runner
+pub fn execute_install(
+    runner: &DetectedRunner,
+    args: &[String],
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<RunResult, RunError> {
+    let cmd_parts = install_command(runner, args, options)?;
+    let program = cmd_parts[0].clone();
+    run_command(runner, cmd_parts, &program, working_dir, options)
+}
+
+/// [`build_install_command`], skipping dependency install hooks in safe mode
+fn install_command(
+    runner: &DetectedRunner,
+    args: &[String],
+    options: &ExecOptions,
+) -> Result<Vec<String>, RunError> {
+    let mut cmd_parts = build_install_command(runner, args)?;
+    // Install hooks are exactly what safe mode exists to skip
+    if options.ignore_scripts && runner.ecosystem == Ecosystem::NodeJs {
+        cmd_parts.insert(2, skip_install_scripts_flag(runner).to_string());
+    }
+    Ok(cmd_parts)
+}
+
+/// The flag that stops `<pm> install` running lifecycle scripts
+///
+/// Yarn 2+ (which reads `.yarnrc.yml`) rejects `--ignore-scripts`; its
+/// equivalent is `--mode=skip-build`.
+fn skip_install_scripts_flag(runner: &DetectedRunner) -> &'static str {
+    if runner.name == "yarn" && runner.detected_files.iter().any(|f| f == ".yarnrc.yml") {
+        "--mode=skip-build"
+    } else {
+        "--ignore-scripts"
+    }
+}
+
+/// The idiomatic dependency install for the runner's ecosystem
+///
+/// - Node.js: `<pm> install`
+/// - Python: `uv sync` / `hatch env create` / `<tool> install` for poetry, pdm and pipenv, or
+///   `pip install -r requirements.txt` (`pip install .` for pyproject-only projects)
+/// - Rust: `cargo fetch`; Go: `go mod download`; Ruby: `bundle install`
+pub fn build_install_command(
+    runner: &DetectedRunner,
+    args: &[String],
+) -> Result<Vec<String>, RunError> {
+    let mut cmd: Vec<String> = match (runner.ecosystem, runner.name.as_str()) {
+        (Ecosystem::NodeJs, pm) => vec![pm.to_string(), "install".into()],
+        (Ecosystem::Python, "uv") => vec!["uv".into(), "sync".into()],
+        (Ecosystem::Python, "hatch") => vec!["hatch".into(), "env".into(), "create".into()],
+        (Ecosystem::Python, "pip") if runner.detected_file() == "requirements.txt" => {
+            vec![
+                "pip".into(),
+                "install".into(),
+                "-r".into(),
+                "requirements.txt".into(),
+            ]
+        }
+        (Ecosystem::Python, "pip") => vec!["pip".into(), "install".into(), ".".into()],
+        (Ecosystem::Python, tool) => vec![tool.to_string(), "install".into()],
+        (Ecosystem::Rust, _) => vec!["cargo".into(), "fetch".into()],
+        (Ecosystem::Go, "go") => vec!["go".into(), "mod".into(), "download".into()],
+        (Ecosystem::Ruby, "bundler") => vec!["bundle".into(), "install".into()],
+        (Ecosystem::Php, "composer") => vec!["composer".into(), "install".into()],
+        (Ecosystem::Elixir, "mix") => vec!["mix".into(), "deps.get".into()],
+        (ecosystem, _) => {
+            return Err(RunError::InvalidArgument(format!(
+                "install has no well-defined step for {} projects (detected {})",
+                ecosystem.as_str(),
+                runner.name
+            )));
+        }
+    };
+
+    cmd.extend(args.iter().cloned());
+    Ok(cmd)
+}
+
+/// What `devrunner clean` does for a project
+#[derive(Debug, Clone, PartialEq)]
+pub enum CleanPlan {
+    /// Run the runner's `clean` task (e.g. `cargo clean`, `make clean`)
+    Task(DetectedRunner),
+    /// Delete these directories, relative to the project root
+    Remove(DetectedRunner, Vec<PathBuf>),
+}
+
+/// Build output and caches removed for Node.js projects without a `clean` script
+const NODE_CLEAN_DIRS: &[&str] = &["node_modules", "dist"];
+
+/// Pick the cleanup for the first detected runner that has one
+///
+/// - Rust: `cargo clean`; Go: `go clean`
+/// - Make, Node.js: the project's own `clean` target/script when it has one
+/// - Node.js otherwise: remove `node_modules/` and `dist/`
+/// - Python: remove `__pycache__/` directories and `.pytest_cache/`
+/// - anything else (maven, gradle, task, dotnet, mix, composer, ...): its own `clean` task
+///
+/// A Makefile without a `clean` target is skipped in favour of the next runner.
+pub fn plan_clean(runners: &[DetectedRunner], working_dir: &Path) -> Result<CleanPlan, RunError> {
+    let has_clean_script = |runner: &DetectedRunner| {
+        scripts::get_scripts_for_runner(runner, working_dir)
+            .is_some_and(|list| list.scripts.iter().any(|s| s.name == "clean"))
+    };
+
+    for runner in runners {
+        match (runner.ecosystem, runner.name.as_str()) {
+            (Ecosystem::Rust, _) | (Ecosystem::Go, "go") => {
+                return Ok(CleanPlan::Task(runner.clone()));
+            }
+            (Ecosystem::Generic, "make") if has_clean_script(runner) => {
+                return Ok(CleanPlan::Task(runner.clone()));
+            }
+            (Ecosystem::NodeJs, _) if has_clean_script(runner) => {
+                return Ok(CleanPlan::Task(runner.clone()));
+            }
+            (Ecosystem::NodeJs, _) => {
+                let dirs = NODE_CLEAN_DIRS
+                    .iter()
+                    .map(PathBuf::from)
+                    .filter(|dir| working_dir.join(dir).is_dir())
+                    .collect();
+                return Ok(CleanPlan::Remove(runner.clone(), dirs));
+            }
+            (Ecosystem::Python, _) => {
+                return Ok(CleanPlan::Remove(
+                    runner.clone(),
+                    python_cache_dirs(working_dir),
+                ));
+            }
+            (Ecosystem::Generic, "make") => {}
+            _ => return Ok(CleanPlan::Task(runner.clone())),
+        }
+    }
+
+    let names: Vec<&str> = runners.iter().map(|r| r.name.as_str()).collect();
+    Err(RunError::InvalidArgument(format!(
+        "clean has no well-defined step for this project (detected {}; a Makefile needs a `clean` target)",
+        names.join(", ")
+    )))
+}
+
+/// `__pycache__/` directories below `root` plus `.pytest_cache/`, skipping virtualenvs
+fn python_cache_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !(entry.depth() > 0
+                && matches!(name.as_ref(), ".venv" | "venv" | ".git" | "node_modules"))
+        })
+        .flatten()
+        .filter(|entry| entry.file_type().is_dir() && entry.file_name() == "__pycache__")
+        .filter_map(|entry| entry.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect();
+    if root.join(".pytest_cache").is_dir() {
+        dirs.push(PathBuf::from(".pytest_cache"));
+    }
+    dirs.sort();
+    dirs
+}
+
+/// Delete the directories of a [`CleanPlan::Remove`] (printing them instead on dry runs)
+pub fn remove_clean_dirs(
+    dirs: &[PathBuf],
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<(), RunError> {
+    for dir in dirs {
+        if options.dry_run {
+            println!("rm -rf {}", shell_quote(&dir.to_string_lossy()));
+            continue;
+        }
+        std::fs::remove_dir_all(working_dir.join(dir))?;
+        if options.verbose {
+            output::info(&format!("Removed {}", dir.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Wrap a tool invocation the way the runner's ecosystem expects
+///
+/// - Node.js: `<pm> exec <tool>` (`bun x` for bun)
+/// - Python: `<tool> run` for uv, poetry, pdm, hatch and pipenv, or the tool itself for pip
+/// - Rust: `cargo run --bin <tool> --` when the tool is one of the crate's binaries
+pub fn build_exec_command(
+    runner: &DetectedRunner,
+    tool: &str,
+    args: &[String],
+    working_dir: &Path,
+) -> Result<Vec<String>, RunError> {
+    let mut cmd: Vec<String> = match (runner.ecosystem, runner.name.as_str()) {
+        // npm would parse the tool's flags as its own without the separator
+        (Ecosystem::NodeJs, "npm") => vec!["npm".into(), "exec".into(), "--".into()],
+        (Ecosystem::NodeJs, "bun") => vec!["bun".into(), "x".into()],
+        (Ecosystem::NodeJs, pm) => vec![pm.to_string(), "exec".into()],
+        (Ecosystem::Python, "pip") => Vec::new(),
+        (Ecosystem::Python, tool_runner) => vec![tool_runner.to_string(), "run".into()],
+        (Ecosystem::Rust, _) => {
+            if !scripts::cargo_bin_targets(working_dir)
+                .iter()
+                .any(|b| b == tool)
+            {
+                return Err(RunError::InvalidArgument(format!(
+                    "\"{}\" is not a binary target of this crate",
+                    tool
+                )));
+            }
+            let mut cmd: Vec<String> =
+                vec!["cargo".into(), "run".into(), "--bin".into(), tool.into()];
+            if !args.is_empty() {
+                cmd.push("--".into());
+            }
+            cmd.extend(args.iter().cloned());
+            return Ok(cmd);
+        }
+        (ecosystem, _) => {
+            return Err(RunError::InvalidArgument(format!(
+                "exec is not supported for {} projects (detected {})",
+                ecosystem.as_str(),
+                runner.name
+            )));
+        }
+    };
+
+    cmd.push(tool.to_string());
+    cmd.extend(args.iter().cloned());
+    Ok(cmd)
+}
+
+/// Wrap a command for `--in-container`, find the virtualenv it should see and
+/// check its program is installed
+fn prepare_command(
+    runner: &DetectedRunner,
+    cmd_parts: Vec<String>,
+    tool: &str,
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<(Vec<String>, Option<PathBuf>), RunError> {
+    // --runner-path: the runner's binary from a fixed location instead of PATH;
+    // a host path means nothing inside a container
+    let (mut cmd_parts, mut tool) = (cmd_parts, tool.to_string());
+    if let Some(path) = runner_path_override(runner, options)? {
+        let program = cmd_parts.first().map(String::as_str).unwrap_or_default();
+        if !options.in_container && program == runner.program() {
+            tool = path.to_string_lossy().to_string();
+            cmd_parts[0] = tool.clone();
+        } else if options.quiet == 0 {
+            let reason = if options.in_container {
+                "the command runs inside the dev container".to_string()
+            } else {
+                format!("the command runs {}", program)
+            };
+            output::warn(&format!(
+                "Not using the runner path for {} ({}): {}",
+                runner.name,
+                path.display(),
+                reason
+            ));
+        }
+    }
+    let tool = tool.as_str();
+
+    // use_node_version_manager: the `.nvmrc` version through fnm, nvm or Volta;
+    // a container brings its own Node
+    let (cmd_parts, tool) = match nvmrc_version(working_dir) {
+        Some(version)
+            if options.node_version_manager
+                && runner.ecosystem == Ecosystem::NodeJs
+                && !options.in_container =>
+        {
+            with_node_version(cmd_parts, tool, &version, options.verbose)
+        }
+        _ => (cmd_parts, tool.to_string()),
+    };
+    let tool = tool.as_str();
+
+    // --in-container: the same command, run inside the project's dev container
+    let (cmd_parts, tool) = if options.in_container {
+        let container = find_devcontainer(working_dir).ok_or_else(|| {
+            RunError::ConfigError(
+                "--in-container needs a .devcontainer/devcontainer.json in the project".to_string(),
+            )
+        })?;
+        let tty = !options.no_stdin && io::stdin().is_terminal() && io::stdout().is_terminal();
+        let wrapped = container
+            .wrap_command(
+                working_dir,
+                &cmd_parts,
+                is_tool_installed("devcontainer"),
+                tty,
+            )
+            .map_err(RunError::ConfigError)?;
+        if options.verbose {
+            output::info(&format!(
+                "Running in {} ({})",
+                container.label(),
+                container.config_file
+            ));
+        }
+        let program = wrapped[0].clone();
+        (wrapped, program)
+    } else {
+        (cmd_parts, tool.to_string())
+    };
+    let tool = tool.as_str();
+
+    // Poetry, uv, pdm, hatch and pipenv manage their own environment through `<tool> run`;
+    // a container has its own environment
+    let venv = if runner.ecosystem == Ecosystem::Python
+        && !matches!(
+            runner.name.as_str(),
+            "poetry" | "uv" | "pdm" | "hatch" | "pipenv"
+        )
+        && !options.no_venv
+        && !options.in_container
+    {
+        find_venv(working_dir)
+    } else {
+        None
+    };
+
     // Check if the tool is installed (skip for dry-run)
-    if !dry_run && !is_tool_installed(&runner.name) {
+    let in_venv = venv
+        .as_ref()
+        .is_some_and(|v| which::which_in(tool, Some(venv_bin_dir(v)), working_dir).is_ok());
+    let installed = match project_program(tool, working_dir) {
+        Some(path) => path.is_file(),
+        None => is_tool_installed(tool),
+    };
+    if !options.dry_run && !options.print_env && !in_venv && !installed {
         return Err(RunError::ToolNotInstalled(format!(
             "{} is not installed. Please install it to continue.",
-            runner.name
+            tool
+        )));
+    }
+    Ok((cmd_parts, venv))
+}
+
+/// The binary `[runner_paths]` sets for this runner, checked to be an
+/// executable file given by an absolute path
+fn runner_path_override<'a>(
+    runner: &DetectedRunner,
+    options: &'a ExecOptions,
+) -> Result<Option<&'a Path>, RunError> {
+    let Some(path) = options.runner_paths.get(&runner.name) else {
+        return Ok(None);
+    };
+    if !path.is_absolute() {
+        return Err(RunError::ConfigError(format!(
+            "runner path for {} must be absolute: {}",
+            runner.name,
+            path.display()
+        )));
+    }
+    if !std::fs::metadata(path).is_ok_and(|m| m.is_file() && is_executable(&m)) {
+        return Err(RunError::ConfigError(format!(
+            "runner path for {} is not an executable file: {}",
+            runner.name,
+            path.display()
         )));
     }
+    Ok(Some(path))
+}
+
+/// Set once the "no Node version manager" warning has been shown
+static NODE_MANAGER_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Wrap a Node.js command to run under `version`, returning it with the program to check
+///
+/// Without fnm, nvm or Volta the command runs on the system node, after a
+/// warning printed once per process.
+fn with_node_version(
+    cmd_parts: Vec<String>,
+    tool: &str,
+    version: &str,
+    verbose: bool,
+) -> (Vec<String>, String) {
+    match pick_node_manager(NodeManager::is_available) {
+        Some(manager) => {
+            if verbose {
+                output::info(&format!(
+                    "Using Node {} from .nvmrc via {}",
+                    version,
+                    manager.name()
+                ));
+            }
+            (
+                manager.wrap_command(version, &cmd_parts),
+                manager.program().to_string(),
+            )
+        }
+        None => {
+            if !NODE_MANAGER_WARNED.swap(true, Ordering::Relaxed) {
+                output::warn(&format!(
+                    ".nvmrc pins Node {} but none of fnm, nvm or volta is available; using the system node",
+                    version
+                ));
+            }
+            (cmd_parts, tool.to_string())
+        }
+    }
+}
+
+/// A relative program path such as `bin/setup`, resolved against the project
+/// rather than wherever devrunner was started; None for bare names looked up on PATH
+fn project_program(program: &str, working_dir: &Path) -> Option<PathBuf> {
+    let path = Path::new(program);
+    (path.is_relative() && path.components().count() > 1).then(|| working_dir.join(path))
+}
+
+/// Print, check and run an assembled command
+fn run_command(
+    runner: &DetectedRunner,
+    cmd_parts: Vec<String>,
+    tool: &str,
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<RunResult, RunError> {
+    let ExecOptions {
+        dry_run,
+        verbose,
+        quiet,
+        ..
+    } = *options;
+    let (cmd_parts, venv) = prepare_command(runner, cmd_parts, tool, working_dir, options)?;
 
-    // Build the command
-    let cmd_parts = runner.build_command(task, extra_args);
-    let cmd_string = cmd_parts.join(" ");
+    // Quoted so the printed line can be pasted back into a shell verbatim
+    let cmd_string = shell_join(&cmd_parts);
 
     if verbose {
-        output::detected(&runner.name, &runner.detected_file);
+        output::detected(&runner.name, runner.detected_file());
+    }
+
+    if options.print_env {
+        let overrides = child_env_overrides(venv.as_deref(), working_dir, options)?;
+        print_env(
+            resolved_env(options.clean_env.as_deref(), overrides),
+            options.json,
+        );
+        return Ok(RunResult {
+            exit_status: std::process::ExitStatus::default(),
+            runner: runner.clone(),
+            working_dir: working_dir.to_path_buf(),
+            command: cmd_parts,
+        });
     }
 
     if dry_run {
-        if !quiet {
+        if options.json {
+            let plan = dry_run_plan(runner, &cmd_parts, working_dir, venv.as_deref(), options);
+            println!("{}", serde_json::to_string_pretty(&plan).unwrap());
+        } else if quiet == 0 {
             println!("{}", cmd_string);
         }
         // Return a fake success for dry run
@@ -162,89 +1027,1115 @@ pub fn execute(
             exit_status: std::process::ExitStatus::default(),
             runner: runner.clone(),
             working_dir: working_dir.to_path_buf(),
+            command: cmd_parts,
         });
     }
 
-    if !quiet {
+    let json_stream = options.output == OutputMode::JsonStream;
+    if quiet == 0 && !json_stream {
         output::executing(&cmd_string);
     }
 
     // Execute the command
-    let program = &cmd_parts[0];
-    let args = &cmd_parts[1..];
+    let program = cmd_parts[0].clone();
+    let mut command = Command::new(
+        project_program(&program, working_dir).unwrap_or_else(|| program.clone().into()),
+    );
+    command.current_dir(working_dir);
+    let env_keys = apply_env(&mut command, venv.as_deref(), working_dir, options)?;
+    let cmd_parts = forward_container_env(cmd_parts, &env_keys, options);
+    command.args(&cmd_parts[1..]);
 
-    let status = Command::new(program)
-        .args(args)
-        .current_dir(working_dir)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .map_err(|e| RunError::CommandFailed(format!("Failed to execute {}: {}", program, e)))?;
+    // Piping is only needed when output has to go somewhere besides the terminal
+    let status = if options.tee.is_some() || json_stream || options.tail_on_failure.is_some() {
+        run_piped(command, &cmd_parts, options, &mut io::stdout())?
+    } else {
+        let stdout = if quiet >= 2 {
+            Stdio::null()
+        } else {
+            Stdio::inherit()
+        };
+        command
+            .stdin(child_stdin(options))
+            .stdout(stdout)
+            .stderr(Stdio::inherit());
+        signals::spawn(&mut command)
+            .and_then(ForwardedChild::wait)
+            .map_err(|e| RunError::spawn_failed(&program, e))?
+    };
 
     Ok(RunResult {
         exit_status: status,
         runner: runner.clone(),
         working_dir: working_dir.to_path_buf(),
+        command: cmd_parts,
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs::File;
-    use tempfile::tempdir;
+/// The planned execution printed by `--dry-run --json`
+///
+/// `--env-from` commands are listed rather than run, since a dry run has no side effects.
+fn dry_run_plan(
+    runner: &DetectedRunner,
+    cmd_parts: &[String],
+    working_dir: &Path,
+    venv: Option<&Path>,
+    options: &ExecOptions,
+) -> serde_json::Value {
+    let env: serde_json::Map<String, serde_json::Value> = venv
+        .map(venv_env)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| (key, json!(value.to_string_lossy())))
+        .collect();
 
-    #[test]
-    fn test_search_runners_current_dir() {
-        let dir = tempdir().unwrap();
-        File::create(dir.path().join("package.json")).unwrap();
+    json!({
+        "runner": runner.name,
+        "ecosystem": runner.ecosystem.as_str(),
+        "detected_file": runner.detected_file(),
+        "detected_files": runner.detected_files,
+        "program": cmd_parts[0],
+        "argv": cmd_parts,
+        "command": shell_join(cmd_parts),
+        "working_dir": working_dir.display().to_string(),
+        "env": env,
+        "env_from": options.env_from,
+    })
+}
 
-        let (runners, found_dir) = search_runners(dir.path(), 3, &[], false).unwrap();
-        assert_eq!(runners.len(), 1);
-        assert_eq!(runners[0].name, "npm");
-        assert_eq!(found_dir, dir.path());
+/// Variables the child gets on top of devrunner's own environment, in the
+/// order they apply: the virtualenv, each `--env-from` command, then the `--select` variant
+fn child_env_overrides(
+    venv: Option<&Path>,
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<Vec<(String, OsString)>, RunError> {
+    let mut vars = Vec::new();
+    if let Some(venv) = venv {
+        if options.verbose {
+            output::info(&format!("Using virtualenv {}", venv.display()));
+        }
+        vars.extend(venv_env(venv));
+    }
+    // Later commands override earlier ones, like repeated `export`s
+    for env_command in &options.env_from {
+        let loaded = env_from_command(env_command, working_dir)?;
+        if options.verbose {
+            output::info(&format!(
+                "Loaded {} variable(s) from `{}`",
+                loaded.len(),
+                env_command
+            ));
+        }
+        vars.extend(loaded.into_iter().map(|(key, value)| (key, value.into())));
     }
+    vars.extend(
+        crate::matrix::env_vars(&options.select)
+            .into_iter()
+            .map(|(key, value)| (key, value.into())),
+    );
+    Ok(vars)
+}
 
-    #[test]
-    fn test_search_runners_parent_dir() {
-        let dir = tempdir().unwrap();
-        File::create(dir.path().join("package.json")).unwrap();
+/// Give `command` its environment: ours (or only the `--clean-env` passthrough)
+/// plus the virtualenv and `--env-from` overrides
+///
+/// Returns the variables set on purpose (overrides and the `--clean-env`
+/// allowlist), which a container has to be handed explicitly.
+fn apply_env(
+    command: &mut Command,
+    venv: Option<&Path>,
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<Vec<String>, RunError> {
+    let mut keys = Vec::new();
+    if let Some(allowlist) = &options.clean_env {
+        command.env_clear().envs(clean_env_passthrough(allowlist));
+        keys.extend(
+            allowlist
+                .iter()
+                .filter(|key| std::env::var_os(key).is_some())
+                .cloned(),
+        );
+    }
+    let overrides = child_env_overrides(venv, working_dir, options)?;
+    for (key, _) in &overrides {
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    command.envs(overrides);
+    Ok(keys)
+}
 
-        let subdir = dir.path().join("src");
-        std::fs::create_dir(&subdir).unwrap();
+/// With `--in-container`, hand `env_keys` on to a `docker run` fallback
+fn forward_container_env(
+    cmd_parts: Vec<String>,
+    env_keys: &[String],
+    options: &ExecOptions,
+) -> Vec<String> {
+    if options.in_container {
+        DevContainer::forward_env(cmd_parts, env_keys)
+    } else {
+        cmd_parts
+    }
+}
 
-        let (runners, found_dir) = search_runners(&subdir, 3, &[], false).unwrap();
-        assert_eq!(runners.len(), 1);
-        assert_eq!(runners[0].name, "npm");
-        assert_eq!(found_dir, dir.path());
+/// The variables of ours that `--clean-env` keeps: [`CLEAN_ENV_BASE`] plus `allowlist`
+fn clean_env_passthrough(allowlist: &[String]) -> Vec<(OsString, OsString)> {
+    std::env::vars_os()
+        .filter(|(key, _)| {
+            let key = key.to_string_lossy();
+            CLEAN_ENV_BASE
+                .iter()
+                .map(|name| name.to_string())
+                .chain(allowlist.iter().cloned())
+                // Windows variable names are case-insensitive
+                .any(|name| {
+                    if cfg!(windows) {
+                        name.eq_ignore_ascii_case(&key)
+                    } else {
+                        name == key
+                    }
+                })
+        })
+        .collect()
+}
+
+/// The full environment a child would see: ours (only the passthrough with
+/// `--clean-env`), with `overrides` applied in order
+pub fn resolved_env(
+    clean_env: Option<&[String]>,
+    overrides: Vec<(String, OsString)>,
+) -> BTreeMap<String, String> {
+    let base = match clean_env {
+        Some(allowlist) => clean_env_passthrough(allowlist),
+        None => std::env::vars_os().collect(),
+    };
+    let mut env: BTreeMap<String, String> = base
+        .into_iter()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    for (key, value) in overrides {
+        env.insert(key, value.to_string_lossy().into_owned());
     }
+    env
+}
 
-    #[test]
-    fn test_search_runners_not_found() {
-        let dir = tempdir().unwrap();
-        let result = search_runners(dir.path(), 3, &[], false);
-        assert!(matches!(result, Err(RunError::RunnerNotFound(3))));
+/// `--print-env`: sorted `KEY=value` lines, or a JSON object with `--json`
+fn print_env(env: BTreeMap<String, String>, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(&env).unwrap());
+    } else {
+        for (key, value) in env {
+            println!("{}={}", key, value);
+        }
     }
+}
 
-    #[test]
-    fn test_search_runners_with_ignore() {
-        let dir = tempdir().unwrap();
-        File::create(dir.path().join("package.json")).unwrap();
+/// Stdin for the child: the terminal, so prompts work, unless `--no-stdin`
+fn child_stdin(options: &ExecOptions) -> Stdio {
+    if options.no_stdin {
+        Stdio::null()
+    } else {
+        Stdio::inherit()
+    }
+}
 
-        let result = search_runners(dir.path(), 3, &["npm".to_string()], false);
-        assert!(matches!(result, Err(RunError::RunnerNotFound(3))));
+/// The platform shell and its run-a-string flag
+fn system_shell() -> (&'static str, &'static str) {
+    if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
     }
+}
 
-    #[test]
-    fn test_check_conflicts_single_runner() {
-        let runners = vec![DetectedRunner::new(
-            "npm",
+/// Run a raw shell snippet (a `!`-prefixed alias) without any runner
+///
+/// Extra args become the snippet's positional parameters (`"$@"`) under `sh`;
+/// `cmd` has no equivalent, so there they are appended to the line. The
+/// environment is set up as for any command (`--clean-env`, `--env-from`, `--select`).
+pub fn execute_shell(
+    snippet: &str,
+    extra_args: &[String],
+    working_dir: &Path,
+    options: &ExecOptions,
+) -> Result<ExitStatus, RunError> {
+    let (shell, flag) = system_shell();
+    let mut cmd_parts = vec![shell.to_string(), flag.to_string()];
+    if cfg!(windows) {
+        let mut line = snippet.to_string();
+        for arg in extra_args {
+            line.push(' ');
+            line.push_str(arg);
+        }
+        cmd_parts.push(line);
+    } else {
+        cmd_parts.push(snippet.to_string());
+        cmd_parts.push("devrunner".to_string());
+        cmd_parts.extend(extra_args.iter().cloned());
+    }
+    // --select: the snippet and its args take the variant's values, like a runner's command
+    let cmd_parts = crate::matrix::substitute(&cmd_parts, &options.select);
+
+    if options.print_env {
+        let overrides = child_env_overrides(None, working_dir, options)?;
+        print_env(
+            resolved_env(options.clean_env.as_deref(), overrides),
+            options.json,
+        );
+        return Ok(ExitStatus::default());
+    }
+    if options.dry_run {
+        if options.quiet == 0 {
+            println!("{}", shell_join(&cmd_parts));
+        }
+        return Ok(ExitStatus::default());
+    }
+    if options.quiet == 0 {
+        output::executing(snippet);
+    }
+    let mut command = Command::new(&cmd_parts[0]);
+    command
+        .args(&cmd_parts[1..])
+        .current_dir(working_dir)
+        .stdin(child_stdin(options));
+    apply_env(&mut command, None, working_dir, options)?;
+    signals::spawn(&mut command)
+        .and_then(ForwardedChild::wait)
+        .map_err(|e| RunError::CommandFailed(format!("Failed to run `{}`: {}", snippet, e)))
+}
+
+/// Run a shell command and parse its stdout as `KEY=value` lines (for `--env-from`)
+pub fn env_from_command(
+    env_command: &str,
+    working_dir: &Path,
+) -> Result<Vec<(String, String)>, RunError> {
+    let (shell, flag) = system_shell();
+    let output = Command::new(shell)
+        .args([flag, env_command])
+        .current_dir(working_dir)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            RunError::CommandFailed(format!("Failed to run --env-from `{}`: {}", env_command, e))
+        })?;
+
+    if !output.status.success() {
+        return Err(RunError::CommandFailed(format!(
+            "--env-from `{}` exited with code {}",
+            env_command,
+            exit_code_from_status(output.status)
+        )));
+    }
+    Ok(parse_env_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `KEY=value` lines, allowing `export` prefixes, quotes and `#` comments
+pub fn parse_env_lines(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            let valid_key = key
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_key {
+                return None;
+            }
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+                .unwrap_or(value);
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Read `--args-file`: one argument per line, taken as-is apart from
+/// surrounding whitespace, skipping blank lines and `#` comment lines
+pub fn read_args_file(path: &Path) -> Result<Vec<String>, RunError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        RunError::InvalidArgument(format!("Can't read --args-file {}: {}", path.display(), e))
+    })?;
+    Ok(parse_args_lines(&content))
+}
+
+fn parse_args_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Find a project-local Python virtualenv (`.venv/` or `venv/`)
+pub fn find_venv(dir: &Path) -> Option<PathBuf> {
+    [".venv", "venv"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.join("pyvenv.cfg").is_file())
+}
+
+/// Directory holding a virtualenv's executables
+fn venv_bin_dir(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts")
+    } else {
+        venv.join("bin")
+    }
+}
+
+/// Environment that activates a virtualenv: its bin dir first on PATH, plus VIRTUAL_ENV
+fn venv_env(venv: &Path) -> Vec<(String, std::ffi::OsString)> {
+    let mut paths = vec![venv_bin_dir(venv)];
+    if let Some(path) = std::env::var_os("PATH") {
+        paths.extend(std::env::split_paths(&path));
+    }
+    let path = std::env::join_paths(paths).unwrap_or_default();
+
+    vec![
+        ("PATH".to_string(), path),
+        ("VIRTUAL_ENV".to_string(), venv.as_os_str().to_os_string()),
+    ]
+}
+
+/// Translate a child's exit status into the exit code devrunner should return
+///
+/// On Unix a child killed by a signal has no exit code, so follow the shell
+/// convention of `128 + signal` (e.g. 130 for SIGINT).
+pub fn exit_code_from_status(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    exit_codes::GENERIC_ERROR
+}
+
+/// Signal that terminated the process, if it was killed by one
+pub fn exit_signal(status: ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+/// Short explanation for exit codes with a conventional meaning
+pub fn exit_hint(code: i32) -> Option<&'static str> {
+    match code {
+        126 => Some("command found but not executable (check permissions)"),
+        127 => Some("command not found (check PATH or install the tool)"),
+        130 => Some("interrupted by Ctrl+C (SIGINT)"),
+        134 => Some("aborted (SIGABRT), often a failed assertion"),
+        137 => Some("killed (SIGKILL), often by the out-of-memory killer"),
+        139 => Some("segmentation fault (SIGSEGV)"),
+        143 => Some("terminated (SIGTERM)"),
+        _ => None,
+    }
+}
+
+/// Join argv into a single line using POSIX shell quoting
+pub fn shell_join(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote one argument so a POSIX shell reads it back as exactly one token
+pub fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        return arg.to_string();
+    }
+    // Inside single quotes nothing is special except the quote itself
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Which child stream a captured line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamKind {
+    Stdout,
+    Stderr,
+}
+
+impl StreamKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StreamKind::Stdout => "stdout",
+            StreamKind::Stderr => "stderr",
+        }
+    }
+}
+
+/// Run a command with piped stdio, forwarding each line in arrival order
+///
+/// Lines go to the terminal (human mode) or to `events` as JSON (json-stream
+/// mode), and are mirrored into the tee file when one is configured. With
+/// `tail_on_failure` human-mode lines are held back and only the tail of each
+/// stream is printed, and only if the command fails.
+fn run_piped(
+    mut command: Command,
+    cmd_parts: &[String],
+    options: &ExecOptions,
+    events: &mut dyn Write,
+) -> Result<ExitStatus, RunError> {
+    let program = &cmd_parts[0];
+    let json_stream = options.output == OutputMode::JsonStream;
+
+    let mut tee_file = match &options.tee {
+        Some(tee_path) => Some(File::create(tee_path).map_err(|e| {
+            RunError::CommandFailed(format!(
+                "Failed to open tee file {}: {}",
+                tee_path.display(),
+                e
+            ))
+        })?),
+        None => None,
+    };
+
+    let start_time = Instant::now();
+    if json_stream {
+        emit_event(
+            events,
+            json!({ "event": "start", "command": cmd_parts.join(" "), "argv": cmd_parts }),
+        );
+    }
+
+    command
+        .stdin(child_stdin(options))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut forwarded =
+        signals::spawn(&mut command).map_err(|e| RunError::spawn_failed(program, e))?;
+    let child = &mut forwarded.child;
+
+    // Each stream is read on its own thread; the channel preserves arrival order
+    let (sender, receiver) = mpsc::channel();
+    let mut handles = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        handles.push(spawn_line_reader(
+            stdout,
+            StreamKind::Stdout,
+            sender.clone(),
+        ));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        handles.push(spawn_line_reader(
+            stderr,
+            StreamKind::Stderr,
+            sender.clone(),
+        ));
+    }
+    drop(sender);
+
+    let mut tails = options
+        .tail_on_failure
+        .filter(|_| !json_stream)
+        .map(|lines| (TailBuffer::new(lines), TailBuffer::new(lines)));
+
+    for (kind, line) in receiver {
+        if let Some((stdout_tail, stderr_tail)) = tails.as_mut() {
+            match kind {
+                StreamKind::Stdout => stdout_tail.push(line.clone()),
+                StreamKind::Stderr => stderr_tail.push(line.clone()),
+            }
+        } else if json_stream {
+            let text = String::from_utf8_lossy(&line);
+            let text = text.trim_end_matches(['\n', '\r']);
+            emit_event(events, json!({ "event": kind.as_str(), "line": text }));
+        } else if kind == StreamKind::Stdout && options.quiet >= 2 {
+            // -qq keeps only stderr on the terminal; the tee file still gets everything
+        } else {
+            match kind {
+                StreamKind::Stdout => write_line(&mut io::stdout(), &line),
+                StreamKind::Stderr => write_line(&mut io::stderr(), &line),
+            }
+        }
+        if let Some(file) = tee_file.as_mut() {
+            write_line(file, &line);
+        }
+    }
+
+    let status = forwarded.wait()?;
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    if let Some((stdout_tail, stderr_tail)) = tails {
+        if !status.success() {
+            if options.quiet < 2 {
+                stdout_tail.flush_to(&mut io::stdout(), "stdout");
+            }
+            stderr_tail.flush_to(&mut io::stderr(), "stderr");
+        }
+    }
+
+    if json_stream {
+        emit_event(
+            events,
+            json!({
+                "event": "exit",
+                "code": exit_code_from_status(status),
+                "duration_ms": start_time.elapsed().as_millis() as u64,
+            }),
+        );
+    }
+
+    Ok(status)
+}
+
+/// Run a command to completion, collecting stdout and stderr (each capped at `max_bytes`)
+fn capture_command(mut command: Command, max_bytes: Option<usize>) -> io::Result<CapturedRun> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Both streams are drained concurrently so neither pipe can fill up and stall the child
+    let stdout = child
+        .stdout
+        .take()
+        .map(|r| spawn_capped_reader(r, max_bytes));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|r| spawn_capped_reader(r, max_bytes));
+    let exit_status = child.wait()?;
+
+    let collect = |handle: Option<JoinHandle<(Vec<u8>, bool)>>| {
+        handle.and_then(|h| h.join().ok()).unwrap_or_default()
+    };
+    let (stdout, stdout_truncated) = collect(stdout);
+    let (stderr, stderr_truncated) = collect(stderr);
+    Ok(CapturedRun {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_status,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+/// Read a child stream to the end, keeping at most `max_bytes` of it
+fn spawn_capped_reader<R>(mut reader: R, max_bytes: Option<usize>) -> JoinHandle<(Vec<u8>, bool)>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut truncated = false;
+        let mut chunk = [0u8; 8192];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let room = max_bytes.map_or(n, |max| max.saturating_sub(kept.len()).min(n));
+                    kept.extend_from_slice(&chunk[..room]);
+                    truncated |= room < n;
+                }
+            }
+        }
+        (kept, truncated)
+    })
+}
+
+/// Ring buffer keeping the last N lines of a stream
+struct TailBuffer {
+    capacity: usize,
+    lines: VecDeque<Vec<u8>>,
+    omitted: usize,
+}
+
+impl TailBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: VecDeque::with_capacity(capacity),
+            omitted: 0,
+        }
+    }
+
+    fn push(&mut self, line: Vec<u8>) {
+        if self.capacity == 0 {
+            self.omitted += 1;
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.omitted += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Write the kept lines, noting how many earlier ones were dropped
+    fn flush_to(&self, writer: &mut dyn Write, stream: &str) {
+        if self.omitted > 0 {
+            output::info(&format!(
+                "{} earlier {} line(s) omitted",
+                self.omitted, stream
+            ));
+        }
+        for line in &self.lines {
+            write_line(writer, line);
+        }
+    }
+}
+
+/// Read a child stream line by line and send each line to the collector
+fn spawn_line_reader<R>(
+    reader: R,
+    kind: StreamKind,
+    sender: Sender<(StreamKind, Vec<u8>)>,
+) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if sender.send((kind, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Write a line and flush immediately so output is never held back
+fn write_line(writer: &mut dyn Write, line: &[u8]) {
+    let _ = writer.write_all(line);
+    let _ = writer.flush();
+}
+
+/// Write a single newline-delimited JSON event
+fn emit_event(writer: &mut dyn Write, event: serde_json::Value) {
+    let _ = writeln!(writer, "{}", event);
+    let _ = writer.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_search_depth_from_str() {
+        assert_eq!("auto".parse(), Ok(SearchDepth::Auto));
+        assert_eq!("5".parse(), Ok(SearchDepth::Levels(5)));
+        assert!("11".parse::<SearchDepth>().is_err());
+        assert!("deep".parse::<SearchDepth>().is_err());
+        assert_eq!(SearchDepth::Auto.max_levels(), SearchDepth::AUTO_MAX_LEVELS);
+    }
+
+    #[test]
+    fn test_search_runner_levels_collects_each_level() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        let nested = dir.path().join("services/api");
+        fs::create_dir_all(&nested).unwrap();
+        File::create(nested.join("Cargo.toml")).unwrap();
+        let options = DetectOptions {
+            root_markers: vec![".git".to_string()],
+            ..Default::default()
+        };
+
+        let levels = search_runner_levels(&nested, &options, false).unwrap();
+        let found: Vec<(u8, &str)> = levels
+            .iter()
+            .map(|l| (l.level, l.runners[0].name.as_str()))
+            .collect();
+        assert_eq!(found, vec![(0, "cargo"), (2, "npm")]);
+        assert_eq!(levels[1].dir, dir.path().canonicalize().unwrap());
+
+        // The fixed-depth search still stops at the nearest one
+        let (runners, _) = search_runners(&nested, 3, &options, false).unwrap();
+        assert_eq!(runners[0].name, "cargo");
+
+        assert_eq!(level_with_runner(&levels, Some("npm")), Some(1));
+        assert_eq!(level_with_runner(&levels, Some("yarn")), None);
+        assert_eq!(level_with_runner(&levels, None), None);
+    }
+
+    #[test]
+    fn test_search_runner_levels_stops_at_root_marker() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Makefile")).unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        File::create(repo.join("go.mod")).unwrap();
+        let nested = repo.join("tools");
+        fs::create_dir(&nested).unwrap();
+        File::create(nested.join("package.json")).unwrap();
+        let options = DetectOptions {
+            root_markers: vec![".git".to_string()],
+            ..Default::default()
+        };
+
+        // The Makefile above the repository is never reported
+        let levels = search_runner_levels(&nested, &options, false).unwrap();
+        let names: Vec<&str> = levels.iter().map(|l| l.runners[0].name.as_str()).collect();
+        assert_eq!(names, vec!["npm", "go"]);
+    }
+
+    #[test]
+    fn test_search_runners_current_dir() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+
+        let (runners, found_dir) =
+            search_runners(dir.path(), 3, &DetectOptions::default(), false).unwrap();
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "npm");
+        assert_eq!(found_dir, dir.path().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_search_runners_parent_dir() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+
+        let subdir = dir.path().join("src");
+        std::fs::create_dir(&subdir).unwrap();
+
+        let (runners, found_dir) =
+            search_runners(&subdir, 3, &DetectOptions::default(), false).unwrap();
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].name, "npm");
+        assert_eq!(found_dir, dir.path().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_search_runners_not_found() {
+        // Deeper than the search, so the walk runs out of levels before parents
+        let dir = tempdir().unwrap();
+        let start = dir.path().join("a").join("b").join("c").join("d");
+        fs::create_dir_all(&start).unwrap();
+        let result = search_runners(&start, 3, &DetectOptions::default(), false);
+        assert!(matches!(result, Err(RunError::RunnerNotFound(3))));
+    }
+
+    #[test]
+    fn test_search_runners_walk_cap() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Makefile")).unwrap();
+        let mut start = dir.path().to_path_buf();
+        for _ in 0..=SearchDepth::WALK_CAP {
+            start.push("d");
+        }
+        fs::create_dir_all(&start).unwrap();
+
+        // The Makefile sits one level beyond the cap
+        let result = search_runners(&start, u8::MAX, &DetectOptions::default(), false);
+        assert!(matches!(
+            result,
+            Err(RunError::RunnerNotFound(SearchDepth::WALK_CAP))
+        ));
+        let (runners, _) =
+            search_runners(&start.join(".."), u8::MAX, &DetectOptions::default(), false).unwrap();
+        assert_eq!(runners[0].name, "make");
+    }
+
+    #[test]
+    fn test_search_runners_reaches_filesystem_root() {
+        // No Zig project anywhere up to `/`, whatever the sandbox holds
+        let dir = tempdir().unwrap();
+        let options = DetectOptions {
+            enabled_ecosystems: Some(vec![Ecosystem::Zig]),
+            ..Default::default()
+        };
+        let result = search_runners(dir.path(), SearchDepth::WALK_CAP, &options, false);
+        match result {
+            Err(RunError::ReachedFilesystemRoot(start)) => {
+                assert_eq!(PathBuf::from(start), dir.path().canonicalize().unwrap());
+            }
+            other => panic!("expected filesystem root error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_search_runners_stops_at_root_marker() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Makefile")).unwrap();
+        let project = dir.path().join("project");
+        let nested = project.join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir(project.join(".git")).unwrap();
+
+        let options = DetectOptions {
+            root_markers: vec![".git".to_string(), ".hg".to_string()],
+            ..Default::default()
+        };
+        // The Makefile above the project root is out of bounds
+        let result = search_runners(&nested, 5, &options, false);
+        match result {
+            Err(RunError::NoRunnerInProjectRoot(root, marker)) => {
+                assert_eq!(PathBuf::from(root), project.canonicalize().unwrap());
+                assert_eq!(marker, ".git");
+            }
+            other => panic!("expected project root error, got {:?}", other),
+        }
+
+        // A runner at the root itself is still found
+        File::create(project.join("package.json")).unwrap();
+        let (runners, found) = search_runners(&nested, 5, &options, false).unwrap();
+        assert_eq!(runners[0].name, "npm");
+        assert_eq!(found, project.canonicalize().unwrap());
+
+        // Without markers the walk continues upward
+        fs::remove_file(project.join("package.json")).unwrap();
+        let (runners, _) = search_runners(&nested, 5, &DetectOptions::default(), false).unwrap();
+        assert_eq!(runners[0].name, "make");
+    }
+
+    #[test]
+    fn test_search_runners_marker_in_start_dir() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Makefile")).unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir(&repo).unwrap();
+        // Markers may be files too (e.g. the .git file of a worktree)
+        File::create(repo.join(".hg")).unwrap();
+
+        let options = DetectOptions {
+            root_markers: vec![".git".to_string(), ".hg".to_string()],
+            ..Default::default()
+        };
+        let result = search_runners(&repo, 3, &options, false);
+        assert!(matches!(result, Err(RunError::NoRunnerInProjectRoot(_, m)) if m == ".hg"));
+    }
+
+    #[test]
+    fn test_search_runners_with_ignore() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+
+        let options = DetectOptions {
+            ignore_list: vec!["npm".to_string()],
+            ..Default::default()
+        };
+        let result = search_runners(dir.path(), 0, &options, false);
+        assert!(matches!(result, Err(RunError::RunnerNotFound(0))));
+    }
+
+    #[test]
+    fn test_search_runners_with_priority_override() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        File::create(dir.path().join("Makefile")).unwrap();
+
+        let options = DetectOptions {
+            priorities: HashMap::from([("make".to_string(), 1)]),
+            ..Default::default()
+        };
+        let (runners, _) = search_runners(dir.path(), 3, &options, false).unwrap();
+        let selected = check_conflicts(&runners, None, None, false).unwrap();
+        assert_eq!(selected.name, "make");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_search_runners_through_symlink() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("real").join("project");
+        fs::create_dir_all(project.join("src").join("deep")).unwrap();
+        File::create(project.join("Makefile")).unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(project.join("src"), &link).unwrap();
+
+        let (runners, found_dir) =
+            search_runners(&link.join("deep"), 3, &DetectOptions::default(), false).unwrap();
+        assert_eq!(runners[0].name, "make");
+        // Reported as the real path, not under the symlink
+        assert_eq!(found_dir, project.canonicalize().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_search_runners_symlink_cycle_terminates() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a");
+        fs::create_dir(&a).unwrap();
+        std::os::unix::fs::symlink(&a, a.join("loop")).unwrap();
+
+        let start = a.join("loop").join("loop").join("loop");
+        let options = DetectOptions {
+            enabled_ecosystems: Some(vec![Ecosystem::Zig]),
+            ..Default::default()
+        };
+        let result = search_runners(&start, 255, &options, false);
+        assert!(matches!(result, Err(RunError::ReachedFilesystemRoot(_))));
+    }
+
+    #[test]
+    fn test_resolved_env_applies_overrides_in_order() {
+        let env = resolved_env(
+            None,
+            vec![
+                ("DEVRUNNER_TEST_VAR".to_string(), "venv".into()),
+                ("DEVRUNNER_TEST_VAR".to_string(), "env-from".into()),
+            ],
+        );
+        assert_eq!(env["DEVRUNNER_TEST_VAR"], "env-from");
+        // Inherited variables are kept
+        assert!(env.contains_key("PATH"));
+    }
+
+    #[test]
+    fn test_resolved_env_clean() {
+        let allowlist = vec!["CARGO".to_string(), "DEVRUNNER_TEST_UNSET".to_string()];
+        let env = resolved_env(
+            Some(&allowlist),
+            vec![("DEVRUNNER_TEST_VAR".to_string(), "env-from".into())],
+        );
+        // cargo sets CARGO and CARGO_PKG_NAME for tests; only the allowlisted one passes
+        assert!(env.contains_key("PATH"));
+        assert!(env.contains_key("CARGO"));
+        assert!(!env.contains_key("CARGO_PKG_NAME"));
+        assert!(!env.contains_key("DEVRUNNER_TEST_UNSET"));
+        assert_eq!(env["DEVRUNNER_TEST_VAR"], "env-from");
+        for key in env.keys() {
+            assert!(
+                ["PATH", "HOME", "CARGO", "DEVRUNNER_TEST_VAR"].contains(&key.as_str()),
+                "unexpected variable {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_args_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("args.txt");
+        fs::write(
+            &path,
+            "# integration suite\n--grep\nuser signup flow\n\n  --reporter=dot  \n--tag=#smoke\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_args_file(&path).unwrap(),
+            vec![
+                "--grep",
+                "user signup flow",
+                "--reporter=dot",
+                "--tag=#smoke"
+            ]
+        );
+
+        let err = read_args_file(&dir.path().join("missing.txt")).unwrap_err();
+        assert!(matches!(err, RunError::InvalidArgument(_)));
+        assert!(err.to_string().contains("missing.txt"));
+    }
+
+    #[test]
+    fn test_parse_env_lines() {
+        let vars = parse_env_lines(
+            "# generated\nexport AWS_KEY=abc\nREGION=\"eu west\"\nnot a var\n1BAD=x\nEMPTY=\n",
+        );
+        assert_eq!(
+            vars,
+            vec![
+                ("AWS_KEY".to_string(), "abc".to_string()),
+                ("REGION".to_string(), "eu west".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_shell_passes_args() {
+        let dir = tempdir().unwrap();
+        let options = ExecOptions {
+            quiet: 1,
+            ..Default::default()
+        };
+        let status = execute_shell(
+            "test \"$1\" = x && touch ran",
+            &["x".to_string()],
+            dir.path(),
+            &options,
+        )
+        .unwrap();
+        assert!(status.success());
+        assert!(dir.path().join("ran").exists());
+
+        // --env-from reaches the snippet like any other command
+        let options = ExecOptions {
+            quiet: 1,
+            env_from: vec!["echo DEVRUNNER_TEST_MARK=set".to_string()],
+            ..Default::default()
+        };
+        let status = execute_shell(
+            "test \"$DEVRUNNER_TEST_MARK\" = set && touch loaded",
+            &[],
+            dir.path(),
+            &options,
+        )
+        .unwrap();
+        assert!(status.success());
+        assert!(dir.path().join("loaded").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env_from_command() {
+        let dir = tempdir().unwrap();
+        let vars = env_from_command("echo A=1; echo B=two", dir.path()).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "two".to_string())
+            ]
+        );
+
+        let err = env_from_command("echo A=1; exit 3", dir.path()).unwrap_err();
+        assert!(err.to_string().contains("exited with code 3"));
+    }
+
+    #[test]
+    fn test_check_conflicts_single_runner() {
+        let runners = vec![DetectedRunner::new(
+            "npm",
             "package.json",
             Ecosystem::NodeJs,
             4,
         )];
-        let result = check_conflicts(&runners, false).unwrap();
+        let result = check_conflicts(&runners, None, None, false).unwrap();
         assert_eq!(result.name, "npm");
     }
 
@@ -254,8 +2145,666 @@ mod tests {
             DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4),
             DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9),
         ];
-        let result = check_conflicts(&runners, false).unwrap();
+        let result = check_conflicts(&runners, None, None, false).unwrap();
         // Should return highest priority
         assert_eq!(result.name, "npm");
     }
+
+    #[test]
+    fn test_check_conflicts_prefers_pinned_runner() {
+        let mut pnpm = DetectedRunner::new("pnpm", ".tool-versions", Ecosystem::NodeJs, 2);
+        pnpm.pinned_by = Some(".tool-versions pins pnpm".to_string());
+        let runners = vec![
+            DetectedRunner::new("npm", "package-lock.json", Ecosystem::NodeJs, 4),
+            pnpm,
+        ];
+        // Resolved by the pin, without looking at which tools are installed
+        let result = check_conflicts(&runners, None, None, false).unwrap();
+        assert_eq!(result.name, "pnpm");
+    }
+
+    #[test]
+    fn test_check_conflicts_remembered_choice() {
+        let mut pnpm = DetectedRunner::new("pnpm", ".tool-versions", Ecosystem::NodeJs, 2);
+        pnpm.pinned_by = Some(".tool-versions pins pnpm".to_string());
+        let runners = vec![
+            DetectedRunner::new("npm", "package-lock.json", Ecosystem::NodeJs, 4),
+            pnpm,
+            DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9),
+        ];
+        // Settles the conflict ahead of the pin and the installed-tools check
+        let result = check_conflicts(&runners, None, Some("npm"), false).unwrap();
+        assert_eq!(result.name, "npm");
+        assert!(result.pinned_by.unwrap().contains("chosen here before"));
+
+        // Only within a conflict, and only when still detected
+        let result = check_conflicts(&runners, None, Some("cargo"), false).unwrap();
+        assert_eq!(result.name, "pnpm");
+        let result = check_conflicts(&runners, None, Some("yarn"), false).unwrap();
+        assert_eq!(result.name, "pnpm");
+    }
+
+    #[test]
+    fn test_check_conflicts_prefer() {
+        let runners = vec![
+            DetectedRunner::new("npm", "package-lock.json", Ecosystem::NodeJs, 4),
+            DetectedRunner::new("pnpm", "pnpm-lock.yaml", Ecosystem::NodeJs, 2),
+            DetectedRunner::new("make", "Makefile", Ecosystem::Generic, 24),
+        ];
+        // Detected: wins the lockfile conflict without looking at installed tools
+        let result = check_conflicts(&runners, Some("PNPM"), None, false).unwrap();
+        assert_eq!(result.name, "pnpm");
+        assert_eq!(result.pinned_by.as_deref(), Some("--prefer pnpm"));
+
+        // Also beats higher-priority runners from other ecosystems
+        let result = check_conflicts(&runners, Some("make"), None, false).unwrap();
+        assert_eq!(result.name, "make");
+    }
+
+    #[test]
+    fn test_check_conflicts_prefer_absent() {
+        let runners = vec![
+            DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4),
+            DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9),
+        ];
+        // Not detected: normal priority resolution, no error
+        let result = check_conflicts(&runners, Some("pnpm"), None, false).unwrap();
+        assert_eq!(result.name, "npm");
+        assert!(result.pinned_by.is_none());
+    }
+
+    #[test]
+    fn test_find_preferred() {
+        let runners = vec![
+            DetectedRunner::new("pnpm", "pnpm-lock.yaml", Ecosystem::NodeJs, 2),
+            DetectedRunner::new("npm", "package-lock.json", Ecosystem::NodeJs, 4),
+        ];
+        assert_eq!(find_preferred(&runners, Some("NPM")).unwrap().name, "npm");
+        assert!(find_preferred(&runners, Some("yarn")).is_none());
+        assert!(find_preferred(&runners, None).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_from_status() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Raw wait statuses: exit code lives in the high byte, signal in the low bits
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(0)), 0);
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(3 << 8)), 3);
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(2)), 130); // SIGINT
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(9)), 137); // SIGKILL
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_exit_code_from_status() {
+        use std::os::windows::process::ExitStatusExt;
+
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(0)), 0);
+        assert_eq!(exit_code_from_status(ExitStatus::from_raw(3)), 3);
+    }
+
+    fn install_argv(runner: &str, detected_file: &str, ecosystem: Ecosystem) -> Vec<String> {
+        let runner = DetectedRunner::new(runner, detected_file, ecosystem, 1);
+        build_install_command(&runner, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_build_install_command() {
+        let node = Ecosystem::NodeJs;
+        assert_eq!(
+            install_argv("pnpm", "pnpm-lock.yaml", node),
+            vec!["pnpm", "install"]
+        );
+        assert_eq!(
+            install_argv("npm", "package.json", node),
+            vec!["npm", "install"]
+        );
+        assert_eq!(
+            install_argv("cargo", "Cargo.toml", Ecosystem::Rust),
+            vec!["cargo", "fetch"]
+        );
+        assert_eq!(
+            install_argv("go", "go.mod", Ecosystem::Go),
+            vec!["go", "mod", "download"]
+        );
+        assert_eq!(
+            install_argv("bundler", "Gemfile", Ecosystem::Ruby),
+            vec!["bundle", "install"]
+        );
+    }
+
+    #[test]
+    fn test_build_install_command_python() {
+        let python = Ecosystem::Python;
+        assert_eq!(install_argv("uv", "uv.lock", python), vec!["uv", "sync"]);
+        assert_eq!(
+            install_argv("poetry", "poetry.lock", python),
+            vec!["poetry", "install"]
+        );
+        assert_eq!(
+            install_argv("hatch", "hatch.toml", python),
+            vec!["hatch", "env", "create"]
+        );
+        assert_eq!(
+            install_argv("pip", "requirements.txt", python),
+            vec!["pip", "install", "-r", "requirements.txt"]
+        );
+        assert_eq!(
+            install_argv("pip", "pyproject.toml", python),
+            vec!["pip", "install", "."]
+        );
+    }
+
+    #[test]
+    fn test_build_install_command_unsupported() {
+        let runner = DetectedRunner::new("make", "Makefile", Ecosystem::Generic, 24);
+        let err = build_install_command(&runner, &[]).unwrap_err();
+        assert!(err.to_string().contains("no well-defined step for Generic"));
+
+        let mut args = build_install_command(
+            &DetectedRunner::new("yarn", "yarn.lock", Ecosystem::NodeJs, 3),
+            &["--frozen-lockfile".to_string()],
+        )
+        .unwrap();
+        assert_eq!(args.pop().unwrap(), "--frozen-lockfile");
+    }
+
+    fn plan_for(dir: &Path) -> Result<CleanPlan, RunError> {
+        plan_clean(&detect_all(dir, &DetectOptions::default()), dir)
+    }
+
+    #[test]
+    fn test_plan_clean_commands() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("Cargo.toml")).unwrap();
+        match plan_for(dir.path()).unwrap() {
+            CleanPlan::Task(runner) => {
+                assert_eq!(runner.build_command("clean", &[]), vec!["cargo", "clean"])
+            }
+            other => panic!("expected cargo clean, got {:?}", other),
+        }
+
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("go.mod")).unwrap();
+        match plan_for(dir.path()).unwrap() {
+            CleanPlan::Task(runner) => {
+                assert_eq!(runner.build_command("clean", &[]), vec!["go", "clean"])
+            }
+            other => panic!("expected go clean, got {:?}", other),
+        }
+
+        // A project's own clean script beats deleting directories
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"clean": "rimraf build"}}"#,
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+        assert!(matches!(plan_for(dir.path()).unwrap(), CleanPlan::Task(r) if r.name == "npm"));
+    }
+
+    #[test]
+    fn test_plan_clean_removes_directories() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("package.json")).unwrap();
+        fs::create_dir(dir.path().join("dist")).unwrap();
+        match plan_for(dir.path()).unwrap() {
+            CleanPlan::Remove(_, dirs) => assert_eq!(dirs, vec![PathBuf::from("dist")]),
+            other => panic!("expected removal, got {:?}", other),
+        }
+
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("requirements.txt")).unwrap();
+        fs::create_dir_all(dir.path().join("pkg/__pycache__")).unwrap();
+        fs::create_dir_all(dir.path().join(".venv/lib/__pycache__")).unwrap();
+        fs::create_dir(dir.path().join(".pytest_cache")).unwrap();
+        match plan_for(dir.path()).unwrap() {
+            CleanPlan::Remove(runner, dirs) => {
+                assert_eq!(runner.name, "pip");
+                assert_eq!(
+                    dirs,
+                    vec![
+                        PathBuf::from(".pytest_cache"),
+                        PathBuf::from("pkg/__pycache__")
+                    ]
+                );
+            }
+            other => panic!("expected removal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plan_clean_make_target() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Makefile"), "all:\n\techo hi\n").unwrap();
+        let err = plan_for(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("needs a `clean` target"));
+
+        // Without a clean target, make steps aside for the next runner
+        File::create(dir.path().join("pyproject.toml")).unwrap();
+        assert!(
+            matches!(plan_for(dir.path()).unwrap(), CleanPlan::Remove(r, _) if r.name == "pip")
+        );
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Makefile"), "clean:\n\trm -rf out\n").unwrap();
+        assert!(matches!(plan_for(dir.path()).unwrap(), CleanPlan::Task(r) if r.name == "make"));
+    }
+
+    #[test]
+    fn test_plan_clean_falls_back_to_runner_task() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("pom.xml")).unwrap();
+        assert!(matches!(plan_for(dir.path()).unwrap(), CleanPlan::Task(r) if r.name == "maven"));
+
+        // A Makefile without a clean target still steps aside
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Makefile"), "all:\n\techo hi\n").unwrap();
+        File::create(dir.path().join("Taskfile.yml")).unwrap();
+        assert!(matches!(plan_for(dir.path()).unwrap(), CleanPlan::Task(r) if r.name == "task"));
+    }
+
+    fn exec_argv(runner: &str, ecosystem: Ecosystem, dir: &Path) -> Result<Vec<String>, RunError> {
+        let runner = DetectedRunner::new(runner, "manifest", ecosystem, 1);
+        build_exec_command(
+            &runner,
+            "eslint",
+            &["--fix".to_string(), ".".to_string()],
+            dir,
+        )
+    }
+
+    #[test]
+    fn test_build_exec_command_node() {
+        let dir = tempdir().unwrap();
+        assert_eq!(
+            exec_argv("npm", Ecosystem::NodeJs, dir.path()).unwrap(),
+            vec!["npm", "exec", "--", "eslint", "--fix", "."]
+        );
+        assert_eq!(
+            exec_argv("pnpm", Ecosystem::NodeJs, dir.path()).unwrap(),
+            vec!["pnpm", "exec", "eslint", "--fix", "."]
+        );
+        assert_eq!(
+            exec_argv("bun", Ecosystem::NodeJs, dir.path()).unwrap(),
+            vec!["bun", "x", "eslint", "--fix", "."]
+        );
+    }
+
+    #[test]
+    fn test_build_exec_command_python() {
+        let dir = tempdir().unwrap();
+        assert_eq!(
+            exec_argv("uv", Ecosystem::Python, dir.path()).unwrap(),
+            vec!["uv", "run", "eslint", "--fix", "."]
+        );
+        assert_eq!(
+            exec_argv("pip", Ecosystem::Python, dir.path()).unwrap(),
+            vec!["eslint", "--fix", "."]
+        );
+    }
+
+    #[test]
+    fn test_build_exec_command_rust_bin() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[[bin]]\nname = \"eslint\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            exec_argv("cargo", Ecosystem::Rust, dir.path()).unwrap(),
+            vec!["cargo", "run", "--bin", "eslint", "--", "--fix", "."]
+        );
+
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        assert!(exec_argv("cargo", Ecosystem::Rust, dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_build_exec_command_unsupported() {
+        let dir = tempdir().unwrap();
+        let err = exec_argv("make", Ecosystem::Generic, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("not supported"));
+    }
+
+    #[test]
+    fn test_ignore_scripts_npm_argv() {
+        let dir = tempdir().unwrap();
+        let runner = DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4);
+        let options = ExecOptions {
+            dry_run: true,
+            quiet: 1,
+            ignore_scripts: true,
+            ..Default::default()
+        };
+
+        let result = execute(
+            &runner,
+            "build",
+            &["--watch".to_string()],
+            dir.path(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            result.command,
+            vec!["npm", "run", "build", "--ignore-scripts", "--", "--watch"]
+        );
+
+        let err = execute(&runner, "postinstall", &[], dir.path(), &options)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("postinstall"));
+
+        // `install` is the package manager's own install, without hooks
+        let result = execute(&runner, "install", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command, vec!["npm", "install", "--ignore-scripts"]);
+        std::fs::write(dir.path().join(".yarnrc.yml"), "").unwrap();
+        let berry = DetectedRunner::new("yarn", "yarn.lock", Ecosystem::NodeJs, 3)
+            .with_evidence(dir.path(), &[".yarnrc.yml"]);
+        let result = execute(&berry, "install", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command, vec!["yarn", "install", "--mode=skip-build"]);
+
+        // A custom "npm" runner's template runs as written
+        let mut custom = runner.clone();
+        custom
+            .custom_scripts
+            .insert("build".to_string(), "echo build".to_string());
+        let result = execute(&custom, "build", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command, vec!["echo", "build"]);
+
+        // Without the flag, lifecycle scripts run as usual
+        let options = ExecOptions {
+            ignore_scripts: false,
+            ..options
+        };
+        assert!(execute(&runner, "postinstall", &[], dir.path(), &options).is_ok());
+    }
+
+    #[test]
+    fn test_default_args_argv() {
+        let dir = tempdir().unwrap();
+        let options = ExecOptions {
+            dry_run: true,
+            quiet: 1,
+            default_args: HashMap::from([
+                ("npm".to_string(), vec!["--silent".to_string()]),
+                ("rust".to_string(), vec!["--offline".to_string()]),
+            ]),
+            ..Default::default()
+        };
+
+        // npm flags stay in front of the `--` that starts the script's args
+        let npm = DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4);
+        let result = execute(
+            &npm,
+            "build",
+            &["--watch".to_string()],
+            dir.path(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            result.command,
+            vec!["npm", "run", "build", "--silent", "--", "--watch"]
+        );
+
+        // Keyed by ecosystem, and ahead of a user's own `--`
+        let cargo = DetectedRunner::new("cargo", "Cargo.toml", Ecosystem::Rust, 9);
+        let args = vec!["--".to_string(), "--nocapture".to_string()];
+        let result = execute(&cargo, "test", &args, dir.path(), &options).unwrap();
+        assert_eq!(
+            result.command,
+            vec!["cargo", "test", "--offline", "--", "--nocapture"]
+        );
+
+        // Other runners are untouched
+        let make = DetectedRunner::new("make", "Makefile", Ecosystem::Generic, 24);
+        let result = execute(&make, "build", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command, vec!["make", "build"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner_path_override() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("pnpm");
+        fs::write(&binary, "#!/bin/sh\n").unwrap();
+        let mut options = ExecOptions {
+            dry_run: true,
+            quiet: 1,
+            runner_paths: HashMap::from([("pnpm".to_string(), binary.clone())]),
+            ..Default::default()
+        };
+        let pnpm = DetectedRunner::new("pnpm", "pnpm-lock.yaml", Ecosystem::NodeJs, 2);
+
+        // Not executable yet
+        let err = execute(&pnpm, "build", &[], dir.path(), &options)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not an executable file"));
+
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).unwrap();
+        let result = execute(&pnpm, "build", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command[0], binary.to_string_lossy());
+        assert_eq!(&result.command[1..], ["run", "build"]);
+
+        // Only the named runner's binary moves
+        let npm = DetectedRunner::new("npm", "package.json", Ecosystem::NodeJs, 4);
+        let result = execute(&npm, "build", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command, vec!["npm", "run", "build"]);
+
+        // pip tasks run python, so that's the binary the path stands for
+        let python = dir.path().join("python");
+        fs::write(&python, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&python, fs::Permissions::from_mode(0o755)).unwrap();
+        options.runner_paths = HashMap::from([("pip".to_string(), python.clone())]);
+        let pip = DetectedRunner::new("pip", "requirements.txt", Ecosystem::Python, 8);
+        let result = execute(&pip, "pytest", &[], dir.path(), &options).unwrap();
+        assert_eq!(result.command[0], python.to_string_lossy());
+        assert_eq!(&result.command[1..], ["-m", "pytest"]);
+
+        options.runner_paths = HashMap::from([("pnpm".to_string(), PathBuf::from("bin/pnpm"))]);
+        let err = execute(&pnpm, "build", &[], dir.path(), &options)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("must be absolute"));
+    }
+
+    #[test]
+    fn test_find_venv() {
+        let dir = tempdir().unwrap();
+        assert_eq!(find_venv(dir.path()), None);
+
+        // A bare directory isn't a virtualenv
+        fs::create_dir(dir.path().join("venv")).unwrap();
+        assert_eq!(find_venv(dir.path()), None);
+
+        File::create(dir.path().join("venv").join("pyvenv.cfg")).unwrap();
+        assert_eq!(find_venv(dir.path()), Some(dir.path().join("venv")));
+
+        // .venv wins over venv
+        fs::create_dir(dir.path().join(".venv")).unwrap();
+        File::create(dir.path().join(".venv").join("pyvenv.cfg")).unwrap();
+        assert_eq!(find_venv(dir.path()), Some(dir.path().join(".venv")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_injects_venv() {
+        let dir = tempdir().unwrap();
+        let venv = dir.path().join(".venv");
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        File::create(venv.join("pyvenv.cfg")).unwrap();
+        let log_path = dir.path().join("env.log");
+
+        let runner = DetectedRunner::new("sh", "requirements.txt", Ecosystem::Python, 8);
+        let args = vec!["echo \"$VIRTUAL_ENV\"; echo \"$PATH\"".to_string()];
+        let mut options = ExecOptions {
+            quiet: 1,
+            tee: Some(log_path.clone()),
+            ..Default::default()
+        };
+        execute(&runner, "-c", &args, dir.path(), &options).unwrap();
+
+        let captured = fs::read_to_string(&log_path).unwrap();
+        let mut lines = captured.lines();
+        assert_eq!(lines.next(), Some(venv.to_str().unwrap()));
+        let first_path = lines.next().unwrap().split(':').next().unwrap();
+        assert_eq!(first_path, venv.join("bin").to_str().unwrap());
+
+        // --no-venv leaves the environment alone
+        options.no_venv = true;
+        execute(&runner, "-c", &args, dir.path(), &options).unwrap();
+        let captured = fs::read_to_string(&log_path).unwrap();
+        assert_ne!(captured.lines().next(), Some(venv.to_str().unwrap()));
+    }
+
+    #[test]
+    fn test_exit_hint() {
+        assert!(exit_hint(127).unwrap().contains("not found"));
+        assert!(exit_hint(126).unwrap().contains("permission"));
+        assert!(exit_hint(130).unwrap().contains("SIGINT"));
+        assert!(exit_hint(137).unwrap().contains("SIGKILL"));
+        assert_eq!(exit_hint(0), None);
+        assert_eq!(exit_hint(1), None);
+        assert_eq!(exit_hint(42), None);
+    }
+
+    #[test]
+    fn test_shell_join_quotes_special_args() {
+        let argv: Vec<String> = ["make", "build", "hello world", "it's", "", "--opt=a/b"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            shell_join(&argv),
+            r#"make build 'hello world' 'it'\''s' '' --opt=a/b"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_captured() {
+        let dir = tempdir().unwrap();
+        let mut runner = DetectedRunner::new("acme", "acme.toml", Ecosystem::Custom, 30);
+        runner.custom_scripts.insert(
+            "both".to_string(),
+            "sh -c 'echo out; echo err >&2; exit 3'".to_string(),
+        );
+
+        let captured = execute_captured(
+            &runner,
+            "both",
+            &[],
+            dir.path(),
+            &ExecOptions::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(captured.stdout, "out\n");
+        assert_eq!(captured.stderr, "err\n");
+        assert_eq!(captured.exit_status.code(), Some(3));
+        assert!(!captured.truncated);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_command_caps_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "yes line | head -n 100000"]);
+
+        let captured = capture_command(command, Some(10)).unwrap();
+        assert_eq!(captured.stdout, "line\nline\n");
+        assert!(captured.truncated);
+        assert!(captured.exit_status.success());
+    }
+
+    #[test]
+    fn test_tail_buffer_keeps_last_lines() {
+        let mut tail = TailBuffer::new(2);
+        for line in ["one\n", "two\n", "three\n"] {
+            tail.push(line.as_bytes().to_vec());
+        }
+        assert_eq!(tail.omitted, 1);
+
+        let mut out = Vec::new();
+        tail.flush_to(&mut out, "stdout");
+        assert_eq!(String::from_utf8(out).unwrap(), "two\nthree\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_piped_json_stream_events() {
+        let cmd_parts: Vec<String> = ["sh", "-c", "echo out; echo err >&2; exit 2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut command = Command::new(&cmd_parts[0]);
+        command.args(&cmd_parts[1..]);
+        let options = ExecOptions {
+            output: OutputMode::JsonStream,
+            ..Default::default()
+        };
+
+        let mut events = Vec::new();
+        let status = run_piped(command, &cmd_parts, &options, &mut events).unwrap();
+        assert_eq!(status.code(), Some(2));
+
+        let events: Vec<serde_json::Value> = String::from_utf8(events)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0]["event"], "start");
+        assert_eq!(events[0]["argv"][0], "sh");
+        let lines: Vec<(&str, &str)> = events[1..3]
+            .iter()
+            .map(|e| (e["event"].as_str().unwrap(), e["line"].as_str().unwrap()))
+            .collect();
+        assert!(lines.contains(&("stdout", "out")));
+        assert!(lines.contains(&("stderr", "err")));
+        assert_eq!(events[3]["event"], "exit");
+        assert_eq!(events[3]["code"], 2);
+        assert!(events[3]["duration_ms"].is_u64());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_tee_writes_output_to_file() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("run.log");
+        // Fallback command building yields `sh -c <script>`
+        let runner = DetectedRunner::new("sh", "Makefile", Ecosystem::Generic, 24);
+        let options = ExecOptions {
+            quiet: 1,
+            tee: Some(log_path.clone()),
+            ..Default::default()
+        };
+
+        let result = execute(
+            &runner,
+            "-c",
+            &["echo first; echo second >&2; echo third; exit 3".to_string()],
+            dir.path(),
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(result.exit_status.code(), Some(3));
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        for line in ["first", "second", "third"] {
+            assert!(log.lines().any(|l| l == line), "missing {line} in {log:?}");
+        }
+        let first = log.find("first").unwrap();
+        let third = log.find("third").unwrap();
+        assert!(first < third);
+    }
 }
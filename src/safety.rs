@@ -0,0 +1,197 @@
+//! Guards for running scripts from untrusted checkouts (`--ignore-scripts` / `safe_mode`)
+//! and for scripts that should be confirmed before they run (`--confirm` / `--yes`)
+
+use crate::detectors::glob_match;
+
+/// npm lifecycle hooks that run automatically around install/publish
+pub const LIFECYCLE_HOOKS: &[&str] = &[
+    "preinstall",
+    "install",
+    "postinstall",
+    "prepare",
+    "preprepare",
+    "postprepare",
+    "prepublish",
+    "prepublishOnly",
+    "prepack",
+    "postpack",
+    "dependencies",
+];
+
+/// Check whether a script name is an install/publish lifecycle hook
+pub fn is_lifecycle_hook(task: &str) -> bool {
+    LIFECYCLE_HOOKS.contains(&task)
+}
+
+/// Detect commands that download a script and pipe it straight into a shell
+///
+/// Matches `curl ... | sh`, `wget -O- ... | sudo bash`, `sh -c "$(curl ...)"`
+/// and `bash <(curl ...)`.
+pub fn pipes_remote_script(command: &str) -> bool {
+    const FETCHERS: &[&str] = &["curl", "wget"];
+    const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "fish"];
+
+    let is_fetcher =
+        |word: &str| FETCHERS.contains(&word.trim_start_matches(['(', '$', '<', '"', '\'']));
+    let is_shell = |word: &str| SHELLS.contains(&word.rsplit('/').next().unwrap_or(word));
+
+    // Pipelines: a fetcher in one stage and a shell at the start of a later stage
+    let stages: Vec<&str> = command.split('|').collect();
+    for (i, stage) in stages.iter().enumerate() {
+        if !stage.split_whitespace().any(is_fetcher) {
+            continue;
+        }
+        let pipes_into_shell = stages[i + 1..].iter().any(|later| {
+            let mut words = later
+                .split_whitespace()
+                .skip_while(|w| *w == "sudo" || w.starts_with('-'));
+            words.next().is_some_and(is_shell)
+        });
+        if pipes_into_shell {
+            return true;
+        }
+    }
+
+    // Substitutions: a shell evaluating a fetcher's output
+    let words: Vec<&str> = command.split_whitespace().collect();
+    words.iter().enumerate().any(|(i, word)| {
+        is_shell(word)
+            && words[i + 1..].iter().take(3).any(|next| {
+                (next.starts_with("\"$(") || next.starts_with("$(") || next.starts_with("<("))
+                    && is_fetcher(next)
+            })
+    })
+}
+
+/// Scripts a script runs by name, e.g. `lint` in `npm run lint && tsc`
+///
+/// Only `<npm|pnpm|yarn|bun> run <name>` (or `npm run-script`) is recognized,
+/// so safe mode can check the scripts a script calls as well as its own body.
+pub fn script_references(command: &str) -> Vec<&str> {
+    const PACKAGE_MANAGERS: &[&str] = &["npm", "pnpm", "yarn", "bun"];
+
+    let words: Vec<&str> = command
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '(' | ')'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut references = Vec::new();
+    for (i, pair) in words.windows(2).enumerate() {
+        if PACKAGE_MANAGERS.contains(&pair[0]) && (pair[1] == "run" || pair[1] == "run-script") {
+            if let Some(name) = words[i + 2..].iter().find(|word| !word.starts_with('-')) {
+                references.push(*name);
+            }
+        }
+    }
+    references
+}
+
+/// Script names that always ask for confirmation before running
+pub const CONFIRM_SCRIPTS: &[&str] = &["deploy", "release", "publish", "clean"];
+
+/// Whether a script should be confirmed before running
+///
+/// Checks the built-in names and the configured `confirm_patterns` (globs such
+/// as `db:*`) against the full name and against its base before any `:env`
+/// suffix, so `deploy:prod` counts as `deploy`.
+pub fn needs_confirmation(script: &str, patterns: &[String]) -> bool {
+    let base = script.split(':').next().unwrap_or(script);
+    CONFIRM_SCRIPTS
+        .iter()
+        .copied()
+        .chain(patterns.iter().map(String::as_str))
+        .any(|pattern| glob_match(pattern, script) || glob_match(pattern, base))
+}
+
+/// What to do before running a script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Run without asking
+    Run,
+    /// Ask on the terminal first
+    Prompt,
+    /// Nobody can answer a prompt, so don't run
+    Refuse,
+}
+
+/// Decide whether to prompt, given whether the script needs it, `--yes`, and
+/// whether devrunner is attached to a terminal
+pub fn confirm_action(needs_confirmation: bool, yes: bool, interactive: bool) -> ConfirmAction {
+    if !needs_confirmation || yes {
+        ConfirmAction::Run
+    } else if interactive {
+        ConfirmAction::Prompt
+    } else {
+        ConfirmAction::Refuse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_lifecycle_hook() {
+        assert!(is_lifecycle_hook("postinstall"));
+        assert!(is_lifecycle_hook("prepare"));
+        assert!(!is_lifecycle_hook("build"));
+        assert!(!is_lifecycle_hook("test"));
+    }
+
+    #[test]
+    fn test_pipes_remote_script() {
+        assert!(pipes_remote_script(
+            "curl -fsSL https://example.com/install.sh | sh"
+        ));
+        assert!(pipes_remote_script(
+            "wget -qO- https://example.com/x | sudo bash"
+        ));
+        assert!(pipes_remote_script(
+            "curl -s https://x.io/i | /bin/bash -s -- --yes"
+        ));
+        assert!(pipes_remote_script(
+            "sh -c \"$(curl -fsSL https://example.com/install.sh)\""
+        ));
+        assert!(pipes_remote_script("bash <(curl -s https://example.com/x)"));
+    }
+
+    #[test]
+    fn test_pipes_remote_script_allows_safe_commands() {
+        assert!(!pipes_remote_script(
+            "curl -o data.json https://example.com/data.json"
+        ));
+        assert!(!pipes_remote_script("curl https://example.com | jq ."));
+        assert!(!pipes_remote_script("cat install.sh | sh"));
+        assert!(!pipes_remote_script("tsc && node dist/index.js"));
+    }
+
+    #[test]
+    fn test_script_references() {
+        assert_eq!(
+            script_references("npm run lint && pnpm run -s build; bun test"),
+            vec!["lint", "build"]
+        );
+        assert_eq!(script_references("npm run-script prep"), vec!["prep"]);
+        assert!(script_references("npm install && node run.js").is_empty());
+    }
+
+    #[test]
+    fn test_needs_confirmation() {
+        let patterns = vec!["db:*".to_string(), "nuke".to_string()];
+        assert!(needs_confirmation("deploy", &[]));
+        assert!(needs_confirmation("deploy:prod", &[]));
+        assert!(needs_confirmation("clean", &[]));
+        assert!(needs_confirmation("db:drop", &patterns));
+        assert!(needs_confirmation("nuke", &patterns));
+        assert!(!needs_confirmation("build", &patterns));
+        assert!(!needs_confirmation("deployment-docs", &[]));
+    }
+
+    #[test]
+    fn test_confirm_action() {
+        assert_eq!(confirm_action(false, false, false), ConfirmAction::Run);
+        assert_eq!(confirm_action(true, true, false), ConfirmAction::Run);
+        assert_eq!(confirm_action(true, false, true), ConfirmAction::Prompt);
+        // Without a terminal and without --yes, refuse rather than hang or run
+        assert_eq!(confirm_action(true, false, false), ConfirmAction::Refuse);
+    }
+}
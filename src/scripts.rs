@@ -1,13 +1,34 @@
-use crate::detectors::{DetectedRunner, Ecosystem};
+use crate::config::Config;
+use crate::detectors::{
+    detect_xcode, glob_match, gradle_flavor, ruby_bin_scripts, DetectedRunner, Ecosystem,
+    GradleFlavor, BAZEL_WORKSPACE_FILES, MISE_CONFIG_FILES, MISE_TASKS_DIR,
+};
 use serde_json::Value;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 /// Represents a script/command available in a project
 #[derive(Debug, Clone)]
 pub struct ProjectScript {
     pub name: String,
     pub command: String,
+    /// Human-readable summary shown next to the command, when the source has one
+    pub description: Option<String>,
+    /// What kind of runnable item this is
+    pub category: ScriptCategory,
+}
+
+/// Kinds of runnable items a project can expose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScriptCategory {
+    /// A named script or task run through the project's runner
+    #[default]
+    Script,
+    /// An executable the package installs (package.json `bin`)
+    Binary,
 }
 
 /// Result of script discovery
@@ -17,33 +38,112 @@ pub struct ScriptList {
     pub source_file: String,
 }
 
+impl ScriptList {
+    /// Whether `devrunner <name>` runs the listed entry `name`
+    ///
+    /// package.json bins run through npx rather than `npm run`, so they don't
+    /// count; Cargo binaries (`run:bin:*`) and Ruby `bin/` scripts do. Under
+    /// Bundler `bin/setup` also runs as plain `setup`.
+    pub fn runs_by_name(&self, name: &str) -> bool {
+        self.scripts.iter().any(|s| {
+            (s.name == name
+                || (self.source_file == "bin/" && s.name.strip_prefix("bin/") == Some(name)))
+                && (s.category == ScriptCategory::Script || self.source_file != "package.json")
+        })
+    }
+}
+
 /// Parse scripts from a package.json file
 pub fn parse_package_json_scripts(project_dir: &Path) -> Option<ScriptList> {
     let package_json_path = project_dir.join("package.json");
-    
+
     if !package_json_path.exists() {
         return None;
     }
-    
-    let content = fs::read_to_string(&package_json_path).ok()?;
-    let json: Value = serde_json::from_str(&content).ok()?;
-    
-    let scripts_obj = json.get("scripts")?.as_object()?;
-    
-    let scripts: Vec<ProjectScript> = scripts_obj
-        .iter()
-        .map(|(name, cmd)| ProjectScript {
-            name: name.clone(),
-            command: cmd.as_str().unwrap_or("").to_string(),
+
+    let content = read_manifest(&package_json_path)?;
+    // Some editors and tools leave `//` comments or trailing commas behind;
+    // only fall back to the JSONC path when strict parsing fails
+    let json: Value = serde_json::from_str(&content)
+        .or_else(|_| serde_json::from_str(&strip_jsonc(&content)))
+        .ok()?;
+
+    let mut scripts: Vec<ProjectScript> = json
+        .get("scripts")
+        .and_then(|s| s.as_object())
+        .map(|scripts_obj| {
+            scripts_obj
+                .iter()
+                .map(|(name, cmd)| ProjectScript {
+                    name: name.clone(),
+                    command: cmd.as_str().unwrap_or("").to_string(),
+                    description: None,
+                    category: ScriptCategory::Script,
+                })
+                .collect()
         })
-        .collect();
-    
+        .unwrap_or_default();
+
+    // `bin` is either a single path named after the package, or a name -> path map
+    let bin_names: Vec<String> = match json.get("bin") {
+        Some(Value::String(_)) => json
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+            .into_iter()
+            .collect(),
+        Some(Value::Object(bins)) => bins.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    scripts.extend(bin_names.into_iter().map(|name| ProjectScript {
+        command: format!("npx {}", name),
+        name,
+        description: None,
+        category: ScriptCategory::Binary,
+    }));
+
+    if scripts.is_empty() {
+        return None;
+    }
+
     Some(ScriptList {
         scripts,
         source_file: "package.json".to_string(),
     })
 }
 
+/// Executable `bin/` scripts of a Ruby app (`bin/setup`, `bin/rails`, ...)
+///
+/// Without a Gemfile they run directly (`devrunner setup` -> `bin/setup`);
+/// under Bundler they're named by path so they run through `bundle exec`.
+pub fn parse_ruby_bin_scripts(project_dir: &Path, runner: &str) -> Option<ScriptList> {
+    let scripts: Vec<ProjectScript> = ruby_bin_scripts(project_dir)
+        .into_iter()
+        .filter_map(|name| {
+            let path = format!("bin/{}", name);
+            let (name, command) = match runner {
+                "binstubs" => (name, path),
+                "bundler" => (path.clone(), format!("bundle exec {}", path)),
+                _ => return None,
+            };
+            Some(ProjectScript {
+                name,
+                command,
+                description: None,
+                category: ScriptCategory::Binary,
+            })
+        })
+        .collect();
+
+    if scripts.is_empty() {
+        return None;
+    }
+    Some(ScriptList {
+        scripts,
+        source_file: "bin/".to_string(),
+    })
+}
+
 /// Parse targets from a Makefile
 pub fn parse_makefile_targets(project_dir: &Path) -> Option<ScriptList> {
     let makefile_path = if project_dir.join("Makefile").exists() {
@@ -53,93 +153,708 @@ pub fn parse_makefile_targets(project_dir: &Path) -> Option<ScriptList> {
     } else {
         return None;
     };
-    
-    let content = fs::read_to_string(&makefile_path).ok()?;
-    
-    let scripts: Vec<ProjectScript> = content
-        .lines()
-        .filter(|line| !line.starts_with('\t') && !line.starts_with(' ') && !line.starts_with('#'))
-        .filter_map(|line| {
-            // Match lines like "target:" or "target: deps"
-            if let Some(colon_pos) = line.find(':') {
-                let target = line[..colon_pos].trim();
-                // Skip special targets and variables
-                if !target.is_empty() 
-                    && !target.starts_with('.') 
-                    && !target.contains('=')
-                    && !target.contains('$')
-                {
-                    return Some(ProjectScript {
-                        name: target.to_string(),
-                        command: format!("make {}", target),
-                    });
-                }
+
+    // CRLF files from Windows would leave `\r` on targets and recipe lines
+    let content = read_manifest(&makefile_path)?.replace("\r\n", "\n");
+
+    let mut scripts = Vec::new();
+    // A `# comment` line directly above a target describes it
+    let mut preceding_comment: Option<String> = None;
+
+    for line in content.lines() {
+        if let Some(comment) = line.strip_prefix('#') {
+            preceding_comment =
+                Some(comment.trim_start_matches('#').trim().to_string()).filter(|c| !c.is_empty());
+            continue;
+        }
+        let comment = preceding_comment.take();
+        if line.starts_with('\t') || line.starts_with(' ') {
+            continue;
+        }
+
+        // Match lines like "target:" or "target: deps ## description"
+        if let Some(colon_pos) = line.find(':') {
+            let target = line[..colon_pos].trim();
+            // Skip special targets and variables
+            if !target.is_empty()
+                && !target.starts_with('.')
+                && !target.contains('=')
+                && !target.contains('$')
+            {
+                let inline = line[colon_pos + 1..]
+                    .split_once("##")
+                    .map(|(_, description)| description.trim().to_string())
+                    .filter(|d| !d.is_empty());
+                scripts.push(ProjectScript {
+                    name: target.to_string(),
+                    command: format!("make {}", target),
+                    description: inline.or(comment),
+                    category: ScriptCategory::Script,
+                });
             }
-            None
-        })
-        .collect();
-    
+        }
+    }
+
     if scripts.is_empty() {
         return None;
     }
-    
+
     Some(ScriptList {
         scripts,
         source_file: "Makefile".to_string(),
     })
 }
 
-/// Parse binary targets from Cargo.toml
+/// Parse common cargo commands plus binary targets from Cargo.toml
+///
+/// A workspace root builds and tests every member, and lists `cargo run -p`
+/// for each member with a binary (`run:package:<member>`); a multi-bin crate
+/// lists `cargo run --bin` per binary (`run:bin:<name>`) instead of a bare
+/// `cargo run`.
 pub fn parse_cargo_targets(project_dir: &Path) -> Option<ScriptList> {
     let cargo_toml_path = project_dir.join("Cargo.toml");
-    
+
     if !cargo_toml_path.exists() {
         return None;
     }
-    
-    // Common cargo commands
-    let scripts = vec![
-        ProjectScript { name: "build".to_string(), command: "cargo build".to_string() },
-        ProjectScript { name: "test".to_string(), command: "cargo test".to_string() },
-        ProjectScript { name: "run".to_string(), command: "cargo run".to_string() },
-        ProjectScript { name: "check".to_string(), command: "cargo check".to_string() },
-        ProjectScript { name: "clippy".to_string(), command: "cargo clippy".to_string() },
-        ProjectScript { name: "fmt".to_string(), command: "cargo fmt".to_string() },
-        ProjectScript { name: "doc".to_string(), command: "cargo doc".to_string() },
-        ProjectScript { name: "bench".to_string(), command: "cargo bench".to_string() },
-    ];
-    
+
+    let toml_value = read_manifest(&cargo_toml_path)
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .unwrap_or(toml::Value::Table(Default::default()));
+    let workspace = toml_value.get("workspace");
+    let bins = cargo_bin_targets(project_dir);
+
+    // Common cargo commands, widened to the whole workspace at a workspace root
+    let mut scripts: Vec<ProjectScript> = [
+        "build", "test", "run", "check", "clippy", "fmt", "doc", "bench",
+    ]
+    .iter()
+    .filter(|subcommand| **subcommand != "run" || bins.len() <= 1)
+    .map(|subcommand| {
+        let command = match (workspace, *subcommand) {
+            (Some(_), "build" | "test" | "check" | "clippy" | "doc" | "bench") => {
+                format!("cargo {} --workspace", subcommand)
+            }
+            (Some(_), "fmt") => "cargo fmt --all".to_string(),
+            _ => format!("cargo {}", subcommand),
+        };
+        ProjectScript {
+            name: subcommand.to_string(),
+            command,
+            description: None,
+            category: ScriptCategory::Script,
+        }
+    })
+    .collect();
+
+    if bins.len() > 1 {
+        scripts.extend(bins.iter().map(|bin| ProjectScript {
+            name: format!("run:bin:{}", bin),
+            command: format!("cargo run --bin {}", bin),
+            description: None,
+            category: ScriptCategory::Binary,
+        }));
+    }
+
+    // Examples and integration tests, run one at a time through `rust::build_command`
+    scripts.extend(
+        cargo_targets(project_dir, &toml_value, CargoTargetKind::Example)
+            .into_iter()
+            .map(|example| ProjectScript {
+                name: format!("run:example:{}", example),
+                command: format!("cargo run --example {}", example),
+                description: None,
+                category: ScriptCategory::Script,
+            }),
+    );
+    scripts.extend(
+        cargo_targets(project_dir, &toml_value, CargoTargetKind::Test)
+            .into_iter()
+            .map(|test| ProjectScript {
+                name: format!("test:{}", test),
+                command: format!("cargo test --test {}", test),
+                description: None,
+                category: ScriptCategory::Script,
+            }),
+    );
+
+    if let Some(workspace) = workspace {
+        scripts.extend(
+            cargo_workspace_members(project_dir, workspace)
+                .into_iter()
+                .map(|member| ProjectScript {
+                    command: format!("cargo run -p {}", member),
+                    name: format!("run:package:{}", member),
+                    description: Some("workspace member".to_string()),
+                    category: ScriptCategory::Binary,
+                }),
+        );
+    }
+
     Some(ScriptList {
         scripts,
         source_file: "Cargo.toml".to_string(),
     })
 }
 
-/// Parse scripts from pyproject.toml (Poetry/UV)
+/// Package names of the workspace members that build a binary
+fn cargo_workspace_members(project_dir: &Path, workspace: &toml::Value) -> Vec<String> {
+    let paths = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str())
+                    .map(|i| i.trim_end_matches('/').to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let excluded = paths("exclude");
+
+    // Members are paths, optionally ending in a `*` component (e.g. "crates/*")
+    let mut member_dirs = Vec::new();
+    for member in paths("members") {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                let Ok(entries) = fs::read_dir(project_dir.join(parent)) else {
+                    continue;
+                };
+                let mut children: Vec<String> = entries
+                    .flatten()
+                    .filter(|e| e.path().join("Cargo.toml").is_file())
+                    .filter_map(|e| e.file_name().to_str().map(|n| format!("{}/{}", parent, n)))
+                    .collect();
+                children.sort();
+                member_dirs.extend(children);
+            }
+            None => member_dirs.push(member),
+        }
+    }
+    member_dirs.retain(|dir| !excluded.contains(dir));
+
+    let mut members: Vec<String> = member_dirs
+        .iter()
+        .map(|dir| project_dir.join(dir))
+        .filter(|dir| !cargo_bin_targets(dir).is_empty())
+        .filter_map(|dir| {
+            let content = read_manifest(dir.join("Cargo.toml"))?;
+            let value = toml::from_str::<toml::Value>(&content).ok()?;
+            value
+                .get("package")?
+                .get("name")?
+                .as_str()
+                .map(|n| n.to_string())
+        })
+        .collect();
+    members.sort();
+    members.dedup();
+    members
+}
+
+/// Names of the binary targets a Cargo package builds
+pub fn cargo_bin_targets(project_dir: &Path) -> Vec<String> {
+    let Some(content) = read_manifest(project_dir.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Ok(toml_value) = toml::from_str::<toml::Value>(&content) else {
+        return Vec::new();
+    };
+
+    let mut bins = Vec::new();
+
+    // The default binary is named after the package
+    if project_dir.join("src").join("main.rs").exists() {
+        if let Some(name) = toml_value
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            bins.push(name.to_string());
+        }
+    }
+
+    // Explicit [[bin]] targets
+    if let Some(targets) = toml_value.get("bin").and_then(|b| b.as_array()) {
+        bins.extend(
+            targets
+                .iter()
+                .filter_map(|t| t.get("name")?.as_str().map(|n| n.to_string())),
+        );
+    }
+
+    // Auto-discovered src/bin/*.rs targets
+    if let Ok(entries) = fs::read_dir(project_dir.join("src").join("bin")) {
+        bins.extend(entries.flatten().filter_map(|e| {
+            let path = e.path();
+            if path.extension()? != "rs" {
+                return None;
+            }
+            path.file_stem()?.to_str().map(|s| s.to_string())
+        }));
+    }
+
+    bins.sort();
+    bins.dedup();
+    bins
+}
+
+/// Cargo targets besides binaries that `list` enumerates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CargoTargetKind {
+    Example,
+    Test,
+}
+
+impl CargoTargetKind {
+    /// Manifest table, conventional directory and the key that turns discovery off
+    fn layout(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            CargoTargetKind::Example => ("example", "examples", "autoexamples"),
+            CargoTargetKind::Test => ("test", "tests", "autotests"),
+        }
+    }
+}
+
+/// Names of a package's examples or integration tests: explicit `[[example]]`/`[[test]]`
+/// entries plus, unless `autoexamples`/`autotests` is off, `<dir>/*.rs` and `<dir>/*/main.rs`
+fn cargo_targets(
+    project_dir: &Path,
+    toml_value: &toml::Value,
+    kind: CargoTargetKind,
+) -> Vec<String> {
+    let (table, dir, auto_key) = kind.layout();
+    let mut names: Vec<String> = toml_value
+        .get(table)
+        .and_then(|t| t.as_array())
+        .map(|targets| {
+            targets
+                .iter()
+                .filter_map(|t| t.get("name")?.as_str().map(|n| n.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let auto = toml_value
+        .get("package")
+        .and_then(|p| p.get(auto_key))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if auto {
+        if let Ok(entries) = fs::read_dir(project_dir.join(dir)) {
+            names.extend(entries.flatten().filter_map(|e| {
+                let path = e.path();
+                let name = if path.is_dir() {
+                    path.join("main.rs").is_file().then(|| path.file_name())??
+                } else if path.extension()? == "rs" {
+                    path.file_stem()?
+                } else {
+                    return None;
+                };
+                name.to_str().map(|s| s.to_string())
+            }));
+        }
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Parse common xcodebuild actions for an Xcode project or workspace
+///
+/// Commands come from the runner's own argv, so the listed scheme is the one that runs.
+pub fn parse_xcode_targets(project_dir: &Path) -> Option<ScriptList> {
+    let runner = detect_xcode(project_dir).into_iter().next()?;
+    let scripts = ["build", "test", "clean", "archive"]
+        .iter()
+        .map(|action| ProjectScript {
+            name: action.to_string(),
+            command: runner.build_command(action, &[]).join(" "),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    Some(ScriptList {
+        scripts,
+        source_file: runner.detected_file().to_string(),
+    })
+}
+
+/// Common Go commands, spanning every module of a go.work workspace
+pub fn parse_go_targets(project_dir: &Path) -> Option<ScriptList> {
+    let workspace = project_dir.join("go.work").exists();
+    let source_file = if workspace {
+        "go.work"
+    } else if project_dir.join("go.mod").exists() {
+        "go.mod"
+    } else {
+        return None;
+    };
+
+    let scripts = ["build", "test", "run", "vet", "tidy"]
+        .iter()
+        .map(|task| ProjectScript {
+            name: task.to_string(),
+            command: crate::detectors::DetectedRunner::new("go", source_file, Ecosystem::Go, 12)
+                .build_command(task, &[])
+                .join(" "),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    Some(ScriptList {
+        scripts,
+        source_file: source_file.to_string(),
+    })
+}
+
+/// A runner's usual tasks, for build tools without a file listing them
+fn common_tasks(runner: &DetectedRunner, tasks: &[&str]) -> ScriptList {
+    let scripts = tasks
+        .iter()
+        .map(|task| ProjectScript {
+            name: task.to_string(),
+            command: runner.build_command(task, &[]).join(" "),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    ScriptList {
+        scripts,
+        source_file: runner.detected_file().to_string(),
+    }
+}
+
+/// Common Terraform/OpenTofu workflow commands
+pub fn parse_terraform_targets(project_dir: &Path, binary: &str) -> Option<ScriptList> {
+    let source_file = if project_dir.join(".terraform.lock.hcl").exists() {
+        ".terraform.lock.hcl".to_string()
+    } else {
+        let mut tf_files: Vec<String> = fs::read_dir(project_dir)
+            .ok()?
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+            .filter(|name| name.ends_with(".tf"))
+            .collect();
+        tf_files.sort();
+        tf_files.into_iter().next()?
+    };
+
+    let scripts = ["init", "plan", "apply", "validate", "fmt"]
+        .iter()
+        .map(|action| ProjectScript {
+            name: action.to_string(),
+            command: format!("{} {}", binary, action),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    Some(ScriptList {
+        scripts,
+        source_file,
+    })
+}
+
+/// List well-known Ansible playbooks in the project directory
+pub fn parse_ansible_playbooks(project_dir: &Path) -> Option<ScriptList> {
+    let scripts: Vec<ProjectScript> = ["playbook", "site"]
+        .iter()
+        .filter(|name| project_dir.join(format!("{}.yml", name)).exists())
+        .map(|name| ProjectScript {
+            name: name.to_string(),
+            command: format!("ansible-playbook {}.yml", name),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    if scripts.is_empty() {
+        return None;
+    }
+
+    let source_file = if project_dir.join("ansible.cfg").exists() {
+        "ansible.cfg"
+    } else {
+        "playbook.yml"
+    };
+
+    Some(ScriptList {
+        scripts,
+        source_file: source_file.to_string(),
+    })
+}
+
+/// Common Gradle tasks, with the Android task set for Android projects
+pub fn parse_gradle_tasks(project_dir: &Path) -> Option<ScriptList> {
+    let source_file = if project_dir.join("build.gradle").exists() {
+        "build.gradle"
+    } else if project_dir.join("build.gradle.kts").exists() {
+        "build.gradle.kts"
+    } else {
+        return None;
+    };
+
+    let tasks: &[&str] = match gradle_flavor(project_dir) {
+        GradleFlavor::Android => &[
+            "assembleDebug",
+            "assembleRelease",
+            "installDebug",
+            "connectedAndroidTest",
+        ],
+        GradleFlavor::Jvm => &["build", "test", "clean", "run"],
+    };
+    let program = if project_dir.join("gradlew").exists() {
+        "./gradlew"
+    } else {
+        "gradle"
+    };
+
+    let scripts = tasks
+        .iter()
+        .map(|task| ProjectScript {
+            name: task.to_string(),
+            command: format!("{} {}", program, task),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    Some(ScriptList {
+        scripts,
+        source_file: source_file.to_string(),
+    })
+}
+
+/// How long `list`/`doctor` wait for `bazel query` (a cold server is slow to start)
+pub const BAZEL_QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a `bazel query` result is reused before querying again
+const BAZEL_QUERY_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Static Bazel entries covering every target (`//...`)
+pub fn parse_bazel_targets(project_dir: &Path) -> Option<ScriptList> {
+    let source_file = BAZEL_WORKSPACE_FILES
+        .iter()
+        .find(|file| project_dir.join(file).is_file())?;
+
+    let scripts = ["build", "test"]
+        .iter()
+        .map(|verb| ProjectScript {
+            name: verb.to_string(),
+            command: format!("bazel {} //...", verb),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    Some(ScriptList {
+        scripts,
+        source_file: source_file.to_string(),
+    })
+}
+
+/// Turn `bazel query` output (one label per line) into `bazel build <label>` entries
+///
+/// Keeps the first `max_targets` labels; progress lines and blanks are skipped.
+pub fn parse_bazel_query_output(output: &str, max_targets: usize) -> Vec<ProjectScript> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("//") || line.starts_with('@'))
+        .take(max_targets)
+        .map(|label| ProjectScript {
+            name: label.to_string(),
+            command: format!("bazel build {}", label),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect()
+}
+
+/// Bazel entries with each real target from `bazel query '//...'`, for `list`/`doctor`
+///
+/// Query output is cached per workspace for an hour. Falls back to the static
+/// entries when bazel isn't installed, fails, or takes longer than `timeout`.
+pub fn query_bazel_targets(
+    project_dir: &Path,
+    max_targets: usize,
+    timeout: Duration,
+) -> Option<ScriptList> {
+    let mut list = parse_bazel_targets(project_dir)?;
+    let cache_path = Config::bazel_query_cache_path(project_dir);
+    let cached = cache_path.as_deref().and_then(|path| {
+        let age = fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+        (age < BAZEL_QUERY_CACHE_TTL).then(|| fs::read_to_string(path).ok())?
+    });
+    let output = cached.or_else(|| {
+        let mut command = Command::new("bazel");
+        command.args(["query", "//..."]).current_dir(project_dir);
+        let output = output_with_timeout(command, timeout)?;
+        if let Some(path) = &cache_path {
+            let _ = path.parent().map(fs::create_dir_all);
+            let _ = fs::write(path, &output);
+        }
+        Some(output)
+    });
+    if let Some(output) = output {
+        list.scripts
+            .extend(parse_bazel_query_output(&output, max_targets));
+    }
+    Some(list)
+}
+
+/// Stdout of a successful `command`, or None if it fails or outlives `timeout`
+fn output_with_timeout(mut command: Command, timeout: Duration) -> Option<String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Drain stdout concurrently so a large result can't fill the pipe and stall the child
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).ok().map(|_| output)
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return reader.join().ok().flatten().filter(|_| status.success()),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+/// Parse mise tasks from `[tasks]` in mise.toml, or from the file-based tasks directory
+pub fn parse_mise_tasks(project_dir: &Path) -> Option<ScriptList> {
+    for file in MISE_CONFIG_FILES {
+        let Some(content) = read_manifest(project_dir.join(file)) else {
+            continue;
+        };
+        let Ok(toml_value) = toml::from_str::<toml::Value>(&content) else {
+            continue;
+        };
+        let Some(tasks) = toml_value.get("tasks").and_then(|t| t.as_table()) else {
+            continue;
+        };
+
+        // Both `[tasks.build]` tables and `build = "..."` shorthands are allowed
+        let scripts: Vec<ProjectScript> = tasks
+            .iter()
+            .map(|(name, task)| ProjectScript {
+                name: name.clone(),
+                command: format!("mise run {}", name),
+                description: task
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.to_string()),
+                category: ScriptCategory::Script,
+            })
+            .collect();
+
+        if scripts.is_empty() {
+            return None;
+        }
+
+        return Some(ScriptList {
+            scripts,
+            source_file: file.to_string(),
+        });
+    }
+
+    parse_mise_task_files(project_dir)
+}
+
+/// List executable task scripts in `.config/mise/tasks`
+fn parse_mise_task_files(project_dir: &Path) -> Option<ScriptList> {
+    let mut scripts: Vec<ProjectScript> = fs::read_dir(project_dir.join(MISE_TASKS_DIR))
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| !name.starts_with('.'))
+        .map(|name| {
+            // Task files carry metadata in comments, e.g. `#MISE description="..."`
+            let description = fs::read_to_string(project_dir.join(MISE_TASKS_DIR).join(&name))
+                .ok()
+                .and_then(|content| {
+                    content.lines().find_map(|line| {
+                        let rest = line.trim_start_matches('#').trim_start();
+                        let rest = rest
+                            .strip_prefix("MISE")
+                            .or_else(|| rest.strip_prefix("mise"))?;
+                        let value = rest.trim_start().strip_prefix("description=")?;
+                        Some(value.trim().trim_matches('"').to_string())
+                    })
+                });
+            ProjectScript {
+                command: format!("mise run {}", name),
+                name,
+                description,
+                category: ScriptCategory::Script,
+            }
+        })
+        .collect();
+
+    if scripts.is_empty() {
+        return None;
+    }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Some(ScriptList {
+        scripts,
+        source_file: MISE_TASKS_DIR.to_string(),
+    })
+}
+
+/// Parse scripts from pyproject.toml (Poetry, PDM, Hatch and PEP 621 `[project.scripts]`)
+///
+/// uv has no task table of its own yet; `uv run` projects list their
+/// `[project.scripts]` entry points.
 pub fn parse_pyproject_scripts(project_dir: &Path) -> Option<ScriptList> {
     let pyproject_path = project_dir.join("pyproject.toml");
-    
+
     if !pyproject_path.exists() {
         return None;
     }
-    
-    let content = fs::read_to_string(&pyproject_path).ok()?;
+
+    let content = read_manifest(&pyproject_path)?;
     let toml_value: toml::Value = toml::from_str(&content).ok()?;
-    
+
     let mut scripts = Vec::new();
-    
+
     // Check for poetry scripts
-    if let Some(poetry) = toml_value.get("tool").and_then(|t| t.get("poetry")).and_then(|p| p.get("scripts")) {
+    if let Some(poetry) = toml_value
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("scripts"))
+    {
         if let Some(scripts_table) = poetry.as_table() {
             for (name, cmd) in scripts_table {
                 scripts.push(ProjectScript {
                     name: name.clone(),
                     command: cmd.as_str().unwrap_or("").to_string(),
+                    description: None,
+                    category: ScriptCategory::Script,
                 });
             }
         }
     }
-    
+
     // Check for project.scripts (PEP 621)
     if let Some(project) = toml_value.get("project").and_then(|p| p.get("scripts")) {
         if let Some(scripts_table) = project.as_table() {
@@ -147,50 +862,762 @@ pub fn parse_pyproject_scripts(project_dir: &Path) -> Option<ScriptList> {
                 scripts.push(ProjectScript {
                     name: name.clone(),
                     command: cmd.as_str().unwrap_or("").to_string(),
+                    description: None,
+                    category: ScriptCategory::Script,
+                });
+            }
+        }
+    }
+
+    // Check for PDM task scripts; `_` holds options shared by every script
+    if let Some(pdm_scripts) = toml_value
+        .get("tool")
+        .and_then(|t| t.get("pdm"))
+        .and_then(|p| p.get("scripts"))
+        .and_then(|s| s.as_table())
+    {
+        for (name, script) in pdm_scripts.iter().filter(|(name, _)| name.as_str() != "_") {
+            scripts.push(ProjectScript {
+                name: name.clone(),
+                command: format!("pdm run {}", name),
+                description: script
+                    .get("help")
+                    .and_then(|h| h.as_str())
+                    .map(|h| h.to_string()),
+                category: ScriptCategory::Script,
+            });
+        }
+    }
+
+    // Check for hatch environment scripts; only the default env runs without a prefix
+    if let Some(envs) = toml_value
+        .get("tool")
+        .and_then(|t| t.get("hatch"))
+        .and_then(|h| h.get("envs"))
+        .and_then(|e| e.as_table())
+    {
+        for (env, env_config) in envs {
+            let Some(env_scripts) = env_config.get("scripts").and_then(|s| s.as_table()) else {
+                continue;
+            };
+            for name in env_scripts.keys() {
+                let qualified = if env == "default" {
+                    name.clone()
+                } else {
+                    format!("{}:{}", env, name)
+                };
+                scripts.push(ProjectScript {
+                    command: format!("hatch run {}", qualified),
+                    name: qualified,
+                    description: None,
+                    category: ScriptCategory::Script,
                 });
             }
         }
     }
-    
+
     if scripts.is_empty() {
         return None;
     }
-    
+
     Some(ScriptList {
         scripts,
         source_file: "pyproject.toml".to_string(),
     })
 }
 
-/// Get scripts for a detected runner
-pub fn get_scripts_for_runner(runner: &DetectedRunner, project_dir: &Path) -> Option<ScriptList> {
-    match runner.ecosystem {
-        Ecosystem::NodeJs => parse_package_json_scripts(project_dir),
-        Ecosystem::Rust => parse_cargo_targets(project_dir),
-        Ecosystem::Python => parse_pyproject_scripts(project_dir),
-        Ecosystem::Generic => parse_makefile_targets(project_dir),
-        _ => None, // Other ecosystems can be added later
-    }
-}
+/// Parse the `[scripts]` table of a Pipfile, which `pipenv run <name>` runs
+pub fn parse_pipfile_scripts(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("Pipfile"))?;
+    let toml_value: toml::Value = toml::from_str(&content).ok()?;
+    let scripts_table = toml_value.get("scripts")?.as_table()?;
 
-/// Get all available scripts from a project directory
-pub fn discover_all_scripts(project_dir: &Path) -> Vec<ScriptList> {
-    let mut results = Vec::new();
-    
-    if let Some(scripts) = parse_package_json_scripts(project_dir) {
-        results.push(scripts);
-    }
-    if let Some(scripts) = parse_cargo_targets(project_dir) {
-        results.push(scripts);
-    }
-    if let Some(scripts) = parse_pyproject_scripts(project_dir) {
-        results.push(scripts);
+    let scripts: Vec<ProjectScript> = scripts_table
+        .iter()
+        .filter_map(|(name, cmd)| {
+            Some(ProjectScript {
+                name: name.clone(),
+                command: cmd.as_str()?.to_string(),
+                description: None,
+                category: ScriptCategory::Script,
+            })
+        })
+        .collect();
+    if scripts.is_empty() {
+        return None;
     }
-    if let Some(scripts) = parse_makefile_targets(project_dir) {
-        results.push(scripts);
+
+    Some(ScriptList {
+        scripts,
+        source_file: "Pipfile".to_string(),
+    })
+}
+
+/// Common commands for a project that only has a requirements.txt
+pub fn parse_requirements_scripts(project_dir: &Path) -> Option<ScriptList> {
+    if !project_dir.join("requirements.txt").is_file() {
+        return None;
     }
-    
-    results
+
+    let mut scripts = vec![ProjectScript {
+        name: "install".to_string(),
+        command: "pip install -r requirements.txt".to_string(),
+        description: None,
+        category: ScriptCategory::Script,
+    }];
+    if project_dir.join("main.py").is_file() {
+        scripts.push(ProjectScript {
+            name: "main".to_string(),
+            command: "python main.py".to_string(),
+            description: None,
+            category: ScriptCategory::Script,
+        });
+    }
+    scripts.push(ProjectScript {
+        name: "pytest".to_string(),
+        command: "pytest".to_string(),
+        description: None,
+        category: ScriptCategory::Script,
+    });
+
+    Some(ScriptList {
+        scripts,
+        source_file: "requirements.txt".to_string(),
+    })
+}
+
+/// Read a manifest, dropping the UTF-8 BOM some Windows editors prepend
+///
+/// serde_json and toml both reject a leading BOM, and it would otherwise end up
+/// glued to the first Makefile target.
+pub fn read_manifest(path: impl AsRef<Path>) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    Some(match content.strip_prefix('\u{feff}') {
+        Some(stripped) => stripped.to_string(),
+        None => content,
+    })
+}
+
+/// Strip `//` and `/* */` comments plus trailing commas so JSONC can be parsed as JSON
+pub fn strip_jsonc(content: &str) -> String {
+    // First pass: drop comments, leaving string literals untouched
+    let mut without_comments = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            without_comments.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    without_comments.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                without_comments.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        without_comments.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            _ => without_comments.push(c),
+        }
+    }
+
+    // Second pass: drop commas that are directly followed by a closing bracket
+    let chars: Vec<char> = without_comments.chars().collect();
+    let mut result = String::with_capacity(chars.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            result.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                result.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            result.push(c);
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if !matches!(next, Some('}') | Some(']')) {
+                result.push(c);
+            }
+        } else {
+            result.push(c);
+        }
+        i += 1;
+    }
+
+    result
+}
+
+/// Build a task list for a workspace tool from the names it defines
+fn framework_tasks(
+    source_file: &str,
+    tasks: &[&str],
+    command: impl Fn(&str) -> String,
+) -> ScriptList {
+    ScriptList {
+        scripts: tasks
+            .iter()
+            .map(|task| ProjectScript {
+                name: task.to_string(),
+                command: command(task),
+                description: None,
+                category: ScriptCategory::Script,
+            })
+            .collect(),
+        source_file: source_file.to_string(),
+    }
+}
+
+/// Angular CLI tasks (`angular.json`)
+pub fn parse_angular_tasks(project_dir: &Path) -> Option<ScriptList> {
+    if !project_dir.join("angular.json").exists() {
+        return None;
+    }
+    Some(framework_tasks(
+        "angular.json",
+        &["serve", "build", "test"],
+        |task| format!("ng {}", task),
+    ))
+}
+
+/// Nx tasks run across every project (`nx.json`)
+pub fn parse_nx_tasks(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("nx.json"))?;
+
+    // Prefer the targets the workspace configures, fall back to the usual ones
+    let json: Value = serde_json::from_str(&content).unwrap_or(Value::Null);
+    let mut targets: Vec<&str> = json
+        .get("targetDefaults")
+        .and_then(|t| t.as_object())
+        .map(|t| t.keys().map(|k| k.as_str()).collect())
+        .unwrap_or_default();
+    if targets.is_empty() {
+        targets = vec!["build", "test", "lint"];
+    }
+
+    Some(framework_tasks("nx.json", &targets, |task| {
+        format!("nx run-many --target={}", task)
+    }))
+}
+
+/// Turborepo pipeline tasks (`turbo.json`)
+pub fn parse_turbo_tasks(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("turbo.json"))?;
+
+    // Turbo 2 names the table `tasks`, Turbo 1 used `pipeline`
+    let json: Value = serde_json::from_str(&strip_jsonc(&content)).unwrap_or(Value::Null);
+    let mut tasks: Vec<&str> = json
+        .get("tasks")
+        .or_else(|| json.get("pipeline"))
+        .and_then(|t| t.as_object())
+        .map(|t| {
+            t.keys()
+                .map(|k| k.as_str())
+                .filter(|k| !k.starts_with("//"))
+                .collect()
+        })
+        .unwrap_or_default();
+    if tasks.is_empty() {
+        tasks = vec!["build"];
+    }
+
+    Some(framework_tasks("turbo.json", &tasks, |task| {
+        format!("turbo run {}", task)
+    }))
+}
+
+/// Parse the `scripts:` map of a melos.yaml (Dart monorepos) without a YAML parser
+pub fn parse_melos_scripts(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("melos.yaml"))?;
+
+    let mut scripts: Vec<ProjectScript> = Vec::new();
+    let mut in_scripts = false;
+    // Indentation of the script names, taken from the first entry
+    let mut script_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            in_scripts = trimmed.trim_end() == "scripts:";
+            continue;
+        }
+        if !in_scripts {
+            continue;
+        }
+
+        // Script names may contain colons (`test:unit`), so split on ": " or a trailing ':'
+        let trimmed = trimmed.trim_end();
+        let (key, value) = match trimmed.split_once(": ") {
+            Some((key, value)) => (key, value.trim()),
+            None => match trimmed.strip_suffix(':') {
+                Some(key) => (key, ""),
+                None => continue,
+            },
+        };
+
+        let script_indent = *script_indent.get_or_insert(indent);
+        if indent == script_indent {
+            let name = key.trim_matches(['\'', '"']);
+            scripts.push(ProjectScript {
+                name: name.to_string(),
+                command: format!("melos run {}", name),
+                description: None,
+                category: ScriptCategory::Script,
+            });
+        } else if indent > script_indent && key == "description" && !value.starts_with(['|', '>']) {
+            if let Some(script) = scripts.last_mut() {
+                script.description =
+                    Some(value.trim_matches(['\'', '"']).to_string()).filter(|d| !d.is_empty());
+            }
+        }
+    }
+
+    if scripts.is_empty() {
+        return None;
+    }
+
+    Some(ScriptList {
+        scripts,
+        source_file: "melos.yaml".to_string(),
+    })
+}
+
+/// Parse Earthfile target declarations (`name:` at the start of a line)
+pub fn parse_earthfile_targets(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("Earthfile"))?;
+
+    let scripts: Vec<ProjectScript> = content
+        .lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| line.trim_end().strip_suffix(':'))
+        .filter(|name| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(|target| ProjectScript {
+            name: target.to_string(),
+            command: format!("earthly +{}", target),
+            description: None,
+            category: ScriptCategory::Script,
+        })
+        .collect();
+
+    if scripts.is_empty() {
+        return None;
+    }
+
+    Some(ScriptList {
+        scripts,
+        source_file: "Earthfile".to_string(),
+    })
+}
+
+/// List `docker build` plus one `--target` build per named stage (`FROM x AS stage`)
+pub fn parse_dockerfile_targets(project_dir: &Path) -> Option<ScriptList> {
+    let content = read_manifest(project_dir.join("Dockerfile"))?;
+
+    let mut scripts = vec![ProjectScript {
+        name: "build".to_string(),
+        command: "docker build .".to_string(),
+        description: None,
+        category: ScriptCategory::Script,
+    }];
+    for line in content.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if let [from, .., as_kw, stage] = words.as_slice() {
+            // `devrunner build` builds the whole image, so a `build` stage (or a
+            // repeated stage name) would only be a second, misleading entry
+            let listed = scripts.iter().any(|s| s.name == *stage);
+            if from.eq_ignore_ascii_case("FROM") && as_kw.eq_ignore_ascii_case("AS") && !listed {
+                scripts.push(ProjectScript {
+                    name: stage.to_string(),
+                    command: format!("docker build --target {} .", stage),
+                    description: None,
+                    category: ScriptCategory::Script,
+                });
+            }
+        }
+    }
+
+    Some(ScriptList {
+        scripts,
+        source_file: "Dockerfile".to_string(),
+    })
+}
+
+/// Parse tasks from `.vscode/tasks.json` (JSONC)
+pub fn parse_vscode_tasks(project_dir: &Path) -> Option<ScriptList> {
+    let tasks_path = project_dir.join(".vscode").join("tasks.json");
+
+    if !tasks_path.exists() {
+        return None;
+    }
+
+    let content = read_manifest(&tasks_path)?;
+    let json: Value = serde_json::from_str(&strip_jsonc(&content)).ok()?;
+
+    let scripts: Vec<ProjectScript> = json
+        .get("tasks")?
+        .as_array()?
+        .iter()
+        .filter_map(|task| {
+            let label = task.get("label")?.as_str()?;
+            let command = match task.get("command").and_then(|c| c.as_str()) {
+                Some(command) => {
+                    let mut parts = vec![command.to_string()];
+                    if let Some(args) = task.get("args").and_then(|a| a.as_array()) {
+                        // Args are either plain strings or { "value": ..., "quoting": ... }
+                        parts.extend(args.iter().filter_map(|arg| {
+                            arg.as_str()
+                                .or_else(|| arg.get("value").and_then(|v| v.as_str()))
+                                .map(|v| v.to_string())
+                        }));
+                    }
+                    parts.join(" ")
+                }
+                // npm tasks reference a package.json script instead of a command
+                None => format!("npm run {}", task.get("script")?.as_str()?),
+            };
+            Some(ProjectScript {
+                name: label.to_string(),
+                command,
+                description: None,
+                category: ScriptCategory::Script,
+            })
+        })
+        .collect();
+
+    if scripts.is_empty() {
+        return None;
+    }
+
+    Some(ScriptList {
+        scripts,
+        source_file: ".vscode/tasks.json".to_string(),
+    })
+}
+
+/// Scripts matching a glob such as `test:*`, in listing order (binaries excluded)
+pub fn matching_scripts(pattern: &str, scripts: &[ProjectScript]) -> Vec<String> {
+    scripts
+        .iter()
+        .filter(|s| s.category == ScriptCategory::Script && glob_match(pattern, &s.name))
+        .map(|s| s.name.clone())
+        .collect()
+}
+
+/// Parse a `list --filter-regex` value
+pub fn parse_filter_regex(value: &str) -> Result<regex::Regex, String> {
+    regex::Regex::new(value).map_err(|e| format!("invalid regex: {}", e))
+}
+
+/// `list --grep`/`--filter-regex`/`--head`, applied one section at a time
+///
+/// `--grep` keeps scripts whose name or command contains it (case-insensitive),
+/// `--filter-regex` those whose name or command matches it. `--head` caps the
+/// whole listing, so each section only gets what the earlier ones left.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptFilter {
+    grep: Option<String>,
+    regex: Option<regex::Regex>,
+    remaining: Option<usize>,
+}
+
+impl ScriptFilter {
+    pub fn new(grep: Option<&str>, regex: Option<regex::Regex>, head: Option<usize>) -> Self {
+        ScriptFilter {
+            grep: grep.map(str::to_string),
+            regex,
+            remaining: head,
+        }
+    }
+
+    /// Whether any of the options was given
+    pub fn is_active(&self) -> bool {
+        self.grep.is_some() || self.regex.is_some() || self.remaining.is_some()
+    }
+
+    /// The `--grep` or `--filter-regex` pattern, if any
+    pub fn pattern(&self) -> Option<&str> {
+        self.grep
+            .as_deref()
+            .or(self.regex.as_ref().map(regex::Regex::as_str))
+    }
+
+    fn matches(&self, script: &ProjectScript) -> bool {
+        let grep_ok = self.grep.as_ref().is_none_or(|grep| {
+            let needle = grep.to_lowercase();
+            script.name.to_lowercase().contains(&needle)
+                || script.command.to_lowercase().contains(&needle)
+        });
+        let regex_ok = self
+            .regex
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(&script.name) || regex.is_match(&script.command));
+        grep_ok && regex_ok
+    }
+
+    /// The matching scripts of one section, counted against `--head`
+    pub fn apply(&mut self, scripts: Vec<ProjectScript>) -> Vec<ProjectScript> {
+        let kept: Vec<ProjectScript> = scripts
+            .into_iter()
+            .filter(|s| self.matches(s))
+            .take(self.remaining.unwrap_or(usize::MAX))
+            .collect();
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= kept.len();
+        }
+        kept
+    }
+}
+
+/// Pick the `<command>:<env>` variant of a script (e.g. `build:prod`) when it exists
+/// Falls back to the command itself, so `--env` is harmless for scripts without variants
+pub fn resolve_env_script(command: &str, env: &str, available_scripts: &[String]) -> String {
+    let variant = format!("{}:{}", command, env);
+    if available_scripts.contains(&variant) {
+        variant
+    } else {
+        command.to_string()
+    }
+}
+
+/// Scripts to show in `list`/`doctor`
+///
+/// Same as [`get_scripts_for_runner`], except that Bazel workspaces run
+/// `bazel query` for up to `bazel_query` real targets when it's set.
+pub fn list_scripts_for_runner(
+    runner: &DetectedRunner,
+    project_dir: &Path,
+    bazel_query: Option<usize>,
+) -> Option<ScriptList> {
+    match bazel_query {
+        Some(max_targets) if runner.name == "bazel" && runner.custom_scripts.is_empty() => {
+            query_bazel_targets(project_dir, max_targets, BAZEL_QUERY_TIMEOUT)
+        }
+        _ => get_scripts_for_runner(runner, project_dir),
+    }
+}
+
+/// Get scripts for a detected runner
+pub fn get_scripts_for_runner(runner: &DetectedRunner, project_dir: &Path) -> Option<ScriptList> {
+    // Config-defined runners list exactly the scripts they declare
+    if !runner.custom_scripts.is_empty() {
+        let mut scripts: Vec<ProjectScript> = runner
+            .custom_scripts
+            .iter()
+            .map(|(name, command)| ProjectScript {
+                name: name.clone(),
+                command: command.clone(),
+                description: None,
+                category: ScriptCategory::Script,
+            })
+            .collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        return Some(ScriptList {
+            scripts,
+            source_file: runner.detected_file().to_string(),
+        });
+    }
+
+    match runner.ecosystem {
+        Ecosystem::NodeJs => parse_package_json_scripts(project_dir),
+        Ecosystem::Rust => parse_cargo_targets(project_dir),
+        // pyproject.toml wins; Pipfile and requirements.txt cover projects without one
+        Ecosystem::Python => {
+            parse_pyproject_scripts(project_dir).or_else(|| match runner.name.as_str() {
+                "pipenv" => parse_pipfile_scripts(project_dir),
+                "pip" => parse_requirements_scripts(project_dir),
+                _ => None,
+            })
+        }
+        Ecosystem::Xcode => parse_xcode_targets(project_dir),
+        Ecosystem::Go if runner.name == "go" => parse_go_targets(project_dir),
+        Ecosystem::Java if runner.name == "gradle" => parse_gradle_tasks(project_dir),
+        Ecosystem::Java if matches!(runner.name.as_str(), "sbt" | "mill") => {
+            Some(common_tasks(runner, &["compile", "test", "run"]))
+        }
+        Ecosystem::Dart if runner.name == "melos" => parse_melos_scripts(project_dir),
+        Ecosystem::Ruby => parse_ruby_bin_scripts(project_dir, &runner.name),
+        Ecosystem::Mise => parse_mise_tasks(project_dir),
+        Ecosystem::Bazel => parse_bazel_targets(project_dir),
+        Ecosystem::V => Some(common_tasks(runner, &["build", "run", "test"])),
+        Ecosystem::Odin => Some(common_tasks(runner, &["build", "run", "test", "check"])),
+        Ecosystem::Generic => parse_makefile_targets(project_dir),
+        Ecosystem::Container => match runner.name.as_str() {
+            "earthly" => parse_earthfile_targets(project_dir),
+            _ => parse_dockerfile_targets(project_dir),
+        },
+        Ecosystem::Infra => match runner.name.as_str() {
+            "ansible-playbook" => parse_ansible_playbooks(project_dir),
+            binary => parse_terraform_targets(project_dir, binary),
+        },
+        _ => None, // Other ecosystems can be added later
+    }
+}
+
+/// Independent parsers used by `discover_all_scripts_with`, in result order
+const DISCOVERY_PARSERS: &[fn(&Path) -> Option<ScriptList>] = &[
+    parse_package_json_scripts,
+    parse_angular_tasks,
+    parse_nx_tasks,
+    parse_turbo_tasks,
+    parse_cargo_targets,
+    parse_pyproject_scripts,
+    parse_pipfile_scripts,
+    parse_xcode_targets,
+    parse_go_targets,
+    parse_melos_scripts,
+    parse_mise_tasks,
+    parse_makefile_targets,
+    parse_earthfile_targets,
+    parse_dockerfile_targets,
+    parse_vscode_tasks,
+];
+
+/// Get all available scripts, running the parsers on at most `max_threads` threads
+///
+/// Results keep the parser order regardless of which thread finishes first.
+pub fn discover_all_scripts_with(project_dir: &Path, max_threads: usize) -> Vec<ScriptList> {
+    run_on_threads(DISCOVERY_PARSERS.len(), max_threads, |index| {
+        DISCOVERY_PARSERS[index](project_dir)
+    })
+}
+
+/// Run `job(0..count)` on at most `max_threads` threads, keeping index order
+///
+/// A single thread runs every job on the caller's thread.
+fn run_on_threads<T: Send>(
+    count: usize,
+    max_threads: usize,
+    job: impl Fn(usize) -> Option<T> + Sync,
+) -> Vec<T> {
+    let workers = max_threads.clamp(1, count.max(1));
+    if workers == 1 {
+        return (0..count).filter_map(job).collect();
+    }
+
+    // Workers pull the next index, so a slow job never idles the others
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let mut slots: Vec<Option<T>> = Vec::new();
+    slots.resize_with(count, || None);
+    let slots = std::sync::Mutex::new(slots);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if index >= count {
+                    break;
+                }
+                let result = job(index);
+                slots.lock().unwrap()[index] = result;
+            });
+        }
+    });
+
+    slots.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+/// Directories `discover_scripts_recursive` never descends into
+const RECURSIVE_SKIP_DIRS: &[&str] = &["node_modules", "target", ".git"];
+
+/// The scripts of one directory found by `discover_scripts_recursive`
+#[derive(Debug)]
+pub struct DirScripts {
+    /// Path relative to the walk's root, `.` for the root itself
+    pub path: String,
+    pub lists: Vec<ScriptList>,
+}
+
+/// Discover scripts in `root` and every directory at most `max_depth` levels below it
+///
+/// Each directory's parsers run on at most `max_threads` threads.
+///
+/// Dependency and build output directories (`node_modules`, `target`,
+/// `.git`) and symlinked directories are skipped. Directories without
+/// scripts are left out; the rest come back parents first, siblings sorted.
+pub fn discover_scripts_recursive(
+    root: &Path,
+    max_depth: usize,
+    max_threads: usize,
+) -> Vec<DirScripts> {
+    fn walk(
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        max_depth: usize,
+        max_threads: usize,
+        found: &mut Vec<DirScripts>,
+    ) {
+        let lists = discover_all_scripts_with(dir, max_threads);
+        if !lists.is_empty() {
+            let relative = dir.strip_prefix(root).unwrap_or(dir);
+            let path = if relative.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                relative.to_string_lossy().replace('\\', "/")
+            };
+            found.push(DirScripts { path, lists });
+        }
+        if depth == max_depth {
+            return;
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter(|entry| {
+                let name = entry.file_name();
+                !RECURSIVE_SKIP_DIRS.iter().any(|skip| name == *skip)
+            })
+            .map(|entry| entry.path())
+            .collect();
+        children.sort();
+        for child in children {
+            walk(root, &child, depth + 1, max_depth, max_threads, found);
+        }
+    }
+
+    let mut found = Vec::new();
+    walk(root, root, 0, max_depth, max_threads, &mut found);
+    found
 }
 
 #[cfg(test)]
@@ -204,34 +1631,679 @@ mod tests {
     fn test_parse_package_json_scripts() {
         let dir = tempdir().unwrap();
         let package_json = dir.path().join("package.json");
-        
+
         let mut file = File::create(&package_json).unwrap();
-        file.write_all(br#"{
+        file.write_all(
+            br#"{
             "name": "test-project",
             "scripts": {
                 "dev": "vite",
                 "build": "vite build",
                 "test": "vitest"
             }
-        }"#).unwrap();
-        
+        }"#,
+        )
+        .unwrap();
+
         let result = parse_package_json_scripts(dir.path()).unwrap();
         assert_eq!(result.scripts.len(), 3);
         assert_eq!(result.source_file, "package.json");
-        
+
         let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
         assert!(names.contains(&"dev"));
         assert!(names.contains(&"build"));
         assert!(names.contains(&"test"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_ruby_bin_scripts() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("bin")).unwrap();
+        for name in ["setup", "rails"] {
+            let path = dir.path().join("bin").join(name);
+            fs::write(&path, "#!/usr/bin/env ruby\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        fs::write(dir.path().join("bin/notes.txt"), "not a script").unwrap();
+
+        let runner = DetectedRunner::new("binstubs", "bin/rails", Ecosystem::Ruby, 14);
+        let result = get_scripts_for_runner(&runner, dir.path()).unwrap();
+        assert_eq!(result.source_file, "bin/");
+        let entries: Vec<(&str, &str)> = result
+            .scripts
+            .iter()
+            .map(|s| (s.name.as_str(), s.command.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("rails", "bin/rails"), ("setup", "bin/setup")]
+        );
+        assert!(result
+            .scripts
+            .iter()
+            .all(|s| s.category == ScriptCategory::Binary));
+
+        let bundler = DetectedRunner::new("bundler", "Gemfile", Ecosystem::Ruby, 13);
+        let result = parse_ruby_bin_scripts(dir.path(), &bundler.name).unwrap();
+        assert_eq!(result.scripts[1].name, "bin/setup");
+        assert_eq!(result.scripts[1].command, "bundle exec bin/setup");
+        assert_eq!(
+            bundler.build_command(&result.scripts[1].name, &[]),
+            vec!["bundle", "exec", "bin/setup"]
+        );
+    }
+
+    #[test]
+    fn test_parse_package_json_with_comments() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"// generated by the project template
+{
+  "$schema": "https://json.schemastore.org/package.json",
+  "scripts": {
+    /* local dev server */
+    "dev": "vite", // hot reload
+    "build": "vite build",
+  },
+}"#,
+        )
+        .unwrap();
+
+        let result = parse_package_json_scripts(dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "dev"]);
+    }
+
+    #[test]
+    fn test_parse_package_json_malformed() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{ "scripts": { "dev": "vite" "#,
+        )
+        .unwrap();
+
+        assert!(parse_package_json_scripts(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_parse_package_json_bin_entries() {
+        let dir = tempdir().unwrap();
+
+        // Object form: one binary per key
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+            "name": "tools",
+            "scripts": { "build": "tsc" },
+            "bin": { "tool-a": "./bin/a.js", "tool-b": "./bin/b.js" }
+        }"#,
+        )
+        .unwrap();
+        let result = parse_package_json_scripts(dir.path()).unwrap();
+        let build = result.scripts.iter().find(|s| s.name == "build").unwrap();
+        assert_eq!(build.category, ScriptCategory::Script);
+        let binaries: Vec<&ProjectScript> = result
+            .scripts
+            .iter()
+            .filter(|s| s.category == ScriptCategory::Binary)
+            .collect();
+        assert_eq!(binaries.len(), 2);
+        assert_eq!(binaries[0].name, "tool-a");
+        assert_eq!(binaries[0].command, "npx tool-a");
+
+        // String form: the binary is named after the (unscoped) package
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{
+            "name": "@acme/cli",
+            "bin": "./dist/cli.js"
+        }"#,
+        )
+        .unwrap();
+        let result = parse_package_json_scripts(dir.path()).unwrap();
+        assert_eq!(result.scripts.len(), 1);
+        assert_eq!(result.scripts[0].name, "cli");
+        assert_eq!(result.scripts[0].command, "npx cli");
+        assert_eq!(result.scripts[0].category, ScriptCategory::Binary);
+    }
+
+    #[test]
+    fn test_parse_angular_tasks() {
+        let dir = tempdir().unwrap();
+        assert!(parse_angular_tasks(dir.path()).is_none());
+
+        File::create(dir.path().join("angular.json")).unwrap();
+        let result = parse_angular_tasks(dir.path()).unwrap();
+        assert_eq!(result.source_file, "angular.json");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["ng serve", "ng build", "ng test"]);
+    }
+
+    #[test]
+    fn test_parse_nx_tasks() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("nx.json"), "{}").unwrap();
+        let result = parse_nx_tasks(dir.path()).unwrap();
+        assert_eq!(result.scripts[0].command, "nx run-many --target=build");
+
+        std::fs::write(
+            dir.path().join("nx.json"),
+            r#"{"targetDefaults": {"e2e": {}}}"#,
+        )
+        .unwrap();
+        let result = parse_nx_tasks(dir.path()).unwrap();
+        assert_eq!(result.scripts.len(), 1);
+        assert_eq!(result.scripts[0].command, "nx run-many --target=e2e");
+    }
+
+    #[test]
+    fn test_parse_turbo_tasks() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("turbo.json"),
+            r#"{
+            // Turbo allows comments
+            "tasks": { "build": {}, "lint": {} }
+        }"#,
+        )
+        .unwrap();
+        let result = parse_turbo_tasks(dir.path()).unwrap();
+        assert_eq!(result.source_file, "turbo.json");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["turbo run build", "turbo run lint"]);
+    }
+
+    #[test]
+    fn test_parse_manifests_with_bom() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            "\u{feff}{\"scripts\": {\"build\": \"tsc\"}}",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "\u{feff}[project.scripts]\ncli = \"app:main\"\n",
+        )
+        .unwrap();
+
+        let package = parse_package_json_scripts(dir.path()).unwrap();
+        assert_eq!(package.scripts[0].name, "build");
+        let pyproject = parse_pyproject_scripts(dir.path()).unwrap();
+        assert_eq!(pyproject.scripts[0].name, "cli");
+    }
+
+    #[test]
+    fn test_parse_makefile_crlf_with_bom() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Makefile"),
+            "\u{feff}build: ## Compile\r\n\tcargo build\r\n\r\n# Run the tests\r\ntest:\r\n\tcargo test\r\n",
+        ).unwrap();
+
+        let result = parse_makefile_targets(dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "test"]);
+        assert_eq!(result.scripts[0].description.as_deref(), Some("Compile"));
+        assert_eq!(
+            result.scripts[1].description.as_deref(),
+            Some("Run the tests")
+        );
+    }
+
+    #[test]
+    fn test_parse_pyproject_pdm_scripts() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            r#"
+[project.scripts]
+cli = "app:main"
+
+[tool.pdm.scripts]
+_.env_file = ".env"
+start = "flask run -p 54321"
+lint = { cmd = "ruff check .", help = "Lint the code" }
+"#,
+        )
+        .unwrap();
+
+        let result = parse_pyproject_scripts(dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["cli", "lint", "start"]);
+
+        let lint = result.scripts.iter().find(|s| s.name == "lint").unwrap();
+        assert_eq!(lint.command, "pdm run lint");
+        assert_eq!(lint.description.as_deref(), Some("Lint the code"));
+    }
+
+    #[test]
+    fn test_parse_pyproject_hatch_scripts() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            r#"
+[tool.hatch.envs.default.scripts]
+test = "pytest {args}"
+
+[tool.hatch.envs.docs.scripts]
+build = ["mkdocs build --clean", "echo done"]
+"#,
+        )
+        .unwrap();
+
+        let result = parse_pyproject_scripts(dir.path()).unwrap();
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["hatch run test", "hatch run docs:build"]);
+        assert_eq!(result.scripts[1].name, "docs:build");
+    }
+
+    #[test]
+    fn test_parse_pipfile_scripts() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Pipfile"),
+            r#"
+[packages]
+requests = "*"
+
+[scripts]
+test = "pytest -x"
+serve = "python -m http.server"
+"#,
+        )
+        .unwrap();
+        let runner = DetectedRunner::new("pipenv", "Pipfile", Ecosystem::Python, 7);
+
+        let result = get_scripts_for_runner(&runner, dir.path()).unwrap();
+        assert_eq!(result.source_file, "Pipfile");
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["serve", "test"]);
+        assert_eq!(result.scripts[1].command, "pytest -x");
+        assert_eq!(
+            runner.build_command("test", &[]),
+            vec!["pipenv", "run", "test"]
+        );
+
+        // pyproject.toml scripts take precedence when both exist
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project.scripts]\ncli = \"app:main\"\n",
+        )
+        .unwrap();
+        let result = get_scripts_for_runner(&runner, dir.path()).unwrap();
+        assert_eq!(result.source_file, "pyproject.toml");
+    }
+
+    #[test]
+    fn test_common_tasks_v_odin() {
+        let dir = tempdir().unwrap();
+        let v = DetectedRunner::new("v", "v.mod", Ecosystem::V, 26);
+        let result = get_scripts_for_runner(&v, dir.path()).unwrap();
+        assert_eq!(result.source_file, "v.mod");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["v .", "v run .", "v test ."]);
+
+        let odin = DetectedRunner::new("odin", "main.odin", Ecosystem::Odin, 27);
+        let result = get_scripts_for_runner(&odin, dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "run", "test", "check"]);
+        assert_eq!(result.scripts[1].command, "odin run .");
+    }
+
+    #[test]
+    fn test_common_tasks_sbt_mill() {
+        let dir = tempdir().unwrap();
+        let sbt = DetectedRunner::new("sbt", "build.sbt", Ecosystem::Java, 16);
+        let result = get_scripts_for_runner(&sbt, dir.path()).unwrap();
+        assert_eq!(result.source_file, "build.sbt");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["sbt compile", "sbt test", "sbt run"]);
+
+        let mill = DetectedRunner::new("mill", "build.sc", Ecosystem::Java, 16);
+        let result = get_scripts_for_runner(&mill, dir.path()).unwrap();
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec!["mill _.compile", "mill _.test", "mill _.run"]
+        );
+
+        // The checked-in wrapper runs instead of a global mill
+        std::fs::write(dir.path().join("mill"), "#!/bin/sh\n").unwrap();
+        let mill = mill.with_evidence(dir.path(), &["mill"]);
+        let result = get_scripts_for_runner(&mill, dir.path()).unwrap();
+        assert_eq!(result.scripts[0].command, "./mill _.compile");
+    }
+
+    #[test]
+    fn test_parse_requirements_scripts() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("requirements.txt"), "flask\n").unwrap();
+        std::fs::write(dir.path().join("main.py"), "print('hi')\n").unwrap();
+        let runner = DetectedRunner::new("pip", "requirements.txt", Ecosystem::Python, 8);
+
+        let result = get_scripts_for_runner(&runner, dir.path()).unwrap();
+        assert_eq!(result.source_file, "requirements.txt");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "pip install -r requirements.txt",
+                "python main.py",
+                "pytest"
+            ]
+        );
+
+        std::fs::remove_file(dir.path().join("main.py")).unwrap();
+        let result = parse_requirements_scripts(dir.path()).unwrap();
+        assert_eq!(result.scripts.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_melos_scripts() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("melos.yaml"),
+            r#"name: acme
+packages:
+  - packages/**
+
+scripts:
+  analyze:
+    run: melos exec -- dart analyze .
+    description: Run the analyzer in every package
+  test:unit: melos exec -- flutter test
+  format:
+    run: |
+      dart format --set-exit-if-changed .
+    description: "Check formatting"
+
+command:
+  bootstrap:
+    usePubspecOverrides: true
+"#,
+        )
+        .unwrap();
+
+        let result = parse_melos_scripts(dir.path()).unwrap();
+        assert_eq!(result.source_file, "melos.yaml");
+        let scripts: Vec<(&str, &str, Option<&str>)> = result
+            .scripts
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.command.as_str(),
+                    s.description.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            scripts,
+            vec![
+                (
+                    "analyze",
+                    "melos run analyze",
+                    Some("Run the analyzer in every package")
+                ),
+                ("test:unit", "melos run test:unit", None),
+                ("format", "melos run format", Some("Check formatting")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_earthfile_targets() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Earthfile"), "VERSION 0.8\nFROM golang:1.22\n\nbuild:\n    COPY . .\n    RUN go build ./...\n\ntest:\n    FROM +build\n    RUN go test ./...\n").unwrap();
+
+        let result = parse_earthfile_targets(dir.path()).unwrap();
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["earthly +build", "earthly +test"]);
+    }
+
+    #[test]
+    fn test_parse_go_targets() {
+        let dir = tempdir().unwrap();
+        assert!(parse_go_targets(dir.path()).is_none());
+
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/app\n\ngo 1.22\n",
+        )
+        .unwrap();
+        let result = parse_go_targets(dir.path()).unwrap();
+        assert_eq!(result.source_file, "go.mod");
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "go build ./...",
+                "go test ./...",
+                "go run .",
+                "go vet ./...",
+                "go mod tidy"
+            ]
+        );
+
+        std::fs::write(dir.path().join("go.work"), "go 1.22\nuse ./api\n").unwrap();
+        let result = parse_go_targets(dir.path()).unwrap();
+        assert_eq!(result.source_file, "go.work");
+        assert_eq!(result.scripts.last().unwrap().command, "go work sync");
+    }
+
+    #[test]
+    fn test_parse_dockerfile_targets_multi_stage() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM node:20 AS deps\nRUN npm ci\n\nFROM node:20-slim as runtime\nCOPY --from=deps /app /app\n").unwrap();
+
+        let result = parse_dockerfile_targets(dir.path()).unwrap();
+        let commands: Vec<&str> = result.scripts.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "docker build .",
+                "docker build --target deps .",
+                "docker build --target runtime .",
+            ]
+        );
+
+        std::fs::write(
+            dir.path().join("Dockerfile"),
+            "FROM rust:1 AS build
+RUN cargo build
+
+FROM debian AS runtime
+FROM debian AS runtime
+",
+        )
+        .unwrap();
+        let result = parse_dockerfile_targets(dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "runtime"]);
+        assert_eq!(result.scripts[0].command, "docker build .");
+    }
+
+    #[test]
+    fn test_resolve_env_script() {
+        let scripts: Vec<String> = ["build", "build:prod", "test"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(resolve_env_script("build", "prod", &scripts), "build:prod");
+        assert_eq!(resolve_env_script("build", "dev", &scripts), "build");
+        assert_eq!(resolve_env_script("test", "prod", &scripts), "test");
+    }
+
+    #[test]
+    fn test_parse_bazel_query_output() {
+        let output = "Loading: 3 packages loaded\n//app:server\n//app:server_test\n\n@rules_go//go:tools\n//lib:util\n";
+        let scripts = parse_bazel_query_output(output, 3);
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["//app:server", "//app:server_test", "@rules_go//go:tools"]
+        );
+        assert_eq!(scripts[0].command, "bazel build //app:server");
+        assert!(parse_bazel_query_output("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_bazel_query_falls_back_to_static_targets() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("MODULE.bazel"), "").unwrap();
+        let runner = DetectedRunner::new("bazel", "MODULE.bazel", Ecosystem::Bazel, 23);
+
+        let static_list = get_scripts_for_runner(&runner, dir.path()).unwrap();
+        assert_eq!(static_list.scripts[0].command, "bazel build //...");
+        assert_eq!(static_list.scripts[1].command, "bazel test //...");
+
+        // A query that can't finish in time leaves just the static entries
+        let list = query_bazel_targets(dir.path(), 10, Duration::ZERO).unwrap();
+        assert_eq!(list.scripts.len(), 2);
+        assert_eq!(list.scripts[0].name, "build");
+    }
+
+    #[test]
+    fn test_matching_scripts() {
+        let script = |name: &str, category| ProjectScript {
+            name: name.to_string(),
+            command: String::new(),
+            description: None,
+            category,
+        };
+        let scripts = vec![
+            script("test:unit", ScriptCategory::Script),
+            script("lint", ScriptCategory::Script),
+            script("test:e2e", ScriptCategory::Script),
+            script("test:bin", ScriptCategory::Binary),
+        ];
+        assert_eq!(
+            matching_scripts("test:*", &scripts),
+            vec!["test:unit", "test:e2e"]
+        );
+        assert!(matching_scripts("build:*", &scripts).is_empty());
+    }
+
+    #[test]
+    fn test_filter_scripts() {
+        let script = |name: &str, command: &str| ProjectScript {
+            name: name.to_string(),
+            command: command.to_string(),
+            description: None,
+            category: ScriptCategory::Script,
+        };
+        let scripts = vec![
+            script("build", "vite build"),
+            script("test", "vitest run"),
+            script("lint", "eslint ."),
+            script("preview", "vite preview"),
+        ];
+        let names = |filtered: Vec<ProjectScript>| -> Vec<String> {
+            filtered.into_iter().map(|s| s.name).collect()
+        };
+        let filter_scripts =
+            |scripts: Vec<ProjectScript>, grep: Option<&str>, head: Option<usize>| {
+                ScriptFilter::new(grep, None, head).apply(scripts)
+            };
+
+        // Name or command, in any case
+        assert_eq!(
+            names(filter_scripts(scripts.clone(), Some("VITE"), None)),
+            vec!["build", "test", "preview"]
+        );
+        assert_eq!(
+            names(filter_scripts(scripts.clone(), Some("run"), None)),
+            vec!["test"]
+        );
+        assert!(filter_scripts(scripts.clone(), Some("deploy"), None).is_empty());
+
+        assert_eq!(
+            names(filter_scripts(scripts.clone(), None, Some(2))),
+            vec!["build", "test"]
+        );
+        assert_eq!(
+            names(filter_scripts(scripts.clone(), Some("vite"), Some(1))),
+            vec!["build"]
+        );
+        assert_eq!(filter_scripts(scripts.clone(), None, None).len(), 4);
+        assert!(!ScriptFilter::default().is_active());
+
+        // Anchored regexes, against the name or the command
+        let regex = |pattern: &str| {
+            ScriptFilter::new(None, Some(parse_filter_regex(pattern).unwrap()), None)
+        };
+        assert_eq!(
+            names(regex("^(build|lint)$").apply(scripts.clone())),
+            vec!["build", "lint"]
+        );
+        assert_eq!(
+            names(regex(r"^vite\b").apply(scripts.clone())),
+            vec!["build", "preview"]
+        );
+        assert_eq!(regex("^vite$").pattern(), Some("^vite$"));
+        assert!(parse_filter_regex("(unclosed").is_err());
+
+        // --head counts across sections: the second gets what the first left
+        let mut filter = ScriptFilter::new(None, None, Some(3));
+        assert_eq!(filter.apply(scripts[..2].to_vec()).len(), 2);
+        assert_eq!(names(filter.apply(scripts[2..].to_vec())), vec!["lint"]);
+        assert!(filter.apply(scripts).is_empty());
+    }
+
+    #[test]
+    fn test_parse_makefile_inline_descriptions() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Makefile"),
+            "build: deps ## Build the project\n\tcargo build\n\nclean:\n\trm -rf target\n",
+        )
+        .unwrap();
+
+        let result = parse_makefile_targets(dir.path()).unwrap();
+        assert_eq!(result.scripts[0].name, "build");
+        assert_eq!(
+            result.scripts[0].description.as_deref(),
+            Some("Build the project")
+        );
+        assert_eq!(result.scripts[1].name, "clean");
+        assert_eq!(result.scripts[1].description, None);
+    }
+
+    #[test]
+    fn test_parse_makefile_preceding_comment() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Makefile"), "# Tooling\n\n# Run the test suite\ntest:\n\tcargo test\n\n# Overridden below\nlint: ## Run clippy\n\tcargo clippy\n\nfmt:\n\tcargo fmt\n").unwrap();
+
+        let result = parse_makefile_targets(dir.path()).unwrap();
+        let descriptions: Vec<(&str, Option<&str>)> = result
+            .scripts
+            .iter()
+            .map(|s| (s.name.as_str(), s.description.as_deref()))
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                ("test", Some("Run the test suite")),
+                ("lint", Some("Run clippy")),
+                ("fmt", None),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_makefile_targets() {
         let dir = tempdir().unwrap();
         let makefile = dir.path().join("Makefile");
-        
+
         let mut file = File::create(&makefile).unwrap();
-        file.write_all(br#"
+        file.write_all(
+            br#"
 .PHONY: all clean
 
 build:
@@ -242,11 +2314,13 @@ test:
 
 clean:
 	rm -rf target
-"#).unwrap();
-        
+"#,
+        )
+        .unwrap();
+
         let result = parse_makefile_targets(dir.path()).unwrap();
         assert!(result.scripts.len() >= 3);
-        
+
         let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
         assert!(names.contains(&"build"));
         assert!(names.contains(&"test"));
@@ -257,25 +2331,528 @@ clean:
     fn test_parse_cargo_targets() {
         let dir = tempdir().unwrap();
         let cargo_toml = dir.path().join("Cargo.toml");
-        
+
         File::create(&cargo_toml).unwrap();
-        
+
         let result = parse_cargo_targets(dir.path()).unwrap();
         assert!(!result.scripts.is_empty());
-        
+
         let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
         assert!(names.contains(&"build"));
         assert!(names.contains(&"test"));
         assert!(names.contains(&"run"));
     }
 
+    #[test]
+    fn test_parse_cargo_targets_single_bin() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        File::create(dir.path().join("src").join("main.rs")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let result = parse_cargo_targets(dir.path()).unwrap();
+        let run = result.scripts.iter().find(|s| s.name == "run").unwrap();
+        assert_eq!(run.command, "cargo run");
+        assert!(result
+            .scripts
+            .iter()
+            .all(|s| s.category == ScriptCategory::Script));
+    }
+
+    #[test]
+    fn test_parse_cargo_targets_multi_bin() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src").join("bin")).unwrap();
+        File::create(dir.path().join("src").join("main.rs")).unwrap();
+        File::create(dir.path().join("src").join("bin").join("migrate.rs")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let result = parse_cargo_targets(dir.path()).unwrap();
+        assert!(!result.scripts.iter().any(|s| s.name == "run"));
+        let bins: Vec<&str> = result
+            .scripts
+            .iter()
+            .filter(|s| s.category == ScriptCategory::Binary)
+            .map(|s| s.command.as_str())
+            .collect();
+        assert_eq!(bins, vec!["cargo run --bin app", "cargo run --bin migrate"]);
+        assert!(result.runs_by_name("run:bin:migrate"));
+
+        // Each listed name builds the command it shows
+        let runner =
+            crate::DetectedRunner::new("cargo", "Cargo.toml", crate::detectors::Ecosystem::Rust, 9);
+        for script in result
+            .scripts
+            .iter()
+            .filter(|s| s.category == ScriptCategory::Binary)
+        {
+            assert_eq!(
+                runner.build_command(&script.name, &[]).join(" "),
+                script.command
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_cargo_targets_workspace_root() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["crates/*", "tools/xtask"]
+exclude = ["crates/scratch"]
+"#,
+        )
+        .unwrap();
+        for (member, name, bin) in [
+            ("crates/server", "server", true),
+            ("crates/core", "core", false),
+            ("crates/scratch", "scratch", true),
+            ("tools/xtask", "xtask", true),
+        ] {
+            let member_dir = dir.path().join(member);
+            std::fs::create_dir_all(member_dir.join("src")).unwrap();
+            let entry = if bin { "main.rs" } else { "lib.rs" };
+            File::create(member_dir.join("src").join(entry)).unwrap();
+            std::fs::write(
+                member_dir.join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", name),
+            )
+            .unwrap();
+        }
+
+        let result = parse_cargo_targets(dir.path()).unwrap();
+        let command = |name: &str| {
+            result
+                .scripts
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.command.as_str())
+        };
+        assert_eq!(command("build"), Some("cargo build --workspace"));
+        assert_eq!(command("test"), Some("cargo test --workspace"));
+        assert_eq!(command("run:package:server"), Some("cargo run -p server"));
+        assert_eq!(command("run:package:xtask"), Some("cargo run -p xtask"));
+        assert_eq!(command("run:package:core"), None);
+        assert_eq!(command("run:package:scratch"), None);
+
+        let runner =
+            crate::DetectedRunner::new("cargo", "Cargo.toml", crate::detectors::Ecosystem::Rust, 9);
+        assert_eq!(
+            runner.build_command("run:package:server", &[]).join(" "),
+            "cargo run -p server"
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_targets_examples_and_tests() {
+        let dir = tempdir().unwrap();
+        for sub in ["src/bin", "examples/server", "tests"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        File::create(dir.path().join("src").join("lib.rs")).unwrap();
+        File::create(dir.path().join("src").join("bin").join("migrate.rs")).unwrap();
+        File::create(dir.path().join("examples").join("hello.rs")).unwrap();
+        File::create(dir.path().join("examples").join("server").join("main.rs")).unwrap();
+        File::create(dir.path().join("examples").join("README.md")).unwrap();
+        File::create(dir.path().join("tests").join("api.rs")).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let result = parse_cargo_targets(dir.path()).unwrap();
+        let command = |name: &str| {
+            result
+                .scripts
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.command.as_str())
+        };
+        // The common verbs stay; the only binary keeps `cargo run`
+        assert_eq!(command("build"), Some("cargo build"));
+        assert_eq!(command("run"), Some("cargo run"));
+        assert_eq!(
+            command("run:example:hello"),
+            Some("cargo run --example hello")
+        );
+        assert_eq!(
+            command("run:example:server"),
+            Some("cargo run --example server")
+        );
+        assert_eq!(command("run:example:README"), None);
+        assert_eq!(command("test:api"), Some("cargo test --test api"));
+        assert_eq!(cargo_bin_targets(dir.path()), vec!["migrate"]);
+
+        // Explicit targets are listed even with discovery turned off
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "app"
+autoexamples = false
+
+[[example]]
+name = "demo"
+path = "demos/demo.rs"
+"#,
+        )
+        .unwrap();
+        let result = parse_cargo_targets(dir.path()).unwrap();
+        let examples: Vec<&str> = result
+            .scripts
+            .iter()
+            .filter(|s| s.name.starts_with("run:example:"))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(examples, vec!["run:example:demo"]);
+        assert!(result.scripts.iter().any(|s| s.name == "test:api"));
+    }
+
+    #[test]
+    fn test_cargo_bin_targets() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src").join("bin")).unwrap();
+        File::create(dir.path().join("src").join("main.rs")).unwrap();
+        File::create(dir.path().join("src").join("bin").join("migrate.rs")).unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "app"
+
+[[bin]]
+name = "worker"
+path = "src/worker.rs"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cargo_bin_targets(dir.path()),
+            vec!["app", "migrate", "worker"]
+        );
+    }
+
+    #[test]
+    fn test_parse_xcode_targets_project() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("MyApp.xcodeproj")).unwrap();
+
+        let result = parse_xcode_targets(dir.path()).unwrap();
+        assert_eq!(result.source_file, "MyApp.xcodeproj");
+        let build = result.scripts.iter().find(|s| s.name == "build").unwrap();
+        assert_eq!(build.command, "xcodebuild build");
+    }
+
+    #[test]
+    fn test_parse_xcode_targets_workspace() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("MyApp.xcodeproj")).unwrap();
+        std::fs::create_dir(dir.path().join("MyApp.xcworkspace")).unwrap();
+
+        let result = parse_xcode_targets(dir.path()).unwrap();
+        assert_eq!(result.source_file, "MyApp.xcworkspace");
+        let test = result.scripts.iter().find(|s| s.name == "test").unwrap();
+        assert_eq!(
+            test.command,
+            "xcodebuild -workspace MyApp.xcworkspace -scheme MyApp test"
+        );
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let input = r#"{
+            // line comment
+            "url": "http://example.com", /* block */
+            "list": [1, 2,],
+            "escaped": "say \"hi\" // not a comment",
+        }"#;
+        let json: Value = serde_json::from_str(&strip_jsonc(input)).unwrap();
+        assert_eq!(json["url"], "http://example.com");
+        assert_eq!(json["list"], serde_json::json!([1, 2]));
+        assert_eq!(json["escaped"], "say \"hi\" // not a comment");
+    }
+
+    #[test]
+    fn test_parse_vscode_tasks() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".vscode")).unwrap();
+        std::fs::write(
+            dir.path().join(".vscode").join("tasks.json"),
+            r#"{
+    // See https://go.microsoft.com/fwlink/?LinkId=733558
+    "version": "2.0.0",
+    "tasks": [
+        {
+            "label": "build",
+            "type": "shell",
+            "command": "cargo",
+            "args": ["build", { "value": "--release", "quoting": "escape" }],
+        },
+        /* npm task delegating to package.json */
+        {
+            "label": "lint",
+            "type": "npm",
+            "script": "lint",
+        },
+        { "type": "shell", "command": "echo unlabeled" },
+    ],
+}"#,
+        )
+        .unwrap();
+
+        let result = parse_vscode_tasks(dir.path()).unwrap();
+        assert_eq!(result.source_file, ".vscode/tasks.json");
+        assert_eq!(result.scripts.len(), 2);
+        assert_eq!(result.scripts[0].name, "build");
+        assert_eq!(result.scripts[0].command, "cargo build --release");
+        assert_eq!(result.scripts[1].name, "lint");
+        assert_eq!(result.scripts[1].command, "npm run lint");
+    }
+
+    #[test]
+    fn test_parse_mise_tasks_inline_tables() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("mise.toml"),
+            r#"
+[tools]
+node = "20"
+
+[tasks]
+clean = "rm -rf dist"
+
+[tasks.build]
+description = "Build the app"
+run = "npm run build"
+
+[tasks.lint]
+run = ["eslint .", "prettier --check ."]
+"#,
+        )
+        .unwrap();
+
+        let result = parse_mise_tasks(dir.path()).unwrap();
+        assert_eq!(result.source_file, "mise.toml");
+        assert_eq!(result.scripts.len(), 3);
+
+        let build = result.scripts.iter().find(|s| s.name == "build").unwrap();
+        assert_eq!(build.command, "mise run build");
+        assert_eq!(build.description.as_deref(), Some("Build the app"));
+
+        let lint = result.scripts.iter().find(|s| s.name == "lint").unwrap();
+        assert_eq!(lint.command, "mise run lint");
+        assert!(lint.description.is_none());
+
+        assert!(result.scripts.iter().any(|s| s.name == "clean"));
+    }
+
+    #[test]
+    fn test_parse_mise_task_files() {
+        let dir = tempdir().unwrap();
+        let tasks_dir = dir.path().join(".config/mise/tasks");
+        std::fs::create_dir_all(&tasks_dir).unwrap();
+        std::fs::write(
+            tasks_dir.join("deploy"),
+            "#!/usr/bin/env bash\n#MISE description=\"Ship it\"\necho deploy\n",
+        )
+        .unwrap();
+
+        let result = parse_mise_tasks(dir.path()).unwrap();
+        assert_eq!(result.source_file, ".config/mise/tasks");
+        assert_eq!(result.scripts[0].name, "deploy");
+        assert_eq!(result.scripts[0].command, "mise run deploy");
+        assert_eq!(result.scripts[0].description.as_deref(), Some("Ship it"));
+    }
+
+    #[test]
+    fn test_parse_terraform_targets() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("main.tf")).unwrap();
+
+        let result = parse_terraform_targets(dir.path(), "tofu").unwrap();
+        assert_eq!(result.source_file, "main.tf");
+        let plan = result.scripts.iter().find(|s| s.name == "plan").unwrap();
+        assert_eq!(plan.command, "tofu plan");
+    }
+
+    #[test]
+    fn test_parse_ansible_playbooks() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("playbook.yml")).unwrap();
+
+        let result = parse_ansible_playbooks(dir.path()).unwrap();
+        assert_eq!(result.scripts.len(), 1);
+        assert_eq!(result.scripts[0].command, "ansible-playbook playbook.yml");
+    }
+
+    #[test]
+    fn test_parse_gradle_tasks_jvm() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.gradle")).unwrap();
+
+        let result = parse_gradle_tasks(dir.path()).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert!(names.contains(&"build"));
+        assert!(!names.contains(&"assembleDebug"));
+        assert_eq!(result.scripts[0].command, "gradle build");
+
+        File::create(dir.path().join("gradlew")).unwrap();
+        let result = parse_gradle_tasks(dir.path()).unwrap();
+        assert_eq!(result.scripts[0].command, "./gradlew build");
+    }
+
+    #[test]
+    fn test_parse_gradle_tasks_android() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("build.gradle")).unwrap();
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+        File::create(dir.path().join("app").join("build.gradle")).unwrap();
+        File::create(dir.path().join("app").join("AndroidManifest.xml")).unwrap();
+
+        let result = parse_gradle_tasks(dir.path()).unwrap();
+        let assemble = result
+            .scripts
+            .iter()
+            .find(|s| s.name == "assembleDebug")
+            .unwrap();
+        assert_eq!(assemble.command, "gradle assembleDebug");
+        assert!(result
+            .scripts
+            .iter()
+            .any(|s| s.name == "connectedAndroidTest"));
+
+        File::create(dir.path().join("gradlew")).unwrap();
+        let result = parse_gradle_tasks(dir.path()).unwrap();
+        assert_eq!(result.scripts[0].command, "./gradlew assembleDebug");
+    }
+
+    #[test]
+    fn test_discover_all_scripts_parallel_matches_sequential() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"test": "jest", "build": "tsc"}}"#,
+        )
+        .unwrap();
+        File::create(dir.path().join("Cargo.toml")).unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project.scripts]\ncli = \"app:main\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Makefile"),
+            "build:\n\techo build\nlint:\n\techo lint\n",
+        )
+        .unwrap();
+
+        let summarize = |lists: Vec<ScriptList>| -> Vec<(String, Vec<String>)> {
+            lists
+                .into_iter()
+                .map(|l| {
+                    (
+                        l.source_file,
+                        l.scripts.into_iter().map(|s| s.command).collect(),
+                    )
+                })
+                .collect()
+        };
+
+        let sequential = summarize(discover_all_scripts_with(dir.path(), 1));
+        assert_eq!(sequential.len(), 4);
+        for threads in [2, 4, 64] {
+            assert_eq!(
+                summarize(discover_all_scripts_with(dir.path(), threads)),
+                sequential
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_on_threads_uses_at_most_max_threads() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+
+        let record = |max_threads: usize| {
+            let threads = Mutex::new(HashSet::new());
+            let results = run_on_threads(8, max_threads, |index| {
+                threads.lock().unwrap().insert(std::thread::current().id());
+                std::thread::sleep(Duration::from_millis(20));
+                Some(index)
+            });
+            assert_eq!(results, (0..8).collect::<Vec<_>>());
+            threads.into_inner().unwrap()
+        };
+
+        assert_eq!(
+            record(1),
+            HashSet::from([std::thread::current().id()]),
+            "one thread runs everything on the caller"
+        );
+        let used = record(4);
+        assert!(
+            used.len() > 1 && used.len() <= 4,
+            "used {} threads",
+            used.len()
+        );
+        assert!(!used.contains(&std::thread::current().id()));
+    }
+
     #[test]
     fn test_no_scripts_found() {
         let dir = tempdir().unwrap();
-        
+
         // Empty directory should return None
         assert!(parse_package_json_scripts(dir.path()).is_none());
         assert!(parse_makefile_targets(dir.path()).is_none());
         assert!(parse_cargo_targets(dir.path()).is_none());
     }
+
+    #[test]
+    fn test_discover_scripts_recursive() {
+        let dir = tempdir().unwrap();
+        let package = |path: &Path, script: &str| {
+            fs::create_dir_all(path).unwrap();
+            fs::write(
+                path.join("package.json"),
+                format!(r#"{{"scripts": {{"{}": "echo {}"}}}}"#, script, script),
+            )
+            .unwrap();
+        };
+        package(dir.path(), "root");
+        package(&dir.path().join("packages").join("web"), "dev");
+        package(&dir.path().join("packages").join("api"), "serve");
+        package(
+            &dir.path()
+                .join("packages")
+                .join("api")
+                .join("fixtures")
+                .join("app"),
+            "deep",
+        );
+        package(
+            &dir.path().join("node_modules").join("left-pad"),
+            "vendored",
+        );
+
+        let found = discover_scripts_recursive(dir.path(), 2, 4);
+        let paths: Vec<&str> = found.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec![".", "packages/api", "packages/web"]);
+        assert_eq!(found[1].lists[0].source_file, "package.json");
+        assert_eq!(found[1].lists[0].scripts[0].name, "serve");
+
+        // Deeper packages show up once the depth allows them
+        let found = discover_scripts_recursive(dir.path(), 4, 1);
+        let paths: Vec<&str> = found.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ".",
+                "packages/api",
+                "packages/api/fixtures/app",
+                "packages/web"
+            ]
+        );
+        assert_eq!(discover_scripts_recursive(dir.path(), 0, 4).len(), 1);
+    }
 }
@@ -0,0 +1,257 @@
+//! Ctrl-C and SIGTERM handling while a command runs
+//!
+//! devrunner stays alive until the command exits, so it never leaves an
+//! orphan behind. A signal sent to devrunner itself is forwarded to the
+//! command, which gets [`GRACE_PERIOD`] to clean up before it is killed.
+//! A terminal Ctrl-C already reaches a command in devrunner's process group,
+//! so it is neither forwarded nor timed: like a shell, devrunner reports the
+//! command's own status, and a REPL that swallows Ctrl-C keeps running.
+//! Commands run one at a time, so the handler state is process-wide.
+
+use std::io;
+use std::process::{Child, Command, ExitStatus};
+use std::time::Duration;
+
+/// How long a command has to exit after a forwarded signal before it is killed
+pub const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// A running command whose signals are being forwarded
+pub struct ForwardedChild {
+    pub child: Child,
+    #[cfg(unix)]
+    guard: unix::Guard,
+}
+
+/// Spawn `command` with signal forwarding in place
+///
+/// When devrunner owns the terminal the command shares its process group, so
+/// Ctrl-C reaches both and job control (Ctrl-Z, prompts) keeps working;
+/// otherwise it gets a process group of its own and signals go to all of it.
+pub fn spawn(command: &mut Command) -> io::Result<ForwardedChild> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        let own_group = !unix::owns_terminal();
+        if own_group {
+            command.process_group(0);
+        }
+        let child = command.spawn()?;
+        let guard = unix::Guard::install(child.id() as i32, own_group);
+        Ok(ForwardedChild { child, guard })
+    }
+
+    #[cfg(windows)]
+    {
+        // The console delivers Ctrl-C to the command itself; devrunner only
+        // has to survive it
+        windows::ignore_ctrl_c(true);
+        match command.spawn() {
+            Ok(child) => Ok(ForwardedChild { child }),
+            Err(e) => {
+                windows::ignore_ctrl_c(false);
+                Err(e)
+            }
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        Ok(ForwardedChild {
+            child: command.spawn()?,
+        })
+    }
+}
+
+impl ForwardedChild {
+    /// Wait for the command to exit
+    ///
+    /// If devrunner forwarded a signal, the status reports that signal however
+    /// the command ended (cleaning up and exiting, or killed after the grace
+    /// period), so the exit code is `128 + signal` (130 for SIGINT). After a
+    /// terminal Ctrl-C, which isn't forwarded, the command's own status stands.
+    pub fn wait(mut self) -> io::Result<ExitStatus> {
+        let status = self.child.wait()?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+
+            match self.guard.finish() {
+                Some(signal) => Ok(ExitStatus::from_raw(signal)),
+                None => Ok(status),
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            Ok(status)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ForwardedChild {
+    fn drop(&mut self) {
+        windows::ignore_ctrl_c(false);
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::GRACE_PERIOD;
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// Where forwarded signals go: `-pgid` for a process group, a pid otherwise
+    static TARGET: AtomicI32 = AtomicI32::new(0);
+    /// Whether the command has a process group of its own
+    static OWN_GROUP: AtomicBool = AtomicBool::new(false);
+    /// Last signal forwarded to the command, 0 for none
+    static FORWARDED: AtomicI32 = AtomicI32::new(0);
+
+    const SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+    /// Whether devrunner's process group is in the foreground of a terminal
+    pub fn owns_terminal() -> bool {
+        // SAFETY: plain queries on the standard descriptors
+        unsafe {
+            let group = libc::getpgrp();
+            [0, 1, 2]
+                .into_iter()
+                .any(|fd| libc::isatty(fd) == 1 && libc::tcgetpgrp(fd) == group)
+        }
+    }
+
+    /// The installed handlers, restored on drop
+    pub struct Guard {
+        previous: Vec<(libc::c_int, libc::sigaction)>,
+        done: Arc<AtomicBool>,
+    }
+
+    impl Guard {
+        pub fn install(pid: i32, own_group: bool) -> Self {
+            TARGET.store(if own_group { -pid } else { pid }, Ordering::SeqCst);
+            OWN_GROUP.store(own_group, Ordering::SeqCst);
+            FORWARDED.store(0, Ordering::SeqCst);
+
+            let previous = SIGNALS
+                .iter()
+                .filter_map(|&signal| {
+                    // SAFETY: the handler only touches atomics and calls kill(2),
+                    // both async-signal-safe
+                    unsafe {
+                        let mut action: libc::sigaction = std::mem::zeroed();
+                        action.sa_sigaction = on_signal as *const () as libc::sighandler_t;
+                        action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+                        libc::sigemptyset(&mut action.sa_mask);
+                        let mut old: libc::sigaction = std::mem::zeroed();
+                        (libc::sigaction(signal, &action, &mut old) == 0).then_some((signal, old))
+                    }
+                })
+                .collect();
+
+            let done = Arc::new(AtomicBool::new(false));
+            spawn_watchdog(Arc::clone(&done));
+            Self { previous, done }
+        }
+
+        /// Stop forwarding; the signal that was forwarded, if any
+        pub fn finish(&mut self) -> Option<i32> {
+            self.done.store(true, Ordering::SeqCst);
+            TARGET.store(0, Ordering::SeqCst);
+            match FORWARDED.swap(0, Ordering::SeqCst) {
+                0 => None,
+                signal => Some(signal),
+            }
+        }
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.finish();
+            for (signal, old) in &self.previous {
+                // SAFETY: restores the action saved by `install`
+                unsafe {
+                    libc::sigaction(*signal, old, std::ptr::null_mut());
+                }
+            }
+        }
+    }
+
+    extern "C" fn on_signal(signal: libc::c_int, info: *mut libc::siginfo_t, _: *mut libc::c_void) {
+        // Ctrl-C from the terminal already reached a command in our process
+        // group; forwarding it would deliver it twice. Only the kernel sends
+        // signals without a sender pid.
+        // SAFETY: the kernel passes a valid siginfo with SA_SIGINFO
+        let from_terminal = unsafe { sender_pid(&*info) } == 0;
+        if from_terminal && !OWN_GROUP.load(Ordering::SeqCst) {
+            return;
+        }
+        let target = TARGET.load(Ordering::SeqCst);
+        if target != 0 {
+            // SAFETY: kill(2) is async-signal-safe
+            unsafe {
+                libc::kill(target, signal);
+            }
+            FORWARDED.store(signal, Ordering::SeqCst);
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe fn sender_pid(info: &libc::siginfo_t) -> libc::pid_t {
+        info.si_pid()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    unsafe fn sender_pid(info: &libc::siginfo_t) -> libc::pid_t {
+        info.si_pid
+    }
+
+    /// SIGKILL the command [`GRACE_PERIOD`] after a forwarded signal
+    fn spawn_watchdog(done: Arc<AtomicBool>) {
+        std::thread::spawn(move || {
+            let mut deadline = None;
+            while !done.load(Ordering::SeqCst) {
+                if deadline.is_none() && FORWARDED.load(Ordering::SeqCst) != 0 {
+                    deadline = Some(Instant::now() + GRACE_PERIOD);
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    let target = TARGET.load(Ordering::SeqCst);
+                    if target != 0 && !done.load(Ordering::SeqCst) {
+                        // SAFETY: signals the command that was spawned
+                        unsafe {
+                            libc::kill(target, libc::SIGKILL);
+                        }
+                    }
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    type HandlerRoutine = unsafe extern "system" fn(ctrl_type: u32) -> i32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetConsoleCtrlHandler(handler: Option<HandlerRoutine>, add: i32) -> i32;
+    }
+
+    /// Handles every console event by ignoring it; unlike a null handler this
+    /// isn't inherited, so the command still gets Ctrl-C
+    unsafe extern "system" fn ignore(_ctrl_type: u32) -> i32 {
+        1
+    }
+
+    pub fn ignore_ctrl_c(enable: bool) {
+        // SAFETY: registers or removes a handler with a 'static lifetime
+        unsafe {
+            SetConsoleCtrlHandler(Some(ignore), enable as i32);
+        }
+    }
+}
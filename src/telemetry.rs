@@ -0,0 +1,370 @@
+//! Optional OTLP trace export of run timing (`telemetry` feature)
+//!
+//! When built with the feature and `DEVRUNNER_OTLP_ENDPOINT` is set, each run
+//! records a root span with child spans for detection, script discovery and
+//! execution, and posts them as OTLP/HTTP JSON. Without the feature, or with
+//! the variable unset, `Tracer` does nothing.
+
+/// Environment variable naming the OTLP/HTTP collector (e.g. http://localhost:4318)
+pub const OTLP_ENDPOINT_ENV: &str = "DEVRUNNER_OTLP_ENDPOINT";
+
+/// Handle to a span started on a `Tracer`
+#[derive(Debug, Clone, Copy)]
+pub struct SpanId(#[cfg_attr(not(feature = "telemetry"), allow(dead_code))] usize);
+
+/// Value of a span attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<i32> for AttributeValue {
+    fn from(value: i32) -> Self {
+        AttributeValue::Int(value.into())
+    }
+}
+
+#[cfg(feature = "telemetry")]
+pub use enabled::{InMemoryExporter, OtlpHttpExporter, SpanExporter, SpanRecord, Tracer};
+
+#[cfg(not(feature = "telemetry"))]
+pub use disabled::Tracer;
+
+#[cfg(not(feature = "telemetry"))]
+mod disabled {
+    use super::{AttributeValue, SpanId};
+
+    /// No-op tracer used when the `telemetry` feature is off
+    pub struct Tracer;
+
+    impl Tracer {
+        #[inline]
+        pub fn from_env(_offline: bool) -> Self {
+            Tracer
+        }
+
+        #[inline]
+        pub fn start(&mut self, _name: &str, _parent: Option<SpanId>) -> SpanId {
+            SpanId(0)
+        }
+
+        #[inline]
+        pub fn set_attribute(
+            &mut self,
+            _span: SpanId,
+            _key: &str,
+            _value: impl Into<AttributeValue>,
+        ) {
+        }
+
+        #[inline]
+        pub fn end(&mut self, _span: SpanId) {}
+
+        #[inline]
+        pub fn finish(self) {}
+    }
+}
+
+#[cfg(feature = "telemetry")]
+mod enabled {
+    use super::{AttributeValue, SpanId, OTLP_ENDPOINT_ENV};
+    use serde_json::{json, Value};
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// A finished (or still open) span as it will be exported
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SpanRecord {
+        pub name: String,
+        pub span_id: u64,
+        pub parent_span_id: Option<u64>,
+        pub start: SystemTime,
+        pub end: Option<SystemTime>,
+        pub attributes: Vec<(String, AttributeValue)>,
+    }
+
+    /// Destination for the spans of one run
+    pub trait SpanExporter {
+        fn export(&self, trace_id: u128, spans: &[SpanRecord]);
+    }
+
+    /// Posts spans to an OTLP/HTTP collector as JSON
+    pub struct OtlpHttpExporter {
+        pub endpoint: String,
+    }
+
+    impl OtlpHttpExporter {
+        /// Accept either the collector base URL or the full `/v1/traces` URL
+        pub fn new(endpoint: &str) -> Self {
+            let endpoint = endpoint.trim_end_matches('/');
+            let endpoint = if endpoint.ends_with("/v1/traces") {
+                endpoint.to_string()
+            } else {
+                format!("{}/v1/traces", endpoint)
+            };
+            Self { endpoint }
+        }
+    }
+
+    impl SpanExporter for OtlpHttpExporter {
+        fn export(&self, trace_id: u128, spans: &[SpanRecord]) {
+            let body = otlp_json(trace_id, spans);
+            let Ok(rt) = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            else {
+                return;
+            };
+            // Telemetry must never fail or noticeably slow down the run
+            let _ = rt.block_on(async {
+                reqwest::Client::builder()
+                    .timeout(Duration::from_secs(2))
+                    .build()?
+                    .post(&self.endpoint)
+                    .json(&body)
+                    .send()
+                    .await
+            });
+        }
+    }
+
+    /// Keeps exported spans in memory, for tests
+    #[derive(Clone, Default)]
+    pub struct InMemoryExporter {
+        pub spans: Arc<Mutex<Vec<SpanRecord>>>,
+    }
+
+    impl SpanExporter for InMemoryExporter {
+        fn export(&self, _trace_id: u128, spans: &[SpanRecord]) {
+            self.spans.lock().unwrap().extend_from_slice(spans);
+        }
+    }
+
+    struct Trace {
+        trace_id: u128,
+        spans: Vec<SpanRecord>,
+        exporter: Box<dyn SpanExporter>,
+    }
+
+    /// Records the spans of one run; inactive unless an endpoint is configured
+    pub struct Tracer {
+        trace: Option<Trace>,
+    }
+
+    impl Tracer {
+        /// Tracer exporting to `DEVRUNNER_OTLP_ENDPOINT`, or an inactive one if
+        /// unset or `offline` (`--offline` keeps spans off the network too)
+        pub fn from_env(offline: bool) -> Self {
+            Self::from_endpoint(std::env::var(OTLP_ENDPOINT_ENV).ok().as_deref(), offline)
+        }
+
+        /// [`Tracer::from_env`] with the endpoint already read
+        pub(crate) fn from_endpoint(endpoint: Option<&str>, offline: bool) -> Self {
+            match endpoint {
+                Some(endpoint) if !offline && !endpoint.trim().is_empty() => {
+                    Self::with_exporter(Box::new(OtlpHttpExporter::new(endpoint)))
+                }
+                _ => Tracer { trace: None },
+            }
+        }
+
+        pub fn with_exporter(exporter: Box<dyn SpanExporter>) -> Self {
+            Tracer {
+                trace: Some(Trace {
+                    trace_id: (u128::from(random_u64()) << 64) | u128::from(random_u64()),
+                    spans: Vec::new(),
+                    exporter,
+                }),
+            }
+        }
+
+        #[cfg(test)]
+        pub(crate) fn is_active(&self) -> bool {
+            self.trace.is_some()
+        }
+
+        pub fn start(&mut self, name: &str, parent: Option<SpanId>) -> SpanId {
+            let Some(trace) = self.trace.as_mut() else {
+                return SpanId(0);
+            };
+            let parent_span_id = parent.and_then(|p| trace.spans.get(p.0)).map(|p| p.span_id);
+            trace.spans.push(SpanRecord {
+                name: name.to_string(),
+                span_id: random_u64(),
+                parent_span_id,
+                start: SystemTime::now(),
+                end: None,
+                attributes: Vec::new(),
+            });
+            SpanId(trace.spans.len() - 1)
+        }
+
+        pub fn set_attribute(&mut self, span: SpanId, key: &str, value: impl Into<AttributeValue>) {
+            if let Some(record) = self.trace.as_mut().and_then(|t| t.spans.get_mut(span.0)) {
+                record.attributes.push((key.to_string(), value.into()));
+            }
+        }
+
+        pub fn end(&mut self, span: SpanId) {
+            if let Some(record) = self.trace.as_mut().and_then(|t| t.spans.get_mut(span.0)) {
+                record.end.get_or_insert_with(SystemTime::now);
+            }
+        }
+
+        /// Close any open spans and hand the trace to the exporter
+        pub fn finish(self) {
+            if let Some(mut trace) = self.trace {
+                let now = SystemTime::now();
+                for span in &mut trace.spans {
+                    span.end.get_or_insert(now);
+                }
+                trace.exporter.export(trace.trace_id, &trace.spans);
+            }
+        }
+    }
+
+    /// OTLP/HTTP JSON body (`ExportTraceServiceRequest`) for one trace
+    pub(super) fn otlp_json(trace_id: u128, spans: &[SpanRecord]) -> Value {
+        let spans: Vec<Value> = spans
+            .iter()
+            .map(|span| {
+                let mut value = json!({
+                    "traceId": format!("{:032x}", trace_id),
+                    "spanId": format!("{:016x}", span.span_id),
+                    "name": span.name,
+                    "kind": 1,
+                    "startTimeUnixNano": unix_nanos(span.start).to_string(),
+                    "endTimeUnixNano": unix_nanos(span.end.unwrap_or(span.start)).to_string(),
+                    "attributes": span.attributes.iter().map(|(key, value)| json!({
+                        "key": key,
+                        "value": match value {
+                            AttributeValue::String(s) => json!({ "stringValue": s }),
+                            AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+                        },
+                    })).collect::<Vec<_>>(),
+                });
+                if let Some(parent) = span.parent_span_id {
+                    value["parentSpanId"] = json!(format!("{:016x}", parent));
+                }
+                value
+            })
+            .collect();
+
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [{ "key": "service.name", "value": { "stringValue": "devrunner" } }],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "devrunner", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+
+    fn unix_nanos(time: SystemTime) -> u128 {
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    }
+
+    /// Non-zero random id from std's randomly seeded hasher (no rand dependency)
+    fn random_u64() -> u64 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(unix_nanos(SystemTime::now()));
+        hasher.finish().max(1)
+    }
+}
+
+#[cfg(all(test, feature = "telemetry"))]
+mod tests {
+    use super::*;
+    use crate::detectors::{DetectedRunner, Ecosystem};
+    use crate::runner::{execute, ExecOptions};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_dry_run_records_spans() {
+        let dir = tempdir().unwrap();
+        let exporter = InMemoryExporter::default();
+        let mut tracer = Tracer::with_exporter(Box::new(exporter.clone()));
+
+        let root = tracer.start("devrunner", None);
+        let exec = tracer.start("execute", Some(root));
+        let runner = DetectedRunner::new("make", "Makefile", Ecosystem::Generic, 24);
+        let options = ExecOptions {
+            dry_run: true,
+            quiet: 1,
+            ..Default::default()
+        };
+        let result = execute(&runner, "build", &[], dir.path(), &options).unwrap();
+        tracer.set_attribute(exec, "runner", result.runner.name.as_str());
+        tracer.end(exec);
+        tracer.set_attribute(root, "exit_code", 0);
+        tracer.finish();
+
+        let spans = exporter.spans.lock().unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[1].name, "execute");
+        assert_eq!(spans[1].parent_span_id, Some(spans[0].span_id));
+        assert_eq!(
+            spans[1].attributes,
+            vec![("runner".to_string(), AttributeValue::from("make"))]
+        );
+        assert!(spans.iter().all(|s| s.end.is_some()));
+    }
+
+    #[test]
+    fn test_otlp_json_shape() {
+        let exporter = InMemoryExporter::default();
+        let mut tracer = Tracer::with_exporter(Box::new(exporter.clone()));
+        let root = tracer.start("devrunner", None);
+        tracer.set_attribute(root, "exit_code", 2);
+        tracer.finish();
+
+        let spans = exporter.spans.lock().unwrap();
+        let body = enabled::otlp_json(0xabc, &spans);
+        let span = &body["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], format!("{:032x}", 0xabc));
+        assert_eq!(span["name"], "devrunner");
+        assert_eq!(span["attributes"][0]["value"]["intValue"], "2");
+        assert!(span.get("parentSpanId").is_none());
+    }
+
+    #[test]
+    fn test_offline_tracer_is_inactive() {
+        let endpoint = Some("http://127.0.0.1:9");
+        assert!(Tracer::from_endpoint(endpoint, false).is_active());
+        assert!(!Tracer::from_endpoint(endpoint, true).is_active());
+        assert!(!Tracer::from_endpoint(Some(" "), false).is_active());
+        assert!(!Tracer::from_endpoint(None, false).is_active());
+    }
+
+    #[test]
+    fn test_endpoint_gets_traces_path() {
+        assert_eq!(
+            OtlpHttpExporter::new("http://localhost:4318/").endpoint,
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            OtlpHttpExporter::new("http://collector/v1/traces").endpoint,
+            "http://collector/v1/traces"
+        );
+    }
+}
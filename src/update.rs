@@ -3,10 +3,15 @@ use crate::output;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::env;
-use std::fs;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration as StdDuration, Instant};
 
 const GITHUB_REPO: &str = "princepal9120/devrunner";
 const UPDATE_TIMEOUT_SECS: u64 = 5;
+/// A lock older than this is assumed to belong to a crashed updater
+const STALE_LOCK_MINUTES: i64 = 30;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -31,16 +36,165 @@ struct GitHubAsset {
     browser_download_url: String,
 }
 
+/// The latest release as last fetched, so checks between refreshes stay offline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseCache {
+    /// Release version without the leading `v`
+    pub version: String,
+    /// Release page, shown as the changelog link
+    pub html_url: String,
+    /// Download URL of this platform's binary, if the release has one
+    pub asset_url: Option<String>,
+    /// First lines of the release notes
+    pub changelog: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl ReleaseCache {
+    fn from_release(release: GitHubRelease, now: DateTime<Utc>) -> Self {
+        let asset_name = get_asset_name();
+        let asset_url = release
+            .assets
+            .into_iter()
+            .find(|a| Some(&a.name) == asset_name.as_ref())
+            .map(|a| a.browser_download_url);
+        Self {
+            version: release.tag_name.trim_start_matches('v').to_string(),
+            html_url: release.html_url,
+            asset_url,
+            changelog: release
+                .body
+                .map(|b| b.lines().take(5).collect::<Vec<_>>().join("\n")),
+            fetched_at: now,
+        }
+    }
+
+    /// Whether the response is recent enough to use instead of refetching
+    pub fn is_fresh(&self, now: DateTime<Utc>, interval_hours: u64) -> bool {
+        // A timestamp in the future (clock changed) can't be trusted
+        self.fetched_at <= now && !is_check_due(Some(self.fetched_at), now, interval_hours)
+    }
+
+    /// The cached version if it is newer than `local_version`
+    pub fn newer_than(&self, local_version: &str) -> Option<&str> {
+        let remote = semver::Version::parse(&self.version).ok()?;
+        let local = semver::Version::parse(local_version).ok()?;
+        (remote > local).then_some(self.version.as_str())
+    }
+}
+
+/// Read the cached release response, if any (never touches the network)
+pub fn read_release_cache() -> Option<ReleaseCache> {
+    let content = fs::read_to_string(Config::release_cache_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_release_cache(cache: &ReleaseCache) {
+    if let Some(path) = Config::release_cache_path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(cache) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+/// The cached release when still fresh, otherwise None (time to refetch)
+pub fn usable_release_cache(
+    cache: Option<ReleaseCache>,
+    now: DateTime<Utc>,
+    interval_hours: u64,
+) -> Option<ReleaseCache> {
+    cache.filter(|c| c.is_fresh(now, interval_hours))
+}
+
+/// GitHub API endpoint for the latest release
+pub fn latest_release_url() -> String {
+    format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        GITHUB_REPO
+    )
+}
+
+/// HTTP client shared by the updater (proxies come from the environment)
+fn http_client(timeout: StdDuration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .user_agent(format!("devrunner/{}", current_version()))
+        .build()
+}
+
+/// Check the release endpoint answers, for `doctor`; returns the HTTP status
+pub async fn probe_release_endpoint(timeout: StdDuration) -> Result<u16, String> {
+    let client = http_client(timeout).map_err(|e| e.to_string())?;
+    let response = client
+        .head(latest_release_url())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response.status().as_u16())
+}
+
+/// Fetch the latest release from GitHub and cache the response
+async fn fetch_latest_release(
+    client: &reqwest::Client,
+) -> Result<ReleaseCache, Box<dyn std::error::Error>> {
+    let release: GitHubRelease = client
+        .get(latest_release_url())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let cache = ReleaseCache::from_release(release, Utc::now());
+    write_release_cache(&cache);
+    Ok(cache)
+}
+
 /// Check if auto-update is disabled via environment variable
 pub fn is_update_disabled() -> bool {
     env::var("RUN_NO_UPDATE").is_ok()
 }
 
+/// Whether to stay off the network: `--offline`, or RUN_NO_UPDATE set
+pub fn is_offline(offline_flag: bool) -> bool {
+    offline_flag || is_update_disabled()
+}
+
+/// Whether a finished run may spawn the background update check
+///
+/// `offline` (`--offline`, or RUN_NO_UPDATE) wins over `auto_update`.
+pub fn should_spawn_background_update(auto_update: bool, offline: bool) -> bool {
+    auto_update && !offline
+}
+
 /// Get the current version of the CLI
 pub fn current_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
 
+/// Build metadata printed by `devrunner --version --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    pub rustc_version: &'static str,
+    pub target: &'static str,
+    pub auto_update: bool,
+}
+
+/// Version and build metadata for support triage
+pub fn version_info(auto_update: bool) -> VersionInfo {
+    VersionInfo {
+        version: current_version(),
+        git_commit: env!("DEVRUNNER_GIT_SHA"),
+        rustc_version: env!("DEVRUNNER_RUSTC_VERSION"),
+        target: env!("DEVRUNNER_TARGET"),
+        auto_update,
+    }
+}
+
 /// Check for and display any pending update notifications
 pub fn check_update_notification(quiet: bool) {
     if quiet {
@@ -53,6 +207,15 @@ pub fn check_update_notification(quiet: bool) {
     };
 
     if !update_path.exists() {
+        // A newer release that wasn't installed (e.g. the binary isn't writable)
+        if let Some(cache) = read_release_cache() {
+            if let Some(version) = cache.newer_than(current_version()) {
+                output::info(&format!(
+                    "devrunner v{} is available (run `devrunner --update`)",
+                    version
+                ));
+            }
+        }
         return;
     }
 
@@ -92,6 +255,83 @@ pub fn check_update_notification(quiet: bool) {
     let _ = fs::remove_file(&update_path);
 }
 
+/// Check whether enough time has passed since the last background check
+pub fn is_check_due(
+    last_check: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    interval_hours: u64,
+) -> bool {
+    // An interval too long to represent is never due
+    let Some(interval) = i64::try_from(interval_hours)
+        .ok()
+        .and_then(Duration::try_hours)
+    else {
+        return false;
+    };
+    match last_check {
+        Some(last) => now - last >= interval,
+        None => true,
+    }
+}
+
+/// Read the timestamp of the last background check, if any
+fn read_last_check() -> Option<DateTime<Utc>> {
+    let path = Config::last_update_check_path()?;
+    let content = fs::read_to_string(path).ok()?;
+    DateTime::parse_from_rfc3339(content.trim())
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Record that a background check is starting now
+fn record_check() {
+    if let Some(path) = Config::last_update_check_path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, Utc::now().to_rfc3339());
+    }
+}
+
+/// Exclusive lock so concurrent invocations never run two updaters at once
+struct UpdateLock {
+    path: PathBuf,
+}
+
+impl UpdateLock {
+    /// Try to take the lock, returning None if another updater holds it
+    fn acquire() -> Option<Self> {
+        let path = Config::update_lock_path()?;
+        fs::create_dir_all(path.parent()?).ok()?;
+
+        // A crashed updater leaves its lock behind, so expire old ones
+        let is_stale = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(|modified| {
+                let age = Utc::now() - DateTime::<Utc>::from(modified);
+                age > Duration::minutes(STALE_LOCK_MINUTES)
+            })
+            .unwrap_or(false);
+        if is_stale {
+            let _ = fs::remove_file(&path);
+        }
+
+        // create_new is atomic: exactly one process can create the file
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .ok()?;
+        Some(Self { path })
+    }
+}
+
+impl Drop for UpdateLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
 /// Get the appropriate asset name for the current platform
 fn get_asset_name() -> Option<String> {
     let os = env::consts::OS;
@@ -109,12 +349,16 @@ fn get_asset_name() -> Option<String> {
     Some(platform.to_string())
 }
 
-/// Spawn background update check
-pub fn spawn_background_update() {
+/// Spawn background update check, at most once per `interval_hours`
+pub fn spawn_background_update(interval_hours: u64) {
     if is_update_disabled() {
         return;
     }
 
+    if !is_check_due(read_last_check(), Utc::now(), interval_hours) {
+        return;
+    }
+
     // Spawn detached process for update check
     // We use std::process::Command with specific flags to detach
     #[cfg(unix)]
@@ -160,53 +404,49 @@ pub fn spawn_background_update() {
 
 /// Perform the actual update check (called from background process)
 pub async fn perform_update_check() -> Result<(), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(UPDATE_TIMEOUT_SECS))
-        .build()?;
-
-    // Fetch latest release info
-    let release: GitHubRelease = client
-        .get(format!(
-            "https://api.github.com/repos/{}/releases/latest",
-            GITHUB_REPO
-        ))
-        .header("User-Agent", format!("devrunner/{}", current_version()))
-        .send()
-        .await?
-        .json()
-        .await?;
+    // Another updater is already running, skip silently
+    let _lock = match UpdateLock::acquire() {
+        Some(lock) => lock,
+        None => return Ok(()),
+    };
+    record_check();
 
-    // Parse versions
-    let remote_version = release.tag_name.trim_start_matches('v');
-    let local_version = current_version();
+    let client = http_client(StdDuration::from_secs(UPDATE_TIMEOUT_SECS))?;
 
-    let remote_semver = semver::Version::parse(remote_version)?;
-    let local_semver = semver::Version::parse(local_version)?;
+    // Only hit the GitHub API once the cached response is older than the interval
+    let interval_hours = Config::load().get_update_interval_hours().unwrap_or(24);
+    let release = match usable_release_cache(read_release_cache(), Utc::now(), interval_hours) {
+        Some(cache) => cache,
+        None => fetch_latest_release(&client).await?,
+    };
 
-    if remote_semver <= local_semver {
-        return Ok(()); // Already up to date
-    }
+    let local_version = current_version();
+    let remote_version = match release.newer_than(local_version) {
+        Some(version) => version.to_string(),
+        None => return Ok(()), // Already up to date
+    };
 
-    // Find the appropriate asset
-    let asset_name = get_asset_name().ok_or("Unsupported platform")?;
-    let asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == asset_name)
+    // Tell an unsupported platform apart from a release that lacks our binary
+    get_asset_name().ok_or("Unsupported platform")?;
+    let asset_url = release
+        .asset_url
+        .as_deref()
         .ok_or("Asset not found for this platform")?;
 
-    // Download the new binary
-    let response = client.get(&asset.browser_download_url).send().await?;
-    let bytes = response.bytes().await?;
-
     // Get current executable path
     let current_exe = env::current_exe()?;
 
     // Create a temporary file for the new binary
     let temp_path = current_exe.with_extension("new");
 
-    // Write the new binary
-    fs::write(&temp_path, bytes)?;
+    // Stream the new binary to disk instead of buffering it whole
+    let response = client.get(asset_url).send().await?.error_for_status()?;
+    // Runs in the background, so count bytes without drawing anything
+    let mut progress = DownloadProgress::new(response.content_length(), false);
+    if let Err(e) = download_to(response, &temp_path, &mut progress).await {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
 
     // Make executable on Unix
     #[cfg(unix)]
@@ -235,11 +475,9 @@ pub async fn perform_update_check() -> Result<(), Box<dyn std::error::Error>> {
     let update_info = UpdateInfo {
         updated_at: Utc::now(),
         from_version: local_version.to_string(),
-        to_version: remote_version.to_string(),
+        to_version: remote_version,
         changelog_url: release.html_url,
-        changelog: release
-            .body
-            .map(|b| b.lines().take(5).collect::<Vec<_>>().join("\n")),
+        changelog: release.changelog,
     };
 
     if let Some(path) = Config::update_info_path() {
@@ -250,41 +488,136 @@ pub async fn perform_update_check() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Byte counter for a download, drawing a progress line on the terminal when enabled
+///
+/// Hand-rolled rather than built on `indicatif`: one redrawn stderr line is all
+/// the updater needs, and it avoids a dependency for it.
+pub struct DownloadProgress {
+    total: Option<u64>,
+    downloaded: u64,
+    started: Instant,
+    last_drawn: Option<Instant>,
+    enabled: bool,
+}
+
+impl DownloadProgress {
+    /// Redraw at most this often so the terminal isn't flooded
+    const REDRAW_INTERVAL: StdDuration = StdDuration::from_millis(100);
+
+    pub fn new(total: Option<u64>, enabled: bool) -> Self {
+        Self {
+            total,
+            downloaded: 0,
+            started: Instant::now(),
+            last_drawn: None,
+            enabled,
+        }
+    }
+
+    /// Record `bytes` more bytes received
+    pub fn advance(&mut self, bytes: usize) {
+        self.downloaded += bytes as u64;
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .last_drawn
+            .is_some_and(|drawn| now.duration_since(drawn) < Self::REDRAW_INTERVAL)
+        {
+            return;
+        }
+        self.last_drawn = Some(now);
+        eprint!("\r{}\x1b[K", self.line(now.duration_since(self.started)));
+        let _ = io::stderr().flush();
+    }
+
+    /// Bytes received so far
+    pub fn downloaded(&self) -> u64 {
+        self.downloaded
+    }
+
+    /// Completed percentage, when the server sent a Content-Length
+    pub fn percent(&self) -> Option<u64> {
+        let total = self.total.filter(|total| *total > 0)?;
+        Some((self.downloaded.min(total) * 100) / total)
+    }
+
+    /// Progress line, e.g. "Downloading  42% (1.2 MiB / 2.9 MiB, 3.4 MiB/s)"
+    pub fn line(&self, elapsed: StdDuration) -> String {
+        let secs = elapsed.as_secs_f64();
+        let speed = if secs > 0.0 {
+            format!("{}/s", format_bytes((self.downloaded as f64 / secs) as u64))
+        } else {
+            "-".to_string()
+        };
+        match (self.percent(), self.total) {
+            (Some(percent), Some(total)) => format!(
+                "Downloading {:>3}% ({} / {}, {})",
+                percent,
+                format_bytes(self.downloaded),
+                format_bytes(total),
+                speed
+            ),
+            _ => format!("Downloading {} ({})", format_bytes(self.downloaded), speed),
+        }
+    }
+
+    /// Draw the final state and end the progress line
+    pub fn finish(&self) {
+        if self.enabled {
+            eprintln!("\r{}\x1b[K", self.line(self.started.elapsed()));
+        }
+    }
+}
+
+/// Human-readable byte count (B, KiB, MiB)
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value >= KIB * KIB {
+        format!("{:.1} MiB", value / (KIB * KIB))
+    } else if value >= KIB {
+        format!("{:.1} KiB", value / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Write a response body to `path` chunk by chunk, reporting each chunk to `progress`
+async fn download_to(
+    mut response: reqwest::Response,
+    path: &Path,
+    progress: &mut DownloadProgress,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = fs::File::create(path)?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        progress.advance(chunk.len());
+    }
+    file.flush()?;
+    Ok(())
+}
+
 /// Perform a synchronous (blocking) update check
 pub async fn perform_blocking_update(quiet: bool) -> Result<bool, Box<dyn std::error::Error>> {
     if !quiet {
         output::info("Checking for updates...");
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
+    let client = http_client(StdDuration::from_secs(30))?;
 
-    // Fetch latest release info
-    let release: GitHubRelease = client
-        .get(format!(
-            "https://api.github.com/repos/{}/releases/latest",
-            GITHUB_REPO
-        ))
-        .header("User-Agent", format!("devrunner/{}", current_version()))
-        .send()
-        .await?
-        .json()
-        .await?;
+    // An explicit --update always asks GitHub, refreshing the cache on the way
+    let release = fetch_latest_release(&client).await?;
 
-    // Parse versions
-    let remote_version = release.tag_name.trim_start_matches('v');
     let local_version = current_version();
-
-    let remote_semver = semver::Version::parse(remote_version)?;
-    let local_semver = semver::Version::parse(local_version)?;
-
-    if remote_semver <= local_semver {
+    semver::Version::parse(&release.version)?;
+    let Some(remote_version) = release.newer_than(local_version) else {
         if !quiet {
             output::success(&format!("Already up to date (v{})", local_version));
         }
         return Ok(false);
-    }
+    };
 
     if !quiet {
         output::info(&format!(
@@ -293,26 +626,28 @@ pub async fn perform_blocking_update(quiet: bool) -> Result<bool, Box<dyn std::e
         ));
     }
 
-    // Find the appropriate asset
-    let asset_name = get_asset_name().ok_or("Unsupported platform")?;
-    let asset = release
-        .assets
-        .iter()
-        .find(|a| a.name == asset_name)
+    // Tell an unsupported platform apart from a release that lacks our binary
+    get_asset_name().ok_or("Unsupported platform")?;
+    let asset_url = release
+        .asset_url
+        .as_deref()
         .ok_or("Asset not found for this platform")?;
 
-    // Download the new binary
-    let response = client.get(&asset.browser_download_url).send().await?;
-    let bytes = response.bytes().await?;
-
     // Get current executable path
     let current_exe = env::current_exe()?;
 
     // Create a temporary file for the new binary
     let temp_path = current_exe.with_extension("new");
 
-    // Write the new binary
-    fs::write(&temp_path, bytes)?;
+    // Stream the new binary to disk instead of buffering it whole
+    let response = client.get(asset_url).send().await?.error_for_status()?;
+    let show_progress = !quiet && io::stdout().is_terminal() && io::stderr().is_terminal();
+    let mut progress = DownloadProgress::new(response.content_length(), show_progress);
+    if let Err(e) = download_to(response, &temp_path, &mut progress).await {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    progress.finish();
 
     // Make executable on Unix
     #[cfg(unix)]
@@ -347,6 +682,21 @@ pub async fn perform_blocking_update(quiet: bool) -> Result<bool, Box<dyn std::e
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_should_spawn_background_update() {
+        // --offline always wins, whatever RUN_NO_UPDATE says
+        assert!(is_offline(true));
+        assert!(!should_spawn_background_update(true, is_offline(true)));
+        // Without the flag, RUN_NO_UPDATE decides
+        let env_disabled = env::var("RUN_NO_UPDATE").is_ok();
+        assert_eq!(is_offline(false), env_disabled);
+        assert_eq!(
+            should_spawn_background_update(true, is_offline(false)),
+            !env_disabled
+        );
+        assert!(!should_spawn_background_update(false, is_offline(false)));
+    }
+
     #[test]
     fn test_current_version() {
         let version = current_version();
@@ -354,6 +704,118 @@ mod tests {
         assert!(semver::Version::parse(version).is_ok());
     }
 
+    #[test]
+    fn test_version_info() {
+        let info = version_info(false);
+        assert_eq!(info.version, current_version());
+        assert!(!info.git_commit.is_empty());
+        assert!(!info.auto_update);
+
+        let json = serde_json::to_value(version_info(true)).unwrap();
+        assert_eq!(json["auto_update"], true);
+        assert!(json["rustc_version"].is_string());
+    }
+
+    #[test]
+    fn test_download_progress_counts_bytes() {
+        let mut progress = DownloadProgress::new(Some(4096), false);
+        assert_eq!(progress.percent(), Some(0));
+        progress.advance(1024);
+        progress.advance(1024);
+        assert_eq!(progress.downloaded(), 2048);
+        assert_eq!(progress.percent(), Some(50));
+        assert_eq!(
+            progress.line(StdDuration::from_secs(2)),
+            "Downloading  50% (2.0 KiB / 4.0 KiB, 1.0 KiB/s)"
+        );
+
+        // More bytes than advertised never reads past 100%
+        progress.advance(4096);
+        assert_eq!(progress.percent(), Some(100));
+    }
+
+    #[test]
+    fn test_download_progress_without_length() {
+        let mut progress = DownloadProgress::new(None, false);
+        progress.advance(3 * 1024 * 1024);
+        assert_eq!(progress.percent(), None);
+        assert_eq!(
+            progress.line(StdDuration::from_secs(1)),
+            "Downloading 3.0 MiB (3.0 MiB/s)"
+        );
+        assert_eq!(format_bytes(512), "512 B");
+    }
+
+    #[test]
+    fn test_is_check_due() {
+        let now = Utc::now();
+        assert!(is_check_due(None, now, 24));
+        assert!(!is_check_due(Some(now - Duration::hours(1)), now, 24));
+        assert!(is_check_due(Some(now - Duration::hours(24)), now, 24));
+        assert!(is_check_due(Some(now - Duration::hours(2)), now, 1));
+        // An interval of zero checks on every run
+        assert!(is_check_due(Some(now), now, 0));
+        // Huge intervals don't overflow, they are never due
+        assert!(!is_check_due(Some(now), now, u64::MAX));
+        assert!(!is_check_due(Some(now), now, i64::MAX as u64));
+    }
+
+    fn cached(version: &str, fetched_at: DateTime<Utc>) -> ReleaseCache {
+        ReleaseCache {
+            version: version.to_string(),
+            html_url: "https://example.com/release".to_string(),
+            asset_url: None,
+            changelog: None,
+            fetched_at,
+        }
+    }
+
+    #[test]
+    fn test_release_cache_refresh_gate() {
+        let now = Utc::now();
+        let fresh = cached("9.9.9", now - Duration::hours(2));
+        assert!(fresh.is_fresh(now, 24));
+        assert_eq!(
+            usable_release_cache(Some(fresh.clone()), now, 24),
+            Some(fresh.clone())
+        );
+
+        // Past the interval (or with no cache at all) it's time to refetch
+        assert!(usable_release_cache(Some(fresh.clone()), now, 1).is_none());
+        assert!(usable_release_cache(None, now, 24).is_none());
+        let later = now + Duration::hours(30);
+        assert!(!fresh.is_fresh(later, 24));
+
+        // A timestamp from the future can't be trusted
+        assert!(!cached("9.9.9", now + Duration::hours(1)).is_fresh(now, 24));
+    }
+
+    #[test]
+    fn test_release_cache_newer_than() {
+        let now = Utc::now();
+        assert_eq!(cached("1.2.0", now).newer_than("1.1.9"), Some("1.2.0"));
+        assert_eq!(cached("1.2.0", now).newer_than("1.2.0"), None);
+        assert_eq!(cached("not-a-version", now).newer_than("1.0.0"), None);
+    }
+
+    #[test]
+    fn test_release_cache_from_release() {
+        let release: GitHubRelease = serde_json::from_str(
+            r#"{"tag_name": "v2.0.0", "html_url": "https://example.com/v2",
+                "body": "one\ntwo", "assets": []}"#,
+        )
+        .unwrap();
+        let now = Utc::now();
+        let cache = ReleaseCache::from_release(release, now);
+        assert_eq!(cache.version, "2.0.0");
+        assert_eq!(cache.asset_url, None);
+        assert_eq!(cache.changelog.as_deref(), Some("one\ntwo"));
+
+        // Round-trips through the on-disk format
+        let json = serde_json::to_string(&cache).unwrap();
+        assert_eq!(serde_json::from_str::<ReleaseCache>(&json).unwrap(), cache);
+    }
+
     #[test]
     fn test_get_asset_name() {
         let asset = get_asset_name();
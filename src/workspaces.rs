@@ -0,0 +1,241 @@
+use crate::detectors::glob_match;
+use crate::scripts::read_manifest;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A package belonging to a pnpm/yarn/npm workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspacePackage {
+    /// `name` from the package's package.json, or its path when unnamed
+    pub name: String,
+    /// Path relative to the workspace root, with `/` separators
+    pub path: String,
+    pub dir: PathBuf,
+}
+
+/// Workspace globs from pnpm-workspace.yaml or package.json `workspaces`
+///
+/// Patterns starting with `!` exclude packages matched by earlier ones.
+pub fn workspace_patterns(root: &Path) -> Vec<String> {
+    if let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        return parse_pnpm_workspace_packages(&content);
+    }
+
+    let Some(json) = read_manifest(root.join("package.json"))
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    // Either an array, or yarn's `{ "packages": [...] }` form
+    let patterns = match json.get("workspaces") {
+        Some(Value::Array(patterns)) => Some(patterns),
+        Some(Value::Object(obj)) => obj.get("packages").and_then(|p| p.as_array()),
+        _ => None,
+    };
+    patterns
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.as_str())
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Read the `packages:` list of pnpm-workspace.yaml without a YAML parser
+fn parse_pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            patterns.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+
+    patterns
+}
+
+/// Packages of the workspace rooted at `root`, sorted by path
+pub fn workspace_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let mut included: Vec<String> = Vec::new();
+    let mut excluded: Vec<String> = Vec::new();
+
+    for pattern in workspace_patterns(root) {
+        match pattern.strip_prefix('!') {
+            Some(negated) => excluded.extend(expand_pattern(root, negated)),
+            None => included.extend(expand_pattern(root, &pattern)),
+        }
+    }
+    included.sort();
+    included.dedup();
+
+    included
+        .into_iter()
+        .filter(|path| !excluded.contains(path))
+        .filter_map(|path| {
+            let dir = root.join(&path);
+            let content = read_manifest(dir.join("package.json"))?;
+            let json: Value = serde_json::from_str(&content).ok()?;
+            let name = json
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| path.clone());
+            Some(WorkspacePackage { name, path, dir })
+        })
+        .collect()
+}
+
+/// Expand a workspace glob into relative directory paths
+///
+/// Each component may use `*`/`?`; a `**` component matches any depth.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<String> {
+    let mut paths = vec![String::new()];
+
+    for component in pattern.trim_end_matches('/').split('/') {
+        if component.is_empty() || component == "." {
+            continue;
+        }
+        let mut next = Vec::new();
+        for base in &paths {
+            if component == "**" {
+                next.push(base.clone());
+                next.extend(descendant_dirs(root, base));
+            } else if component.contains(['*', '?']) {
+                next.extend(
+                    child_dirs(root, base)
+                        .into_iter()
+                        .filter(|name| glob_match(component, name))
+                        .map(|name| join_rel(base, &name)),
+                );
+            } else if root.join(base).join(component).is_dir() {
+                next.push(join_rel(base, component));
+            }
+        }
+        paths = next;
+    }
+
+    paths.retain(|path| !path.is_empty());
+    paths
+}
+
+/// Names of the directories directly inside `root/base`, skipping node_modules
+fn child_dirs(root: &Path, base: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root.join(base)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| name != "node_modules" && !name.starts_with('.'))
+        .collect()
+}
+
+/// Every directory below `root/base`, as paths relative to `root`
+fn descendant_dirs(root: &Path, base: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    for name in child_dirs(root, base) {
+        let path = join_rel(base, &name);
+        found.extend(descendant_dirs(root, &path));
+        found.push(path);
+    }
+    found
+}
+
+fn join_rel(base: &str, name: &str) -> String {
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_package(root: &Path, path: &str, name: &str) {
+        let dir = root.join(path);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("package.json"),
+            format!(r#"{{"name": "{}", "scripts": {{"build": "tsc"}}}}"#, name),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_package_json_workspaces() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"workspaces": ["packages/*", "tools/cli"]}"#,
+        )
+        .unwrap();
+        write_package(dir.path(), "packages/web", "@acme/web");
+        write_package(dir.path(), "packages/api", "@acme/api");
+        write_package(dir.path(), "tools/cli", "acme-cli");
+        fs::create_dir_all(dir.path().join("packages/empty")).unwrap();
+
+        let packages = workspace_packages(dir.path());
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["@acme/api", "@acme/web", "acme-cli"]);
+        assert_eq!(packages[0].path, "packages/api");
+    }
+
+    #[test]
+    fn test_yarn_object_workspaces() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"workspaces": {"packages": ["apps/*"], "nohoist": ["**/react"]}}"#,
+        )
+        .unwrap();
+        write_package(dir.path(), "apps/site", "site");
+
+        assert_eq!(workspace_patterns(dir.path()), vec!["apps/*"]);
+        assert_eq!(workspace_packages(dir.path())[0].name, "site");
+    }
+
+    #[test]
+    fn test_pnpm_workspace_yaml() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/**'\n  - \"!packages/legacy\" # frozen\n\ncatalog:\n  - react\n",
+        )
+        .unwrap();
+        write_package(dir.path(), "packages/ui", "ui");
+        write_package(dir.path(), "packages/ui/nested", "ui-nested");
+        write_package(dir.path(), "packages/legacy", "legacy");
+
+        assert_eq!(
+            workspace_patterns(dir.path()),
+            vec!["packages/**", "!packages/legacy"]
+        );
+        let names: Vec<String> = workspace_packages(dir.path())
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["ui", "ui-nested"]);
+    }
+
+    #[test]
+    fn test_not_a_workspace() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "solo"}"#).unwrap();
+
+        assert!(workspace_packages(dir.path()).is_empty());
+    }
+}
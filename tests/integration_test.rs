@@ -9,6 +9,11 @@ fn run_cmd() -> Command {
     Command::cargo_bin("devrunner").unwrap()
 }
 
+/// Whether `tool` is on PATH; tests that run it skip themselves otherwise
+fn has_tool(tool: &str) -> bool {
+    devrunner::detectors::is_tool_installed(tool)
+}
+
 #[test]
 fn test_help() {
     run_cmd()
@@ -27,6 +32,22 @@ fn test_version() {
         .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
 }
 
+#[test]
+fn test_version_json() {
+    let output = run_cmd()
+        .args(["--version", "--json"])
+        .env("RUN_NO_UPDATE", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(info["auto_update"], false);
+    assert!(info["git_commit"].is_string());
+    assert!(info["target"].is_string());
+}
+
 #[test]
 fn test_no_runner_found() {
     let dir = tempdir().unwrap();
@@ -53,240 +74,2437 @@ fn test_dry_run_npm() {
 }
 
 #[test]
-fn test_dry_run_cargo() {
+fn test_tool_versions_pin_beats_stray_lockfile() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("Cargo.toml")).unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("package-lock.json")).unwrap();
+    fs::write(dir.path().join(".tool-versions"), "pnpm 8.15.1\n").unwrap();
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["build", "--dry-run"])
+        .args(["test", "--dry-run"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("cargo build"));
+        .stdout(predicate::str::contains("pnpm run test"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("why")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Chosen because .tool-versions pins pnpm",
+        ));
 }
 
+#[cfg(unix)]
 #[test]
-fn test_dry_run_pnpm() {
+fn test_env_from_injects_variables() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("package.json")).unwrap();
-    File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "show:\n\t@echo \"$$FIRST-$$SECOND\"\n",
+    )
+    .unwrap();
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run"])
+        .args(["show", "--env-from", "echo FIRST=one; echo SECOND=two"])
+        .args(["--env-from", "echo SECOND=override"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("pnpm run test"));
+        .stdout(predicate::str::contains("one-override"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["show", "--env-from", "exit 4"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--env-from `exit 4` exited with code 4",
+        ));
 }
 
 #[test]
-fn test_dry_run_yarn() {
+fn test_install_dry_run() {
     let dir = tempdir().unwrap();
     File::create(dir.path().join("package.json")).unwrap();
     File::create(dir.path().join("yarn.lock")).unwrap();
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run"])
+        .args(["--dry-run", "--ignore-scripts", "install"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("yarn run test"));
+        .stdout(predicate::str::contains("yarn install --ignore-scripts"));
+
+    let make_dir = tempdir().unwrap();
+    File::create(make_dir.path().join("Makefile")).unwrap();
+    run_cmd()
+        .current_dir(make_dir.path())
+        .args(["--dry-run", "install"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no well-defined step"));
 }
 
 #[test]
-fn test_dry_run_bun() {
+fn test_dry_run_after_subcommand_runs_nothing() {
+    // A broken Cargo.toml makes a real `cargo fetch` fail
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("package.json")).unwrap();
-    File::create(dir.path().join("bun.lockb")).unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "not toml").unwrap();
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["install", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo fetch"))
+        .stdout(predicate::str::contains("--dry-run").not());
 
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("acme.toml")).unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "[[custom_runners]]\nname = \"acme\"\ndetect_file = \"acme.toml\"\nscripts = { clean = \"touch cleaned\" }\n",
+    )
+    .unwrap();
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run"])
+        .env("RUN_NO_UPDATE", "1")
+        .args(["clean", "--dry-run"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("bun run test"));
+        .stdout(predicate::str::contains("touch cleaned"));
+    assert!(!dir.path().join("cleaned").exists());
 }
 
 #[test]
-fn test_dry_run_poetry() {
+fn test_profile_detection_json() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("pyproject.toml")).unwrap();
-    File::create(dir.path().join("poetry.lock")).unwrap();
+    File::create(dir.path().join("Cargo.toml")).unwrap();
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["--profile-detection", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["dirs_walked"], 1);
+    let detectors = report["detectors"].as_array().unwrap();
+    assert!(detectors.iter().any(|d| d["name"] == "node"));
+    let rust = detectors.iter().find(|d| d["name"] == "rust").unwrap();
+    assert_eq!(rust["found"], serde_json::json!(["Cargo.toml"]));
+    assert!(rust["elapsed_ms"].is_f64());
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run"])
+        .arg("--profile-detection")
         .assert()
         .success()
-        .stdout(predicate::str::contains("poetry run test"));
+        .stdout(predicate::str::contains("1 directory walked"))
+        .stdout(predicate::str::contains("(found Cargo.toml)"));
 }
 
 #[test]
-fn test_ignore_flag() {
+fn test_dry_run_json_plan() {
     let dir = tempdir().unwrap();
     File::create(dir.path().join("package.json")).unwrap();
 
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run", "--json", "--", "--watch", "a b"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["runner"], "npm");
+    assert_eq!(plan["program"], "npm");
+    // npm needs its own `--` before the script's args
+    assert_eq!(
+        plan["argv"],
+        serde_json::json!(["npm", "run", "test", "--", "--watch", "a b"])
+    );
+    assert_eq!(
+        plan["working_dir"],
+        dir.path().canonicalize().unwrap().display().to_string()
+    );
+    assert!(plan["env"].as_object().unwrap().is_empty());
+}
+
+#[test]
+fn test_go_work_workspace() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("go.work"),
+        "go 1.22\n\nuse (\n\t./api\n\t./worker\n)\n",
+    )
+    .unwrap();
+
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--ignore=npm", "--dry-run"])
+        .args(["test", "--dry-run", "--", "-race"])
         .assert()
-        .failure()
-        .stderr(predicate::str::contains("No runner found"));
+        .success()
+        .stdout(predicate::str::contains("go test -race ./..."));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("why")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "span 2 module(s) (./api, ./worker)",
+        ));
 }
 
 #[test]
-fn test_recursive_search() {
+fn test_levels_auto_picks_between_nested_projects() {
     let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
     File::create(dir.path().join("package.json")).unwrap();
+    let nested = dir.path().join("crates/core");
+    fs::create_dir_all(&nested).unwrap();
+    File::create(nested.join("Cargo.toml")).unwrap();
 
-    let subdir = dir.path().join("src").join("components");
-    fs::create_dir_all(&subdir).unwrap();
+    // Without a terminal the nearest project still wins
+    run_cmd()
+        .current_dir(&nested)
+        .args(["test", "--dry-run", "--levels=auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo test"));
 
     run_cmd()
-        .current_dir(&subdir)
-        .args(["test", "--dry-run"])
+        .current_dir(&nested)
+        .args(["test", "--dry-run", "--levels=auto", "--prefer", "npm"])
         .assert()
         .success()
         .stdout(predicate::str::contains("npm run test"));
+
+    run_cmd()
+        .current_dir(&nested)
+        .args(["--levels=auto", "why"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Runners at other levels"))
+        .stdout(predicate::str::contains("level 2:"));
 }
 
+#[cfg(unix)]
 #[test]
-fn test_levels_limit() {
+fn test_glob_fail_fast_and_keep_going() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("acme.toml")).unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        r#"
+[[custom_runners]]
+name = "acme"
+detect_file = "acme.toml"
+scripts = { "check:a" = "touch a", "check:b" = "false", "check:c" = "touch c" }
+"#,
+    )
+    .unwrap();
 
-    let deep_subdir = dir.path().join("a").join("b").join("c").join("d");
-    fs::create_dir_all(&deep_subdir).unwrap();
+    // Stops at the failing middle script by default
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("check:*")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("skipping 1 remaining script"));
+    assert!(dir.path().join("a").exists());
+    assert!(!dir.path().join("c").exists());
 
-    // With levels=0, should not find it
     run_cmd()
-        .current_dir(&deep_subdir)
-        .args(["test", "--levels=0"])
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["check:*", "--keep-going"])
         .assert()
-        .failure()
-        .stderr(predicate::str::contains("No runner found"));
+        .code(1)
+        .stderr(predicate::str::contains("1 of 3 scripts failed: check:b"))
+        .stderr(predicate::str::contains("Timing summary:"));
+    assert!(dir.path().join("c").exists());
 
-    // With levels=5, should find it
+    // A single script keeps the one-line timing, and fast runs stay under a threshold
     run_cmd()
-        .current_dir(&deep_subdir)
-        .args(["test", "--levels=5", "--dry-run"])
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["--timings-threshold", "0s", "check:a"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("npm run test"));
+        .stderr(predicate::str::contains("Timing summary:").not())
+        .stderr(predicate::str::contains("Completed in"));
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["--timings-threshold", "10s", "check:*", "--keep-going"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Timing summary:").not());
 }
 
+#[cfg(unix)]
 #[test]
-fn test_extra_args() {
+fn test_script_dirs_override_working_directory() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("package.json")).unwrap();
+    fs::create_dir(dir.path().join("db")).unwrap();
+    File::create(dir.path().join("acme.toml")).unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        r#"
+[[custom_runners]]
+name = "acme"
+detect_file = "acme.toml"
+scripts = { migrate = "pwd", where = "pwd" }
+
+[script_dirs]
+migrate = "db"
+bogus = "nope"
+"#,
+    )
+    .unwrap();
 
+    let db = dir.path().join("db").canonicalize().unwrap();
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run", "--", "--coverage", "--verbose"])
+        .env("RUN_NO_UPDATE", "1")
+        .arg("migrate")
         .assert()
         .success()
-        .stdout(predicate::str::contains(
-            "npm run test --coverage --verbose",
+        .stdout(format!("{}\n", db.display()));
+
+    // Scripts without an override still run at the project root
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("where")
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\n",
+            dir.path().canonicalize().unwrap().display()
         ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("bogus")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a directory"));
 }
 
+#[cfg(unix)]
 #[test]
-fn test_verbose_mode() {
+fn test_shell_snippet_alias_skips_detection() {
     let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "[aliases]\nhello = \"!echo hello from $1\"\nt = \"test\"\n",
+    )
+    .unwrap();
+
+    // No runner is detected here, and none is needed
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["hello", "snippet"])
+        .assert()
+        .success()
+        .stdout("hello from snippet\n");
+
+    // --print-env shows the alias's environment instead of running it
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args([
+            "--print-env",
+            "--json",
+            "--env-from",
+            "echo DEVRUNNER_TEST_LOADED=loaded",
+            "hello",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""DEVRUNNER_TEST_LOADED": "loaded""#,
+        ))
+        .stdout(predicate::str::contains("hello from").not());
+
+    // A plain alias still resolves to a runner script
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("t")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No runner found"));
     File::create(dir.path().join("package.json")).unwrap();
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["t", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ruby_bin_scripts_run_without_gemfile() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("bin")).unwrap();
+    fs::create_dir_all(dir.path().join("app/models")).unwrap();
+    for (name, body) in [
+        ("rails", "#!/usr/bin/env ruby\n"),
+        ("setup", "#!/bin/sh\necho setup in $(pwd)\n"),
+    ] {
+        let path = dir.path().join("bin").join(name);
+        fs::write(&path, body).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    // Found from a subdirectory, and run from the project root
+    let root = dir.path().canonicalize().unwrap();
+    run_cmd()
+        .current_dir(dir.path().join("app/models"))
+        .env("RUN_NO_UPDATE", "1")
+        .arg("setup")
+        .assert()
+        .success()
+        .stdout(format!("setup in {}\n", root.display()));
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run", "--verbose"])
+        .arg("list")
         .assert()
         .success()
-        .stderr(predicate::str::contains("Detected"));
+        .stdout(predicate::str::contains("Binaries:"))
+        .stdout(predicate::str::contains("bin/setup"));
 }
 
+#[cfg(unix)]
 #[test]
-fn test_completions_bash() {
+fn test_bundler_runs_bin_scripts_by_name() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Gemfile")).unwrap();
+    fs::create_dir_all(dir.path().join("bin")).unwrap();
+    let setup = dir.path().join("bin/setup");
+    fs::write(&setup, "#!/bin/sh\necho setup\n").unwrap();
+    fs::set_permissions(&setup, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // `setup` is the binstub, not whatever `setup` is on PATH
+    for task in ["setup", "bin/setup"] {
+        run_cmd()
+            .current_dir(dir.path())
+            .args([task, "--check"])
+            .assert()
+            .success()
+            .stdout("bundle exec bin/setup\n");
+        run_cmd()
+            .current_dir(dir.path())
+            .args([task, "--if-present", "--dry-run"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("bundle exec bin/setup"));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_no_stdin_gives_child_eof() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("acme.toml")).unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        r#"
+[[custom_runners]]
+name = "acme"
+detect_file = "acme.toml"
+scripts = { ask = "sh -c 'read answer && echo got $answer || echo eof'" }
+"#,
+    )
+    .unwrap();
+
+    // stdin is passed through by default so prompts can be answered
     run_cmd()
-        .args(["completions", "bash"])
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("ask")
+        .write_stdin("yes\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains("_devrunner"));
+        .stdout("got yes\n");
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["ask", "--no-stdin"])
+        .write_stdin("yes\n")
+        .assert()
+        .success()
+        .stdout("eof\n");
 }
 
 #[test]
-fn test_completions_zsh() {
+fn test_package_manager_field_beats_lockfile() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"packageManager": "pnpm@8.6.0", "scripts": {"test": "vitest"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("package-lock.json")).unwrap();
+
     run_cmd()
-        .args(["completions", "zsh"])
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("#compdef devrunner"));
+        .stdout(predicate::str::contains("pnpm run test"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("why")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Chosen because package.json packageManager=pnpm",
+        ));
 }
 
 #[test]
-fn test_completions_fish() {
+fn test_prefer_is_a_soft_preference() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+
     run_cmd()
-        .args(["completions", "fish"])
+        .current_dir(dir.path())
+        .args(["why", "--prefer", "make"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("complete"));
+        .stdout(predicate::str::contains("Using: make"))
+        .stdout(predicate::str::contains(
+            "Chosen because of --prefer make (would otherwise use npm)",
+        ));
+
+    // An undetected preference falls back to the usual pick
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--prefer", "cargo", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test"));
 }
 
 #[test]
-fn test_completions_powershell() {
+fn test_print_env_includes_env_from_variables() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "test:\n\t@echo ran\n").unwrap();
+    fs::write(dir.path().join("app.env"), "GREETING=hello\nTARGET=world\n").unwrap();
+
     run_cmd()
-        .args(["completions", "powershell"])
+        .current_dir(dir.path())
+        .args(["test", "--print-env", "--env-from", "cat app.env"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Register-ArgumentCompleter"));
+        .stdout(predicate::str::contains("GREETING=hello\n"))
+        .stdout(predicate::str::contains("TARGET=world\n"))
+        .stdout(predicate::str::contains("ran").not());
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args([
+            "test",
+            "--print-env",
+            "--json",
+            "--env-from",
+            "echo GREETING=hi",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let env: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(env["GREETING"], "hi");
+    assert!(env["PATH"].is_string());
 }
 
 #[test]
-fn test_makefile_detection() {
+fn test_destructive_script_needs_confirmation() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("Makefile")).unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "deploy:\n\t@echo shipped\n\nwipe:\n\t@echo wiped\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "confirm_patterns = [\"wip*\"]\n",
+    )
+    .unwrap();
+
+    // Not a terminal and no --yes: refuse with a dedicated exit code
+    for script in ["deploy", "wipe"] {
+        run_cmd()
+            .current_dir(dir.path())
+            .arg(script)
+            .assert()
+            .code(4)
+            .stderr(predicate::str::contains("pass --yes"))
+            .stdout(predicate::str::contains("shipped").not());
+    }
 
     run_cmd()
         .current_dir(dir.path())
-        .args(["build", "--dry-run"])
+        .args(["deploy", "--yes"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("make build"));
+        .stdout(predicate::str::contains("shipped"));
+
+    // Dry runs never prompt
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["deploy", "--dry-run"])
+        .assert()
+        .success();
 }
 
 #[test]
-fn test_gradle_detection() {
+fn test_clean_removes_node_directories() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("build.gradle")).unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    fs::create_dir_all(dir.path().join("node_modules/left-pad")).unwrap();
 
+    // Deleting directories needs --yes without a terminal
     run_cmd()
         .current_dir(dir.path())
-        .args(["build", "--dry-run"])
+        .arg("clean")
+        .assert()
+        .code(4);
+    assert!(dir.path().join("node_modules").exists());
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--yes"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("gradle build"));
+        .stderr(predicate::str::contains("Removed node_modules"));
+    assert!(!dir.path().join("node_modules").exists());
 }
 
 #[test]
-fn test_maven_detection() {
+fn test_clean_runs_the_runners_own_task() {
     let dir = tempdir().unwrap();
     File::create(dir.path().join("pom.xml")).unwrap();
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dry-run", "clean"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mvn clean"));
 
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Taskfile.yml")).unwrap();
     run_cmd()
         .current_dir(dir.path())
-        .args(["compile", "--dry-run"])
+        .args(["--dry-run", "clean"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("mvn compile"));
+        .stdout(predicate::str::contains("task clean"));
+
+    // A runner's clean task is confirmed like a `clean` script
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("acme.toml")).unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "[[custom_runners]]\nname = \"acme\"\ndetect_file = \"acme.toml\"\nscripts = { clean = \"touch cleaned\" }\n",
+    )
+    .unwrap();
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("clean")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--yes"));
+    assert!(!dir.path().join("cleaned").exists());
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["clean", "--yes"])
+        .assert()
+        .success();
+    assert!(dir.path().join("cleaned").exists());
 }
 
 #[test]
-fn test_mix_detection() {
+fn test_in_container_dry_run() {
     let dir = tempdir().unwrap();
-    File::create(dir.path().join("mix.exs")).unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+    fs::create_dir(dir.path().join(".devcontainer")).unwrap();
+    fs::write(
+        dir.path().join(".devcontainer/devcontainer.json"),
+        "{\n  // base image\n  \"name\": \"app\",\n  \"image\": \"debian:12\",\n  \"workspaceFolder\": \"/work\",\n}\n",
+    )
+    .unwrap();
 
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--in-container", "--dry-run", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let argv: Vec<&str> = plan["argv"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a.as_str().unwrap())
+        .collect();
+    let first = argv[0];
+    assert!(first == "docker" || first == "devcontainer");
+    assert_eq!(&argv[argv.len() - 2..], ["make", "build"]);
+
+    // Without the flag the host runs it
     run_cmd()
         .current_dir(dir.path())
-        .args(["test", "--dry-run"])
+        .args(["build", "--dry-run"])
+        .assert()
+        .success()
+        .stdout("make build\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_in_container_docker_run_gets_the_child_env() {
+    use std::os::unix::fs::PermissionsExt;
+
+    if has_tool("devcontainer") {
+        eprintln!("skipping: the devcontainer CLI replaces docker run");
+        return;
+    }
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+    fs::write(
+        dir.path().join(".devcontainer.json"),
+        r#"{"image": "debian:12"}"#,
+    )
+    .unwrap();
+    // A stand-in docker reporting its args and what it would pass through
+    let bin = tempdir().unwrap();
+    let docker = bin.path().join("docker");
+    fs::write(
+        &docker,
+        "#!/bin/sh\necho \"$@\"\necho \"token=$API_TOKEN\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.path().display(),
+        std::env::var("PATH").unwrap()
+    );
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .env("PATH", path)
+        .args([
+            "--in-container",
+            "--env-from",
+            "echo API_TOKEN=secret",
+            "-q",
+            "build",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("run --rm -e API_TOKEN -v "))
+        .stdout(predicate::str::contains("token=secret"));
+}
+
+#[test]
+fn test_dry_run_cargo() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Cargo.toml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo build"));
+}
+
+#[test]
+fn test_dry_run_quotes_args_with_spaces() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--dry-run", "--", "hello world", "--flag"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("make build 'hello world' --flag"));
+}
+
+#[test]
+fn test_dry_run_pnpm() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm run test"));
+}
+
+#[test]
+fn test_dry_run_yarn() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("yarn.lock")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("yarn run test"));
+}
+
+#[test]
+fn test_dry_run_bun() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("bun.lockb")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bun run test"));
+}
+
+#[test]
+fn test_dry_run_bun_file() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"dev": "bun --watch index.ts"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("bunfig.toml")).unwrap();
+
+    // A path isn't a package.json script, so it runs as a file
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["./scripts/seed.ts", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bun ./scripts/seed.ts"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["dev", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bun run dev"));
+}
+
+#[test]
+fn test_args_file_appends_to_argv() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+    fs::write(
+        dir.path().join("test.args"),
+        "# filter, then harness flags\n--features=e2e\n\n--\n--test-threads=1\n",
+    )
+    .unwrap();
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args([
+            "--args-file",
+            "test.args",
+            "--dry-run",
+            "--json",
+            "test",
+            "signup",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        plan["argv"],
+        serde_json::json!([
+            "cargo",
+            "test",
+            "signup",
+            "--features=e2e",
+            "--",
+            "--test-threads=1"
+        ])
+    );
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--args-file", "missing.args", "test"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Can't read --args-file missing.args",
+        ));
+}
+
+#[test]
+fn test_dry_run_poetry() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("pyproject.toml")).unwrap();
+    File::create(dir.path().join("poetry.lock")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("poetry run test"));
+}
+
+#[test]
+fn test_ignore_flag() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--ignore=npm", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No runner found"));
+}
+
+#[test]
+fn test_recursive_search() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+
+    let subdir = dir.path().join("src").join("components");
+    fs::create_dir_all(&subdir).unwrap();
+
+    run_cmd()
+        .current_dir(&subdir)
+        .args(["test", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test"));
+}
+
+#[test]
+fn test_levels_limit() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+
+    let deep_subdir = dir.path().join("a").join("b").join("c").join("d");
+    fs::create_dir_all(&deep_subdir).unwrap();
+
+    // With levels=0, should not find it
+    run_cmd()
+        .current_dir(&deep_subdir)
+        .args(["test", "--levels=0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No runner found"));
+
+    // With levels=5, should find it
+    run_cmd()
+        .current_dir(&deep_subdir)
+        .args(["test", "--levels=5", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run test"));
+}
+
+#[test]
+fn test_extra_args() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run", "--", "--coverage", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "npm run test -- --coverage --verbose",
+        ));
+}
+
+#[test]
+fn test_extra_args_cargo_test_binary() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Cargo.toml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run", "--", "--release", "--", "--nocapture"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "cargo test --release -- --nocapture",
+        ));
+}
+
+#[test]
+fn test_verbose_mode() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run", "--verbose"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Detected"));
+}
+
+#[test]
+fn test_completions_bash() {
+    run_cmd()
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_devrunner"));
+}
+
+#[test]
+fn test_completions_zsh() {
+    run_cmd()
+        .args(["completions", "zsh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#compdef devrunner"));
+}
+
+#[test]
+fn test_completions_fish() {
+    run_cmd()
+        .args(["completions", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete"));
+}
+
+#[test]
+fn test_completions_powershell() {
+    run_cmd()
+        .args(["completions", "powershell"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Register-ArgumentCompleter"));
+}
+
+#[test]
+fn test_makefile_detection() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("make build"));
+}
+
+#[test]
+fn test_gradle_detection() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("build.gradle")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gradle build"));
+}
+
+#[test]
+fn test_maven_detection() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("pom.xml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["compile", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mvn compile"));
+}
+
+#[test]
+fn test_mix_detection() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("mix.exs")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["test", "--dry-run"])
         .assert()
         .success()
         .stdout(predicate::str::contains("mix test"));
 }
+
+#[test]
+fn test_list_vscode_tasks() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts":{"dev":"vite"}}"#,
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join(".vscode")).unwrap();
+    fs::write(
+        dir.path().join(".vscode").join("tasks.json"),
+        r#"{
+  // JSONC comment
+  "tasks": [{ "label": "compile", "command": "tsc", "args": ["-p", "."], }],
+}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dev"))
+        .stdout(predicate::str::contains(".vscode/tasks.json"))
+        .stdout(predicate::str::contains("tsc -p ."));
+}
+
+#[test]
+fn test_init_writes_detected_runner() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("init")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(".devrunner.toml"));
+
+    let content = fs::read_to_string(dir.path().join(".devrunner.toml")).unwrap();
+    assert!(content.contains("preferred_runner = \"pnpm\""));
+
+    // A second init refuses to overwrite without --force
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("init")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["init", "--force"])
+        .assert()
+        .success();
+
+    // From a subdirectory, the config goes to the detected project root
+    fs::remove_file(dir.path().join(".devrunner.toml")).unwrap();
+    let sub = dir.path().join("src");
+    fs::create_dir(&sub).unwrap();
+    run_cmd().current_dir(&sub).arg("init").assert().success();
+    assert!(dir.path().join(".devrunner.toml").exists());
+    assert!(!sub.join(".devrunner.toml").exists());
+
+    // ...where runs and `alias add` from the subdirectory find it
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"lint": "eslint ."}}"#,
+    )
+    .unwrap();
+    run_cmd()
+        .current_dir(&sub)
+        .args(["alias", "add", "l", "lint"])
+        .assert()
+        .success();
+    assert!(!sub.join(".devrunner.toml").exists());
+    let content = fs::read_to_string(dir.path().join(".devrunner.toml")).unwrap();
+    assert!(content.contains("l = \"lint\""));
+    run_cmd()
+        .current_dir(&sub)
+        .env("RUN_NO_UPDATE", "1")
+        .args(["l", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm run lint"));
+}
+
+#[test]
+fn test_exec_wraps_tool_with_package_manager() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("package.json")).unwrap();
+    File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dry-run", "exec", "eslint", "--", "--fix", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm exec eslint --fix ."));
+}
+
+#[test]
+fn test_exec_unsupported_ecosystem() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("Makefile")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dry-run", "exec", "eslint"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("exec is not supported"));
+}
+
+#[test]
+fn test_list_since_counts_changes_inside_the_project() {
+    if !has_tool("git") {
+        eprintln!("skipping: git is not installed");
+        return;
+    }
+    let dir = tempdir().unwrap();
+    let app = dir.path().join("app");
+    fs::create_dir_all(app.join("src")).unwrap();
+    fs::write(
+        app.join("package.json"),
+        r#"{"scripts": {"build": "tsc", "docs": "typedoc"}}"#,
+    )
+    .unwrap();
+    fs::write(app.join("src/index.ts"), "").unwrap();
+    fs::write(dir.path().join("README.md"), "").unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-qm", "init"]);
+    fs::write(app.join("src/index.ts"), "export {};\n").unwrap();
+    fs::write(dir.path().join("README.md"), "# repo\n").unwrap();
+
+    // The README outside the project doesn't count, so docs isn't suggested
+    run_cmd()
+        .current_dir(&app)
+        .env("RUN_NO_UPDATE", "1")
+        .args(["list", "--since", "HEAD"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1 file(s) changed since HEAD, consider: build",
+        ));
+}
+
+#[test]
+fn test_ignore_scripts_refuses_pipe_to_shell() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"setup": "curl -fsSL https://example.com/install.sh | sh"}}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["setup", "--dry-run", "--ignore-scripts"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("safe mode"));
+
+    // A script calling it by name is refused too
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"bootstrap": "npm run setup", "install": "node-gyp rebuild", "setup": "curl -fsSL https://example.com/install.sh | sh"}}"#,
+    )
+    .unwrap();
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["bootstrap", "--dry-run", "--ignore-scripts"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("it runs \"setup\""));
+
+    // `install` runs the package manager's install rather than being refused as a hook
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dry-run", "--ignore-scripts", "--", "install"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm install --ignore-scripts"));
+}
+
+#[test]
+fn test_graph_makefile() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "all: build test\nbuild: deps\n\tcc main.c\ntest: build\ndeps:\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("graph")
+        .assert()
+        .success()
+        .stdout("all\n  build\n    deps\n  test\n    build\n      deps\n");
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["graph", "--format=dot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"test\" -> \"build\";"));
+}
+
+#[test]
+fn test_dump_plan_makefile() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "all: build test\nbuild: deps\n\tcc main.c\ntest: build\ndeps:\nloop: a\na: loop\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dump-plan", "all"])
+        .assert()
+        .success()
+        .stdout("1. deps\n2. build\n3. test\n4. all\n");
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dump-plan", "loop"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Dependency cycle: loop -> a -> loop",
+        ));
+}
+
+#[test]
+fn test_list_only_found() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"lint": "eslint ."}}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lint", "--list-only"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run lint"));
+}
+
+#[test]
+fn test_list_only_not_found_suggests() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"lint": "eslint ."}}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lnt", "--check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("not found").and(predicate::str::contains("lint")));
+}
+
+#[test]
+fn test_completions_install_refuses_overwrite() {
+    let home = tempdir().unwrap();
+
+    run_cmd()
+        .env("HOME", home.path())
+        .env("SHELL", "/bin/zsh")
+        .args(["completions", "--install"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("fpath="));
+    assert!(home.path().join(".zsh/completions/_devrunner").exists());
+
+    run_cmd()
+        .env("HOME", home.path())
+        .env("SHELL", "/bin/zsh")
+        .args(["completions", "--install"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tail_on_failure_truncates_output() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "fail:\n\t@for i in $$(seq 1 50); do echo line$$i; done; exit 1\n\npass:\n\t@echo all good\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["fail", "--tail-on-failure", "3"])
+        .assert()
+        .failure()
+        .stdout("line48\nline49\nline50\n")
+        .stderr(predicate::str::contains(
+            "47 earlier stdout line(s) omitted",
+        ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["pass", "--tail-on-failure", "3"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+fn write_two_package_workspace(root: &std::path::Path) {
+    fs::write(
+        root.join("package.json"),
+        r#"{"name": "root", "private": true, "workspaces": ["packages/*"]}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("packages/web")).unwrap();
+    fs::write(
+        root.join("packages/web/package.json"),
+        r#"{"name": "@acme/web", "scripts": {"dev": "vite", "build": "vite build"}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("packages/api")).unwrap();
+    fs::write(
+        root.join("packages/api/package.json"),
+        r#"{"name": "@acme/api", "scripts": {"serve": "node server.js"}}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_list_all_workspaces_grouped() {
+    let dir = tempdir().unwrap();
+    write_two_package_workspace(dir.path());
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--all-workspaces"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("@acme/web"))
+        .stdout(predicate::str::contains("(packages/web)"))
+        .stdout(predicate::str::contains("vite build"))
+        .stdout(predicate::str::contains("@acme/api"))
+        .stdout(predicate::str::contains("node server.js"));
+}
+
+#[test]
+fn test_list_all_workspaces_json() {
+    let dir = tempdir().unwrap();
+    write_two_package_workspace(dir.path());
+
+    let output = run_cmd()
+        .current_dir(dir.path().join("packages/api"))
+        .args(["list", "--all-workspaces", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listing["@acme/web"]["path"], "packages/web");
+    assert_eq!(listing["@acme/web"]["scripts"]["build"], "vite build");
+    assert_eq!(listing["@acme/api"]["scripts"]["serve"], "node server.js");
+    assert!(listing["@acme/api"]["scripts"].get("dev").is_none());
+}
+
+#[test]
+fn test_list_recursive_json() {
+    let dir = tempdir().unwrap();
+    write_two_package_workspace(dir.path());
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--recursive", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        listing["packages/web"]["package.json"]["build"],
+        "vite build"
+    );
+    assert_eq!(
+        listing["packages/api"]["package.json"]["serve"],
+        "node server.js"
+    );
+
+    // --depth 1 stops above packages/*
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--recursive", "--depth", "1", "--json"])
+        .output()
+        .unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(listing.get("packages/web").is_none());
+}
+
+#[test]
+fn test_list_recursive_single_discovery_thread() {
+    let dir = tempdir().unwrap();
+    write_two_package_workspace(dir.path());
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "discovery_threads = 1\n",
+    )
+    .unwrap();
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["-v", "list", "--recursive", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Discovering scripts on up to 1 thread(s) per directory"));
+
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        listing["packages/web"]["package.json"]["build"],
+        "vite build"
+    );
+    assert_eq!(
+        listing["packages/api"]["package.json"]["serve"],
+        "node server.js"
+    );
+}
+
+#[test]
+fn test_list_grep_head() {
+    let dir = tempdir().unwrap();
+    write_two_package_workspace(dir.path());
+
+    // dev and build match through their commands; api has no match and drops out
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--recursive", "--json", "--grep", "VITE"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let web = listing["packages/web"]["package.json"].as_object().unwrap();
+    assert_eq!(web.len(), 2);
+    assert!(listing.get("packages/api").is_none());
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--all-workspaces", "--json", "--head", "1"])
+        .output()
+        .unwrap();
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let total: usize = ["@acme/web", "@acme/api"]
+        .iter()
+        .map(|package| listing[package]["scripts"].as_object().unwrap().len())
+        .sum();
+    assert_eq!(total, 1, "--head caps the whole listing, not each package");
+
+    // A regex over names: `^(dev|serve)$` spans both packages
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .args([
+            "list",
+            "--recursive",
+            "--json",
+            "--filter-regex",
+            "^(dev|serve)$",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let web = listing["packages/web"]["package.json"].as_object().unwrap();
+    assert_eq!(web.keys().collect::<Vec<_>>(), ["dev"]);
+    assert_eq!(
+        listing["packages/api"]["package.json"]["serve"],
+        "node server.js"
+    );
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--filter-regex", "(unclosed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid regex"));
+
+    run_cmd()
+        .current_dir(dir.path().join("packages/web"))
+        .args(["list", "--grep", "build"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("build"))
+        .stdout(predicate::str::contains("dev").not());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sigint_is_forwarded_to_the_command() {
+    use std::time::{Duration, Instant};
+
+    if !has_tool("make") {
+        eprintln!("skipping: make is not installed");
+        return;
+    }
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "sleeper:\n\t@trap 'echo cleaned up > cleanup.txt; exit 0' INT; touch started; \
+         while true; do sleep 0.1; done\n",
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("devrunner"))
+        .arg("sleeper")
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !dir.path().join("started").exists() {
+        assert!(Instant::now() < deadline, "sleeper never started");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let kill = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(kill.success());
+
+    // devrunner waits for the command to clean up, then exits with 128 + SIGINT
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("cleanup.txt")).unwrap(),
+        "cleaned up\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_clean_env_applies_to_shell_aliases() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "[aliases]\nshow = \"!echo keep=$DEVRUNNER_TEST_KEEP ambient=$DEVRUNNER_TEST_AMBIENT\"\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .env("DEVRUNNER_TEST_KEEP", "kept")
+        .env("DEVRUNNER_TEST_AMBIENT", "leaked")
+        .args(["--clean-env", "show"])
+        .assert()
+        .success()
+        .stdout("keep= ambient=\n");
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .env("DEVRUNNER_TEST_AMBIENT", "leaked")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout("keep= ambient=leaked\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_clean_env_passes_only_allowlisted_vars() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "env:\n\t@env > child.env\n").unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "clean_env_allowlist = [\"DEVRUNNER_TEST_KEEP\"]\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("DEVRUNNER_TEST_KEEP", "kept")
+        .env("DEVRUNNER_TEST_AMBIENT", "leaked")
+        .args([
+            "--clean-env",
+            "--env-from",
+            "echo DEVRUNNER_TEST_LOADED=loaded",
+            "env",
+        ])
+        .assert()
+        .success();
+
+    let child_env = fs::read_to_string(dir.path().join("child.env")).unwrap();
+    let names: Vec<&str> = child_env
+        .lines()
+        .filter_map(|line| line.split('=').next())
+        .collect();
+    assert!(names.contains(&"PATH"));
+    assert!(child_env.contains("DEVRUNNER_TEST_KEEP=kept"));
+    assert!(child_env.contains("DEVRUNNER_TEST_LOADED=loaded"));
+    assert!(!names.contains(&"DEVRUNNER_TEST_AMBIENT"));
+
+    // make and the shell add a few of their own; nothing else is inherited
+    let added_by_make = [
+        "MAKEFLAGS",
+        "MAKELEVEL",
+        "MFLAGS",
+        "PWD",
+        "SHLVL",
+        "OLDPWD",
+        "_",
+    ];
+    for name in names {
+        assert!(
+            [
+                "PATH",
+                "HOME",
+                "DEVRUNNER_TEST_KEEP",
+                "DEVRUNNER_TEST_LOADED"
+            ]
+            .contains(&name)
+                || added_by_make.contains(&name),
+            "unexpected variable {} in the child's environment",
+            name
+        );
+    }
+
+    // Without --clean-env everything is inherited
+    run_cmd()
+        .current_dir(dir.path())
+        .env("DEVRUNNER_TEST_AMBIENT", "leaked")
+        .arg("env")
+        .assert()
+        .success();
+    let child_env = fs::read_to_string(dir.path().join("child.env")).unwrap();
+    assert!(child_env.contains("DEVRUNNER_TEST_AMBIENT=leaked"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_quiet_levels_route_output() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "both:\n\t@echo to-stdout; echo to-stderr >&2\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("both")
+        .assert()
+        .success()
+        .stdout("to-stdout\n")
+        .stderr(predicate::str::contains("Executing"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["both", "-q"])
+        .assert()
+        .success()
+        .stdout("to-stdout\n")
+        .stderr("to-stderr\n");
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["both", "-qq"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("to-stderr\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_quiet_twice_with_tee_keeps_log() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "both:\n\t@echo to-stdout; echo to-stderr >&2\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["both", "-qq", "--tee", "out.log"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("to-stderr\n");
+
+    let log = fs::read_to_string(dir.path().join("out.log")).unwrap();
+    assert!(log.contains("to-stdout"));
+}
+
+#[test]
+fn test_env_prefers_script_variant() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build": "vite build", "build:prod": "vite build --mode production"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("package-lock.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--env", "prod", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run build:prod"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--env", "staging", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run build"))
+        .stdout(predicate::str::contains("build:").not());
+}
+
+#[test]
+fn test_typo_suggestion_pools_makefile_targets() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"bundle": "rollup -c", "lint": "eslint ."}}"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\tcargo build\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("buld")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Did you mean: devrunner --prefer make build (Makefile)?",
+        ));
+
+    // The exact name under the selected runner would only repeat the failure
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("build")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Did you mean: devrunner --prefer make build (Makefile)?",
+        ));
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--prefer", "make", "--dry-run", "build"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("make build"));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["bundel", "--list-only"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Did you mean: devrunner bundle (package.json)?",
+        ));
+}
+
+#[test]
+fn test_typo_suggests_subcommand() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build": "vite build"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("package-lock.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("doctr")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "hint: Did you mean: devrunner doctor?",
+        ));
+
+    // Clap rejects the subcommand's own flag; the error still names the subcommand
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lst", "--all-workspaces"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unexpected argument"))
+        .stderr(predicate::str::contains("Did you mean: devrunner list?"));
+
+    // A near-miss of a script still suggests the script
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("buld")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Did you mean: devrunner build"))
+        .stdout(predicate::str::contains("devrunner doctor").not());
+}
+
+#[test]
+fn test_env_suggests_variant_for_typo() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build:prod": "vite build --mode production", "test": "vitest"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("package-lock.json")).unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["biuld", "--env", "prod", "--dry-run"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_match("Did you mean:.*build:prod").unwrap());
+}
+
+#[test]
+fn test_if_present_skips_missing_script() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"test": "vitest"}}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lint", "--if-present"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_if_present_runs_existing_script() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"lint": "eslint ."}}"#,
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lint", "--if-present", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm run lint"));
+}
+
+#[test]
+fn test_cargo_binaries_count_as_present() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src/bin")).unwrap();
+    File::create(dir.path().join("src/main.rs")).unwrap();
+    File::create(dir.path().join("src/bin/migrate.rs")).unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["run:bin:migrate", "--check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo run --bin migrate"));
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["run:bin:migrate", "--if-present", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo run --bin migrate"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_if_present_checks_makefile_targets() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\t@echo built\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["lint", "--if-present"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["build", "--if-present", "-q"])
+        .assert()
+        .success()
+        .stdout("built\n");
+}
+
+#[test]
+fn test_color_flag_controls_ansi() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\t@echo built\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--color=always"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b["));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--color=never"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not())
+        .stdout(predicate::str::contains("make build"));
+
+    // Piped output defaults to plain text
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_alias_add_list_remove() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "test:\n\t@echo ok\n").unwrap();
+
+    run_cmd()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .current_dir(dir.path())
+        .args(["alias", "add", "t", "test"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(dir.path().join(".devrunner.toml"))
+        .unwrap()
+        .contains("t = \"test\""));
+
+    run_cmd()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .current_dir(dir.path())
+        .args(["alias", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("t -> test"));
+
+    run_cmd()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .current_dir(dir.path())
+        .args(["t", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("make test"));
+
+    run_cmd()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .current_dir(dir.path())
+        .args(["alias", "remove", "t"])
+        .assert()
+        .success();
+    run_cmd()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .current_dir(dir.path())
+        .args(["alias", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No aliases configured"));
+}
+
+#[test]
+fn test_doctor_diagnostics_honor_no_color() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\ttrue\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .env("NO_COLOR", "1")
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ok: No lockfile conflicts detected",
+        ))
+        .stdout(predicate::str::contains("\x1b[").not())
+        .stdout(predicate::str::contains("✓").not());
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["--color=always", "doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\x1b[32m✓ No lockfile conflicts detected",
+        ));
+}
+
+#[test]
+fn test_select_runs_every_combination() {
+    if !has_tool("make") {
+        eprintln!("skipping: make is not installed");
+        return;
+    }
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Makefile"),
+        "test:\n\t@echo \"$(B)/$$SELECT_os\" >> runs.txt\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args([
+            "--select",
+            "browser=chrome,firefox",
+            "--select",
+            "os=linux,mac",
+            "test",
+            "--",
+            "B={browser}",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Variant 4/4: browser=firefox os=mac",
+        ))
+        .stderr(predicate::str::contains("test [browser=chrome os=mac]"))
+        // Piped output has no glyphs
+        .stderr(predicate::str::contains("  passed"))
+        .stderr(predicate::str::contains("✓").not());
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("runs.txt")).unwrap(),
+        "chrome/linux\nchrome/mac\nfirefox/linux\nfirefox/mac\n"
+    );
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--select", "os=linux", "--select", "os=mac", "test"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("more than once"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_select_expands_shell_aliases() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "[aliases]\ne2e = \"!echo {browser} on $SELECT_os\"\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args([
+            "--select",
+            "browser=chrome,firefox",
+            "--select",
+            "os=linux,mac",
+            "e2e",
+        ])
+        .assert()
+        .success()
+        .stdout("chrome on linux\nchrome on mac\nfirefox on linux\nfirefox on mac\n");
+}
+
+#[test]
+fn test_why_and_doctor_list_all_evidence() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build": "tsc"}}"#,
+    )
+    .unwrap();
+    File::create(dir.path().join("pnpm-lock.yaml")).unwrap();
+    fs::write(dir.path().join("pnpm-workspace.yaml"), "packages: []\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .arg("why")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found pnpm-lock.yaml"))
+        .stdout(predicate::str::contains(
+            "Also found: pnpm-workspace.yaml, package.json",
+        ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .arg("doctor")
+        .assert()
+        .stdout(predicate::str::contains(
+            "pnpm (pnpm-lock.yaml, pnpm-workspace.yaml, package.json)",
+        ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--dry-run", "--json", "build"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""detected_file": "pnpm-lock.yaml""#,
+        ))
+        .stdout(predicate::str::contains(r#""pnpm-workspace.yaml""#));
+}
+
+#[test]
+fn test_doctor_json_exit_thresholds() {
+    // doctor fails a detected runner that isn't installed
+    if !has_tool("make") || !has_tool("cargo") {
+        eprintln!("skipping: make or cargo is not installed");
+        return;
+    }
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\ttrue\n").unwrap();
+
+    let output = run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["doctor", "--json", "--exit-on-warn"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let conflicts = report["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|section| section["title"] == "Conflict Analysis")
+        .unwrap();
+    assert_eq!(conflicts["checks"][0]["status"], "ok");
+
+    // A lockfile older than its manifest is a warning, not a failure
+    let lockfile = File::create(dir.path().join("Cargo.lock")).unwrap();
+    lockfile
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(600))
+        .unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["doctor", "--exit-on-warn"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "Cargo.lock is older than Cargo.toml",
+        ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env("RUN_NO_UPDATE", "1")
+        .args(["doctor", "--exit-on-fail"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_alias_add_rejects_subcommand_name() {
+    let dir = tempdir().unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["alias", "add", "doctor", "test"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("built-in subcommand"));
+    assert!(!dir.path().join(".devrunner.toml").exists());
+}
+
+#[test]
+fn test_remembered_conflict_choice() {
+    let dir = tempdir().unwrap();
+    let cache = tempdir().unwrap();
+    fs::write(
+        dir.path().join("package.json"),
+        r#"{"scripts": {"build": "echo build"}}"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("package-lock.json"), "{}").unwrap();
+    fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+    fs::write(
+        dir.path().join(".devrunner.toml"),
+        "remember_choices = true\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        let mut cmd = run_cmd();
+        cmd.current_dir(dir.path())
+            .env("RUN_NO_UPDATE", "1")
+            .env("XDG_CACHE_HOME", cache.path())
+            .args(args);
+        cmd
+    };
+
+    run(&["--prefer", "pnpm", "--dry-run", "build"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm run build"));
+    assert!(cache.path().join("run").join("choices.json").is_file());
+
+    // Settled by the stored choice, without --prefer
+    run(&["--dry-run", "build"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pnpm run build"));
+
+    run(&["--forget"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Forgot the runner remembered for"));
+    run(&["--dry-run", "build"])
+        .assert()
+        .stdout(predicate::str::contains("pnpm run build").not());
+
+    // A choice remembered for another project, which --forget here leaves alone
+    let other = tempdir().unwrap();
+    for file in [
+        "package.json",
+        "package-lock.json",
+        "pnpm-lock.yaml",
+        ".devrunner.toml",
+    ] {
+        fs::copy(dir.path().join(file), other.path().join(file)).unwrap();
+    }
+    let run_other = |args: &[&str]| {
+        let mut cmd = run_cmd();
+        cmd.current_dir(other.path())
+            .env("RUN_NO_UPDATE", "1")
+            .env("XDG_CACHE_HOME", cache.path())
+            .args(args);
+        cmd
+    };
+    run_other(&["--prefer", "pnpm", "--dry-run", "build"])
+        .assert()
+        .success();
+    run(&["--forget"]).assert().success();
+    run_other(&["--dry-run", "build"])
+        .assert()
+        .stdout(predicate::str::contains("pnpm run build"));
+
+    // --forget doesn't swallow a subcommand given with it: only `cache clear`
+    // drops the other project's choice
+    run(&["--forget", "cache", "clear"]).assert().success();
+    assert!(!cache.path().join("run").join("choices.json").exists());
+    run_other(&["--dry-run", "build"])
+        .assert()
+        .stdout(predicate::str::contains("pnpm run build").not());
+}
+
+#[test]
+fn test_offline_skips_network() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\ttrue\n").unwrap();
+
+    run_cmd()
+        .current_dir(dir.path())
+        .env_remove("RUN_NO_UPDATE")
+        .args(["--offline", "doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Offline (--offline), skipping network check",
+        ));
+
+    run_cmd()
+        .current_dir(dir.path())
+        .args(["--no-update", "--update"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--update needs the network"));
+}
+
+#[test]
+fn test_debug_prints_error_details() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Makefile"), "build:\n\ttrue\n").unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    let project = dir.path().join("app");
+    fs::create_dir_all(project.join(".git")).unwrap();
+
+    run_cmd()
+        .current_dir(&project)
+        .env("RUN_NO_UPDATE", "1")
+        .args(["--debug", "build"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Hint: Set root_markers = [] in config",
+        ))
+        .stderr(predicate::str::contains("Debug: NoRunnerInProjectRoot("));
+}